@@ -6,6 +6,8 @@ use crate::listener::ListenerBuilder;
 #[cfg(feature = "rocket_listener")]
 use crate::listener::RocketListener;
 use anyhow::anyhow;
+#[cfg(feature = "rocket_listener")]
+use chronicle_common::get_config_async;
 
 #[async_trait]
 impl<H> Starter<H> for ChronicleAPIBuilder<H>
@@ -21,7 +23,9 @@ where
     async fn starter(mut self, handle: H, input: Option<Self::Input>) -> Result<Self::Ok, Self::Error> {
         #[cfg(feature = "rocket_listener")]
         let rocket_listener = {
-            let rocket = rocket::ignite();
+            let api_config = get_config_async().await.api_config;
+            api_config.verify_listen_address()?;
+            let rocket = rocket::custom(RocketListener::figment(&api_config));
             let rocket_listener_handle = rocket.shutdown();
             let rocket_listener = ListenerBuilder::new().data(RocketListener::new(rocket)).build();
             self = self.rocket_listener_handle(rocket_listener_handle);
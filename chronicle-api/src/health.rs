@@ -0,0 +1,88 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+/// The app names which must be running before chronicle is ready to serve requests
+pub const READINESS_APPS: [&str; 2] = ["Scylla", "ChronicleBroker"];
+
+/// The liveness and readiness of the launcher apps, derived from the shared launcher service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Health {
+    /// Whether none of the registered apps is stopped
+    pub live: bool,
+    /// Whether all the apps chronicle depends on are up and running
+    pub ready: bool,
+}
+
+impl Health {
+    /// Compute the health of the provided launcher service
+    pub fn from_service(service: &Service) -> Self {
+        let live = !std::iter::once(service)
+            .chain(service.microservices.values())
+            .any(|service| service.is_stopped());
+        let ready = live
+            && READINESS_APPS.iter().all(|name| {
+                service
+                    .microservices
+                    .get(*name)
+                    .map(|app| app.is_running())
+                    .unwrap_or(false)
+            });
+        Self { live, ready }
+    }
+
+    /// Get the current health of the launcher apps
+    pub async fn current() -> Self {
+        Self::from_service(&*SERVICE.read().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(name: &str, status: ServiceStatus) -> Service {
+        let mut service = Service::new();
+        service.update_name(name.to_string());
+        service.update_status(status);
+        service
+    }
+
+    #[test]
+    fn readiness_follows_apps() {
+        let mut launcher = app("Launcher", ServiceStatus::Running);
+        assert_eq!(
+            Health::from_service(&launcher),
+            Health {
+                live: true,
+                ready: false
+            }
+        );
+        launcher.update_microservice("Scylla".to_string(), app("Scylla", ServiceStatus::Running));
+        launcher.update_microservice(
+            "ChronicleBroker".to_string(),
+            app("ChronicleBroker", ServiceStatus::Initializing),
+        );
+        assert!(!Health::from_service(&launcher).ready);
+        launcher.update_microservice(
+            "ChronicleBroker".to_string(),
+            app("ChronicleBroker", ServiceStatus::Running),
+        );
+        assert!(Health::from_service(&launcher).ready);
+        launcher.update_microservice("Scylla".to_string(), app("Scylla", ServiceStatus::Degraded));
+        assert!(!Health::from_service(&launcher).ready);
+        launcher.update_microservice("Scylla".to_string(), app("Scylla", ServiceStatus::Stopped));
+        assert_eq!(
+            Health::from_service(&launcher),
+            Health {
+                live: false,
+                ready: false
+            }
+        );
+    }
+}
@@ -11,16 +11,47 @@
 //! ### HTTP Endpoints
 //! - `/api/<keyspace>`
 //!     - `/messages`
-//!         - `?<index>[&<page_size>]`
+//!         - `POST` with a JSON array of (at most 100) message ids, responds with the messages by id, `null` for the
+//!           missing ones
+//!         - `?<index>[&<page_size>][&<payload_type>][&<since_milestone>][&<include_unconfirmed>]`, with `payload_type`
+//!           one of `transaction`, `indexation` or `milestone`. `since_milestone` drops the messages confirmed before
+//!           that milestone, the unconfirmed ones are kept unless `include_unconfirmed=false`
 //!         - `/<message_id>`
 //!         - `/<message_id>/metadata`
+//!         - `/<message_id>/raw`, which supports single byte range requests
+//!         - `/<message_id>/milestone`
+//!         - `/<message_id>/inclusion` the inclusion state of the message and, if it's included, its merkle inclusion
+//!           proof within the confirming milestone
+//!         - `/<message_id>/parents`
 //!         - `/<message_id>/children[?<page_size>]`
+//!     - `/tags?<prefix>[&<page_size>]` the distinct indexation tags which start with the hex `prefix`. The tags are
+//!       range scanned within the bucket of their first byte (in every partition holding tags of the bucket), so the
+//!       prefix must span at least one byte. The tags of the pruned milestones are dropped, unless a later milestone of
+//!       their partition holds them too
 //!     - `/outputs/<output_id>`
-//!     - `/addresses/ed25519/<address>/outputs[?<page_size>]`
+//!     - `/outputs/<output_id>/spending`
+//!     - `/outputs/<output_id>/history` the creating and spending transactions of the output
+//!     - `/addresses/ed25519/<address>/outputs[?<page_size>][&<type>]`, with `type` one of `single` or `dust`
+//!     - `/addresses/ed25519/<address>/spent-outputs[?<page_size>]` the spent outputs of the address with their
+//!       spending transactions
+//!     - `/addresses/<bech32>/outputs[?<page_size>][&<type>]`
 //!     - `/milestones/<index>`
+//!     - `/milestones/diff?<a>&<b>`
+//!     - `/analytics[?<start>][&<end>]` the analytics of the continuous milestone ranges, the stored milestones which
+//!       have none are computed and stored on demand (a bounded number per query)
+//!     - `/analytics/series?<from>&<to>&<bucket>` the summed analytics of every `bucket` milestones
+//! - `/api/jobs` the progress of the active broker jobs, ie the LogFile imports
+//! - `/api/openapi.json` the OpenAPI document of the endpoints
+//! - `/healthz` and `/readyz` liveness and readiness probes
+//!
+//! The output responses include the `ledgerIndex` and `pruningIndex` hints, the latest stored milestone index and
+//! the one below the earliest stored milestone index of the keyspace. The hints are omitted when the sync data of the
+//! keyspace can't be fetched or is partial.
 
 /// The main actor for the API
 pub mod application;
+/// The liveness and readiness of the launcher apps
+pub mod health;
 /// The http endpoint listener
 pub mod listener;
 /// API response structs
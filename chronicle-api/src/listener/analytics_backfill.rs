@@ -0,0 +1,241 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::ListenerError;
+use chronicle_storage::access::AnalyticRecord;
+use futures::{
+    channel::oneshot,
+    future::{
+        FutureExt,
+        Shared,
+    },
+    stream::StreamExt,
+};
+use log::{
+    debug,
+    warn,
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::Mutex,
+};
+
+/// The max number of milestones whose analytics are computed concurrently by a backfill
+const ANALYTICS_BACKFILL_CONCURRENCY: usize = 8;
+/// The max number of milestones backfilled per analytics query, the rest is left to the following queries
+pub(crate) const MAX_ANALYTICS_BACKFILL: usize = 100;
+
+/// The computed analytics of a milestone being backfilled, None if they failed to compute
+type InFlight = Shared<oneshot::Receiver<Option<AnalyticRecord>>>;
+
+/// Backfills the analytics of the stored milestones which have none (ie stored before the analytics were), so the
+/// following queries of their range are served by the analytics table
+pub(crate) struct AnalyticsBackfill {
+    concurrency: usize,
+    /// The (keyspace, milestone index) pairs which are being backfilled, along with their computed analytics
+    in_flight: Mutex<HashMap<(String, u32), InFlight>>,
+}
+
+impl AnalyticsBackfill {
+    /// Create an analytics backfill with the default concurrency
+    pub(crate) fn new() -> Self {
+        Self::with_concurrency(ANALYTICS_BACKFILL_CONCURRENCY)
+    }
+
+    fn with_concurrency(concurrency: usize) -> Self {
+        Self {
+            concurrency,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Compute and store the analytics of the missing milestones, returning the computed ones. The milestones which
+    /// are already being backfilled (by a concurrent query) are awaited rather than computed again, while the ones
+    /// which fail to compute are left out (ie not stored)
+    pub(crate) async fn backfill<C, CFut, S>(
+        &self,
+        keyspace: &str,
+        missing: Vec<u32>,
+        compute: C,
+        store: S,
+    ) -> Vec<AnalyticRecord>
+    where
+        C: Fn(u32) -> CFut,
+        CFut: Future<Output = Result<AnalyticRecord, ListenerError>>,
+        S: Fn(&AnalyticRecord) -> anyhow::Result<()>,
+    {
+        let claims = self.claim(keyspace, missing);
+        let compute = &compute;
+        let store = &store;
+        futures::stream::iter(claims)
+            .map(|claimed| async move {
+                let claim = match claimed {
+                    Claimed::Claim(claim) => claim,
+                    // the concurrent query shares its analytics, None if its holder is gone without them
+                    Claimed::InFlight(in_flight) => return in_flight.await.ok().flatten(),
+                };
+                let record = match compute(claim.milestone_index).await {
+                    Ok(record) => Some(record),
+                    Err(e) => {
+                        debug!(
+                            "Unable to backfill the analytics of milestone {}: {}",
+                            claim.milestone_index, e
+                        );
+                        None
+                    }
+                };
+                if let Some(record) = record.as_ref() {
+                    if let Err(e) = store(record) {
+                        warn!(
+                            "Unable to store the analytics of milestone {}: {}",
+                            claim.milestone_index, e
+                        );
+                    }
+                }
+                claim.complete(record.clone());
+                record
+            })
+            .buffer_unordered(self.concurrency)
+            .filter_map(futures::future::ready)
+            .collect()
+            .await
+    }
+
+    /// Claim the milestones which are not being backfilled yet, the claims are released once dropped, while the ones
+    /// being backfilled are returned as in flight
+    fn claim(&self, keyspace: &str, missing: Vec<u32>) -> Vec<Claimed<'_>> {
+        let mut in_flight = self.in_flight.lock().expect("Poisoned analytics backfill");
+        missing
+            .into_iter()
+            .map(|milestone_index| {
+                let key = (keyspace.to_owned(), milestone_index);
+                if let Some(shared) = in_flight.get(&key) {
+                    return Claimed::InFlight(shared.clone());
+                }
+                let (sender, receiver) = oneshot::channel();
+                in_flight.insert(key, receiver.shared());
+                Claimed::Claim(Claim {
+                    backfill: self,
+                    keyspace: keyspace.to_owned(),
+                    milestone_index,
+                    sender: Some(sender),
+                })
+            })
+            .collect()
+    }
+}
+
+/// A missing milestone, either claimed by the backfill or being backfilled by a concurrent one
+enum Claimed<'a> {
+    Claim(Claim<'a>),
+    InFlight(InFlight),
+}
+
+/// A milestone claimed by a backfill
+struct Claim<'a> {
+    backfill: &'a AnalyticsBackfill,
+    keyspace: String,
+    milestone_index: u32,
+    /// Shares the computed analytics with the concurrent backfills of the milestone
+    sender: Option<oneshot::Sender<Option<AnalyticRecord>>>,
+}
+
+impl Claim<'_> {
+    /// Share the computed analytics with the concurrent backfills, and release the claim
+    fn complete(mut self, record: Option<AnalyticRecord>) {
+        if let Some(sender) = self.sender.take() {
+            sender.send(record).ok();
+        }
+    }
+}
+
+impl Drop for Claim<'_> {
+    fn drop(&mut self) {
+        self.backfill
+            .in_flight
+            .lock()
+            .expect("Poisoned analytics backfill")
+            .remove(&(std::mem::take(&mut self.keyspace), self.milestone_index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bee_message::milestone::MilestoneIndex;
+    use chronicle_storage::access::{
+        MessageCount,
+        ReattachmentCount,
+        TransactionCount,
+        TransferredTokens,
+    };
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+
+    fn record(milestone_index: u32) -> AnalyticRecord {
+        AnalyticRecord::new(
+            MilestoneIndex(milestone_index),
+            MessageCount(1),
+            TransactionCount(0),
+            TransferredTokens(0),
+            ReattachmentCount(0),
+        )
+    }
+
+    #[tokio::test]
+    async fn concurrent_backfills_compute_once() {
+        let backfill = AnalyticsBackfill::with_concurrency(2);
+        let computed = AtomicUsize::new(0);
+        let stored = Mutex::new(Vec::new());
+        let compute = |milestone_index: u32| {
+            computed.fetch_add(1, Ordering::SeqCst);
+            async move {
+                // yield, so the concurrent backfill runs while the milestones are claimed
+                tokio::task::yield_now().await;
+                if milestone_index == 3 {
+                    Err(ListenerError::NoResults)
+                } else {
+                    Ok(record(milestone_index))
+                }
+            }
+        };
+        let store = |record: &AnalyticRecord| {
+            stored.lock().unwrap().push(**record.milestone_index());
+            Ok(())
+        };
+        let (first, second) = futures::future::join(
+            backfill.backfill("permanode", vec![1, 2, 3], compute, store),
+            backfill.backfill("permanode", vec![2, 3, 4], compute, store),
+        )
+        .await;
+        let backfilled = |records: &Vec<AnalyticRecord>| {
+            let mut backfilled = records
+                .iter()
+                .map(|record| **record.milestone_index())
+                .collect::<Vec<_>>();
+            backfilled.sort_unstable();
+            backfilled
+        };
+        // the milestone claimed by the first backfill is awaited by the second one, rather than skipped
+        assert_eq!(backfilled(&first), vec![1, 2]);
+        assert_eq!(backfilled(&second), vec![2, 4]);
+        // each milestone is computed once, and the ones which failed to compute are not stored
+        assert_eq!(computed.load(Ordering::SeqCst), 4);
+        stored.lock().unwrap().sort_unstable();
+        assert_eq!(*stored.lock().unwrap(), vec![1, 2, 4]);
+        // the claims are released, so a later backfill retries the failed milestone
+        let retried = backfill
+            .backfill(
+                "permanode",
+                vec![3],
+                |milestone_index: u32| async move { Ok(record(milestone_index)) },
+                store,
+            )
+            .await;
+        assert_eq!(retried.len(), 1);
+        assert!(backfill.in_flight.lock().unwrap().is_empty());
+    }
+}
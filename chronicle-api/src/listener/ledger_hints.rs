@@ -0,0 +1,160 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::ListenerError;
+use crate::responses::LedgerHints;
+use chronicle_broker::SyncData;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::Mutex,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// The time the ledger hints of a keyspace are reused before the sync data is fetched again
+const LEDGER_HINTS_TTL: Duration = Duration::from_secs(10);
+
+impl From<&SyncData> for LedgerHints {
+    fn from(sync_data: &SyncData) -> Self {
+        sync_data
+            .stored_bounds()
+            .map(|(earliest, latest)| LedgerHints {
+                ledger_index: latest,
+                pruning_index: earliest.saturating_sub(1),
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Caches the ledger hints per keyspace, as computing them requires the whole sync data
+pub(crate) struct LedgerHintsCache {
+    ttl: Duration,
+    cache: Mutex<HashMap<String, (Instant, Option<LedgerHints>)>>,
+}
+
+impl LedgerHintsCache {
+    /// Create a ledger hints cache with the default ttl
+    pub(crate) fn new() -> Self {
+        Self::with_ttl(LEDGER_HINTS_TTL)
+    }
+
+    fn with_ttl(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get the ledger hints of the keyspace, fetching the sync data once the cached hints expire. A partial sync data
+    /// yields no hints, as the earliest stored milestone may be below its truncated rows.
+    pub(crate) async fn get<F, Fut>(
+        &self,
+        keyspace: &str,
+        fetch_sync_data: F,
+    ) -> Result<Option<LedgerHints>, ListenerError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<SyncData>>,
+    {
+        if let Some((fetched_at, hints)) = self.cache.lock().expect("Poisoned ledger hints cache").get(keyspace) {
+            if fetched_at.elapsed() < self.ttl {
+                return Ok(*hints);
+            }
+        }
+        // the fetch may be rejected by the query limiter
+        let sync_data = fetch_sync_data()
+            .await
+            .map_err(|e| e.downcast::<ListenerError>().unwrap_or_else(ListenerError::Other))?;
+        let hints = (!sync_data.partial).then(|| LedgerHints::from(&sync_data));
+        self.cache
+            .lock()
+            .expect("Poisoned ledger hints cache")
+            .insert(keyspace.to_owned(), (Instant::now(), hints));
+        Ok(hints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::ready;
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+
+    #[tokio::test]
+    async fn hints_reflect_stored_bounds() {
+        let cache = LedgerHintsCache::new();
+        let fetches = AtomicUsize::new(0);
+        let fetch = || {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            ready(SyncData::from_ranges(
+                vec![10..20, 30..40],
+                vec![40..45],
+                vec![20..30, 45..50],
+            ))
+        };
+        let hints = cache.get("permanode", fetch).await.unwrap();
+        assert_eq!(
+            hints,
+            Some(LedgerHints {
+                ledger_index: 44,
+                pruning_index: 9,
+            })
+        );
+        // reused within the ttl
+        assert_eq!(cache.get("permanode", fetch).await.unwrap(), hints);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        // nothing stored yet
+        let empty = cache
+            .get("other", || {
+                ready(SyncData::from_ranges(Vec::new(), Vec::new(), vec![1..10]))
+            })
+            .await
+            .unwrap();
+        assert_eq!(empty, Some(LedgerHints::default()));
+    }
+
+    #[tokio::test]
+    async fn partial_sync_data_has_no_hints() {
+        let cache = LedgerHintsCache::new();
+        let hints = cache
+            .get("permanode", || {
+                ready(
+                    SyncData::from_ranges(vec![10..20], Vec::new(), Vec::new()).map(|sync_data| SyncData {
+                        partial: true,
+                        ..sync_data
+                    }),
+                )
+            })
+            .await
+            .unwrap();
+        assert_eq!(hints, None);
+    }
+
+    #[tokio::test]
+    async fn expired_hints_are_refetched() {
+        let cache = LedgerHintsCache::with_ttl(Duration::from_millis(0));
+        cache
+            .get("permanode", || {
+                ready(SyncData::from_ranges(vec![10..20], Vec::new(), Vec::new()))
+            })
+            .await
+            .unwrap();
+        let hints = cache
+            .get("permanode", || {
+                ready(SyncData::from_ranges(vec![10..30], Vec::new(), Vec::new()))
+            })
+            .await
+            .unwrap();
+        assert_eq!(hints.unwrap().ledger_index, 29);
+        assert!(cache
+            .get("permanode", || ready(Err(anyhow::anyhow!("timeout"))))
+            .await
+            .is_err());
+    }
+}
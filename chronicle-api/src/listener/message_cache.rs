@@ -0,0 +1,132 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_message::prelude::{
+    Message,
+    MessageId,
+};
+use chronicle_common::metrics::{
+    MESSAGE_CACHE_HITS,
+    MESSAGE_CACHE_MISSES,
+};
+use lru::LruCache;
+use std::{
+    future::Future,
+    sync::Mutex,
+};
+
+/// A bounded LRU cache in front of the message lookups.
+/// Messages are immutable, so entries are only ever evicted, never invalidated.
+pub(crate) struct MessageCache {
+    cache: Option<Mutex<LruCache<(String, MessageId), Message>>>,
+}
+
+impl MessageCache {
+    /// Create a message cache holding up to `capacity` messages, a zero capacity disables the cache
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            cache: (capacity > 0).then(|| Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    /// Get the message from the cache, or fetch it and cache it on success
+    pub(crate) async fn get_or_fetch<F, Fut, E>(
+        &self,
+        keyspace: &str,
+        message_id: MessageId,
+        fetch: F,
+    ) -> Result<Message, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Message, E>>,
+    {
+        let cache = match self.cache.as_ref() {
+            Some(cache) => cache,
+            None => return fetch().await,
+        };
+        let key = (keyspace.to_owned(), message_id);
+        if let Some(message) = cache.lock().expect("Poisoned message cache").get(&key) {
+            MESSAGE_CACHE_HITS.with_label_values(&[keyspace]).inc();
+            return Ok(message.clone());
+        }
+        MESSAGE_CACHE_MISSES.with_label_values(&[keyspace]).inc();
+        // The lock is not held while fetching, concurrent misses of the same message may both hit the storage
+        let message = fetch().await?;
+        cache.lock().expect("Poisoned message cache").put(key, message.clone());
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bee_message::{
+        parents::Parents,
+        MessageBuilder,
+    };
+    use bee_pow::providers::miner::Miner;
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+
+    fn message() -> Message {
+        MessageBuilder::<Miner>::new()
+            .with_network_id(0)
+            .with_parents(Parents::new(vec![MessageId::new([1; 32])]).unwrap())
+            .finish()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn second_fetch_hits_cache() {
+        let cache = MessageCache::new(1);
+        let fetches = AtomicUsize::new(0);
+        let storage = || async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ()>(message())
+        };
+        let id = MessageId::new([0; 32]);
+        let first = cache.get_or_fetch("permanode", id, storage).await.unwrap();
+        let second = cache.get_or_fetch("permanode", id, storage).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        // A different keyspace is a different entry, and evicts the first one
+        cache.get_or_fetch("other", id, storage).await.unwrap();
+        cache.get_or_fetch("permanode", id, storage).await.unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn cache_metrics_are_labelled_by_keyspace() {
+        let cache = MessageCache::new(2);
+        let storage = || async { Ok::<_, ()>(message()) };
+        let id = MessageId::new([0; 32]);
+        cache.get_or_fetch("metrics_a", id, storage).await.unwrap();
+        cache.get_or_fetch("metrics_a", id, storage).await.unwrap();
+        cache.get_or_fetch("metrics_b", id, storage).await.unwrap();
+        assert_eq!(MESSAGE_CACHE_MISSES.with_label_values(&["metrics_a"]).get(), 1);
+        assert_eq!(MESSAGE_CACHE_HITS.with_label_values(&["metrics_a"]).get(), 1);
+        assert_eq!(MESSAGE_CACHE_MISSES.with_label_values(&["metrics_b"]).get(), 1);
+        assert_eq!(MESSAGE_CACHE_HITS.with_label_values(&["metrics_b"]).get(), 0);
+    }
+
+    #[tokio::test]
+    async fn disabled_cache_always_fetches() {
+        let cache = MessageCache::new(0);
+        let fetches = AtomicUsize::new(0);
+        let storage = || async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ()>(message())
+        };
+        cache
+            .get_or_fetch("permanode", MessageId::new([0; 32]), storage)
+            .await
+            .unwrap();
+        cache
+            .get_or_fetch("permanode", MessageId::new([0; 32]), storage)
+            .await
+            .unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+}
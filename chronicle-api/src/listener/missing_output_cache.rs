@@ -0,0 +1,84 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_message::prelude::OutputId;
+use lru::LruCache;
+use std::{
+    sync::Mutex,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+/// The max number of missing output ids tracked at once
+const MISSING_OUTPUT_CACHE_CAPACITY: usize = 10000;
+
+/// A short lived negative cache of the output ids which were not found in the storage.
+/// Entries expire after the ttl, so a later created output becomes visible promptly.
+pub(crate) struct MissingOutputCache {
+    ttl: Duration,
+    cache: Option<Mutex<LruCache<(String, OutputId), Instant>>>,
+}
+
+impl MissingOutputCache {
+    /// Create a missing output cache with the provided ttl, a zero ttl disables the cache
+    pub(crate) fn new(ttl_ms: u64) -> Self {
+        Self {
+            ttl: Duration::from_millis(ttl_ms),
+            cache: (ttl_ms > 0).then(|| Mutex::new(LruCache::new(MISSING_OUTPUT_CACHE_CAPACITY))),
+        }
+    }
+
+    /// Check whether the output id is known to be missing, expired entries are evicted
+    pub(crate) fn is_missing(&self, keyspace: &str, output_id: OutputId) -> bool {
+        if let Some(cache) = self.cache.as_ref() {
+            let mut cache = cache.lock().expect("Poisoned missing output cache");
+            let key = (keyspace.to_owned(), output_id);
+            match cache.get(&key) {
+                Some(missed_at) if missed_at.elapsed() < self.ttl => return true,
+                Some(_) => {
+                    cache.pop(&key);
+                }
+                None => (),
+            }
+        }
+        false
+    }
+
+    /// Record that the output id was not found
+    pub(crate) fn insert(&self, keyspace: &str, output_id: OutputId) {
+        if let Some(cache) = self.cache.as_ref() {
+            cache
+                .lock()
+                .expect("Poisoned missing output cache")
+                .put((keyspace.to_owned(), output_id), Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bee_message::prelude::TransactionId;
+
+    #[test]
+    fn miss_expires_after_ttl() {
+        let cache = MissingOutputCache::new(50);
+        let output_id = OutputId::new(TransactionId::new([0; 32]), 0).unwrap();
+        assert!(!cache.is_missing("permanode", output_id));
+        cache.insert("permanode", output_id);
+        assert!(cache.is_missing("permanode", output_id));
+        assert!(!cache.is_missing("other", output_id));
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(!cache.is_missing("permanode", output_id));
+    }
+
+    #[test]
+    fn disabled_cache_never_misses() {
+        let cache = MissingOutputCache::new(0);
+        let output_id = OutputId::new(TransactionId::new([0; 32]), 0).unwrap();
+        cache.insert("permanode", output_id);
+        assert!(!cache.is_missing("permanode", output_id));
+    }
+}
@@ -20,8 +20,20 @@ use std::{
 };
 use thiserror::Error;
 
+#[cfg(feature = "rocket_listener")]
+mod analytics_backfill;
 mod init;
 #[cfg(feature = "rocket_listener")]
+mod ledger_hints;
+#[cfg(feature = "rocket_listener")]
+mod message_cache;
+#[cfg(feature = "rocket_listener")]
+mod missing_output_cache;
+#[cfg(feature = "rocket_listener")]
+mod openapi;
+#[cfg(feature = "rocket_listener")]
+mod query_limiter;
+#[cfg(feature = "rocket_listener")]
 mod rocket_event_loop;
 mod terminating;
 
@@ -37,10 +49,22 @@ enum ListenerError {
     InvalidHex,
     #[error("Specified keyspace ({0}) is not configured!")]
     InvalidKeyspace(String),
+    #[error("Provided tag prefix is too short! (Min 1 byte)")]
+    TagPrefixTooShort,
     #[error("Invalid state provided!")]
     InvalidState,
     #[error("No endpoint found!")]
     NotFound,
+    #[error("Storage query timed out!")]
+    Timeout,
+    #[error("Too many queries in flight, retry later!")]
+    Overloaded,
+    #[error("Requested range is not satisfiable, the content is {0} bytes!")]
+    RangeNotSatisfiable(usize),
+    #[error("Response exceeds the max response size of {0} bytes!")]
+    ResponseTooLarge(usize),
+    #[error("Address HRP ({0}) does not match the keyspace HRP ({1})!")]
+    HrpMismatch(String, String),
     #[error(transparent)]
     BadParse(anyhow::Error),
     #[error(transparent)]
@@ -51,7 +75,15 @@ impl ListenerError {
     pub fn status(&self) -> Status {
         match self {
             ListenerError::NoResults | ListenerError::InvalidKeyspace(_) => Status::NotFound,
-            ListenerError::IndexTooLarge | ListenerError::InvalidHex | ListenerError::BadParse(_) => Status::BadRequest,
+            ListenerError::IndexTooLarge
+            | ListenerError::InvalidHex
+            | ListenerError::TagPrefixTooShort
+            | ListenerError::HrpMismatch(..)
+            | ListenerError::BadParse(_) => Status::BadRequest,
+            ListenerError::Timeout => Status::GatewayTimeout,
+            ListenerError::Overloaded => Status::ServiceUnavailable,
+            ListenerError::RangeNotSatisfiable(_) => Status::RangeNotSatisfiable,
+            ListenerError::ResponseTooLarge(_) => Status::PayloadTooLarge,
             _ => Status::InternalServerError,
         }
     }
@@ -173,6 +205,8 @@ struct ErrorBody {
     status: Status,
     code: u16,
     message: Cow<'static, str>,
+    #[serde(rename = "requestId", skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
 }
 
 impl From<ListenerError> for ErrorBody {
@@ -181,6 +215,7 @@ impl From<ListenerError> for ErrorBody {
             status: err.status(),
             code: err.code(),
             message: err.to_string().into(),
+            request_id: None,
         }
     }
 }
@@ -0,0 +1,148 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::responses::ListenerResponse;
+use rocket::{
+    http::Method,
+    Route,
+};
+use schemars::gen::SchemaSettings;
+use serde_json::{
+    json,
+    Map,
+    Value,
+};
+
+/// The OpenAPI document of the mounted routes, served by `GET /api/openapi.json`
+pub(crate) struct OpenApi(pub(crate) Value);
+
+impl OpenApi {
+    /// Describe the provided routes, with the response schemas derived from the `responses` structs
+    pub(crate) fn new<'a>(routes: impl Iterator<Item = &'a Route>) -> Self {
+        let root = SchemaSettings::openapi3()
+            .into_generator()
+            .into_root_schema_for::<ListenerResponse>();
+        let mut schemas = root
+            .definitions
+            .into_iter()
+            .map(|(name, schema)| (name, json!(schema)))
+            .collect::<Map<_, _>>();
+        schemas.insert("ListenerResponse".to_string(), json!(root.schema));
+
+        let mut paths = Map::new();
+        // The CORS preflight catch-all is not an endpoint
+        for route in routes.filter(|route| route.method != Method::Options) {
+            let uri = route.uri.to_string();
+            let mut uri = uri.splitn(2, '?');
+            let path = path_template(uri.next().unwrap_or_default());
+            let mut operation = json!({
+                "parameters": parameters(&path, uri.next()),
+                "responses": {
+                    "200": response(&path),
+                    "default": {
+                        "description": "Error",
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "error": { "type": "object" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+            // The only request body is the message ids array of the batch messages lookup
+            if route.method == Method::Post {
+                operation["requestBody"] = json!({
+                    "required": true,
+                    "content": {
+                        "application/json": {
+                            "schema": { "type": "array", "items": { "type": "string" } }
+                        }
+                    }
+                });
+            }
+            paths
+                .entry(path)
+                .or_insert_with(|| json!({}))
+                .as_object_mut()
+                .expect("Invalid path item")
+                .insert(route.method.as_str().to_lowercase(), operation);
+        }
+
+        Self(json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "Chronicle API",
+                "version": std::env!("CARGO_PKG_VERSION"),
+            },
+            "paths": paths,
+            "components": {
+                "schemas": schemas,
+            },
+        }))
+    }
+}
+
+/// Convert a rocket path (ie `/api/<keyspace>/sync`) to an OpenAPI path template (ie `/api/{keyspace}/sync`)
+fn path_template(path: &str) -> String {
+    path.replace('<', "{").replace('>', "}")
+}
+
+/// The parameters of an OpenAPI path template and of the rocket query (ie `<page_size>&<state>`), the query ones
+/// are optional
+fn parameters(path: &str, query: Option<&str>) -> Vec<Value> {
+    let path_parameters = path
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+        .map(|name| (name, "path"));
+    let query_parameters = query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .filter_map(|segment| segment.strip_prefix('<').and_then(|s| s.strip_suffix('>')))
+        .map(|name| (name, "query"));
+    path_parameters
+        .chain(query_parameters)
+        .map(|(name, location)| {
+            json!({
+                "name": name,
+                "in": location,
+                "required": location == "path",
+                "schema": { "type": "string" }
+            })
+        })
+        .collect()
+}
+
+/// The success response of a path, the keyspace endpoints respond with a `ListenerResponse` wrapped in `data`
+fn response(path: &str) -> Value {
+    if path.ends_with("/raw") {
+        json!({
+            "description": "The packed message bytes, a `Range` request responds with `206` and the requested byte range",
+            "content": { "application/octet-stream": { "schema": { "type": "string", "format": "binary" } } }
+        })
+    } else if (path.starts_with("/api/{keyspace}/") && !path.ends_with("/sync")) || path == "/api/info" {
+        json!({
+            "description": "Success",
+            "content": {
+                "application/json": {
+                    "schema": {
+                        "type": "object",
+                        "properties": {
+                            "data": { "$ref": "#/components/schemas/ListenerResponse" }
+                        }
+                    }
+                }
+            }
+        })
+    } else if path.ends_with("/sync") {
+        json!({
+            "description": "The sync data, a truncated read responds with `206` and the `partial` flag set, as the milestones below its lowest range are unknown"
+        })
+    } else {
+        json!({ "description": "Success" })
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::ListenerError;
+use scylla_rs::prelude::*;
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{
+    OwnedSemaphorePermit,
+    Semaphore,
+};
+
+/// Bounds the number of storage queries the api has in flight at once.
+/// The queries beyond the limit queue for a free slot up to the queue timeout, then fail with `Overloaded`.
+pub(crate) struct QueryLimiter {
+    /// The in-flight slots, none doesn't limit the queries
+    permits: Option<Arc<Semaphore>>,
+    queue_timeout: Duration,
+}
+
+impl QueryLimiter {
+    /// Create a query limiter allowing up to `max_in_flight` queries (zero doesn't limit them), which queue up to
+    /// `queue_timeout_ms`
+    pub(crate) fn new(max_in_flight: usize, queue_timeout_ms: u64) -> Self {
+        Self {
+            permits: (max_in_flight > 0).then(|| Arc::new(Semaphore::new(max_in_flight))),
+            queue_timeout: Duration::from_millis(queue_timeout_ms),
+        }
+    }
+
+    /// Acquire an in-flight slot, which is released when the permit is dropped. No permit is needed if the queries
+    /// aren't limited.
+    pub(crate) async fn acquire(&self) -> Result<Option<OwnedSemaphorePermit>, ListenerError> {
+        let permits = match self.permits.as_ref() {
+            Some(permits) => permits,
+            None => return Ok(None),
+        };
+        if let Ok(permit) = permits.clone().try_acquire_owned() {
+            return Ok(Some(permit));
+        }
+        match tokio::time::timeout(self.queue_timeout, permits.clone().acquire_owned()).await {
+            Ok(Ok(permit)) => Ok(Some(permit)),
+            _ => Err(ListenerError::Overloaded),
+        }
+    }
+}
+
+/// Holds the in-flight permit of a query along its worker, so the slot is released once the query completes, rather
+/// than when the api stops waiting on it (ie on a query timeout)
+pub(crate) struct PermitWorker<W> {
+    worker: Box<W>,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl<W> PermitWorker<W> {
+    /// Wrap the worker of the query the permit was acquired for
+    pub(crate) fn boxed(worker: Box<W>, permit: Option<OwnedSemaphorePermit>) -> Box<Self> {
+        Box::new(Self {
+            worker,
+            _permit: permit,
+        })
+    }
+}
+
+impl<W: Worker> Worker for PermitWorker<W> {
+    fn handle_response(self: Box<Self>, giveload: Vec<u8>) -> anyhow::Result<()> {
+        self.worker.handle_response(giveload)
+    }
+
+    fn handle_error(self: Box<Self>, error: WorkerError, reporter: &Option<ReporterHandle>) -> anyhow::Result<()> {
+        self.worker.handle_error(error, reporter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::Status;
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+
+    #[tokio::test]
+    async fn caps_in_flight_queries() {
+        let limiter = QueryLimiter::new(4, 1000);
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+        let storage = || async {
+            let _permit = limiter.acquire().await?;
+            let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            max_in_flight.fetch_max(current, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok::<_, ListenerError>(())
+        };
+        let results = futures::future::join_all((0..32).map(|_| storage())).await;
+        // The queued queries get a slot within the queue timeout
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn overloaded_after_queue_timeout() {
+        let limiter = QueryLimiter::new(1, 10);
+        let permit = limiter.acquire().await.unwrap();
+        let res = limiter.acquire().await;
+        assert!(matches!(res, Err(ListenerError::Overloaded)));
+        assert_eq!(res.unwrap_err().status(), Status::ServiceUnavailable);
+        drop(permit);
+        assert!(limiter.acquire().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn zero_max_in_flight_is_unlimited() {
+        let limiter = QueryLimiter::new(0, 10);
+        let permits = futures::future::join_all((0..32).map(|_| limiter.acquire())).await;
+        assert!(permits.iter().all(|permit| matches!(permit, Ok(None))));
+    }
+
+    #[tokio::test]
+    async fn permit_held_until_the_query_completes() {
+        struct Completion(tokio::sync::oneshot::Sender<()>);
+        impl Worker for Completion {
+            fn handle_response(self: Box<Self>, _giveload: Vec<u8>) -> anyhow::Result<()> {
+                self.0.send(()).ok();
+                Ok(())
+            }
+            fn handle_error(
+                self: Box<Self>,
+                _error: WorkerError,
+                _reporter: &Option<ReporterHandle>,
+            ) -> anyhow::Result<()> {
+                self.0.send(()).ok();
+                Ok(())
+            }
+        }
+        let limiter = QueryLimiter::new(1, 10);
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let worker = PermitWorker::boxed(Box::new(Completion(tx)), limiter.acquire().await.unwrap());
+        // the api stopped waiting on the response, the query is still in flight
+        drop(rx);
+        assert!(matches!(limiter.acquire().await, Err(ListenerError::Overloaded)));
+        worker.handle_response(Vec::new()).unwrap();
+        assert!(limiter.acquire().await.unwrap().is_some());
+    }
+}
@@ -1,26 +1,76 @@
 // Copyright 2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use super::*;
-use crate::responses::*;
+use super::{
+    analytics_backfill::{
+        AnalyticsBackfill,
+        MAX_ANALYTICS_BACKFILL,
+    },
+    ledger_hints::LedgerHintsCache,
+    message_cache::MessageCache,
+    missing_output_cache::MissingOutputCache,
+    openapi::OpenApi,
+    query_limiter::{
+        PermitWorker,
+        QueryLimiter,
+    },
+    *,
+};
+use crate::{
+    health::Health,
+    responses::*,
+};
 use anyhow::anyhow;
+use bech32::FromBase32;
+use bee_common::packable::Packable;
 use bee_message::{
+    address::Address,
     milestone::Milestone,
     prelude::{
         Ed25519Address,
+        Essence,
         Message,
         MessageId,
         MilestoneIndex,
         OutputId,
+        Payload,
+        SignatureLockedDustAllowanceOutput,
+        SignatureLockedSingleOutput,
         TransactionId,
     },
 };
 use chronicle_broker::{
+    AnalyticData,
     AnalyticsData,
+    CreatedBy,
+    FullMessage,
+    MilestoneData,
+    MilestoneDataBuilder,
     SyncData,
 };
+#[cfg(feature = "otel")]
+use chronicle_common::telemetry::{
+    self,
+    opentelemetry::{
+        trace::Span,
+        KeyValue,
+    },
+};
 use chronicle_common::{
-    config::PartitionConfig,
+    config::{
+        ApiConfig,
+        Config,
+        KeyspaceConfig,
+        PartitionConfig,
+        ReadConsistency,
+        StorageConfig,
+        WriteConsistency,
+    },
+    jobs::{
+        ActiveJobs,
+        Jobs,
+        JOBS,
+    },
     metrics::{
         prometheus::{
             self,
@@ -33,6 +83,7 @@ use chronicle_common::{
         RESPONSE_TIME_COLLECTOR,
     },
     SyncRange,
+    Synckey,
 };
 use chronicle_storage::{
     access::{
@@ -40,9 +91,14 @@ use chronicle_storage::{
         OutputRes,
         PartitionId,
         Partitioned,
+        TagPrefix,
     },
     keyspaces::ChronicleKeyspace,
 };
+use futures::{
+    StreamExt,
+    TryStreamExt,
+};
 use hex::FromHex;
 use mpsc::unbounded_channel;
 use rocket::{
@@ -53,7 +109,12 @@ use rocket::{
     },
     get,
     http::ContentType,
+    request::{
+        self,
+        FromRequest,
+    },
     response::{
+        status,
         Content,
         Responder,
     },
@@ -63,6 +124,7 @@ use rocket::{
     State,
 };
 use rocket_contrib::json::Json;
+use scylla_rs::cql::ErrorCodes;
 use std::{
     borrow::Borrow,
     collections::{
@@ -70,14 +132,146 @@ use std::{
         HashSet,
         VecDeque,
     },
-    convert::TryInto,
+    convert::{
+        TryFrom,
+        TryInto,
+    },
+    fmt::Display,
+    future::Future,
     io::Cursor,
+    ops::Range,
     path::PathBuf,
     str::FromStr,
-    time::SystemTime,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    time::{
+        Duration,
+        SystemTime,
+        UNIX_EPOCH,
+    },
 };
 use tokio::sync::mpsc;
 
+/// The bech32 human readable parts of the configured keyspaces, keyed by keyspace name
+struct KeyspaceHrps(HashMap<String, String>);
+
+impl KeyspaceHrps {
+    /// Collect the keyspaces human readable parts, the keyspaces without one use the api one (which defaults to the
+    /// first keyspace one)
+    fn new(keyspaces: &[KeyspaceConfig], api_config: &ApiConfig) -> Self {
+        let api_hrp = api_config.effective_bech32_hrp(keyspaces.first().and_then(|k| k.bech32_hrp.as_ref()));
+        Self(
+            keyspaces
+                .iter()
+                .map(|k| (k.name.clone(), k.bech32_hrp.clone().unwrap_or_else(|| api_hrp.clone())))
+                .collect(),
+        )
+    }
+}
+
+/// The storage query settings of the listener, which are managed by rocket (so a relaunch picks up the reconfigured
+/// ones)
+pub(crate) struct QuerySettings {
+    /// The storage query timeout in milliseconds, zero disables it
+    timeout_ms: u64,
+    /// The storage read consistency
+    read_consistency: ReadConsistency,
+    /// The limiter of the in-flight storage queries
+    limiter: QueryLimiter,
+}
+
+impl QuerySettings {
+    /// Get the query settings the api is configured with
+    fn new(api_config: &ApiConfig) -> Self {
+        Self {
+            timeout_ms: api_config.query_timeout_ms,
+            read_consistency: api_config.read_consistency,
+            limiter: QueryLimiter::new(api_config.max_in_flight_queries, api_config.query_queue_timeout_ms),
+        }
+    }
+    /// Get the configured storage read consistency
+    fn consistency(&self) -> Consistency {
+        match self.read_consistency {
+            ReadConsistency::One => Consistency::One,
+            ReadConsistency::LocalQuorum => Consistency::LocalQuorum,
+            ReadConsistency::Quorum => Consistency::Quorum,
+        }
+    }
+    /// Set the configured timeout on the keyspace, so the queried node aborts the timed out select requests
+    fn keyspace(&self, keyspace: ChronicleKeyspace) -> ChronicleKeyspace {
+        keyspace.with_query_timeout(Some(self.timeout_ms))
+    }
+    /// Await the storage reads while holding an in-flight query permit, for the reads which aren't sent through
+    /// `query`
+    async fn limited<T, E, F>(&self, reads: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: From<ListenerError>,
+    {
+        let _permit = self.limiter.acquire().await?;
+        reads.await
+    }
+}
+
+impl Default for QuerySettings {
+    fn default() -> Self {
+        Self::new(&ApiConfig::default())
+    }
+}
+
+/// The storage settings of the backfilled analytics records, which are written like the broker writes them
+pub(crate) struct AnalyticsStoreSettings {
+    /// The storage config, which holds the row TTL of each keyspace
+    storage_config: StorageConfig,
+    /// The storage write consistency of the broker
+    write_consistency: WriteConsistency,
+    /// The retries of a failed write
+    retries_per_query: usize,
+}
+
+impl AnalyticsStoreSettings {
+    /// Get the analytics store settings chronicle is configured with
+    fn new(config: &Config) -> Self {
+        Self {
+            storage_config: config.storage_config.clone(),
+            write_consistency: config.broker_config.sync_write_consistency,
+            retries_per_query: config.broker_config.retries_per_query,
+        }
+    }
+    /// Get the keyspace the records are written to, which applies its configured row TTL
+    fn keyspace(&self, keyspace: &str) -> ChronicleKeyspace {
+        ChronicleKeyspace::configured(keyspace.to_owned(), &self.storage_config)
+    }
+    /// Get the configured storage write consistency
+    fn consistency(&self) -> Consistency {
+        match self.write_consistency {
+            WriteConsistency::One => Consistency::One,
+            WriteConsistency::LocalQuorum => Consistency::LocalQuorum,
+            WriteConsistency::Quorum => Consistency::Quorum,
+        }
+    }
+}
+
+impl RocketListener {
+    /// Build the rocket config of the api listener, which serves on the configured address (over tls if configured)
+    /// with the configured keep-alive. The requests are served by the chronicle runtime, so the rocket workers are
+    /// left unset
+    pub fn figment(api_config: &ApiConfig) -> rocket::figment::Figment {
+        let mut figment = rocket::Config::figment()
+            .merge(("address", api_config.listen_address.ip()))
+            .merge(("port", api_config.listen_address.port()))
+            .merge(("keep_alive", api_config.keep_alive_secs));
+        if let Some(tls) = api_config.tls.as_ref() {
+            figment = figment
+                .merge(("tls.certs", &tls.cert_path))
+                .merge(("tls.key", &tls.key_path));
+        }
+        figment
+    }
+}
+
 #[async_trait]
 impl<H: ChronicleAPIScope> EventLoop<ChronicleAPISender<H>> for Listener<RocketListener> {
     async fn event_loop(
@@ -94,7 +288,9 @@ impl<H: ChronicleAPIScope> EventLoop<ChronicleAPISender<H>> for Listener<RocketL
                 .map_err(|_| Need::Abort)?;
         }
 
-        let storage_config = get_config_async().await.storage_config;
+        let config = get_config_async().await;
+        let analytics_store_settings = AnalyticsStoreSettings::new(&config);
+        let storage_config = config.storage_config;
 
         let keyspaces = storage_config
             .keyspaces
@@ -102,6 +298,13 @@ impl<H: ChronicleAPIScope> EventLoop<ChronicleAPISender<H>> for Listener<RocketL
             .cloned()
             .map(|k| k.name)
             .collect::<HashSet<_>>();
+        let keyspace_hrps = KeyspaceHrps::new(&storage_config.keyspaces, &config.api_config);
+        let query_settings = QuerySettings::new(&config.api_config);
+        info!(
+            "Serving the api on {}://{}",
+            config.api_config.scheme(),
+            config.api_config.listen_address
+        );
 
         construct_rocket(
             self.data
@@ -109,7 +312,16 @@ impl<H: ChronicleAPIScope> EventLoop<ChronicleAPISender<H>> for Listener<RocketL
                 .take()
                 .ok_or_else(|| Need::Abort)?
                 .manage(storage_config.partition_config.clone())
+                .manage(MessageCache::new(config.api_config.message_cache_capacity))
+                .manage(MissingOutputCache::new(config.api_config.missing_output_ttl_ms))
+                .manage(LedgerHintsCache::new())
+                .manage(AnalyticsBackfill::new())
+                .manage(analytics_store_settings)
+                .manage(JOBS.clone())
+                .manage(query_settings)
+                .manage(config.api_config)
                 .manage(keyspaces)
+                .manage(keyspace_hrps)
                 .register(catchers![internal_error, not_found]),
         )
         .launch()
@@ -119,7 +331,7 @@ impl<H: ChronicleAPIScope> EventLoop<ChronicleAPISender<H>> for Listener<RocketL
 }
 
 fn construct_rocket(rocket: Rocket) -> Rocket {
-    rocket
+    let rocket = rocket
         .mount(
             "/api",
             routes![
@@ -127,20 +339,39 @@ fn construct_rocket(rocket: Rocket) -> Rocket {
                 info,
                 metrics,
                 service,
+                jobs,
                 sync,
                 get_message,
+                get_messages,
                 get_message_metadata,
+                get_message_raw,
+                get_message_milestone,
+                get_message_inclusion,
+                get_message_parents,
                 get_message_children,
                 get_message_by_index,
+                get_tags,
                 get_output,
+                get_output_spending,
+                get_output_history,
                 get_ed25519_outputs,
+                get_ed25519_spent_outputs,
+                get_bech32_outputs,
                 get_transaction_included_message,
                 get_milestone,
-                get_analytics
+                get_milestone_diff,
+                get_analytics,
+                get_analytics_series,
+                openapi
             ],
         )
+        .mount("/", routes![healthz, readyz]);
+    let openapi = OpenApi::new(rocket.routes());
+    rocket
+        .manage(openapi)
         .attach(CORS)
         .attach(RequestTimer)
+        .attach(RequestTracer)
 }
 
 struct CORS;
@@ -156,7 +387,7 @@ impl Fairing for CORS {
 
     async fn on_response<'r>(&self, _request: &'r Request<'_>, response: &mut Response<'r>) {
         response.set_raw_header("Access-Control-Allow-Origin", "*");
-        response.set_raw_header("Access-Control-Allow-Methods", "GET, OPTIONS");
+        response.set_raw_header("Access-Control-Allow-Methods", "GET, POST, OPTIONS");
         response.set_raw_header("Access-Control-Allow-Headers", "*");
         response.set_raw_header("Access-Control-Allow-Credentials", "true");
     }
@@ -182,68 +413,374 @@ impl Fairing for RequestTimer {
         // to ensure that this usage doesn't conflict with anything else
         // that might store a `SystemTime` in request-local cache.
         request.local_cache(|| TimerStart(Some(SystemTime::now())));
-        INCOMING_REQUESTS.inc();
+        let path = request.uri().path().to_string();
+        INCOMING_REQUESTS
+            .with_label_values(&[metric_keyspace(&path, request.managed_state())])
+            .inc();
     }
 
     /// Adds a header to the response indicating how long the server took to
     /// process the request.
     async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
         let start_time = req.local_cache(|| TimerStart(None));
+        let path = req.uri().path().to_string();
+        let keyspace = metric_keyspace(&path, req.managed_state());
         if let Some(Ok(duration)) = start_time.0.map(|st| st.elapsed()) {
             let ms = (duration.as_secs() * 1000 + duration.subsec_millis() as u64) as f64;
             RESPONSE_TIME_COLLECTOR
-                .with_label_values(&[&format!("{} {}", req.method(), req.uri())])
+                .with_label_values(&[keyspace, &format!("{} {}", req.method(), req.uri())])
                 .observe(ms)
         }
         match res.status().code {
             500..=599 => RESPONSE_CODE_COLLECTOR
-                .with_label_values(&[&res.status().code.to_string(), "500"])
+                .with_label_values(&[keyspace, &res.status().code.to_string(), "500"])
                 .inc(),
             400..=499 => RESPONSE_CODE_COLLECTOR
-                .with_label_values(&[&res.status().code.to_string(), "400"])
+                .with_label_values(&[keyspace, &res.status().code.to_string(), "400"])
                 .inc(),
             300..=399 => RESPONSE_CODE_COLLECTOR
-                .with_label_values(&[&res.status().code.to_string(), "300"])
+                .with_label_values(&[keyspace, &res.status().code.to_string(), "300"])
                 .inc(),
             200..=299 => RESPONSE_CODE_COLLECTOR
-                .with_label_values(&[&res.status().code.to_string(), "200"])
+                .with_label_values(&[keyspace, &res.status().code.to_string(), "200"])
                 .inc(),
             100..=199 => RESPONSE_CODE_COLLECTOR
-                .with_label_values(&[&res.status().code.to_string(), "100"])
+                .with_label_values(&[keyspace, &res.status().code.to_string(), "100"])
                 .inc(),
             _ => (),
         }
     }
 }
 
+/// The keyspace label of the request metrics, which is the keyspace segment of the `/api/<keyspace>/..` paths.
+/// Only the configured keyspaces are used as labels to bound the metrics cardinality, the other requests are
+/// counted under an empty label
+fn metric_keyspace<'a>(path: &'a str, keyspaces: Option<&HashSet<String>>) -> &'a str {
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    match (segments.next(), segments.next(), keyspaces) {
+        (Some("api"), Some(keyspace), Some(keyspaces)) if keyspaces.contains(keyspace) => keyspace,
+        _ => "",
+    }
+}
+
+/// The header which carries the request correlation id
+const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// The max number of points returned by the analytics series endpoint
+const MAX_SERIES_POINTS: usize = 500;
+
+/// The max number of concurrent bucket fetches of an analytics series
+const SERIES_CONCURRENCY: usize = 4;
+
+/// The max number of message ids of a batch messages lookup
+const MAX_BATCH_MESSAGES: usize = 100;
+
+/// The max number of concurrent message lookups of a batch messages lookup
+const BATCH_MESSAGES_CONCURRENCY: usize = 8;
+
+/// The max number of concurrent message lookups of an index messages page filtered by payload type
+const PAYLOAD_TYPE_CONCURRENCY: usize = 8;
+
+/// The max number of concurrent output lookups of a spent outputs page
+const SPENT_OUTPUTS_CONCURRENCY: usize = 8;
+
+/// The max number of address pages scanned for a spent outputs page, the returned state resumes the scan
+const MAX_SPENT_OUTPUTS_SCANNED_PAGES: usize = 10;
+
+/// The correlation id of a request, which is either provided by the client through the `X-Request-Id` header
+/// or generated, and used to correlate the request logs with its storage calls
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(String);
+
+impl RequestId {
+    /// Generate a new (process wide unique) request id
+    fn generate() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self(format!(
+            "{:016x}{:08x}",
+            nanos,
+            COUNTER.fetch_add(1, Ordering::Relaxed) as u32
+        ))
+    }
+
+    /// Accept a client provided request id, as long as it's a reasonably sized printable ascii string
+    fn from_header(value: &str) -> Option<Self> {
+        let value = value.trim();
+        (!value.is_empty() && value.len() <= 128 && value.chars().all(|c| c.is_ascii_graphic()))
+            .then(|| Self(value.to_owned()))
+    }
+}
+
+impl Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for RequestId {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        request::Outcome::Success(request.local_cache(RequestId::generate).clone())
+    }
+}
+
+/// The `Range` header of the request, if provided
+pub struct RangeHeader(Option<String>);
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for RangeHeader {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        request::Outcome::Success(RangeHeader(request.headers().get_one("Range").map(str::to_owned)))
+    }
+}
+
+/// The `type` query parameter of the request, if provided.
+/// Note: it's read by a guard as `type` can't name a route parameter
+pub struct TypeQuery(Option<String>);
+
+#[rocket::async_trait]
+impl<'a, 'r> FromRequest<'a, 'r> for TypeQuery {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        request::Outcome::Success(TypeQuery(
+            request.get_query_value::<String>("type").and_then(Result::ok),
+        ))
+    }
+}
+
+/// Binary content, either in full or the requested byte range of it
+#[derive(Debug, PartialEq, Eq)]
+enum RawBytes {
+    Full(Vec<u8>),
+    Partial {
+        bytes: Vec<u8>,
+        range: Range<usize>,
+        len: usize,
+    },
+}
+
+impl<'r> Responder<'r, 'static> for RawBytes {
+    fn respond_to(self, _: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let mut response = Response::build();
+        response
+            .header(ContentType::Binary)
+            .raw_header("Accept-Ranges", "bytes");
+        match self {
+            RawBytes::Full(bytes) => {
+                response.sized_body(bytes.len(), Cursor::new(bytes));
+            }
+            RawBytes::Partial { bytes, range, len } => {
+                response
+                    .status(Status::PartialContent)
+                    .raw_header(
+                        "Content-Range",
+                        format!("bytes {}-{}/{}", range.start, range.end - 1, len),
+                    )
+                    .sized_body(bytes.len(), Cursor::new(bytes));
+            }
+        }
+        response.ok()
+    }
+}
+
+/// Slice the bytes to the single byte range of the `Range` header (ie `bytes=0-99`, `bytes=100-` or the suffix
+/// `bytes=-100`). As the header is optional, a missing, malformed or multi range header gets the full bytes.
+fn ranged_bytes(bytes: Vec<u8>, range: Option<&str>) -> Result<RawBytes, ListenerError> {
+    let len = bytes.len();
+    let (start, end) = match range
+        .and_then(|range| range.trim().strip_prefix("bytes="))
+        .filter(|spec| !spec.contains(','))
+        .and_then(|spec| spec.split_once('-'))
+    {
+        Some((start, end)) => (start.trim(), end.trim()),
+        None => return Ok(RawBytes::Full(bytes)),
+    };
+    let range = if start.is_empty() {
+        match end.parse::<usize>() {
+            Ok(suffix) if suffix > 0 => len.saturating_sub(suffix)..len,
+            Ok(_) => return Err(ListenerError::RangeNotSatisfiable(len)),
+            Err(_) => return Ok(RawBytes::Full(bytes)),
+        }
+    } else {
+        let start = match start.parse::<usize>() {
+            Ok(start) => start,
+            Err(_) => return Ok(RawBytes::Full(bytes)),
+        };
+        let end = if end.is_empty() {
+            len
+        } else {
+            match end.parse::<usize>() {
+                // the last byte position is inclusive
+                Ok(last) if last >= start => last.saturating_add(1).min(len),
+                _ => return Ok(RawBytes::Full(bytes)),
+            }
+        };
+        start..end
+    };
+    if range.start >= len {
+        return Err(ListenerError::RangeNotSatisfiable(len));
+    }
+    Ok(RawBytes::Partial {
+        bytes: bytes[range.clone()].to_vec(),
+        range,
+        len,
+    })
+}
+
+pub struct RequestTracer;
+
+#[rocket::async_trait]
+impl Fairing for RequestTracer {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request Tracer",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    /// Stores the request id (provided or generated) in request-local state.
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data) {
+        let request_id = request
+            .headers()
+            .get_one(REQUEST_ID_HEADER)
+            .and_then(RequestId::from_header)
+            .unwrap_or_else(RequestId::generate);
+        debug!("[{}] {} {}", request_id, request.method(), request.uri());
+        #[cfg(feature = "otel")]
+        {
+            let span = telemetry::start_span(
+                "api_request",
+                vec![
+                    KeyValue::new("http.method", request.method().as_str()),
+                    KeyValue::new("http.target", request.uri().to_string()),
+                    KeyValue::new("request_id", request_id.to_string()),
+                ],
+            );
+            request.local_cache(|| RequestSpan(std::sync::Mutex::new(Some(span))));
+        }
+        request.local_cache(|| request_id);
+    }
+
+    /// Echoes the request id in the response headers.
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let request_id = req.local_cache(RequestId::generate);
+        debug!("[{}] responded with {}", request_id, res.status());
+        #[cfg(feature = "otel")]
+        {
+            // Ending the request span, which will be exported by the installed OTLP exporter (if any)
+            let span = req
+                .local_cache(|| RequestSpan(Default::default()))
+                .0
+                .lock()
+                .unwrap()
+                .take();
+            if let Some(mut span) = span {
+                span.set_attribute(KeyValue::new("http.status_code", res.status().code as i64));
+                span.end();
+            }
+        }
+        res.set_raw_header(REQUEST_ID_HEADER, request_id.0.clone());
+    }
+}
+
+/// The OpenTelemetry span of a request, which is started on request and ended on response
+#[cfg(feature = "otel")]
+struct RequestSpan(std::sync::Mutex<Option<telemetry::opentelemetry::global::BoxedSpan>>);
+
 impl<'r> Responder<'r, 'static> for ListenerError {
-    fn respond_to(self, _req: &'r Request<'_>) -> rocket::response::Result<'static> {
-        let err = ErrorBody::from(self);
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let request_id = req.local_cache(RequestId::generate);
+        debug!("[{}] {}", request_id, self);
+        let header = match &self {
+            ListenerError::Overloaded => Some(("Retry-After", "1".to_string())),
+            ListenerError::RangeNotSatisfiable(len) => Some(("Content-Range", format!("bytes */{}", len))),
+            _ => None,
+        };
+        let mut err = ErrorBody::from(self);
+        err.request_id = Some(request_id.to_string());
         let string = serde_json::to_string(&err).map_err(|e| {
             error!("JSON failed to serialize: {:?}", e);
             Status::InternalServerError
         })?;
 
-        Response::build()
+        let mut response = Response::build();
+        response
             .sized_body(None, Cursor::new(string))
             .status(err.status)
-            .header(ContentType::JSON)
-            .ok()
+            .header(ContentType::JSON);
+        if let Some((name, value)) = header {
+            response.raw_header(name, value);
+        }
+        response.ok()
     }
 }
 
 impl<'r> Responder<'r, 'static> for ListenerResponse {
     fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
         let success = SuccessBody::from(self);
-        let string = serde_json::to_string(&success).map_err(|e| {
-            error!("JSON failed to serialize: {:?}", e);
-            Status::InternalServerError
-        })?;
+        let max_response_bytes = req
+            .managed_state::<ApiConfig>()
+            .map(|api_config| api_config.max_response_bytes)
+            .unwrap_or_default();
+        let string = match json_body(&success, max_response_bytes) {
+            Ok(string) => string,
+            Err(e @ ListenerError::ResponseTooLarge(_)) => return e.respond_to(req),
+            Err(e) => {
+                error!("JSON failed to serialize: {:?}", e);
+                return Err(Status::InternalServerError);
+            }
+        };
 
         Content(ContentType::JSON, string).respond_to(req)
     }
 }
 
+/// A buffer which refuses the writes beyond its max length (zero being unbounded)
+struct BoundedBuffer {
+    buffer: Vec<u8>,
+    max_len: usize,
+}
+
+impl std::io::Write for BoundedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.max_len > 0 && self.buffer.len() + buf.len() > self.max_len {
+            return Err(std::io::Error::new(std::io::ErrorKind::Other, "max length exceeded"));
+        }
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Serialize the response body, which fails as soon as it exceeds the max bytes, so an oversized response is never
+/// fully buffered
+fn json_body<T: Serialize>(body: &T, max_bytes: usize) -> Result<String, ListenerError> {
+    let mut buffer = BoundedBuffer {
+        buffer: Vec::new(),
+        max_len: max_bytes,
+    };
+    serde_json::to_writer(&mut buffer, body).map_err(|e| {
+        if e.is_io() {
+            ListenerError::ResponseTooLarge(max_bytes)
+        } else {
+            ListenerError::Other(e.into())
+        }
+    })?;
+    String::from_utf8(buffer.buffer).map_err(|e| ListenerError::Other(e.into()))
+}
+
 type ListenerResult = Result<ListenerResponse, ListenerError>;
 
 #[options("/<_path..>")]
@@ -289,35 +826,120 @@ async fn metrics() -> Result<String, ListenerError> {
     Ok(format!("{}{}", res_custom, res_default))
 }
 
+#[get("/healthz")]
+async fn healthz() -> Status {
+    if Health::current().await.live {
+        Status::Ok
+    } else {
+        Status::ServiceUnavailable
+    }
+}
+
+#[get("/readyz")]
+async fn readyz() -> Status {
+    if Health::current().await.ready {
+        Status::Ok
+    } else {
+        Status::ServiceUnavailable
+    }
+}
+
 #[get("/service")]
 async fn service() -> Json<Service> {
     Json(SERVICE.read().await.clone())
 }
 
+#[get("/jobs")]
+async fn jobs(jobs: State<'_, ActiveJobs>) -> Json<Jobs> {
+    Json(jobs.snapshot())
+}
+
+#[get("/openapi.json")]
+async fn openapi(openapi: State<'_, OpenApi>) -> Json<serde_json::Value> {
+    Json(openapi.0.clone())
+}
+
+/// Responds with the sync data of the keyspace, the sync data whose rows got truncated responds with `206`, as the
+/// milestones below its lowest processed row are unknown
 #[get("/<keyspace>/sync")]
-async fn sync(keyspaces: State<'_, HashSet<String>>, keyspace: String) -> Result<Json<SyncData>, ListenerError> {
+async fn sync(
+    keyspaces: State<'_, HashSet<String>>,
+    settings: State<'_, QuerySettings>,
+    keyspace: String,
+) -> Result<status::Custom<Json<SyncData>>, ListenerError> {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
     let keyspace = ChronicleKeyspace::new(keyspace);
-    SyncData::try_fetch(&keyspace, &SyncRange::default(), 3)
+    let sync_data = settings
+        .limited(SyncData::try_fetch_with_consistency(
+            &keyspace,
+            &SyncRange::default(),
+            3,
+            settings.consistency(),
+        ))
         .await
-        .map(|s| Json(s))
-        .map_err(|e| ListenerError::Other(e.into()))
+        .map_err(|e| e.downcast::<ListenerError>().unwrap_or_else(ListenerError::Other))?;
+    let status = if sync_data.partial {
+        Status::PartialContent
+    } else {
+        Status::Ok
+    };
+    Ok(status::Custom(status, Json(sync_data)))
+}
+
+/// Get the ledger hints of the keyspace, out of its sync data. The hints are best-effort, they're omitted if the sync
+/// data can't be fetched or is partial.
+async fn ledger_hints(
+    keyspace: &str,
+    ledger_hints: &LedgerHintsCache,
+    settings: &QuerySettings,
+) -> Option<LedgerHints> {
+    let hints = ledger_hints
+        .get(keyspace, || async move {
+            settings
+                .limited(SyncData::try_fetch_with_consistency(
+                    &ChronicleKeyspace::new(keyspace.to_owned()),
+                    &SyncRange::default(),
+                    3,
+                    settings.consistency(),
+                ))
+                .await
+        })
+        .await;
+    match hints {
+        Ok(hints) => {
+            if hints.is_none() {
+                debug!(
+                    "Omitting the ledger hints of keyspace {}, its sync data is partial",
+                    keyspace
+                );
+            }
+            hints
+        }
+        Err(e) => {
+            warn!("Unable to get the ledger hints of keyspace {}: {}", keyspace, e);
+            None
+        }
+    }
 }
 
-async fn query<V, S, K>(
-    keyspace: S,
+async fn query<V, K>(
+    request_id: &RequestId,
+    settings: &QuerySettings,
+    keyspace: ChronicleKeyspace,
     key: K,
     page_size: Option<i32>,
     paging_state: Option<Vec<u8>>,
 ) -> Result<V, ListenerError>
 where
-    S: 'static + Select<K, V>,
+    ChronicleKeyspace: Select<K, V>,
     K: 'static + Send + Clone,
     V: 'static + Send + Clone,
 {
-    let request = keyspace.select::<V>(&key).consistency(Consistency::One);
+    debug!("[{}] Querying {}", request_id, std::any::type_name::<V>());
+    let keyspace = settings.keyspace(keyspace);
+    let request = keyspace.select::<V>(&key).consistency(settings.consistency());
     let request = if let Some(page_size) = page_size {
         request.page_size(page_size).paging_state(&paging_state)
     } else {
@@ -329,32 +951,144 @@ where
     if let Some(page_size) = page_size {
         worker = worker.with_paging(page_size, paging_state);
     }
-    let worker = Box::new(worker);
 
-    request.send_local(worker);
+    // The permit is held by the worker until the query completes, even past the query timeout
+    let permit = settings.limiter.acquire().await.map_err(|e| {
+        debug!("[{}] Query rejected, too many queries in flight", request_id);
+        e
+    })?;
+    request.send_local(PermitWorker::boxed(Box::new(worker), permit));
 
-    while let Some(event) = inbox.recv().await {
-        match event {
-            Ok(res) => return res.ok_or_else(|| ListenerError::NoResults),
-            Err(worker_error) => return Err(ListenerError::Other(worker_error.into())),
+    let response = async move {
+        while let Some(event) = inbox.recv().await {
+            match event {
+                Ok(res) => return res.ok_or_else(|| ListenerError::NoResults),
+                Err(worker_error) if is_timeout(&worker_error) => {
+                    debug!("[{}] Query aborted by the node: {}", request_id, worker_error);
+                    return Err(ListenerError::Timeout);
+                }
+                Err(worker_error) => {
+                    debug!("[{}] Query failed: {}", request_id, worker_error);
+                    return Err(ListenerError::Other(worker_error.into()));
+                }
+            }
         }
-    }
+        Err(ListenerError::NoResponseError)
+    };
+    with_query_timeout(request_id, settings.timeout_ms, response).await
+}
+
+/// Whether the node aborted the query past its `USING TIMEOUT`
+fn is_timeout(worker_error: &WorkerError) -> bool {
+    matches!(worker_error, WorkerError::Cql(cql_error) if matches!(cql_error.code, ErrorCodes::ReadTimeout))
+}
 
-    Err(ListenerError::NoResponseError)
+/// Await the storage response within the timeout. The node aborts the query itself past the same timeout (see
+/// `QuerySettings::keyspace`), this only bounds the wait on a node which doesn't respond at all, in which case the
+/// response future, and with it the worker inbox, is dropped.
+async fn with_query_timeout<V, F>(request_id: &RequestId, timeout_ms: u64, response: F) -> Result<V, ListenerError>
+where
+    F: Future<Output = Result<V, ListenerError>>,
+{
+    if timeout_ms == 0 {
+        return response.await;
+    }
+    tokio::time::timeout(Duration::from_millis(timeout_ms), response)
+        .await
+        .unwrap_or_else(|_| {
+            debug!("[{}] Query timed out after {} ms", request_id, timeout_ms);
+            Err(ListenerError::Timeout)
+        })
 }
 
 async fn page<K, V>(
+    request_id: &RequestId,
+    settings: &QuerySettings,
+    keyspace: String,
+    hint: Hint,
+    page_size: usize,
+    state: &mut Option<StateData>,
+    partition_config: &PartitionConfig,
+    key: K,
+) -> Result<Vec<Partitioned<V>>, ListenerError>
+where
+    K: 'static + Send + Clone,
+    V: 'static + Send + Clone,
+    ChronicleKeyspace: Select<Partitioned<K>, Paged<VecDeque<Partitioned<V>>>>,
+{
+    page_filtered(
+        request_id,
+        settings,
+        keyspace,
+        hint,
+        page_size,
+        state,
+        partition_config,
+        key,
+        None,
+        |_| true,
+    )
+    .await
+}
+
+/// Page the records like `page`, keeping only the records the filter matches. The records are filtered as they're
+/// paged, so the page is filled with the matching records and the paging state resumes past the filtered ones.
+/// The page keeps every record of its last milestone past the page size, up to `max_results` if provided: the
+/// records of the milestone which would exceed it are left to the next page, unless they are the whole page.
+async fn page_filtered<K, V, F>(
+    request_id: &RequestId,
+    settings: &QuerySettings,
+    keyspace: String,
+    hint: Hint,
+    page_size: usize,
+    state: &mut Option<StateData>,
+    partition_config: &PartitionConfig,
+    key: K,
+    max_results: Option<usize>,
+    keep: F,
+) -> Result<Vec<Partitioned<V>>, ListenerError>
+where
+    K: 'static + Send + Clone,
+    V: 'static + Send + Clone,
+    ChronicleKeyspace: Select<Partitioned<K>, Paged<VecDeque<Partitioned<V>>>>,
+    F: Fn(&Partitioned<V>) -> bool,
+{
+    let keep = &keep;
+    page_filtered_with(
+        request_id,
+        settings,
+        keyspace,
+        hint,
+        page_size,
+        state,
+        partition_config,
+        key,
+        max_results,
+        |records: Vec<Partitioned<V>>| futures::future::ready(Ok(records.iter().map(keep).collect())),
+    )
+    .await
+}
+
+/// Page the records like `page_filtered`, where the filter decides which records are kept a fetched list of records
+/// at a time, ie to look up what the records refer to concurrently rather than one by one
+async fn page_filtered_with<K, V, F, KFut>(
+    request_id: &RequestId,
+    settings: &QuerySettings,
     keyspace: String,
     hint: Hint,
     page_size: usize,
     state: &mut Option<StateData>,
     partition_config: &PartitionConfig,
     key: K,
+    max_results: Option<usize>,
+    keep: F,
 ) -> Result<Vec<Partitioned<V>>, ListenerError>
 where
     K: 'static + Send + Clone,
     V: 'static + Send + Clone,
     ChronicleKeyspace: Select<Partitioned<K>, Paged<VecDeque<Partitioned<V>>>>,
+    F: Fn(Vec<Partitioned<V>>) -> KFut,
+    KFut: Future<Output = Result<Vec<bool>, ListenerError>>,
 {
     let total_start_time = std::time::Instant::now();
     let mut start_time = total_start_time;
@@ -376,8 +1110,15 @@ where
             (latest_milestone, state.partition_ids.clone())
         }
         None => {
-            let mut partition_ids =
-                query::<Vec<(MilestoneIndex, PartitionId)>, _, _>(keyspace.clone(), hint, None, None).await?;
+            let mut partition_ids = query::<Vec<(MilestoneIndex, PartitionId)>, _>(
+                request_id,
+                settings,
+                keyspace.clone(),
+                hint,
+                None,
+                None,
+            )
+            .await?;
             if partition_ids.is_empty() {
                 return Err(ListenerError::NoResults);
             }
@@ -413,18 +1154,22 @@ where
     let prev_paging_state = state.paging_state.take();
 
     debug!(
-        "Setup time: {} ms",
+        "[{}] Setup time: {} ms",
+        request_id,
         (std::time::Instant::now() - start_time).as_millis()
     );
     start_time = std::time::Instant::now();
 
     debug!(
-        "Reorder time: {} ms",
+        "[{}] Reorder time: {} ms",
+        request_id,
         (std::time::Instant::now() - start_time).as_millis()
     );
 
     // This will hold lists of results keyed by partition id
     let mut list_map = HashMap::new();
+    // Whether the records of the lists are kept, keyed by partition id
+    let mut kept_map: HashMap<PartitionId, VecDeque<bool>> = HashMap::new();
 
     // The number of queries we will dispatch at a time.
     // Two queries seems to cover most cases. In extreme circumstances we can fetch more as needed.
@@ -442,14 +1187,14 @@ where
         if !last_index_map.contains_key(partition_id) {
             last_index_map.insert(*partition_id, index.0);
         }
-        debug!("Gathering results from partition {}", partition_id);
+        debug!("[{}] Gathering results from partition {}", request_id, partition_id);
         // Make sure we stop iterating if all of our partitions are depleted.
         if depleted_partitions.len() == partition_ids.len() {
             break;
         }
         // Skip depleted partitions
         if depleted_partitions.contains(partition_id) {
-            debug!("Skipping partition");
+            debug!("[{}] Skipping partition", request_id);
             continue;
         }
 
@@ -460,12 +1205,15 @@ where
                 (partition_ind..partition_ind + fetch_size).filter_map(|ind| partition_ids.get(ind).map(|v| v.1));
             let res = futures::future::join_all(fetch_ids.clone().map(|partition_id| {
                 debug!(
-                    "Fetching results for partition id: {}, milestone: {}, with paging state: {:?}",
+                    "[{}] Fetching results for partition id: {}, milestone: {}, with paging state: {:?}",
+                    request_id,
                     partition_id,
                     latest_milestone,
                     prev_last_partition_id.map(|id| partition_id == id)
                 );
-                query::<Paged<VecDeque<Partitioned<V>>>, _, _>(
+                query::<Paged<VecDeque<Partitioned<V>>>, _>(
+                    request_id,
+                    settings,
                     keyspace.clone(),
                     Partitioned::new(key.clone(), partition_id, latest_milestone),
                     Some(page_size as i32),
@@ -480,16 +1228,20 @@ where
             }))
             .await;
             debug!(
-                "Fetch time: {} ms",
+                "[{}] Fetch time: {} ms",
+                request_id,
                 (std::time::Instant::now() - start_time).as_millis()
             );
             for (partition_id, list) in fetch_ids.zip(res) {
-                list_map.insert(partition_id, list?);
+                let list = list?;
+                kept_map.insert(partition_id, keep(list.iter().cloned().collect()).await?.into());
+                list_map.insert(partition_id, list);
             }
         }
         let list = list_map
             .get_mut(&partition_id)
             .ok_or_else(|| anyhow!("Unexpected error retrieving list by partition!"))?;
+        let kept = kept_map.entry(*partition_id).or_default();
 
         // Iterate the list, pulling records from the front until we hit
         // a milestone in the next chunk or run out
@@ -506,26 +1258,39 @@ where
                         // because we won't be able to recover lost records
                         // with a paging state
                         if last_index_map[partition_id] == list[0].milestone_index() {
-                            // debug!("Adding extra records past page_size");
-                            results.push(list.pop_front().unwrap());
+                            if max_results.map_or(false, |max_results| results.len() >= max_results)
+                                && defer_milestone(&mut results, list[0].milestone_index())
+                            {
+                                debug!("[{}] Deferring a milestone past max_results", request_id);
+                                state.last_partition_id = Some(*partition_id);
+                                state.last_milestone_index = Some(list[0].milestone_index());
+                                return Ok(results);
+                            }
+                            debug!("[{}] Adding extra records past page_size", request_id);
+                            let partitioned_value = list.pop_front().unwrap();
+                            if kept.pop_front().unwrap_or(false) {
+                                results.push(partitioned_value);
+                            }
                             *loop_timings.entry("Adding additional").or_insert(0) +=
                                 (std::time::Instant::now() - loop_start_time).as_nanos();
                         // Otherwise we can stop here and set our cookies
                         } else {
-                            debug!("Finished a milestone");
+                            debug!("[{}] Finished a milestone", request_id);
                             state.last_partition_id = Some(*partition_id);
                             state.last_milestone_index = Some(list[0].milestone_index());
                             *loop_timings.entry("Finish Adding Additional").or_insert(0) +=
                                 (std::time::Instant::now() - loop_start_time).as_nanos();
                             debug!(
-                                "{:#?}",
+                                "[{}] {:#?}",
+                                request_id,
                                 loop_timings
                                     .iter()
                                     .map(|(k, v)| (k, format!("{} ms", *v as f32 / 1000000.0)))
                                     .collect::<HashMap<_, _>>()
                             );
                             debug!(
-                                "Total time: {} ms",
+                                "[{}] Total time: {} ms",
+                                request_id,
                                 (std::time::Instant::now() - total_start_time).as_millis()
                             );
                             return Ok(results);
@@ -533,15 +1298,17 @@ where
                     // Otherwise, business as usual
                     } else {
                         let partitioned_value = list.pop_front().unwrap();
-                        // debug!("Adding result normally");
+                        debug!("[{}] Adding result normally", request_id);
                         last_index_map.insert(*partition_id, partitioned_value.milestone_index());
-                        results.push(partitioned_value);
+                        if kept.pop_front().unwrap_or(false) {
+                            results.push(partitioned_value);
+                        }
                         *loop_timings.entry("Adding normally").or_insert(0) +=
                             (std::time::Instant::now() - loop_start_time).as_nanos();
                     }
                 // We hit a new chunk, so we want to look at the next partition now
                 } else {
-                    debug!("Hit a chunk boundary");
+                    debug!("[{}] Hit a chunk boundary", request_id);
                     last_index_map.insert(*partition_id, list[0].milestone_index());
                     *loop_timings.entry("Chunk Boundary").or_insert(0) +=
                         (std::time::Instant::now() - loop_start_time).as_nanos();
@@ -551,43 +1318,51 @@ where
             // So we will get the next page_size records by re-running the same query with the paging state
             // or just give it to the client if we already have enough records.
             } else {
-                debug!("Results list is empty");
+                debug!("[{}] Results list is empty", request_id);
                 if results.len() >= page_size {
-                    debug!("...but we already have enough results so returning the paging state");
+                    debug!(
+                        "[{}] ...but we already have enough results so returning the paging state",
+                        request_id
+                    );
                     state.paging_state = list.paging_state.take();
                     state.last_partition_id = Some(*partition_id);
                     state.last_milestone_index = Some(latest_milestone);
                     *loop_timings.entry("Returning page_state").or_insert(0) +=
                         (std::time::Instant::now() - loop_start_time).as_nanos();
                     debug!(
-                        "{:#?}",
+                        "[{}] {:#?}",
+                        request_id,
                         loop_timings
                             .iter()
                             .map(|(k, v)| (k, format!("{} ms", *v as f32 / 1000000.0)))
                             .collect::<HashMap<_, _>>()
                     );
                     debug!(
-                        "Total time: {} ms",
+                        "[{}] Total time: {} ms",
+                        request_id,
                         (std::time::Instant::now() - total_start_time).as_millis()
                     );
                     return Ok(results);
                 } else {
-                    debug!("...and we need more results");
+                    debug!("[{}] ...and we need more results", request_id);
                     if list.paging_state.is_some() {
-                        debug!("......so we're querying for them");
-                        *list = query::<Paged<VecDeque<Partitioned<V>>>, _, _>(
+                        debug!("[{}] ......so we're querying for them", request_id);
+                        *list = query::<Paged<VecDeque<Partitioned<V>>>, _>(
+                            request_id,
+                            settings,
                             keyspace.clone(),
                             Partitioned::new(key.clone(), *partition_id, latest_milestone),
                             Some((page_size - results.len()) as i32),
                             list.paging_state.clone(),
                         )
                         .await?;
+                        *kept = keep(list.iter().cloned().collect()).await?.into();
                         *loop_timings.entry("Requery").or_insert(0) +=
                             (std::time::Instant::now() - loop_start_time).as_nanos();
                     // Unless it didn't have one, in which case we mark it as a depleted partition and
                     // move on to the next one.
                     } else {
-                        debug!("......but there's no paging state");
+                        debug!("[{}] ......but there's no paging state", request_id);
                         depleted_partitions.insert(*partition_id);
                         *loop_timings.entry("Depleted partition").or_insert(0) +=
                             (std::time::Instant::now() - loop_start_time).as_nanos();
@@ -599,7 +1374,8 @@ where
     }
 
     debug!(
-        "{:#?}",
+        "[{}] {:#?}",
+        request_id,
         loop_timings
             .iter()
             .map(|(k, v)| (k, format!("{} ms", *v as f32 / 1000000.0)))
@@ -607,58 +1383,462 @@ where
     );
 
     debug!(
-        "Total time: {} ms",
+        "[{}] Total time: {} ms",
+        request_id,
         (std::time::Instant::now() - total_start_time).as_millis()
     );
 
     Ok(results)
 }
 
+/// Lookup a message through the message cache
+async fn fetch_message(
+    keyspace: &str,
+    message_id: MessageId,
+    message_cache: &MessageCache,
+    request_id: &RequestId,
+    settings: &QuerySettings,
+) -> Result<Message, ListenerError> {
+    message_cache
+        .get_or_fetch(keyspace, message_id, || {
+            query::<Message, _>(
+                request_id,
+                settings,
+                ChronicleKeyspace::new(keyspace.to_owned()),
+                message_id,
+                None,
+                None,
+            )
+        })
+        .await
+}
+
 #[get("/<keyspace>/messages/<message_id>")]
-async fn get_message(keyspace: String, message_id: String, keyspaces: State<'_, HashSet<String>>) -> ListenerResult {
+async fn get_message(
+    keyspace: String,
+    message_id: String,
+    keyspaces: State<'_, HashSet<String>>,
+    message_cache: State<'_, MessageCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
-    let keyspace = ChronicleKeyspace::new(keyspace);
     let message_id = MessageId::from_str(&message_id).map_err(|e| ListenerError::BadParse(e.into()))?;
-    query::<Message, _, _>(keyspace, message_id, None, None)
+    fetch_message(&keyspace, message_id, &message_cache, &request_id, &settings)
         .await
         .and_then(|message| message.try_into().map_err(|e: Cow<'static, str>| anyhow!(e).into()))
 }
 
+#[post("/<keyspace>/messages", data = "<message_ids>")]
+async fn get_messages(
+    keyspace: String,
+    message_ids: Json<Vec<String>>,
+    keyspaces: State<'_, HashSet<String>>,
+    message_cache: State<'_, MessageCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+    batch_messages(message_ids.into_inner(), |message_id| {
+        fetch_message(&keyspace, message_id, &message_cache, &request_id, &settings)
+    })
+    .await
+}
+
+/// Lookup the messages of the provided ids, at most `BATCH_MESSAGES_CONCURRENCY` at once.
+/// The missing messages are mapped to `None`, while any other lookup error fails the whole batch
+async fn batch_messages<F, Fut>(message_ids: Vec<String>, fetch: F) -> ListenerResult
+where
+    F: Fn(MessageId) -> Fut,
+    Fut: Future<Output = Result<Message, ListenerError>>,
+{
+    if message_ids.len() > MAX_BATCH_MESSAGES {
+        return Err(ListenerError::BadParse(anyhow!(
+            "Too many message ids ({}), the max is {}",
+            message_ids.len(),
+            MAX_BATCH_MESSAGES
+        )));
+    }
+    let message_ids = message_ids
+        .iter()
+        .map(|message_id| MessageId::from_str(message_id).map_err(|e| ListenerError::BadParse(e.into())))
+        .collect::<Result<HashSet<_>, _>>()?;
+    let messages = futures::stream::iter(message_ids.into_iter().map(|message_id| {
+        let message = fetch(message_id);
+        async move { (message_id, message.await) }
+    }))
+    .buffer_unordered(BATCH_MESSAGES_CONCURRENCY)
+    .map(|(message_id, message)| {
+        let message = match message {
+            Ok(message) => Some(message.try_into().map_err(|e: Cow<'static, str>| anyhow!(e))?),
+            Err(ListenerError::NoResults) => None,
+            Err(e) => return Err(e),
+        };
+        Ok((message_id.to_string(), message))
+    })
+    .try_collect()
+    .await?;
+    Ok(ListenerResponse::Messages { messages })
+}
+
 #[get("/<keyspace>/messages/<message_id>/metadata")]
 async fn get_message_metadata(
     keyspace: String,
     message_id: String,
     keyspaces: State<'_, HashSet<String>>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
 ) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
     let keyspace = ChronicleKeyspace::new(keyspace);
     let message_id = MessageId::from_str(&message_id).map_err(|e| ListenerError::BadParse(e.into()))?;
-    query::<MessageMetadata, _, _>(keyspace, message_id, None, None)
+    query::<MessageMetadata, _>(&request_id, &settings, keyspace, message_id, None, None)
         .await
         .map(|metadata| metadata.into())
 }
 
-#[get("/<keyspace>/messages/<message_id>/children?<page_size>&<expanded>&<state>")]
-async fn get_message_children(
+#[get("/<keyspace>/messages/<message_id>/raw")]
+async fn get_message_raw(
     keyspace: String,
     message_id: String,
-    page_size: Option<usize>,
-    expanded: Option<bool>,
-    state: Option<String>,
-    partition_config: State<'_, PartitionConfig>,
     keyspaces: State<'_, HashSet<String>>,
-) -> ListenerResult {
+    message_cache: State<'_, MessageCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+    range: RangeHeader,
+) -> Result<RawBytes, ListenerError> {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
     let message_id = MessageId::from_str(&message_id).map_err(|e| ListenerError::BadParse(e.into()))?;
-    let page_size = page_size.unwrap_or(100);
+    fetch_message(&keyspace, message_id, &message_cache, &request_id, &settings)
+        .await
+        .and_then(|message| {
+            let Content(_, bytes) = raw_message(&message);
+            ranged_bytes(bytes, range.0.as_deref())
+        })
+}
 
-    let mut state = state
+/// The packed bytes of a message, which hash to its message id
+fn raw_message(message: &Message) -> Content<Vec<u8>> {
+    Content(ContentType::Binary, message.pack_new())
+}
+
+#[get("/<keyspace>/messages/<message_id>/milestone")]
+async fn get_message_milestone(
+    keyspace: String,
+    message_id: String,
+    keyspaces: State<'_, HashSet<String>>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+    let keyspace = ChronicleKeyspace::new(keyspace);
+    let message_id = MessageId::from_str(&message_id).map_err(|e| ListenerError::BadParse(e.into()))?;
+
+    let metadata =
+        query::<MessageMetadata, _>(&request_id, &settings, keyspace.clone(), message_id, None, None).await?;
+    confirming_milestone(&metadata, |milestone_index| {
+        query::<Milestone, _>(&request_id, &settings, keyspace.clone(), milestone_index, None, None)
+    })
+    .await
+    .map(|(milestone_index, milestone)| ListenerResponse::MessageMilestone {
+        message_id: message_id.to_string(),
+        milestone_index: milestone_index.0,
+        milestone_id: milestone.message_id().to_string(),
+    })
+}
+
+/// Get the milestone which confirmed the message, if any
+async fn confirming_milestone<F, Fut>(
+    metadata: &MessageMetadata,
+    fetch_milestone: F,
+) -> Result<(MilestoneIndex, Milestone), ListenerError>
+where
+    F: FnOnce(MilestoneIndex) -> Fut,
+    Fut: Future<Output = Result<Milestone, ListenerError>>,
+{
+    let milestone_index = MilestoneIndex(metadata.referenced_by_milestone_index.ok_or(ListenerError::NoResults)?);
+    fetch_milestone(milestone_index)
+        .await
+        .map(|milestone| (milestone_index, milestone))
+}
+
+/// The max number of messages walked to rebuild the milestone data out of the past cone of a milestone
+const MAX_MILESTONE_CONE: usize = 10000;
+
+/// The max number of concurrent lookups of a layer of the past cone of a milestone
+const MILESTONE_CONE_CONCURRENCY: usize = 16;
+
+#[get("/<keyspace>/messages/<message_id>/inclusion")]
+async fn get_message_inclusion(
+    keyspace: String,
+    message_id: String,
+    keyspaces: State<'_, HashSet<String>>,
+    message_cache: State<'_, MessageCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+    let chronicle_keyspace = ChronicleKeyspace::new(keyspace.clone());
+    let message_id = MessageId::from_str(&message_id).map_err(|e| ListenerError::BadParse(e.into()))?;
+
+    let metadata = query::<MessageMetadata, _>(
+        &request_id,
+        &settings,
+        chronicle_keyspace.clone(),
+        message_id,
+        None,
+        None,
+    )
+    .await?;
+    message_inclusion(
+        metadata,
+        |milestone_index| {
+            query::<Milestone, _>(
+                &request_id,
+                &settings,
+                chronicle_keyspace.clone(),
+                milestone_index,
+                None,
+                None,
+            )
+        },
+        |message_id| fetch_message(&keyspace, message_id, &message_cache, &request_id, &settings),
+        |message_id| {
+            query::<MessageMetadata, _>(
+                &request_id,
+                &settings,
+                chronicle_keyspace.clone(),
+                message_id,
+                None,
+                None,
+            )
+        },
+    )
+    .await
+}
+
+/// Get the inclusion state of the message and, if it's included, its merkle inclusion proof within the confirming
+/// milestone. The inclusion merkle tree is rebuilt out of the past cone of the milestone, in white flag order
+async fn message_inclusion<S, SFut, G, GFut, M, MFut>(
+    metadata: MessageMetadata,
+    fetch_milestone: S,
+    fetch_message: G,
+    fetch_metadata: M,
+) -> ListenerResult
+where
+    S: FnOnce(MilestoneIndex) -> SFut,
+    SFut: Future<Output = Result<Milestone, ListenerError>>,
+    G: Fn(MessageId) -> GFut,
+    GFut: Future<Output = Result<Message, ListenerError>>,
+    M: Fn(MessageId) -> MFut,
+    MFut: Future<Output = Result<MessageMetadata, ListenerError>>,
+{
+    let message_id = metadata.message_id;
+    let (milestone_index, ledger_inclusion_state) =
+        match (metadata.referenced_by_milestone_index, metadata.ledger_inclusion_state) {
+            (Some(milestone_index), Some(ledger_inclusion_state)) => (milestone_index, ledger_inclusion_state),
+            _ => {
+                return Ok(ListenerResponse::MessageInclusion {
+                    message_id: message_id.to_string(),
+                    state: InclusionState::Unconfirmed,
+                    milestone_index: None,
+                    milestone_id: None,
+                    proof: None,
+                })
+            }
+        };
+    let milestone = fetch_milestone(MilestoneIndex(milestone_index)).await?;
+    // Only the included messages are leaves of the inclusion merkle tree
+    let proof = if let LedgerInclusionState::Included = ledger_inclusion_state {
+        let milestone_data =
+            milestone_cone(milestone_index, *milestone.message_id(), fetch_message, fetch_metadata).await?;
+        let proof = milestone_data.inclusion_proof(&message_id).ok_or_else(|| {
+            anyhow!(
+                "Message {} is missing from the inclusion merkle tree of milestone {}",
+                message_id,
+                milestone_index
+            )
+        })?;
+        let merkle_proof = milestone_data
+            .milestone()
+            .ok_or_else(|| anyhow!("Missing milestone payload of milestone {}", milestone_index))?
+            .essence()
+            .merkle_proof();
+        // The rebuilt tree must hash up to the merkle proof the milestone was issued with
+        if !proof.verify(&message_id, merkle_proof) {
+            return Err(anyhow!(
+                "The inclusion proof of message {} does not match the merkle proof of milestone {}",
+                message_id,
+                milestone_index
+            )
+            .into());
+        }
+        Some(MerkleProof {
+            merkle_root: hex::encode(merkle_proof),
+            index: proof.index,
+            count: proof.count,
+            path: proof.path.iter().map(hex::encode).collect(),
+        })
+    } else {
+        None
+    };
+    Ok(ListenerResponse::MessageInclusion {
+        message_id: message_id.to_string(),
+        state: ledger_inclusion_state.into(),
+        milestone_index: Some(milestone_index),
+        milestone_id: Some(milestone.message_id().to_string()),
+        proof,
+    })
+}
+
+/// Rebuild the milestone data of the milestone out of its past cone, which holds the milestone message and the
+/// messages referenced by it, as collected by the solidifier. The cone is walked a layer of parents at a time, whose
+/// lookups run concurrently, and it fails if any message of the cone (or any parent at its border) is missing
+async fn milestone_cone<G, GFut, M, MFut>(
+    milestone_index: u32,
+    milestone_id: MessageId,
+    fetch_message: G,
+    fetch_metadata: M,
+) -> Result<MilestoneData, ListenerError>
+where
+    G: Fn(MessageId) -> GFut,
+    GFut: Future<Output = Result<Message, ListenerError>>,
+    M: Fn(MessageId) -> MFut,
+    MFut: Future<Output = Result<MessageMetadata, ListenerError>>,
+{
+    let mut milestone_data = MilestoneDataBuilder::new(milestone_index, CreatedBy::Syncer);
+    let mut visited = HashSet::new();
+    visited.insert(milestone_id);
+    let mut layer = vec![milestone_id];
+    while !layer.is_empty() {
+        let cone = futures::stream::iter(layer.into_iter().map(|message_id| {
+            let metadata = fetch_metadata(message_id);
+            async move {
+                match metadata.await {
+                    Ok(metadata) => Ok(metadata),
+                    Err(ListenerError::NoResults) if message_id == milestone_id => Err(ListenerError::NoResults),
+                    Err(ListenerError::NoResults) => Err(anyhow!(
+                        "The past cone of milestone {} is incomplete, message {} is missing",
+                        milestone_index,
+                        message_id
+                    )
+                    .into()),
+                    Err(e) => Err(e),
+                }
+            }
+        }))
+        .buffered(MILESTONE_CONE_CONCURRENCY)
+        .try_filter(|metadata| {
+            // Messages referenced by older milestones are out of the cone
+            futures::future::ready(metadata.referenced_by_milestone_index == Some(milestone_index))
+        })
+        .map_ok(|metadata| {
+            let message = fetch_message(metadata.message_id);
+            async move { Ok::<_, ListenerError>((message.await?, metadata)) }
+        })
+        .try_buffered(MILESTONE_CONE_CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await?;
+        layer = Vec::new();
+        for (message, metadata) in cone {
+            if metadata.message_id == milestone_id {
+                match message.payload() {
+                    Some(Payload::Milestone(milestone_payload)) => {
+                        milestone_data = milestone_data.with_milestone(milestone_payload.clone());
+                    }
+                    _ => return Err(anyhow!("Message {} is not a milestone", milestone_id).into()),
+                }
+            }
+            // The null message id is the genesis, which is no message
+            layer.extend(
+                message
+                    .parents()
+                    .iter()
+                    .filter(|parent| **parent != MessageId::new([0; 32]) && visited.insert(**parent))
+                    .copied(),
+            );
+            milestone_data = milestone_data.with_message(FullMessage::new(message, metadata));
+        }
+        if visited.len() > MAX_MILESTONE_CONE {
+            return Err(anyhow!(
+                "The past cone of milestone {} exceeds {} messages",
+                milestone_index,
+                MAX_MILESTONE_CONE
+            )
+            .into());
+        }
+    }
+    let milestone_data = milestone_data.build()?;
+    if !milestone_data.milestone_exist() {
+        return Err(ListenerError::NoResults);
+    }
+    Ok(milestone_data)
+}
+
+#[get("/<keyspace>/messages/<message_id>/parents")]
+async fn get_message_parents(
+    keyspace: String,
+    message_id: String,
+    keyspaces: State<'_, HashSet<String>>,
+    message_cache: State<'_, MessageCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+    let message_id = MessageId::from_str(&message_id).map_err(|e| ListenerError::BadParse(e.into()))?;
+    fetch_message(&keyspace, message_id, &message_cache, &request_id, &settings)
+        .await
+        .map(|message| message_parents(message_id, &message))
+}
+
+/// The parents of the message, as stored in its parents field
+fn message_parents(message_id: MessageId, message: &Message) -> ListenerResponse {
+    let parent_message_ids = message
+        .parents()
+        .iter()
+        .map(|parent| parent.to_string())
+        .collect::<Vec<_>>();
+    ListenerResponse::MessageParents {
+        message_id: message_id.to_string(),
+        count: parent_message_ids.len(),
+        parent_message_ids,
+    }
+}
+
+#[get("/<keyspace>/messages/<message_id>/children?<page_size>&<expanded>&<state>")]
+async fn get_message_children(
+    keyspace: String,
+    message_id: String,
+    page_size: Option<usize>,
+    expanded: Option<bool>,
+    state: Option<String>,
+    partition_config: State<'_, PartitionConfig>,
+    api_config: State<'_, ApiConfig>,
+    keyspaces: State<'_, HashSet<String>>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+    let message_id = MessageId::from_str(&message_id).map_err(|e| ListenerError::BadParse(e.into()))?;
+    let page_size = api_config.children_page_size(page_size);
+
+    let mut state = state
         .map(|state| {
             hex::decode(state)
                 .map_err(|_| ListenerError::InvalidState)
@@ -666,41 +1846,84 @@ async fn get_message_children(
         })
         .transpose()?;
 
-    let mut messages = page(
+    let max_children = Some(api_config.max_children).filter(|max_children| *max_children > 0);
+    let mut messages = page_filtered(
+        &request_id,
+        &settings,
         keyspace.clone(),
         Hint::parent(message_id.to_string()),
         page_size,
         &mut state,
         partition_config.borrow(),
         message_id,
+        max_children,
+        |_: &Partitioned<ParentRecord>| true,
     )
     .await?;
+    let has_more = cap_children(&mut messages, api_config.max_children);
 
+    let has_next = state.as_ref().map(StateData::has_next).unwrap_or(false);
     let state = state
         .map(|state| bincode::serialize(&state).map(|v| hex::encode(v)))
         .transpose()
         .map_err(|e| anyhow!(e))?;
+    let next = state.clone().filter(|_| has_next);
 
     if let Some(true) = expanded {
         Ok(ListenerResponse::MessageChildrenExpanded {
             message_id: message_id.to_string(),
             max_results: 2 * page_size,
+            page_size,
             count: messages.len(),
             children_message_ids: messages.drain(..).map(|record| record.into()).collect(),
+            has_more,
             state,
+            next,
         })
     } else {
         Ok(ListenerResponse::MessageChildren {
             message_id: message_id.to_string(),
             max_results: 2 * page_size,
+            page_size,
             count: messages.len(),
             children_message_ids: messages.drain(..).map(|record| record.message_id.to_string()).collect(),
+            has_more,
             state,
+            next,
         })
     }
 }
 
-#[get("/<keyspace>/messages?<index>&<page_size>&<utf8>&<expanded>&<state>")]
+/// Drop the records of the milestone from the tail of the page, so the next page starts with the whole milestone.
+/// Returns false, leaving the page as is, if the milestone is the whole page.
+fn defer_milestone<V>(results: &mut Vec<Partitioned<V>>, milestone_index: u32) -> bool {
+    match results
+        .iter()
+        .rposition(|record| record.milestone_index() != milestone_index)
+    {
+        Some(last_kept) => {
+            results.truncate(last_kept + 1);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Truncate the children to the max children. The page defers the milestones which would exceed it to the next
+/// page, so only the children of a single milestone with more approvers than the cap are truncated, and skipped by
+/// the next page. Returns whether any children were truncated.
+fn cap_children<T>(children: &mut Vec<T>, max_children: usize) -> bool {
+    if max_children > 0 && children.len() > max_children {
+        children.truncate(max_children);
+        true
+    } else {
+        false
+    }
+}
+
+#[get(
+    "/<keyspace>/messages?<index>&<page_size>&<utf8>&<expanded>&<state>&<payload_type>&<since_milestone>&<include_unconfirmed>"
+)]
 async fn get_message_by_index(
     keyspace: String,
     mut index: String,
@@ -708,12 +1931,20 @@ async fn get_message_by_index(
     utf8: Option<bool>,
     expanded: Option<bool>,
     state: Option<String>,
+    payload_type: Option<String>,
+    since_milestone: Option<u32>,
+    include_unconfirmed: Option<bool>,
     partition_config: State<'_, PartitionConfig>,
+    api_config: State<'_, ApiConfig>,
     keyspaces: State<'_, HashSet<String>>,
+    message_cache: State<'_, MessageCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
 ) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
+    let payload_type = payload_type.map(|p| p.parse::<PayloadType>()).transpose()?;
     if let Some(true) = utf8 {
         index = hex::encode(index);
     }
@@ -734,17 +1965,48 @@ async fn get_message_by_index(
         .transpose()?;
 
     let indexation = Indexation(index.clone());
-    let page_size = page_size.unwrap_or(1000);
+    let page_size = api_config.page_size(page_size);
 
-    let mut messages = page(
-        keyspace.clone(),
-        Hint::index(index.clone()),
-        page_size,
-        &mut state,
-        partition_config.borrow(),
-        indexation,
-    )
-    .await?;
+    let include_unconfirmed = include_unconfirmed.unwrap_or(true);
+    let keep =
+        |record: &Partitioned<IndexationRecord>| keeps_since_milestone(since_milestone, include_unconfirmed, record);
+    let mut messages = match payload_type {
+        Some(payload_type) => {
+            let (keyspace, message_cache, request_id, settings) = (&keyspace, &*message_cache, &request_id, &*settings);
+            page_filtered_with(
+                request_id,
+                settings,
+                keyspace.clone(),
+                Hint::index(index.clone()),
+                page_size,
+                &mut state,
+                partition_config.borrow(),
+                indexation,
+                None,
+                |records| {
+                    keeps_payload_type(records, payload_type, &keep, move |message_id| {
+                        fetch_message(keyspace, message_id, message_cache, request_id, settings)
+                    })
+                },
+            )
+            .await?
+        }
+        None => {
+            page_filtered(
+                &request_id,
+                &settings,
+                keyspace.clone(),
+                Hint::index(index.clone()),
+                page_size,
+                &mut state,
+                partition_config.borrow(),
+                indexation,
+                None,
+                keep,
+            )
+            .await?
+        }
+    };
 
     let state = state
         .map(|state| bincode::serialize(&state).map(|v| hex::encode(v)))
@@ -755,6 +2017,7 @@ async fn get_message_by_index(
         Ok(ListenerResponse::MessagesForIndexExpanded {
             index,
             max_results: 2 * page_size,
+            page_size,
             count: messages.len(),
             message_ids: messages.drain(..).map(|record| record.into()).collect(),
             state,
@@ -763,6 +2026,7 @@ async fn get_message_by_index(
         Ok(ListenerResponse::MessagesForIndex {
             index,
             max_results: 2 * page_size,
+            page_size,
             count: messages.len(),
             message_ids: messages.drain(..).map(|record| record.message_id.to_string()).collect(),
             state,
@@ -770,6 +2034,226 @@ async fn get_message_by_index(
     }
 }
 
+/// The payload variants the index messages can be filtered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PayloadType {
+    Transaction,
+    Indexation,
+    Milestone,
+}
+
+impl FromStr for PayloadType {
+    type Err = ListenerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "transaction" => Ok(PayloadType::Transaction),
+            "indexation" => Ok(PayloadType::Indexation),
+            "milestone" => Ok(PayloadType::Milestone),
+            _ => Err(ListenerError::BadParse(anyhow!(
+                "Invalid payload type: {}, expected transaction, indexation or milestone",
+                s
+            ))),
+        }
+    }
+}
+
+impl PayloadType {
+    fn matches(&self, payload: Option<&Payload>) -> bool {
+        matches!(
+            (self, payload),
+            (PayloadType::Transaction, Some(Payload::Transaction(_)))
+                | (PayloadType::Indexation, Some(Payload::Indexation(_)))
+                | (PayloadType::Milestone, Some(Payload::Milestone(_)))
+        )
+    }
+}
+
+/// Decide which of the index records are kept, ie those the filter keeps whose message payload is of the provided
+/// type. Only the messages of the records the filter keeps are looked up, `PAYLOAD_TYPE_CONCURRENCY` at once.
+async fn keeps_payload_type<K, F, Fut>(
+    records: Vec<Partitioned<IndexationRecord>>,
+    payload_type: PayloadType,
+    keep: K,
+    fetch_message: F,
+) -> Result<Vec<bool>, ListenerError>
+where
+    K: Fn(&Partitioned<IndexationRecord>) -> bool,
+    F: Fn(MessageId) -> Fut,
+    Fut: Future<Output = Result<Message, ListenerError>>,
+{
+    let (keep, fetch_message) = (&keep, &fetch_message);
+    futures::stream::iter(records.into_iter().map(|record| async move {
+        if !keep(&record) {
+            return Ok(false);
+        }
+        let message = fetch_message(record.message_id).await?;
+        Ok(payload_type.matches(message.payload().as_ref()))
+    }))
+    .buffered(PAYLOAD_TYPE_CONCURRENCY)
+    .try_collect()
+    .await
+}
+
+#[get("/<keyspace>/tags?<prefix>&<page_size>")]
+async fn get_tags(
+    keyspace: String,
+    prefix: String,
+    page_size: Option<usize>,
+    api_config: State<'_, ApiConfig>,
+    keyspaces: State<'_, HashSet<String>>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+    if !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ListenerError::InvalidHex);
+    }
+    // The tags are stored lowercase hex encoded, and can only be scanned within the bucket of their first byte
+    let prefix = TagPrefix::new(prefix.to_lowercase()).ok_or(ListenerError::TagPrefixTooShort)?;
+    let page_size = api_config.page_size(page_size);
+    let chronicle_keyspace = ChronicleKeyspace::new(keyspace);
+    let tags = prefixed_tags(
+        &prefix,
+        page_size,
+        |hint| {
+            query::<Vec<(MilestoneIndex, PartitionId)>, _>(
+                &request_id,
+                &settings,
+                chronicle_keyspace.clone(),
+                hint,
+                None,
+                None,
+            )
+        },
+        |prefix, page_size| {
+            query::<Vec<String>, _>(
+                &request_id,
+                &settings,
+                chronicle_keyspace.clone(),
+                prefix,
+                Some(page_size as i32),
+                None,
+            )
+        },
+    )
+    .await?;
+    Ok(ListenerResponse::Tags {
+        prefix: prefix.to_string(),
+        page_size,
+        count: tags.len(),
+        tags,
+    })
+}
+
+/// The max number of concurrent scans of the partitions of a tag bucket
+const TAG_PARTITIONS_CONCURRENCY: usize = 8;
+
+/// Fetch the first page of the distinct tags which start with the prefix, empty if none does. The prefix is scanned
+/// within each partition the hints of its bucket point to, and the first page of every partition is merged
+async fn prefixed_tags<H, HFut, F, Fut>(
+    prefix: &TagPrefix,
+    page_size: usize,
+    fetch_partitions: H,
+    fetch_tags: F,
+) -> Result<Vec<String>, ListenerError>
+where
+    H: FnOnce(Hint) -> HFut,
+    HFut: Future<Output = Result<Vec<(MilestoneIndex, PartitionId)>, ListenerError>>,
+    F: Fn(Partitioned<TagPrefix>, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<String>, ListenerError>>,
+{
+    let partitions = match fetch_partitions(Hint::tag(prefix.bucket().to_owned())).await {
+        Ok(partitions) => partitions,
+        Err(ListenerError::NoResults) => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let pages = futures::stream::iter(partitions.into_iter().map(|(milestone_index, partition_id)| {
+        let tags = fetch_tags(
+            Partitioned::new(prefix.clone(), partition_id, milestone_index.0),
+            page_size,
+        );
+        async move {
+            match tags.await {
+                Err(ListenerError::NoResults) => Ok(Vec::new()),
+                tags => tags,
+            }
+        }
+    }))
+    .buffer_unordered(TAG_PARTITIONS_CONCURRENCY)
+    .try_collect::<Vec<_>>()
+    .await?;
+    // the pages are sorted, and a tag shows up in every partition it's written to
+    Ok(pages
+        .into_iter()
+        .flatten()
+        .filter(|tag| prefix.matches(tag))
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .take(page_size)
+        .collect())
+}
+
+/// Whether the index record is confirmed by the since milestone or a later one, according to the inclusion state and
+/// the milestone index of the row. The unconfirmed records are kept only if included, every record is kept without a
+/// since milestone
+fn keeps_since_milestone(
+    since_milestone: Option<u32>,
+    include_unconfirmed: bool,
+    record: &Partitioned<IndexationRecord>,
+) -> bool {
+    match (since_milestone, record.ledger_inclusion_state.is_some()) {
+        (None, _) => true,
+        (Some(since_milestone), true) => record.milestone_index() >= since_milestone,
+        (Some(_), false) => include_unconfirmed,
+    }
+}
+
+/// The output types the address outputs can be filtered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputTypeFilter {
+    Single,
+    Dust,
+}
+
+impl FromStr for OutputTypeFilter {
+    type Err = ListenerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single" => Ok(OutputTypeFilter::Single),
+            "dust" => Ok(OutputTypeFilter::Dust),
+            _ => Err(ListenerError::BadParse(anyhow!(
+                "Invalid output type: {}, expected single or dust",
+                s
+            ))),
+        }
+    }
+}
+
+impl OutputTypeFilter {
+    fn matches(&self, output_type: u8) -> bool {
+        match self {
+            OutputTypeFilter::Single => output_type == SignatureLockedSingleOutput::KIND,
+            OutputTypeFilter::Dust => output_type == SignatureLockedDustAllowanceOutput::KIND,
+        }
+    }
+}
+
+impl TypeQuery {
+    /// Parse the output type the address outputs are filtered by, if any
+    fn output_type(self) -> Result<Option<OutputTypeFilter>, ListenerError> {
+        self.0.map(|t| t.parse::<OutputTypeFilter>()).transpose()
+    }
+}
+
+/// Whether the address record is of the filtered output type, every record is kept without a filter
+fn keeps_output_type(output_type: Option<OutputTypeFilter>, record: &AddressRecord) -> bool {
+    output_type.map_or(true, |output_type| output_type.matches(record.output_type))
+}
+
 #[get("/<keyspace>/addresses/ed25519/<address>/outputs?<page_size>&<expanded>&<state>")]
 async fn get_ed25519_outputs(
     keyspace: String,
@@ -777,12 +2261,89 @@ async fn get_ed25519_outputs(
     page_size: Option<usize>,
     expanded: Option<bool>,
     state: Option<String>,
+    output_type: TypeQuery,
+    partition_config: State<'_, PartitionConfig>,
+    api_config: State<'_, ApiConfig>,
+    keyspaces: State<'_, HashSet<String>>,
+    ledger_hints: State<'_, LedgerHintsCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+    let ed25519_address = Ed25519Address::from_str(&address).map_err(|e| ListenerError::BadParse(e.into()))?;
+    outputs_for_address(
+        &request_id,
+        &settings,
+        keyspace,
+        address,
+        ed25519_address,
+        api_config.page_size(page_size),
+        expanded,
+        state,
+        output_type.output_type()?,
+        partition_config.borrow(),
+        ledger_hints.borrow(),
+    )
+    .await
+}
+
+#[get("/<keyspace>/addresses/<address>/outputs?<page_size>&<expanded>&<state>")]
+async fn get_bech32_outputs(
+    keyspace: String,
+    address: String,
+    page_size: Option<usize>,
+    expanded: Option<bool>,
+    state: Option<String>,
+    output_type: TypeQuery,
+    partition_config: State<'_, PartitionConfig>,
+    api_config: State<'_, ApiConfig>,
+    keyspace_hrps: State<'_, KeyspaceHrps>,
+    ledger_hints: State<'_, LedgerHintsCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
+    let hrp = keyspace_hrps
+        .0
+        .get(&keyspace)
+        .ok_or_else(|| ListenerError::InvalidKeyspace(keyspace.clone()))?;
+    let ed25519_address = decode_bech32_address(&address, hrp)?;
+    let address = encode_bech32_address(&ed25519_address, hrp);
+    outputs_for_address(
+        &request_id,
+        &settings,
+        keyspace,
+        address,
+        ed25519_address,
+        api_config.page_size(page_size),
+        expanded,
+        state,
+        output_type.output_type()?,
+        partition_config.borrow(),
+        ledger_hints.borrow(),
+    )
+    .await
+}
+
+#[get("/<keyspace>/addresses/ed25519/<address>/spent-outputs?<page_size>&<state>")]
+async fn get_ed25519_spent_outputs(
+    keyspace: String,
+    address: String,
+    page_size: Option<usize>,
+    state: Option<String>,
     partition_config: State<'_, PartitionConfig>,
+    api_config: State<'_, ApiConfig>,
     keyspaces: State<'_, HashSet<String>>,
+    ledger_hints_cache: State<'_, LedgerHintsCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
 ) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
+    let ed25519_address = Ed25519Address::from_str(&address).map_err(|e| ListenerError::BadParse(e.into()))?;
+    let page_size = api_config.page_size(page_size);
     let mut state = state
         .map(|state| {
             hex::decode(state)
@@ -791,16 +2352,168 @@ async fn get_ed25519_outputs(
         })
         .transpose()?;
 
-    let ed25519_address = Ed25519Address::from_str(&address).map_err(|e| ListenerError::BadParse(e.into()))?;
-    let page_size = page_size.unwrap_or(100);
+    let chronicle_keyspace = ChronicleKeyspace::new(keyspace.clone());
+    // The address pages are scanned till a page worth of spent outputs is found, so the unspent outputs don't
+    // shrink the page
+    let mut spent_outputs = Vec::new();
+    for _ in 0..MAX_SPENT_OUTPUTS_SCANNED_PAGES {
+        let outputs = page(
+            &request_id,
+            &settings,
+            keyspace.clone(),
+            Hint::address(ed25519_address.to_string()),
+            page_size - spent_outputs.len(),
+            &mut state,
+            partition_config.borrow(),
+            ed25519_address,
+        )
+        .await?;
+        spent_outputs.extend(
+            spent_outputs_of(
+                &outputs,
+                |output_id| {
+                    query::<OutputRes, _>(
+                        &request_id,
+                        &settings,
+                        chronicle_keyspace.clone(),
+                        output_id,
+                        None,
+                        None,
+                    )
+                },
+                |message_id| {
+                    query::<MessageMetadata, _>(
+                        &request_id,
+                        &settings,
+                        chronicle_keyspace.clone(),
+                        message_id,
+                        None,
+                        None,
+                    )
+                },
+                |message_id| {
+                    query::<Message, _>(
+                        &request_id,
+                        &settings,
+                        chronicle_keyspace.clone(),
+                        message_id,
+                        None,
+                        None,
+                    )
+                },
+            )
+            .await?,
+        );
+        // without a last partition to resume from, every record of the address got paged
+        let depleted = state.as_ref().map_or(true, |state| state.last_partition_id.is_none());
+        if spent_outputs.len() >= page_size || depleted {
+            break;
+        }
+    }
+
+    let state = state
+        .map(|state| bincode::serialize(&state).map(|v| hex::encode(v)))
+        .transpose()
+        .map_err(|e| anyhow!(e))?;
+    Ok(ListenerResponse::SpentOutputsForAddress {
+        address_type: 1,
+        address,
+        max_results: 2 * page_size,
+        page_size,
+        count: spent_outputs.len(),
+        spent_outputs,
+        state,
+        hints: ledger_hints(&keyspace, ledger_hints_cache.borrow(), &settings).await,
+    })
+}
 
-    let mut outputs = page(
+/// Keep the spent outputs of the address records, along with the transaction which spent each of them. The outputs
+/// are looked up `SPENT_OUTPUTS_CONCURRENCY` at once, in the order of the records.
+async fn spent_outputs_of<O, OFut, M, MFut, G, GFut>(
+    records: &[Partitioned<AddressRecord>],
+    fetch_output: O,
+    fetch_metadata: M,
+    fetch_message: G,
+) -> Result<Vec<SpentOutput>, ListenerError>
+where
+    O: Fn(OutputId) -> OFut,
+    OFut: Future<Output = Result<OutputRes, ListenerError>>,
+    M: Fn(MessageId) -> MFut,
+    MFut: Future<Output = Result<MessageMetadata, ListenerError>>,
+    G: Fn(MessageId) -> GFut,
+    GFut: Future<Output = Result<Message, ListenerError>>,
+{
+    let (fetch_output, fetch_metadata, fetch_message) = (&fetch_output, &fetch_metadata, &fetch_message);
+    let spent_outputs = futures::stream::iter(records.iter().map(|record| async move {
+        let output_id = OutputId::new(record.transaction_id, record.index).map_err(|e| anyhow!(e))?;
+        let output_data = fetch_output(output_id).await?;
+        let metadata = match spending_metadata(&output_data.unlock_blocks, fetch_metadata).await? {
+            Some(metadata) => metadata,
+            None => return Ok(None),
+        };
+        let message = fetch_message(metadata.message_id).await?;
+        Ok::<_, ListenerError>(Some(SpentOutput {
+            output_id: output_id.to_string(),
+            message_id: metadata.message_id.to_string(),
+            transaction_id: spending_transaction_id(&metadata.message_id, &message)?.to_string(),
+            milestone_index: metadata.referenced_by_milestone_index,
+        }))
+    }))
+    .buffered(SPENT_OUTPUTS_CONCURRENCY)
+    .try_collect::<Vec<_>>()
+    .await?;
+    Ok(spent_outputs.into_iter().flatten().collect())
+}
+
+/// Decode a bech32 ed25519 address, whose human readable part must match the expected one
+fn decode_bech32_address(address: &str, expected_hrp: &str) -> Result<Ed25519Address, ListenerError> {
+    let (hrp, data, _) = bech32::decode(address).map_err(|e| ListenerError::BadParse(e.into()))?;
+    if hrp != expected_hrp {
+        return Err(ListenerError::HrpMismatch(hrp, expected_hrp.to_string()));
+    }
+    let bytes = Vec::<u8>::from_base32(&data).map_err(|e| ListenerError::BadParse(e.into()))?;
+    let Address::Ed25519(ed25519_address) =
+        Address::unpack(&mut bytes.as_slice()).map_err(|e| ListenerError::BadParse(anyhow!("{:?}", e)))?;
+    Ok(ed25519_address)
+}
+
+/// Format an ed25519 address as a bech32 address with the provided human readable part
+fn encode_bech32_address(ed25519_address: &Ed25519Address, hrp: &str) -> String {
+    Address::Ed25519(*ed25519_address).to_bech32(hrp)
+}
+
+async fn outputs_for_address(
+    request_id: &RequestId,
+    settings: &QuerySettings,
+    keyspace: String,
+    address: String,
+    ed25519_address: Ed25519Address,
+    page_size: usize,
+    expanded: Option<bool>,
+    state: Option<String>,
+    output_type: Option<OutputTypeFilter>,
+    partition_config: &PartitionConfig,
+    ledger_hints_cache: &LedgerHintsCache,
+) -> ListenerResult {
+    let mut state = state
+        .map(|state| {
+            hex::decode(state)
+                .map_err(|_| ListenerError::InvalidState)
+                .and_then(|v| bincode::deserialize::<StateData>(&v).map_err(|_| ListenerError::InvalidState))
+        })
+        .transpose()?;
+
+    let mut outputs = page_filtered(
+        request_id,
+        settings,
         keyspace.clone(),
         Hint::address(ed25519_address.to_string()),
         page_size,
         &mut state,
-        partition_config.borrow(),
+        partition_config,
         ed25519_address,
+        None,
+        |record: &Partitioned<AddressRecord>| keeps_output_type(output_type, record),
     )
     .await?;
 
@@ -808,12 +2521,14 @@ async fn get_ed25519_outputs(
         .map(|state| bincode::serialize(&state).map(|v| hex::encode(v)))
         .transpose()
         .map_err(|e| anyhow!(e))?;
+    let hints = ledger_hints(&keyspace, ledger_hints_cache, settings).await;
 
     if let Some(true) = expanded {
         Ok(ListenerResponse::OutputsForAddressExpanded {
             address_type: 1,
             address,
             max_results: 2 * page_size,
+            page_size,
             count: outputs.len(),
             output_ids: outputs
                 .drain(..)
@@ -821,12 +2536,14 @@ async fn get_ed25519_outputs(
                 .filter_map(|r: anyhow::Result<responses::Record>| r.ok())
                 .collect(),
             state,
+            hints,
         })
     } else {
         Ok(ListenerResponse::OutputsForAddress {
             address_type: 1,
             address,
             max_results: 2 * page_size,
+            page_size,
             count: outputs.len(),
             output_ids: outputs
                 .drain(..)
@@ -834,18 +2551,44 @@ async fn get_ed25519_outputs(
                 .filter_map(|r: anyhow::Result<OutputId>| r.ok())
                 .collect(),
             state,
+            hints,
         })
     }
 }
 
 #[get("/<keyspace>/outputs/<output_id>")]
-async fn get_output(keyspace: String, output_id: String, keyspaces: State<'_, HashSet<String>>) -> ListenerResult {
+async fn get_output(
+    keyspace: String,
+    output_id: String,
+    keyspaces: State<'_, HashSet<String>>,
+    missing_outputs: State<'_, MissingOutputCache>,
+    ledger_hints_cache: State<'_, LedgerHintsCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
     let output_id = OutputId::from_str(&output_id).map_err(|e| ListenerError::BadParse(e.into()))?;
+    if missing_outputs.is_missing(&keyspace, output_id) {
+        return Err(ListenerError::NoResults);
+    }
 
-    let output_data = query::<OutputRes, _, _>(ChronicleKeyspace::new(keyspace.clone()), output_id, None, None).await?;
+    let output_data = query::<OutputRes, _>(
+        &request_id,
+        &settings,
+        ChronicleKeyspace::new(keyspace.clone()),
+        output_id,
+        None,
+        None,
+    )
+    .await
+    .map_err(|e| {
+        if let ListenerError::NoResults = e {
+            missing_outputs.insert(&keyspace, output_id);
+        }
+        e
+    })?;
     let is_spent = if output_data.unlock_blocks.is_empty() {
         false
     } else {
@@ -866,7 +2609,14 @@ async fn get_output(keyspace: String, output_id: String, keyspaces: State<'_, Ha
         }
         if !query_message_ids.is_empty() {
             let queries = query_message_ids.drain().map(|&message_id| {
-                query::<MessageMetadata, _, _>(ChronicleKeyspace::new(keyspace.clone()), message_id.clone(), None, None)
+                query::<MessageMetadata, _>(
+                    &request_id,
+                    &settings,
+                    ChronicleKeyspace::new(keyspace.clone()),
+                    message_id.clone(),
+                    None,
+                    None,
+                )
             });
             is_spent = futures::future::join_all(queries)
                 .await
@@ -882,14 +2632,169 @@ async fn get_output(keyspace: String, output_id: String, keyspaces: State<'_, Ha
         output_index: output_id.index(),
         is_spent,
         output: output_data.output.borrow().into(),
+        hints: ledger_hints(&keyspace, ledger_hints_cache.borrow(), &settings).await,
     })
 }
 
+#[get("/<keyspace>/outputs/<output_id>/spending")]
+async fn get_output_spending(
+    keyspace: String,
+    output_id: String,
+    keyspaces: State<'_, HashSet<String>>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+    let keyspace = ChronicleKeyspace::new(keyspace);
+    let output_id = OutputId::from_str(&output_id).map_err(|e| ListenerError::BadParse(e.into()))?;
+
+    let output_data = query::<OutputRes, _>(&request_id, &settings, keyspace.clone(), output_id, None, None).await?;
+    let metadata = spending_metadata(&output_data.unlock_blocks, |message_id| {
+        query::<MessageMetadata, _>(&request_id, &settings, keyspace.clone(), message_id, None, None)
+    })
+    .await?
+    .ok_or(ListenerError::NoResults)?;
+    let message = query::<Message, _>(&request_id, &settings, keyspace, metadata.message_id, None, None).await?;
+    let transaction_id = spending_transaction_id(&metadata.message_id, &message)?;
+    Ok(ListenerResponse::OutputSpending {
+        message_id: metadata.message_id.to_string(),
+        transaction_id: transaction_id.to_string(),
+        milestone_index: metadata.referenced_by_milestone_index,
+    })
+}
+
+#[get("/<keyspace>/outputs/<output_id>/history")]
+async fn get_output_history(
+    keyspace: String,
+    output_id: String,
+    keyspaces: State<'_, HashSet<String>>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+    let keyspace = ChronicleKeyspace::new(keyspace);
+    let output_id = OutputId::from_str(&output_id).map_err(|e| ListenerError::BadParse(e.into()))?;
+
+    let output_data = query::<OutputRes, _>(&request_id, &settings, keyspace.clone(), output_id, None, None).await?;
+    let (created, spent) = output_history(
+        output_id,
+        &output_data,
+        |message_id| query::<MessageMetadata, _>(&request_id, &settings, keyspace.clone(), message_id, None, None),
+        |message_id| query::<Message, _>(&request_id, &settings, keyspace.clone(), message_id, None, None),
+    )
+    .await?;
+    Ok(ListenerResponse::OutputHistory {
+        output_id: output_id.to_string(),
+        created,
+        spent,
+    })
+}
+
+/// Walk the UTXO chain of the output: the transaction which created it and, if the output is spent, the transaction
+/// which consumed it
+async fn output_history<M, MFut, G, GFut>(
+    output_id: OutputId,
+    output_data: &OutputRes,
+    mut fetch_metadata: M,
+    mut fetch_message: G,
+) -> Result<(OutputTransaction, Option<OutputTransaction>), ListenerError>
+where
+    M: FnMut(MessageId) -> MFut,
+    MFut: Future<Output = Result<MessageMetadata, ListenerError>>,
+    G: FnMut(MessageId) -> GFut,
+    GFut: Future<Output = Result<Message, ListenerError>>,
+{
+    // The outputs of the milestone receipts are not created by a transaction payload, so the transaction id is
+    // taken from the output id
+    let milestone_index = match fetch_metadata(output_data.message_id).await {
+        Ok(metadata) => metadata.referenced_by_milestone_index,
+        Err(ListenerError::NoResults) => None,
+        Err(e) => return Err(e),
+    };
+    let message = fetch_message(output_data.message_id).await?;
+    let created = OutputTransaction {
+        message_id: output_data.message_id.to_string(),
+        transaction_id: output_id.transaction_id().to_string(),
+        milestone_index,
+        outputs: transaction_outputs(message.payload()),
+    };
+    let spent = match spending_metadata(&output_data.unlock_blocks, &mut fetch_metadata).await? {
+        Some(metadata) => {
+            let message = fetch_message(metadata.message_id).await?;
+            let transaction_id = spending_transaction_id(&metadata.message_id, &message)?;
+            Some(OutputTransaction {
+                message_id: metadata.message_id.to_string(),
+                transaction_id: transaction_id.to_string(),
+                milestone_index: metadata.referenced_by_milestone_index,
+                outputs: transaction_outputs(message.payload()),
+            })
+        }
+        None => None,
+    };
+    Ok((created, spent))
+}
+
+/// The ids of the outputs produced by the transaction payload
+fn transaction_outputs(payload: Option<&Payload>) -> Vec<String> {
+    match payload {
+        Some(Payload::Transaction(payload)) => {
+            let Essence::Regular(essence) = payload.essence();
+            (0..essence.outputs().len())
+                .filter_map(|index| OutputId::new(payload.id(), index as u16).ok())
+                .map(|output_id| output_id.to_string())
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The id of the transaction carried by the spending message
+fn spending_transaction_id(message_id: &MessageId, message: &Message) -> Result<TransactionId, ListenerError> {
+    match message.payload() {
+        Some(Payload::Transaction(payload)) => Ok(payload.id()),
+        _ => Err(anyhow!("The spending message {} has no transaction payload", message_id).into()),
+    }
+}
+
+/// Find the metadata of the message which spent the output, which is the only unlocking message included in the
+/// ledger
+async fn spending_metadata<F, Fut>(
+    unlock_blocks: &[UnlockRes],
+    mut fetch_metadata: F,
+) -> Result<Option<MessageMetadata>, ListenerError>
+where
+    F: FnMut(MessageId) -> Fut,
+    Fut: Future<Output = Result<MessageMetadata, ListenerError>>,
+{
+    for unlock in unlock_blocks.iter() {
+        if let Some(LedgerInclusionState::Conflicting) | Some(LedgerInclusionState::NoTransaction) =
+            unlock.inclusion_state
+        {
+            continue;
+        }
+        // The stored inclusion state might be outdated, so the metadata of the message is the source of truth
+        match fetch_metadata(unlock.message_id).await {
+            Ok(metadata) if metadata.ledger_inclusion_state == Some(LedgerInclusionState::Included) => {
+                return Ok(Some(metadata));
+            }
+            Ok(_) | Err(ListenerError::NoResults) => (),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(None)
+}
+
 #[get("/<keyspace>/transactions/<transaction_id>/included-message")]
 async fn get_transaction_included_message(
     keyspace: String,
     transaction_id: String,
     keyspaces: State<'_, HashSet<String>>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
 ) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
@@ -898,26 +2803,230 @@ async fn get_transaction_included_message(
 
     let transaction_id = TransactionId::from_str(&transaction_id).map_err(|e| ListenerError::Other(anyhow!(e)))?;
 
-    let message_id = query::<MessageId, _, _>(keyspace.clone(), transaction_id, None, None).await?;
-    query::<Message, _, _>(keyspace, message_id, None, None)
+    let message_id =
+        query::<MessageId, _>(&request_id, &settings, keyspace.clone(), transaction_id, None, None).await?;
+    query::<Message, _>(&request_id, &settings, keyspace, message_id, None, None)
         .await
         .and_then(|message| message.try_into().map_err(|e: Cow<'static, str>| anyhow!(e).into()))
 }
 
 #[get("/<keyspace>/milestones/<index>")]
-async fn get_milestone(keyspace: String, index: u32, keyspaces: State<'_, HashSet<String>>) -> ListenerResult {
+async fn get_milestone(
+    keyspace: String,
+    index: u32,
+    keyspaces: State<'_, HashSet<String>>,
+    message_cache: State<'_, MessageCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
-    let keyspace = ChronicleKeyspace::new(keyspace);
 
-    query::<Milestone, _, _>(keyspace, MilestoneIndex::from(index), None, None)
+    milestone_with_message_count(
+        index,
+        query::<Milestone, _>(
+            &request_id,
+            &settings,
+            ChronicleKeyspace::new(keyspace.clone()),
+            MilestoneIndex::from(index),
+            None,
+            None,
+        ),
+        milestone_analytics(&keyspace, index, &message_cache, &request_id, &settings),
+        query::<SyncRecord, _>(
+            &request_id,
+            &settings,
+            ChronicleKeyspace::new(keyspace.clone()),
+            (Synckey, MilestoneIndex::from(index)),
+            None,
+            None,
+        ),
+    )
+    .await
+}
+
+/// Build the milestone response along with the number of messages it confirmed, which is absent if the milestone
+/// got no analytics (yet), and the source which created its milestone data, which is absent if the milestone is not
+/// synced (yet) or it was synced before the source was recorded
+async fn milestone_with_message_count<M, A, S>(index: u32, milestone: M, analytics: A, synced: S) -> ListenerResult
+where
+    M: Future<Output = Result<Milestone, ListenerError>>,
+    A: Future<Output = Result<AnalyticData, ListenerError>>,
+    S: Future<Output = Result<SyncRecord, ListenerError>>,
+{
+    let (milestone, analytics, synced) = futures::future::join3(milestone, analytics, synced).await;
+    let milestone = milestone?;
+    let confirmed_message_count = match analytics {
+        Ok(analytics) => Some(analytics.message_count()),
+        Err(ListenerError::NoResults) => None,
+        Err(e) => return Err(e),
+    };
+    let created_by = match synced {
+        Ok(synced) => synced
+            .created_by
+            .and_then(|created_by| CreatedBy::try_from(created_by).ok())
+            .map(|created_by| created_by.to_string()),
+        Err(ListenerError::NoResults) => None,
+        Err(e) => return Err(e),
+    };
+    Ok(ListenerResponse::Milestone {
+        milestone_index: index,
+        message_id: milestone.message_id().to_string(),
+        timestamp: milestone.timestamp(),
+        confirmed_message_count,
+        created_by,
+    })
+}
+
+#[get("/<keyspace>/milestones/diff?<a>&<b>")]
+async fn get_milestone_diff(
+    keyspace: String,
+    a: u32,
+    b: u32,
+    keyspaces: State<'_, HashSet<String>>,
+    message_cache: State<'_, MessageCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
+) -> ListenerResult {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+
+    let (a, b) = futures::future::try_join(
+        milestone_analytics(&keyspace, a, &message_cache, &request_id, &settings),
+        milestone_analytics(&keyspace, b, &message_cache, &request_id, &settings),
+    )
+    .await?;
+    Ok(ListenerResponse::MilestoneDiff {
+        delta: a.delta(&b),
+        a,
+        b,
+    })
+}
+
+/// Fetch the analytics of a single milestone, as precomputed on ingestion, or compute them out of the past cone of
+/// the milestone if they're not stored
+async fn milestone_analytics(
+    keyspace: &str,
+    milestone_index: u32,
+    message_cache: &MessageCache,
+    request_id: &RequestId,
+    settings: &QuerySettings,
+) -> Result<AnalyticData, ListenerError> {
+    let chronicle_keyspace = ChronicleKeyspace::new(keyspace.to_owned());
+    match stored_milestone_analytics(&chronicle_keyspace, milestone_index, settings).await {
+        Err(ListenerError::NoResults) => (),
+        stored => return stored,
+    }
+    debug!(
+        "[{}] No stored analytics for milestone {}, computing them",
+        request_id, milestone_index
+    );
+    milestone_analytic_record(keyspace, milestone_index, message_cache, request_id, settings)
         .await
-        .map(|milestone| ListenerResponse::Milestone {
-            milestone_index: index,
-            message_id: milestone.message_id().to_string(),
-            timestamp: milestone.timestamp(),
-        })
+        .map(Into::into)
+}
+
+/// Compute the analytic record of a single stored milestone out of its past cone
+async fn milestone_analytic_record(
+    keyspace: &str,
+    milestone_index: u32,
+    message_cache: &MessageCache,
+    request_id: &RequestId,
+    settings: &QuerySettings,
+) -> Result<AnalyticRecord, ListenerError> {
+    let chronicle_keyspace = ChronicleKeyspace::new(keyspace.to_owned());
+    let milestone = query::<Milestone, _>(
+        request_id,
+        settings,
+        chronicle_keyspace.clone(),
+        MilestoneIndex(milestone_index),
+        None,
+        None,
+    )
+    .await?;
+    computed_milestone_record(
+        milestone_index,
+        *milestone.message_id(),
+        |message_id| fetch_message(keyspace, message_id, message_cache, request_id, settings),
+        |message_id| {
+            query::<MessageMetadata, _>(request_id, settings, chronicle_keyspace.clone(), message_id, None, None)
+        },
+    )
+    .await
+}
+
+/// Fetch the precomputed analytics of a single milestone
+async fn stored_milestone_analytics(
+    keyspace: &ChronicleKeyspace,
+    milestone_index: u32,
+    settings: &QuerySettings,
+) -> Result<AnalyticData, ListenerError> {
+    let range = milestone_index..milestone_index.saturating_add(1);
+    settings
+        .limited(async { Ok::<_, ListenerError>(AnalyticsData::try_fetch(keyspace, &range.into(), 1, 1).await?) })
+        .await?
+        .analytics
+        .pop()
+        .ok_or(ListenerError::NoResults)
+}
+
+/// Compute the analytics of a single milestone out of its past cone, the same way the broker does on ingestion. An
+/// incomplete cone fails, as its analytics would silently undercount the milestone
+async fn computed_milestone_analytics<G, GFut, M, MFut>(
+    milestone_index: u32,
+    milestone_id: MessageId,
+    fetch_message: G,
+    fetch_metadata: M,
+) -> Result<AnalyticData, ListenerError>
+where
+    G: Fn(MessageId) -> GFut,
+    GFut: Future<Output = Result<Message, ListenerError>>,
+    M: Fn(MessageId) -> MFut,
+    MFut: Future<Output = Result<MessageMetadata, ListenerError>>,
+{
+    Ok(
+        computed_milestone_record(milestone_index, milestone_id, fetch_message, fetch_metadata)
+            .await?
+            .into(),
+    )
+}
+
+/// Compute the analytic record of a single milestone out of its past cone
+async fn computed_milestone_record<G, GFut, M, MFut>(
+    milestone_index: u32,
+    milestone_id: MessageId,
+    fetch_message: G,
+    fetch_metadata: M,
+) -> Result<AnalyticRecord, ListenerError>
+where
+    G: Fn(MessageId) -> GFut,
+    GFut: Future<Output = Result<Message, ListenerError>>,
+    M: Fn(MessageId) -> MFut,
+    MFut: Future<Output = Result<MessageMetadata, ListenerError>>,
+{
+    let milestone_data = milestone_cone(milestone_index, milestone_id, fetch_message, fetch_metadata).await?;
+    Ok(milestone_data.get_analytic_record()?)
+}
+
+/// Store the analytic record of a milestone in the analytics table, as the broker does on ingestion
+fn store_analytic_record(
+    keyspace: &ChronicleKeyspace,
+    record: &AnalyticRecord,
+    store_settings: &AnalyticsStoreSettings,
+) -> anyhow::Result<()> {
+    keyspace
+        .insert(&Synckey, record)
+        .consistency(store_settings.consistency())
+        .build()?
+        .send_local(InsertWorker::boxed(
+            keyspace.clone(),
+            Synckey,
+            record.clone(),
+            store_settings.retries_per_query,
+        ));
+    Ok(())
 }
 
 #[get("/<keyspace>/analytics?<start>&<end>")]
@@ -926,21 +3035,170 @@ async fn get_analytics(
     start: Option<u32>,
     end: Option<u32>,
     keyspaces: State<'_, HashSet<String>>,
+    ledger_hints_cache: State<'_, LedgerHintsCache>,
+    analytics_backfill: State<'_, AnalyticsBackfill>,
+    analytics_store_settings: State<'_, AnalyticsStoreSettings>,
+    message_cache: State<'_, MessageCache>,
+    settings: State<'_, QuerySettings>,
+    request_id: RequestId,
 ) -> ListenerResult {
     if !keyspaces.contains(&keyspace) {
         return Err(ListenerError::InvalidKeyspace(keyspace));
     }
-    let keyspace = ChronicleKeyspace::new(keyspace);
+    let chronicle_keyspace = &ChronicleKeyspace::new(keyspace.clone());
+    let store_keyspace = &analytics_store_settings.keyspace(&keyspace);
 
     let range = start.unwrap_or(1)..end.unwrap_or(i32::MAX as u32);
+    // without the hints of the stored milestones nothing is backfilled
+    let hints = ledger_hints(&keyspace, &ledger_hints_cache, &settings)
+        .await
+        .unwrap_or_default();
 
-    let ranges = AnalyticsData::try_fetch(&keyspace, &range.into(), 1, 5000)
-        .await?
-        .analytics;
+    let ranges = analytics_with_backfill(
+        &keyspace,
+        range,
+        hints,
+        &analytics_backfill,
+        |range| {
+            settings
+                .limited(async move { Ok(AnalyticsData::try_fetch(chronicle_keyspace, &range.into(), 1, 5000).await?) })
+        },
+        |milestone_index| milestone_analytic_record(&keyspace, milestone_index, &message_cache, &request_id, &settings),
+        |record| store_analytic_record(store_keyspace, record, &analytics_store_settings),
+    )
+    .await?
+    .analytics;
 
     Ok(ListenerResponse::Analytics { ranges })
 }
 
+/// Fetch the analytics of the range, and backfill the ones of the stored milestones which have none (up to
+/// `MAX_ANALYTICS_BACKFILL` per query), so the following queries of the range are served by the analytics table
+async fn analytics_with_backfill<F, FFut, C, CFut, S>(
+    keyspace: &str,
+    range: Range<u32>,
+    hints: LedgerHints,
+    analytics_backfill: &AnalyticsBackfill,
+    fetch_analytics: F,
+    compute: C,
+    store: S,
+) -> Result<AnalyticsData, ListenerError>
+where
+    F: FnOnce(Range<u32>) -> FFut,
+    FFut: Future<Output = Result<AnalyticsData, ListenerError>>,
+    C: Fn(u32) -> CFut,
+    CFut: Future<Output = Result<AnalyticRecord, ListenerError>>,
+    S: Fn(&AnalyticRecord) -> anyhow::Result<()>,
+{
+    let mut analytics_data = fetch_analytics(range.clone()).await?;
+    let stored = SyncRange {
+        from: range.start.max(hints.pruning_index.saturating_add(1)),
+        to: range.end.min(hints.ledger_index.saturating_add(1)),
+    };
+    if stored.from >= stored.to {
+        return Ok(analytics_data);
+    }
+    let analyzed = analytics_data
+        .analytics
+        .iter()
+        .map(|analytic_data| analytic_data.range().clone())
+        .collect::<Vec<_>>();
+    // the gaps are in descending order, the lowest milestones are backfilled first
+    let missing = SyncData::gaps_within(&stored, &analyzed)
+        .into_iter()
+        .rev()
+        .flatten()
+        .take(MAX_ANALYTICS_BACKFILL)
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        debug!(
+            "Backfilling the analytics of {} milestones of keyspace {}",
+            missing.len(),
+            keyspace
+        );
+        let backfilled = analytics_backfill.backfill(keyspace, missing, compute, store).await;
+        analytics_data.merge(backfilled);
+    }
+    Ok(analytics_data)
+}
+
+#[get("/<keyspace>/analytics/series?<from>&<to>&<bucket>")]
+async fn get_analytics_series(
+    keyspace: String,
+    from: u32,
+    to: u32,
+    bucket: u32,
+    keyspaces: State<'_, HashSet<String>>,
+    settings: State<'_, QuerySettings>,
+) -> ListenerResult {
+    if !keyspaces.contains(&keyspace) {
+        return Err(ListenerError::InvalidKeyspace(keyspace));
+    }
+    let keyspace = ChronicleKeyspace::new(keyspace);
+
+    let (buckets, truncated) = series_buckets(from, to, bucket)?;
+    let keyspace = &keyspace;
+    let settings = settings.inner();
+    let points = analytics_series(buckets, move |range| {
+        settings.limited(async move {
+            Ok(AnalyticsData::try_fetch(keyspace, &range.into(), 1, 5000)
+                .await?
+                .analytics)
+        })
+    })
+    .await?;
+
+    Ok(ListenerResponse::AnalyticsSeries {
+        bucket,
+        points,
+        truncated,
+    })
+}
+
+/// Split the milestone range `from..to` into ranges of `bucket` milestones, the last one may be shorter.
+/// The number of buckets is capped to `MAX_SERIES_POINTS`, so a too wide range is truncated, which is flagged along
+/// with the buckets.
+fn series_buckets(from: u32, to: u32, bucket: u32) -> Result<(Vec<Range<u32>>, bool), ListenerError> {
+    if bucket == 0 {
+        return Err(ListenerError::BadParse(anyhow!("The bucket size must be positive")));
+    }
+    if from >= to {
+        return Err(ListenerError::BadParse(anyhow!(
+            "Invalid milestone range: {}..{}, from must be lower than to",
+            from,
+            to
+        )));
+    }
+    let buckets = (from..to)
+        .step_by(bucket as usize)
+        .take(MAX_SERIES_POINTS)
+        .map(|start| start..start.saturating_add(bucket).min(to))
+        .collect::<Vec<_>>();
+    let truncated = buckets.last().map_or(false, |last| last.end < to);
+    Ok((buckets, truncated))
+}
+
+/// Fetch the analytics of every bucket, up to `SERIES_CONCURRENCY` buckets at once, and sum them into one data point
+/// per bucket
+async fn analytics_series<F, Fut>(
+    buckets: Vec<Range<u32>>,
+    fetch_analytics: F,
+) -> Result<Vec<AnalyticData>, ListenerError>
+where
+    F: Fn(Range<u32>) -> Fut,
+    Fut: Future<Output = Result<Vec<AnalyticData>, ListenerError>>,
+{
+    let mut points = futures::stream::iter(buckets.into_iter().enumerate().map(|(i, range)| {
+        let analytics = fetch_analytics(range.clone());
+        async move { Ok((i, AnalyticData::sum(range, analytics.await?))) }
+    }))
+    .buffer_unordered(SERIES_CONCURRENCY)
+    .try_collect::<Vec<_>>()
+    .await?;
+    points.sort_by_key(|(i, _)| *i);
+    Ok(points.into_iter().map(|(_, point)| point).collect())
+}
+
 #[catch(500)]
 fn internal_error() -> ListenerError {
     ListenerError::Other(anyhow!("Internal server error!"))
@@ -954,6 +3212,23 @@ fn not_found() -> ListenerError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bee_message::{
+        parents::Parents,
+        prelude::{
+            Ed25519Signature,
+            Input,
+            Output,
+            ReferenceUnlock,
+            RegularEssence,
+            SignatureUnlock,
+            TransactionPayload,
+            UnlockBlock,
+            UnlockBlocks,
+            UtxoInput,
+        },
+        MessageBuilder,
+    };
+    use bee_pow::providers::miner::Miner;
     use chronicle_common::config::StorageConfig;
     use rocket::{
         http::{
@@ -968,6 +3243,54 @@ mod tests {
     };
     use serde_json::Value;
 
+    /// A signature locked single output of the amount to the ed25519 address
+    fn single_output(address: Ed25519Address, amount: u64) -> Output {
+        Output::SignatureLockedSingle(SignatureLockedSingleOutput::new(Address::Ed25519(address), amount).unwrap())
+    }
+
+    /// A transaction spending the inputs into the outputs, with the optional embedded payload, whose first input is
+    /// unlocked by a signature and the rest by references to it
+    fn transaction_payload(
+        inputs: Vec<UtxoInput>,
+        outputs: Vec<Output>,
+        payload: Option<Payload>,
+    ) -> TransactionPayload {
+        let unlock_blocks = (0..inputs.len())
+            .map(|index| {
+                if index == 0 {
+                    UnlockBlock::Signature(SignatureUnlock::Ed25519(Ed25519Signature::new(
+                        [0; 32],
+                        Box::new([0; 64]),
+                    )))
+                } else {
+                    UnlockBlock::Reference(ReferenceUnlock::new(0).unwrap())
+                }
+            })
+            .collect();
+        let mut essence = RegularEssence::builder()
+            .with_inputs(inputs.into_iter().map(Input::Utxo).collect())
+            .with_outputs(outputs);
+        if let Some(payload) = payload {
+            essence = essence.with_payload(payload);
+        }
+        TransactionPayload::builder()
+            .with_essence(Essence::Regular(essence.finish().unwrap()))
+            .with_unlock_blocks(UnlockBlocks::new(unlock_blocks).unwrap())
+            .finish()
+            .unwrap()
+    }
+
+    /// A message of the network on top of the parents, with the optional payload
+    fn message(network_id: u64, parents: Vec<MessageId>, payload: Option<Payload>) -> Message {
+        let mut builder = MessageBuilder::<Miner>::new()
+            .with_network_id(network_id)
+            .with_parents(Parents::new(parents).unwrap());
+        if let Some(payload) = payload {
+            builder = builder.with_payload(payload);
+        }
+        builder.finish().unwrap()
+    }
+
     fn check_cors_headers(res: &LocalResponse) {
         assert_eq!(
             res.headers().get_one("Access-Control-Allow-Origin"),
@@ -975,7 +3298,7 @@ mod tests {
         );
         assert_eq!(
             res.headers().get_one("Access-Control-Allow-Methods"),
-            Some(Header::new("Access-Control-Allow-Methods", "GET, OPTIONS").value())
+            Some(Header::new("Access-Control-Allow-Methods", "GET, POST, OPTIONS").value())
         );
         assert_eq!(
             res.headers().get_one("Access-Control-Allow-Headers"),
@@ -1017,6 +3340,109 @@ mod tests {
         }
     }
 
+    #[rocket::async_test]
+    async fn openapi() {
+        let rocket = construct_rocket(rocket::ignite());
+        let client = Client::tracked(rocket).await.expect("Invalid rocket instance!");
+
+        let res = client.get("/api/openapi.json").dispatch().await;
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(res.content_type(), Some(ContentType::JSON));
+        let body: Value = serde_json::from_str(&res.into_string().await.expect("No body returned!"))
+            .expect("Failed to deserialize OpenAPI document!");
+        let paths = body["paths"].as_object().expect("No paths listed!");
+        for path in [
+            "/api/info",
+            "/api/{keyspace}/messages/{message_id}",
+            "/api/{keyspace}/messages/{message_id}/raw",
+            "/api/{keyspace}/outputs/{output_id}",
+            "/api/{keyspace}/addresses/{address}/outputs",
+            "/api/{keyspace}/milestones/{index}",
+            "/healthz",
+            "/readyz",
+        ]
+        .iter()
+        {
+            assert!(paths[*path]["get"].is_object(), "{} is not listed", path);
+        }
+        assert_eq!(
+            paths["/api/{keyspace}/messages/{message_id}"]["get"]["parameters"]
+                .as_array()
+                .map(|p| p.len()),
+            Some(2)
+        );
+        let parameters = paths["/api/{keyspace}/messages/{message_id}/children"]["get"]["parameters"]
+            .as_array()
+            .expect("No parameters listed!");
+        assert!(parameters
+            .iter()
+            .any(|p| p["name"] == "page_size" && p["in"] == "query" && p["required"] == false));
+        assert!(paths["/api/{keyspace}/messages"]["post"]["requestBody"].is_object());
+        assert!(body["components"]["schemas"]["ListenerResponse"].is_object());
+        assert!(body["components"]["schemas"]["Record"].is_object());
+    }
+
+    #[test]
+    fn error_statuses() {
+        for (err, status) in vec![
+            (ListenerError::NoResults, Status::NotFound),
+            (ListenerError::InvalidKeyspace("other".to_owned()), Status::NotFound),
+            (ListenerError::IndexTooLarge, Status::BadRequest),
+            (ListenerError::InvalidHex, Status::BadRequest),
+            (ListenerError::TagPrefixTooShort, Status::BadRequest),
+            (
+                ListenerError::HrpMismatch("atoi".to_owned(), "iota".to_owned()),
+                Status::BadRequest,
+            ),
+            (
+                ListenerError::BadParse(anyhow!("Invalid message id")),
+                Status::BadRequest,
+            ),
+            (ListenerError::Timeout, Status::GatewayTimeout),
+            (ListenerError::Overloaded, Status::ServiceUnavailable),
+            (ListenerError::RangeNotSatisfiable(10), Status::RangeNotSatisfiable),
+            (ListenerError::ResponseTooLarge(1024), Status::PayloadTooLarge),
+            (ListenerError::NoResponseError, Status::InternalServerError),
+            (
+                ListenerError::Other(anyhow!("Internal server error!")),
+                Status::InternalServerError,
+            ),
+        ] {
+            let message = err.to_string();
+            let body = ErrorBody::from(err);
+            assert_eq!(body.status, status, "{}", message);
+            let body: Value = serde_json::to_value(&body).unwrap();
+            assert_eq!(body["code"], status.code);
+            assert_eq!(body["message"], message);
+        }
+    }
+
+    #[test]
+    fn response_size_cap() {
+        let messages = (0..100u8)
+            .map(|i| (MessageId::new([i; 32]).to_string(), None))
+            .collect::<std::collections::BTreeMap<_, _>>();
+        let body = SuccessBody::from(ListenerResponse::Messages { messages });
+        let unbounded = json_body(&body, 0).unwrap();
+        assert_eq!(unbounded, serde_json::to_string(&body).unwrap());
+        assert_eq!(json_body(&body, unbounded.len()).unwrap(), unbounded);
+        let error = json_body(&body, unbounded.len() - 1).unwrap_err();
+        assert!(matches!(error, ListenerError::ResponseTooLarge(max_bytes) if max_bytes == unbounded.len() - 1));
+        assert_eq!(ErrorBody::from(error).status, Status::PayloadTooLarge);
+    }
+
+    #[rocket::async_test]
+    async fn health_probes() {
+        let rocket = construct_rocket(rocket::ignite());
+        let client = Client::tracked(rocket).await.expect("Invalid rocket instance!");
+
+        let res = client.get("/healthz").dispatch().await;
+        assert_eq!(res.status(), Status::Ok);
+        // Neither scylla nor the broker are registered
+        let res = client.get("/readyz").dispatch().await;
+        assert_eq!(res.status(), Status::ServiceUnavailable);
+    }
+
     #[rocket::async_test]
     async fn service() {
         let rocket = construct_rocket(rocket::ignite());
@@ -1030,6 +3456,37 @@ mod tests {
             .expect("Failed to deserialize Service Response!");
     }
 
+    #[rocket::async_test]
+    async fn active_jobs() {
+        let active_jobs = ActiveJobs::default();
+        let rocket = construct_rocket(rocket::ignite().manage(active_jobs.clone()));
+        let client = Client::tracked(rocket).await.expect("Invalid rocket instance!");
+
+        // Simulate an active import of the 1to100.log
+        let import_job = active_jobs.track_import(1, 100, 5000);
+        active_jobs.update(|jobs| jobs.import_progress(1, 100, 5000, 1200, 1, false));
+        let res = client.get("/api/jobs").dispatch().await;
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(res.content_type(), Some(ContentType::JSON));
+        let body: Jobs = serde_json::from_str(&res.into_string().await.expect("No body returned!"))
+            .expect("Failed to deserialize Jobs Response!");
+        let import = body
+            .imports
+            .iter()
+            .find(|job| (job.from_ms, job.to_ms) == (1, 100))
+            .expect("The active import is not reported!");
+        assert_eq!(import.log_file_size, 5000);
+        assert_eq!(import.processed_bytes, 1200);
+        assert_eq!(import.imported, 1);
+        assert_eq!(import.milestone_index, Some(1));
+
+        drop(import_job);
+        let res = client.get("/api/jobs").dispatch().await;
+        let body: Jobs = serde_json::from_str(&res.into_string().await.expect("No body returned!"))
+            .expect("Failed to deserialize Jobs Response!");
+        assert!(body.imports.is_empty());
+    }
+
     #[rocket::async_test]
     async fn get_message() {
         let storage_config = StorageConfig::default();
@@ -1042,6 +3499,8 @@ mod tests {
         let rocket = construct_rocket(
             rocket::ignite()
                 .manage(storage_config.partition_config.clone())
+                .manage(MessageCache::new(ApiConfig::default().message_cache_capacity))
+                .manage(QuerySettings::default())
                 .manage(keyspaces),
         );
         let client = Client::tracked(rocket).await.expect("Invalid rocket instance!");
@@ -1057,4 +3516,1633 @@ mod tests {
             .expect("Failed to deserialize response!");
         assert_eq!(body.get("message").and_then(Value::as_str), Some("Worker NoRing"));
     }
+
+    #[rocket::async_test]
+    async fn batch_messages_lookup() {
+        let found = MessageId::new([1; 32]);
+        let missing = MessageId::new([2; 32]);
+        let fetch = |message_id: MessageId| {
+            futures::future::ready(if message_id == found {
+                Ok(message(1, vec![MessageId::new([0; 32])], None))
+            } else {
+                Err(ListenerError::NoResults)
+            })
+        };
+        let messages =
+            match batch_messages(vec![found.to_string(), missing.to_string(), found.to_string()], fetch).await {
+                Ok(ListenerResponse::Messages { messages }) => messages,
+                _ => panic!("Expected the messages by id"),
+            };
+        assert_eq!(messages.len(), 2);
+        match messages.get(&found.to_string()) {
+            Some(Some(ListenerResponse::Message { network_id, .. })) => assert_eq!(network_id, "1"),
+            _ => panic!("Expected the found message"),
+        }
+        assert!(matches!(messages.get(&missing.to_string()), Some(None)));
+        // the batch size is capped
+        assert!(matches!(
+            batch_messages(vec![found.to_string(); MAX_BATCH_MESSAGES + 1], fetch).await,
+            Err(ListenerError::BadParse(_))
+        ));
+        assert!(matches!(
+            batch_messages(vec!["invalid".to_owned()], fetch).await,
+            Err(ListenerError::BadParse(_))
+        ));
+    }
+
+    #[test]
+    fn metric_keyspace_label() {
+        let keyspaces = vec!["permanode".to_owned()].into_iter().collect::<HashSet<_>>();
+        assert_eq!(
+            metric_keyspace("/api/permanode/messages/0", Some(&keyspaces)),
+            "permanode"
+        );
+        assert_eq!(metric_keyspace("/api/permanode", Some(&keyspaces)), "permanode");
+        // unknown keyspaces and the routes without a keyspace are not labelled
+        assert_eq!(metric_keyspace("/api/unknown/messages/0", Some(&keyspaces)), "");
+        assert_eq!(metric_keyspace("/api/info", Some(&keyspaces)), "");
+        assert_eq!(metric_keyspace("/healthz", Some(&keyspaces)), "");
+        assert_eq!(metric_keyspace("/api/permanode/sync", None), "");
+    }
+
+    #[test]
+    fn raw_message_rehashes_to_id() {
+        use bee_message::{
+            parents::Parents,
+            MessageBuilder,
+        };
+        use bee_pow::providers::miner::Miner;
+
+        let message = MessageBuilder::<Miner>::new()
+            .with_network_id(1)
+            .with_parents(Parents::new(vec![MessageId::new([1; 32])]).unwrap())
+            .finish()
+            .unwrap();
+        let (message_id, _) = message.id();
+        let Content(content_type, bytes) = raw_message(&message);
+        assert_eq!(content_type, ContentType::Binary);
+        let unpacked = Message::unpack(&mut bytes.as_slice()).expect("Failed to unpack raw message!");
+        assert_eq!(unpacked.id().0, message_id);
+    }
+
+    #[test]
+    fn raw_bytes_ranges() {
+        let bytes = (0..100).collect::<Vec<u8>>();
+        // no range gets the full content
+        assert_eq!(
+            ranged_bytes(bytes.clone(), None).unwrap(),
+            RawBytes::Full(bytes.clone())
+        );
+        // and so does a range the header syntax does not support
+        assert_eq!(
+            ranged_bytes(bytes.clone(), Some("bytes=0-9,20-29")).unwrap(),
+            RawBytes::Full(bytes.clone())
+        );
+        assert_eq!(
+            ranged_bytes(bytes.clone(), Some("bytes=10-19")).unwrap(),
+            RawBytes::Partial {
+                bytes: bytes[10..20].to_vec(),
+                range: 10..20,
+                len: 100
+            }
+        );
+        // the open and the suffix ranges, the last byte position is clamped to the content length
+        assert_eq!(
+            ranged_bytes(bytes.clone(), Some("bytes=90-")).unwrap(),
+            ranged_bytes(bytes.clone(), Some("bytes=90-1000")).unwrap(),
+        );
+        assert_eq!(
+            ranged_bytes(bytes.clone(), Some("bytes=-10")).unwrap(),
+            RawBytes::Partial {
+                bytes: bytes[90..].to_vec(),
+                range: 90..100,
+                len: 100
+            }
+        );
+        let out_of_bounds = ranged_bytes(bytes.clone(), Some("bytes=100-"));
+        assert!(matches!(out_of_bounds, Err(ListenerError::RangeNotSatisfiable(100))));
+        assert_eq!(out_of_bounds.unwrap_err().status(), Status::RangeNotSatisfiable);
+    }
+
+    fn bech32_address(hrp: &str, ed25519_address: [u8; 32]) -> String {
+        use bech32::{
+            ToBase32,
+            Variant,
+        };
+        // the address kind byte followed by the ed25519 address
+        let bytes = [&[0u8][..], &ed25519_address[..]].concat();
+        bech32::encode(hrp, bytes.to_base32(), Variant::Bech32).unwrap()
+    }
+
+    #[test]
+    fn decode_bech32() {
+        let address = bech32_address("iota", [7; 32]);
+        assert_eq!(
+            decode_bech32_address(&address, "iota").unwrap(),
+            Ed25519Address::new([7; 32])
+        );
+        assert!(matches!(
+            decode_bech32_address(&address, "atoi"),
+            Err(ListenerError::HrpMismatch(hrp, expected)) if hrp == "iota" && expected == "atoi"
+        ));
+        assert!(matches!(
+            decode_bech32_address("not a bech32 address", "iota"),
+            Err(ListenerError::BadParse(_))
+        ));
+    }
+
+    #[test]
+    fn bech32_round_trip() {
+        let api_config = ApiConfig {
+            bech32_hrp: "atoi".to_string(),
+            ..Default::default()
+        };
+        let ed25519_address = Ed25519Address::new([7; 32]);
+        let address = encode_bech32_address(&ed25519_address, &api_config.bech32_hrp);
+        assert!(address.starts_with("atoi1"));
+        assert_eq!(address, bech32_address("atoi", [7; 32]));
+        assert_eq!(
+            decode_bech32_address(&address, &api_config.bech32_hrp).unwrap(),
+            ed25519_address
+        );
+        // keyspaces without their own human readable part use the api one
+        let keyspace_hrps = KeyspaceHrps::new(&StorageConfig::default().keyspaces, &api_config);
+        assert_eq!(keyspace_hrps.0.get("permanode").map(String::as_str), Some("atoi"));
+        // which defaults to the first keyspace one, or the mainnet one
+        let mut keyspaces = StorageConfig::default().keyspaces;
+        let keyspace_hrps = KeyspaceHrps::new(&keyspaces, &ApiConfig::default());
+        assert_eq!(keyspace_hrps.0.get("permanode").map(String::as_str), Some("iota"));
+        keyspaces[0].bech32_hrp = Some("atoi".to_string());
+        let keyspace_hrps = KeyspaceHrps::new(&keyspaces, &ApiConfig::default());
+        assert_eq!(keyspace_hrps.0.get("permanode").map(String::as_str), Some("atoi"));
+    }
+
+    #[rocket::async_test]
+    async fn bech32_hrp_mismatch() {
+        let storage_config = StorageConfig::default();
+        let keyspace_hrps = KeyspaceHrps::new(&storage_config.keyspaces, &ApiConfig::default());
+        let rocket = construct_rocket(
+            rocket::ignite()
+                .manage(storage_config.partition_config.clone())
+                .manage(ApiConfig::default())
+                .manage(LedgerHintsCache::new())
+                .manage(QuerySettings::default())
+                .manage(keyspace_hrps),
+        );
+        let client = Client::tracked(rocket).await.expect("Invalid rocket instance!");
+
+        let res = client
+            .get(format!(
+                "/api/permanode/addresses/{}/outputs",
+                bech32_address("atoi", [7; 32])
+            ))
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::BadRequest);
+        check_cors_headers(&res);
+        let res = client
+            .get(format!(
+                "/api/other/addresses/{}/outputs",
+                bech32_address("iota", [7; 32])
+            ))
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn message_children_cursor() {
+        let message_id = "91515c13d2025f79ded3758abe5dc640591c3b6d58b1c52cd51d1fa0585774bc";
+        let state: StateData = (Some(vec![1, 2, 3]), Some(7), Some(100), vec![(MilestoneIndex(100), 7)]).into();
+        let cursor = hex::encode(bincode::serialize(&state).unwrap());
+        let response = ListenerResponse::MessageChildren {
+            message_id: message_id.to_string(),
+            max_results: 200,
+            page_size: 100,
+            count: 0,
+            children_message_ids: Vec::new(),
+            has_more: false,
+            state: Some(cursor.clone()),
+            next: Some(cursor).filter(|_| state.has_next()),
+        };
+        let body: Value = serde_json::to_value(&response).unwrap();
+        assert_eq!(body.get("messageId").and_then(Value::as_str), Some(message_id));
+        assert_eq!(body.get("pageSize").and_then(Value::as_u64), Some(100));
+        let next = body.get("next").and_then(Value::as_str).expect("No next cursor!");
+        let decoded = bincode::deserialize::<StateData>(&hex::decode(next).unwrap()).unwrap();
+        assert_eq!(decoded.paging_state, state.paging_state);
+        assert_eq!(decoded.last_partition_id, Some(7));
+        assert_eq!(decoded.last_milestone_index, Some(100));
+        assert_eq!(decoded.partition_ids, state.partition_ids);
+    }
+
+    #[test]
+    fn message_children_cap() {
+        let api_config = ApiConfig {
+            max_children: 10,
+            ..Default::default()
+        };
+        // The children of a single milestone are kept past the page size, so the page may exceed the cap
+        let children = || {
+            (0..25u8)
+                .map(|i| Partitioned::new(ParentRecord::new(MessageId::new([i; 32]), None), 0, 1))
+                .collect::<Vec<_>>()
+        };
+        let page_size = api_config.children_page_size(Some(100));
+        assert_eq!(page_size, 10);
+        let mut messages = children();
+        assert!(cap_children(&mut messages, api_config.max_children));
+        assert_eq!(messages.len(), 10);
+        assert_eq!(messages[9].message_id, MessageId::new([9; 32]));
+
+        // A page within the cap is not truncated
+        let mut messages = children();
+        messages.truncate(10);
+        assert!(!cap_children(&mut messages, api_config.max_children));
+        assert_eq!(messages.len(), 10);
+
+        // Zero disables the cap
+        let mut messages = children();
+        assert!(!cap_children(&mut messages, 0));
+        assert_eq!(messages.len(), 25);
+    }
+
+    #[test]
+    fn message_children_deferred_milestone() {
+        let child = |i: u8, milestone_index: u32| {
+            Partitioned::new(ParentRecord::new(MessageId::new([i; 32]), None), 0, milestone_index)
+        };
+        // The page reached the cap within the milestone 3, whose children are left to the next page
+        let mut messages = vec![child(0, 5), child(1, 5), child(2, 3), child(3, 3)];
+        assert!(defer_milestone(&mut messages, 3));
+        assert_eq!(
+            messages.iter().map(|record| record.message_id).collect::<Vec<_>>(),
+            vec![MessageId::new([0; 32]), MessageId::new([1; 32])]
+        );
+        // A milestone which is the whole page can't be deferred, it's truncated to the cap instead
+        let mut messages = (0..25u8).map(|i| child(i, 3)).collect::<Vec<_>>();
+        assert!(!defer_milestone(&mut messages, 3));
+        assert_eq!(messages.len(), 25);
+        assert!(cap_children(&mut messages, 10));
+    }
+
+    #[rocket::async_test]
+    async fn output_spending_linkage() {
+        use bee_message::prelude::{
+            ReferenceUnlock,
+            UnlockBlock,
+        };
+        let unlock = |byte: u8, inclusion_state: Option<LedgerInclusionState>| UnlockRes {
+            message_id: MessageId::new([byte; 32]),
+            block: UnlockBlock::Reference(ReferenceUnlock::new(0).unwrap()),
+            inclusion_state,
+        };
+        let metadata = |message_id: MessageId| {
+            let ledger_inclusion_state = match message_id.as_ref()[0] {
+                1 => LedgerInclusionState::Conflicting,
+                // The stored inclusion state of the spend is not yet updated
+                2 => LedgerInclusionState::Included,
+                _ => return futures::future::ready(Err(ListenerError::NoResults)),
+            };
+            futures::future::ready(Ok(MessageMetadata {
+                message_id,
+                parent_message_ids: Vec::new(),
+                is_solid: true,
+                referenced_by_milestone_index: Some(42),
+                ledger_inclusion_state: Some(ledger_inclusion_state),
+                should_promote: None,
+                should_reattach: None,
+                conflict_reason: None,
+            }))
+        };
+
+        let unlock_blocks = vec![
+            unlock(1, Some(LedgerInclusionState::Conflicting)),
+            unlock(3, None),
+            unlock(2, None),
+        ];
+        let spending = spending_metadata(&unlock_blocks, metadata)
+            .await
+            .unwrap()
+            .expect("Output is not spent!");
+        assert_eq!(spending.message_id, MessageId::new([2; 32]));
+        assert_eq!(spending.referenced_by_milestone_index, Some(42));
+
+        let unspent = spending_metadata(&unlock_blocks[..2], metadata).await.unwrap();
+        assert!(unspent.is_none());
+    }
+
+    #[rocket::async_test]
+    async fn output_history_walk() {
+        // A transaction spending the given input into two outputs
+        let transaction = |input: UtxoInput| {
+            let output = || single_output(Ed25519Address::new([0; 32]), 1_000_000);
+            transaction_payload(vec![input], vec![output(), output()], None)
+        };
+        let transaction_message = |payload: TransactionPayload| {
+            message(
+                0,
+                vec![MessageId::new([0; 32])],
+                Some(Payload::Transaction(Box::new(payload))),
+            )
+        };
+        // The message 1 creates the output, which is spent by the message 2
+        let creating = transaction(UtxoInput::new(TransactionId::new([0; 32]), 0).unwrap());
+        let output_id = OutputId::new(creating.id(), 1).unwrap();
+        let spending = transaction(UtxoInput::new(creating.id(), 1).unwrap());
+        let spending_id = spending.id();
+        let messages = vec![transaction_message(creating.clone()), transaction_message(spending)];
+        let fetch_message = |message_id: MessageId| {
+            futures::future::ready(
+                messages
+                    .get(message_id.as_ref()[0] as usize - 1)
+                    .cloned()
+                    .ok_or(ListenerError::NoResults),
+            )
+        };
+        let fetch_metadata = |message_id: MessageId| {
+            futures::future::ready(Ok(MessageMetadata {
+                message_id,
+                parent_message_ids: Vec::new(),
+                is_solid: true,
+                referenced_by_milestone_index: Some(40 + message_id.as_ref()[0] as u32),
+                ledger_inclusion_state: Some(LedgerInclusionState::Included),
+                should_promote: None,
+                should_reattach: None,
+                conflict_reason: None,
+            }))
+        };
+        let output_data = |unlock_blocks| OutputRes {
+            message_id: MessageId::new([1; 32]),
+            output: single_output(Ed25519Address::new([0; 32]), 1_000_000),
+            unlock_blocks,
+        };
+
+        let spent_output = output_data(vec![UnlockRes {
+            message_id: MessageId::new([2; 32]),
+            block: UnlockBlock::Reference(ReferenceUnlock::new(0).unwrap()),
+            inclusion_state: Some(LedgerInclusionState::Included),
+        }]);
+        let (created, spent) = output_history(output_id, &spent_output, fetch_metadata, fetch_message)
+            .await
+            .unwrap();
+        assert_eq!(created.message_id, MessageId::new([1; 32]).to_string());
+        assert_eq!(created.transaction_id, creating.id().to_string());
+        assert_eq!(created.milestone_index, Some(41));
+        assert_eq!(created.outputs.len(), 2);
+        assert!(created.outputs.contains(&output_id.to_string()));
+        let spent = spent.expect("Output is not spent!");
+        assert_eq!(spent.message_id, MessageId::new([2; 32]).to_string());
+        assert_eq!(spent.transaction_id, spending_id.to_string());
+        assert_eq!(spent.milestone_index, Some(42));
+        assert_eq!(
+            spent.outputs,
+            vec![
+                OutputId::new(spending_id, 0).unwrap().to_string(),
+                OutputId::new(spending_id, 1).unwrap().to_string()
+            ]
+        );
+
+        // An unspent output only has the creation side
+        let (created, spent) = output_history(output_id, &output_data(Vec::new()), fetch_metadata, fetch_message)
+            .await
+            .unwrap();
+        assert_eq!(created.milestone_index, Some(41));
+        assert!(spent.is_none());
+    }
+
+    #[rocket::async_test]
+    async fn spent_outputs_for_address() {
+        let address = Ed25519Address::new([0; 32]);
+        let output = || single_output(address, 1_000_000);
+        // The address owns three outputs, the first and last of which are spent by the message 1
+        let spending = transaction_payload(
+            vec![
+                UtxoInput::new(TransactionId::new([1; 32]), 0).unwrap(),
+                UtxoInput::new(TransactionId::new([1; 32]), 2).unwrap(),
+            ],
+            vec![output()],
+            None,
+        );
+        let spending_id = spending.id();
+        let spending_message = message(
+            0,
+            vec![MessageId::new([0; 32])],
+            Some(Payload::Transaction(Box::new(spending))),
+        );
+        let records = (0..3)
+            .map(|index| {
+                Partitioned::new(
+                    AddressRecord::new(
+                        output().kind(),
+                        TransactionId::new([1; 32]),
+                        index,
+                        1_000_000,
+                        Some(LedgerInclusionState::Included),
+                    ),
+                    0,
+                    1,
+                )
+            })
+            .collect::<Vec<_>>();
+        let fetch_output = |output_id: OutputId| {
+            let unlock_blocks = if output_id.index() == 1 {
+                Vec::new()
+            } else {
+                vec![UnlockRes {
+                    message_id: MessageId::new([1; 32]),
+                    block: UnlockBlock::Reference(ReferenceUnlock::new(0).unwrap()),
+                    inclusion_state: Some(LedgerInclusionState::Included),
+                }]
+            };
+            futures::future::ready(Ok(OutputRes {
+                message_id: MessageId::new([0; 32]),
+                output: output(),
+                unlock_blocks,
+            }))
+        };
+        let fetch_metadata = |message_id: MessageId| {
+            futures::future::ready(Ok(MessageMetadata {
+                message_id,
+                parent_message_ids: Vec::new(),
+                is_solid: true,
+                referenced_by_milestone_index: Some(42),
+                ledger_inclusion_state: Some(LedgerInclusionState::Included),
+                should_promote: None,
+                should_reattach: None,
+                conflict_reason: None,
+            }))
+        };
+        let fetch_message = |_| futures::future::ready(Ok(spending_message.clone()));
+
+        let spent = spent_outputs_of(&records, fetch_output, fetch_metadata, fetch_message)
+            .await
+            .unwrap();
+        assert_eq!(
+            spent.iter().map(|output| output.output_id.clone()).collect::<Vec<_>>(),
+            vec![
+                OutputId::new(TransactionId::new([1; 32]), 0).unwrap().to_string(),
+                OutputId::new(TransactionId::new([1; 32]), 2).unwrap().to_string()
+            ]
+        );
+        for output in spent.iter() {
+            assert_eq!(output.message_id, MessageId::new([1; 32]).to_string());
+            assert_eq!(output.transaction_id, spending_id.to_string());
+            assert_eq!(output.milestone_index, Some(42));
+        }
+    }
+
+    #[rocket::async_test]
+    async fn spent_outputs_lookups_run_concurrently() {
+        use std::sync::atomic::{
+            AtomicUsize,
+            Ordering,
+        };
+        let address = Ed25519Address::new([0; 32]);
+        let spending_message = message(
+            0,
+            vec![MessageId::new([0; 32])],
+            Some(Payload::Transaction(Box::new(transaction_payload(
+                vec![UtxoInput::new(TransactionId::new([1; 32]), 0).unwrap()],
+                vec![single_output(address, 1_000_000)],
+                None,
+            )))),
+        );
+        let records = (0..32)
+            .map(|index| {
+                Partitioned::new(
+                    AddressRecord::new(
+                        single_output(address, 1_000_000).kind(),
+                        TransactionId::new([1; 32]),
+                        index,
+                        1_000_000,
+                        Some(LedgerInclusionState::Included),
+                    ),
+                    0,
+                    1,
+                )
+            })
+            .collect::<Vec<_>>();
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+        let fetch_output = |output_id: OutputId| {
+            let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+            let output = single_output(address, 1_000_000);
+            async move {
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                // the later outputs are looked up faster, yet they're returned in the order of the records
+                tokio::time::sleep(Duration::from_millis(32 - output_id.index() as u64)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(OutputRes {
+                    message_id: MessageId::new([0; 32]),
+                    output,
+                    unlock_blocks: vec![UnlockRes {
+                        message_id: MessageId::new([1; 32]),
+                        block: UnlockBlock::Reference(ReferenceUnlock::new(0).unwrap()),
+                        inclusion_state: Some(LedgerInclusionState::Included),
+                    }],
+                })
+            }
+        };
+        let fetch_metadata = |message_id: MessageId| {
+            futures::future::ready(Ok(MessageMetadata {
+                message_id,
+                parent_message_ids: Vec::new(),
+                is_solid: true,
+                referenced_by_milestone_index: Some(42),
+                ledger_inclusion_state: Some(LedgerInclusionState::Included),
+                should_promote: None,
+                should_reattach: None,
+                conflict_reason: None,
+            }))
+        };
+        let fetch_message = |_| futures::future::ready(Ok(spending_message.clone()));
+
+        let spent = spent_outputs_of(&records, fetch_output, fetch_metadata, fetch_message)
+            .await
+            .unwrap();
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), SPENT_OUTPUTS_CONCURRENCY);
+        assert_eq!(
+            spent.iter().map(|output| output.output_id.clone()).collect::<Vec<_>>(),
+            (0..32)
+                .map(|index| OutputId::new(TransactionId::new([1; 32]), index).unwrap().to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[rocket::async_test]
+    async fn index_messages_payload_type_filter() {
+        use bee_message::prelude::IndexationPayload;
+
+        let indexation = Payload::Indexation(Box::new(IndexationPayload::new(b"index", b"data").unwrap()));
+        let transaction = Payload::Transaction(Box::new(transaction_payload(
+            vec![UtxoInput::new(TransactionId::new([0; 32]), 0).unwrap()],
+            vec![single_output(Ed25519Address::new([0; 32]), 1_000_000)],
+            Some(indexation.clone()),
+        )));
+        let parents = || vec![MessageId::new([0; 32])];
+        // The index rows of a transaction, an indexation and a payload-less message
+        let messages = vec![
+            message(0, parents(), Some(transaction)),
+            message(0, parents(), Some(indexation)),
+            message(0, parents(), None),
+        ];
+        let records = || {
+            (0..messages.len())
+                .map(|i| Partitioned::new(IndexationRecord::new(MessageId::new([i as u8; 32]), None), 0, 1))
+                .collect::<Vec<_>>()
+        };
+        let fetch = |message_id: MessageId| {
+            futures::future::ready(
+                messages
+                    .get(message_id.as_ref()[0] as usize)
+                    .cloned()
+                    .ok_or(ListenerError::NoResults),
+            )
+        };
+
+        let keep_all = |_: &Partitioned<IndexationRecord>| true;
+
+        let transactions = keeps_payload_type(records(), PayloadType::Transaction, keep_all, fetch)
+            .await
+            .unwrap();
+        assert_eq!(transactions, vec![true, false, false]);
+        let indexations = keeps_payload_type(records(), PayloadType::Indexation, keep_all, fetch)
+            .await
+            .unwrap();
+        assert_eq!(indexations, vec![false, true, false]);
+        let milestones = keeps_payload_type(records(), PayloadType::Milestone, keep_all, fetch)
+            .await
+            .unwrap();
+        assert_eq!(milestones, vec![false, false, false]);
+        assert!("value".parse::<PayloadType>().is_err());
+
+        // The messages of the records the filter drops aren't looked up
+        let fetched = std::sync::Mutex::new(Vec::new());
+        let fetch_logged = |message_id: MessageId| {
+            fetched.lock().unwrap().push(message_id);
+            fetch(message_id)
+        };
+        let keep_later = |record: &Partitioned<IndexationRecord>| record.message_id != MessageId::new([0; 32]);
+        let transactions = keeps_payload_type(records(), PayloadType::Transaction, keep_later, fetch_logged)
+            .await
+            .unwrap();
+        assert_eq!(transactions, vec![false, false, false]);
+        assert_eq!(
+            *fetched.lock().unwrap(),
+            vec![MessageId::new([1; 32]), MessageId::new([2; 32])]
+        );
+    }
+
+    #[rocket::async_test]
+    async fn tags_by_prefix() {
+        use bee_message::prelude::IndexationPayload;
+        use chronicle_storage::access::Tag;
+
+        // The tags of several indexation messages sharing a prefix, written by milestones of several partitions
+        let partition_config = PartitionConfig {
+            partition_count: 4,
+            milestone_chunk_size: 10,
+        };
+        let mut tables = HashMap::<(String, PartitionId), Vec<String>>::new();
+        let mut hints = HashMap::<String, HashMap<PartitionId, MilestoneIndex>>::new();
+        let indexes: [(&[u8], u32); 7] = [
+            (b"chronicle", 1),
+            (b"chronicle.rs", 15),
+            (b"chrysalis", 22),
+            (b"chronicle", 25),
+            (b"chronicle", 41),
+            (b"hornet", 3),
+            (b"bee", 3),
+        ];
+        for (index, milestone_index) in indexes.iter() {
+            let payload = IndexationPayload::new(index, b"data").unwrap();
+            let tag = Partitioned::new(
+                Tag(hex::encode(payload.index())),
+                partition_config.partition_id(*milestone_index),
+                *milestone_index,
+            );
+            let table = tables.entry((tag.bucket().to_owned(), tag.partition_id())).or_default();
+            if !table.contains(&tag.0) {
+                table.push(tag.0.clone());
+                table.sort();
+            }
+            hints
+                .entry(tag.bucket().to_owned())
+                .or_default()
+                .insert(tag.partition_id(), MilestoneIndex(tag.milestone_index()));
+        }
+        let fetch_partitions = |hint: Hint| {
+            futures::future::ready(
+                hints
+                    .get(&hint.hint)
+                    .filter(|_| hint.variant.to_string() == "tag")
+                    .map(|partitions| partitions.iter().map(|(id, index)| (*index, *id)).collect::<Vec<_>>())
+                    .ok_or(ListenerError::NoResults),
+            )
+        };
+        let fetch = |prefix: Partitioned<TagPrefix>, page_size: usize| {
+            let (from, to) = prefix.range();
+            futures::future::ready(
+                tables
+                    .get(&(prefix.bucket().to_owned(), prefix.partition_id()))
+                    .map(|table| {
+                        table
+                            .iter()
+                            .filter(|tag| **tag >= from && **tag < to)
+                            .take(page_size)
+                            .cloned()
+                            .collect::<Vec<_>>()
+                    })
+                    .ok_or(ListenerError::NoResults),
+            )
+        };
+
+        let prefix = TagPrefix::new(hex::encode("chr")).unwrap();
+        let tags = prefixed_tags(&prefix, 10, fetch_partitions, fetch).await.unwrap();
+        assert_eq!(
+            tags,
+            vec![
+                hex::encode("chronicle"),
+                hex::encode("chronicle.rs"),
+                hex::encode("chrysalis")
+            ]
+        );
+        let prefix = TagPrefix::new(hex::encode("chronicle")).unwrap();
+        assert_eq!(
+            prefixed_tags(&prefix, 1, fetch_partitions, fetch).await.unwrap(),
+            vec![hex::encode("chronicle")]
+        );
+        // An odd length prefix matches on the nibble
+        let prefix = TagPrefix::new("686f7".to_owned()).unwrap();
+        assert_eq!(
+            prefixed_tags(&prefix, 10, fetch_partitions, fetch).await.unwrap(),
+            vec![hex::encode("hornet")]
+        );
+        let prefix = TagPrefix::new(hex::encode("iota")).unwrap();
+        assert!(prefixed_tags(&prefix, 10, fetch_partitions, fetch)
+            .await
+            .unwrap()
+            .is_empty());
+        let no_partitions = |_: Hint| futures::future::ready(Err(ListenerError::NoResults));
+        assert!(prefixed_tags(&prefix, 10, no_partitions, fetch)
+            .await
+            .unwrap()
+            .is_empty());
+        // The tags of every partition are merged in order, each one once
+        let prefix = TagPrefix::new(hex::encode("ch")).unwrap();
+        assert_eq!(
+            prefixed_tags(&prefix, 2, fetch_partitions, fetch).await.unwrap(),
+            vec![hex::encode("chronicle"), hex::encode("chronicle.rs")]
+        );
+
+        assert!(TagPrefix::new("6".to_owned()).is_none());
+        assert!(TagPrefix::new("6G".to_owned()).is_none());
+        assert!(TagPrefix::new("6g".to_owned()).is_none());
+    }
+
+    #[test]
+    fn index_messages_since_milestone_filter() {
+        // The index rows of messages confirmed by the milestones 10 to 14 and an unconfirmed one
+        let records = (0..6u8)
+            .map(|i| {
+                Partitioned::new(
+                    IndexationRecord::new(
+                        MessageId::new([i; 32]),
+                        Some(LedgerInclusionState::NoTransaction).filter(|_| i < 5),
+                    ),
+                    0,
+                    10 + i as u32,
+                )
+            })
+            .collect::<Vec<_>>();
+        let ids = |since_milestone: Option<u32>, include_unconfirmed: bool| {
+            records
+                .iter()
+                .filter(|record| keeps_since_milestone(since_milestone, include_unconfirmed, record))
+                .map(|r| r.message_id.as_ref()[0])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(Some(12), true), vec![2, 3, 4, 5]);
+        assert_eq!(ids(Some(12), false), vec![2, 3, 4]);
+        assert_eq!(ids(Some(0), false), vec![0, 1, 2, 3, 4]);
+        assert!(ids(Some(15), false).is_empty());
+        // the unconfirmed row is kept by its inclusion state, not by its milestone index
+        assert_eq!(ids(Some(16), true), vec![5]);
+        assert_eq!(ids(None, false), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn address_outputs_type_filter() {
+        use bee_message::prelude::Output;
+        let address = Ed25519Address::new([7; 32]);
+        let outputs = vec![
+            Output::SignatureLockedSingle(
+                SignatureLockedSingleOutput::new(Address::Ed25519(address), 1_000_000).unwrap(),
+            ),
+            Output::SignatureLockedDustAllowance(
+                SignatureLockedDustAllowanceOutput::new(Address::Ed25519(address), 1_000_000).unwrap(),
+            ),
+        ];
+        let records = || {
+            outputs
+                .iter()
+                .enumerate()
+                .map(|(index, output)| {
+                    Partitioned::new(
+                        AddressRecord::new(
+                            output.kind(),
+                            TransactionId::new([1; 32]),
+                            index as u16,
+                            1_000_000,
+                            Some(LedgerInclusionState::Included),
+                        ),
+                        0,
+                        1,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let filter = |output_type: Option<&str>| {
+            let output_type = TypeQuery(output_type.map(str::to_owned)).output_type().unwrap();
+            records()
+                .iter()
+                .filter(|record| keeps_output_type(output_type, record))
+                .map(|record| record.index)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(filter(None), vec![0, 1]);
+        assert_eq!(filter(Some("single")), vec![0]);
+        assert_eq!(filter(Some("dust")), vec![1]);
+        assert!(TypeQuery(Some("treasury".to_owned())).output_type().is_err());
+    }
+
+    #[test]
+    fn message_parents_lookup() {
+        let parents = vec![MessageId::new([1; 32]), MessageId::new([2; 32])];
+        let message = message(0, parents.clone(), None);
+        let message_id = message.id().0;
+        let body = serde_json::to_value(&message_parents(message_id, &message)).unwrap();
+        assert_eq!(
+            body.get("messageId").and_then(Value::as_str),
+            Some(message_id.to_string().as_str())
+        );
+        assert_eq!(body.get("count").and_then(Value::as_u64), Some(2));
+        let parent_message_ids = body
+            .get("parentMessageIds")
+            .and_then(Value::as_array)
+            .expect("No parent message ids!")
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parent_message_ids,
+            parents.iter().map(|parent| parent.to_string()).collect::<Vec<_>>()
+        );
+    }
+
+    #[rocket::async_test]
+    async fn listener_tuning() {
+        let api_config = ApiConfig {
+            listen_address: ([127, 0, 0, 1], 8080).into(),
+            keep_alive_secs: 0,
+            ..Default::default()
+        };
+        // the config the listener is launched with
+        let rocket = construct_rocket(rocket::custom(RocketListener::figment(&api_config)));
+        let client = Client::tracked(rocket).await.expect("Invalid rocket instance!");
+        assert_eq!(client.rocket().config().keep_alive, 0);
+        assert_eq!(client.rocket().config().port, 8080);
+        let rocket = construct_rocket(rocket::custom(RocketListener::figment(&ApiConfig::default())));
+        let client = Client::tracked(rocket).await.expect("Invalid rocket instance!");
+        assert_eq!(client.rocket().config().keep_alive, 5);
+    }
+
+    #[test]
+    fn conflicting_metadata_reason() {
+        let metadata = |ledger_inclusion_state, conflict_reason| MessageMetadata {
+            message_id: MessageId::new([0; 32]),
+            parent_message_ids: Vec::new(),
+            is_solid: true,
+            referenced_by_milestone_index: Some(42),
+            ledger_inclusion_state: Some(ledger_inclusion_state),
+            should_promote: None,
+            should_reattach: None,
+            conflict_reason,
+        };
+
+        let body = serde_json::to_value(&ListenerResponse::from(metadata(
+            LedgerInclusionState::Conflicting,
+            Some(1),
+        )))
+        .unwrap();
+        assert_eq!(
+            body.get("ledgerInclusionState").and_then(Value::as_str),
+            Some("conflicting")
+        );
+        assert_eq!(
+            body.get("conflictReason").and_then(Value::as_str),
+            Some("input UTXO already spent")
+        );
+        // the "none" reason of included messages, and an absent reason, are omitted
+        for conflict_reason in [Some(0), None].iter() {
+            let body = serde_json::to_value(&ListenerResponse::from(metadata(
+                LedgerInclusionState::Included,
+                *conflict_reason,
+            )))
+            .unwrap();
+            assert!(body.get("conflictReason").is_none());
+        }
+    }
+
+    #[rocket::async_test]
+    async fn message_milestone() {
+        let milestone_id = MessageId::new([1; 32]);
+        let metadata = |referenced_by_milestone_index| MessageMetadata {
+            message_id: MessageId::new([0; 32]),
+            parent_message_ids: Vec::new(),
+            is_solid: true,
+            referenced_by_milestone_index,
+            ledger_inclusion_state: None,
+            should_promote: None,
+            should_reattach: None,
+            conflict_reason: None,
+        };
+        let milestones = |milestone_index: MilestoneIndex| {
+            futures::future::ready(if milestone_index.0 == 42 {
+                Ok(Milestone::new(milestone_id, 0))
+            } else {
+                Err(ListenerError::NoResults)
+            })
+        };
+
+        let (milestone_index, milestone) = confirming_milestone(&metadata(Some(42)), milestones).await.unwrap();
+        assert_eq!(milestone_index, MilestoneIndex(42));
+        assert_eq!(milestone.message_id(), &milestone_id);
+        assert!(matches!(
+            confirming_milestone(&metadata(None), milestones).await,
+            Err(ListenerError::NoResults)
+        ));
+    }
+
+    #[rocket::async_test]
+    async fn message_inclusion_proof() {
+        use bee_message::prelude::{
+            MilestonePayload,
+            MilestonePayloadEssence,
+            MILESTONE_MERKLE_PROOF_LENGTH,
+        };
+        use crypto::hashes::{
+            blake2b::Blake2b256,
+            Digest,
+        };
+        let leaf = |message_id: &MessageId| -> [u8; 32] {
+            Blake2b256::new()
+                .chain([0u8])
+                .chain(message_id.as_ref())
+                .finalize()
+                .into()
+        };
+        let node = |left: [u8; 32], right: [u8; 32]| -> [u8; 32] {
+            Blake2b256::new()
+                .chain([1u8])
+                .chain(left)
+                .chain(right)
+                .finalize()
+                .into()
+        };
+        let mut messages = HashMap::new();
+        let mut add_message = |parents: Vec<MessageId>, payload: Option<Payload>, referenced_by_milestone_index| {
+            let message = message(1, parents.clone(), payload);
+            let metadata = MessageMetadata {
+                message_id: message.id().0,
+                parent_message_ids: parents,
+                is_solid: true,
+                referenced_by_milestone_index,
+                ledger_inclusion_state: referenced_by_milestone_index.map(|_| LedgerInclusionState::Included),
+                should_promote: None,
+                should_reattach: None,
+                conflict_reason: None,
+            };
+            messages.insert(metadata.message_id, (message, metadata.clone()));
+            metadata
+        };
+        // old is referenced by an older milestone, so it's out of the cone
+        let old = add_message(vec![MessageId::new([0; 32])], None, Some(41));
+        let a = add_message(vec![old.message_id], None, Some(42));
+        let b = add_message(vec![a.message_id], None, Some(42));
+        let c = add_message(vec![a.message_id, old.message_id], None, Some(42));
+        let pending = add_message(vec![c.message_id], None, None);
+        // White flag order is a, b, c
+        let merkle_root = node(node(leaf(&a.message_id), leaf(&b.message_id)), leaf(&c.message_id));
+        let mut merkle_proof = [0; MILESTONE_MERKLE_PROOF_LENGTH];
+        merkle_proof.copy_from_slice(&merkle_root);
+        let milestone_payload = |merkle_proof| {
+            let essence = MilestonePayloadEssence::new(
+                MilestoneIndex(42),
+                0,
+                Parents::new(vec![b.message_id, c.message_id]).unwrap(),
+                merkle_proof,
+                0,
+                0,
+                vec![[0; 32]],
+                None,
+            )
+            .unwrap();
+            Payload::Milestone(Box::new(
+                MilestonePayload::new(essence, vec![Box::new([0; 64])]).unwrap(),
+            ))
+        };
+        let milestone = add_message(
+            vec![b.message_id, c.message_id],
+            Some(milestone_payload(merkle_proof)),
+            Some(42),
+        );
+        // A milestone issued with a merkle proof other than the one of its past cone
+        let forged = add_message(
+            vec![b.message_id, c.message_id],
+            Some(milestone_payload([0; MILESTONE_MERKLE_PROOF_LENGTH])),
+            Some(42),
+        );
+        let fetch_milestone = |milestone_index: MilestoneIndex| {
+            futures::future::ready(if milestone_index.0 == 42 {
+                Ok(Milestone::new(milestone.message_id, 0))
+            } else {
+                Err(ListenerError::NoResults)
+            })
+        };
+        let fetch_message = |message_id: MessageId| {
+            futures::future::ready(
+                messages
+                    .get(&message_id)
+                    .map(|(message, _)| message.clone())
+                    .ok_or(ListenerError::NoResults),
+            )
+        };
+        let fetch_metadata = |message_id: MessageId| {
+            futures::future::ready(
+                messages
+                    .get(&message_id)
+                    .map(|(_, metadata)| metadata.clone())
+                    .ok_or(ListenerError::NoResults),
+            )
+        };
+
+        let response = message_inclusion(b.clone(), fetch_milestone, fetch_message, fetch_metadata)
+            .await
+            .unwrap();
+        let body: Value = serde_json::to_value(&response).unwrap();
+        assert_eq!(body.get("state").and_then(Value::as_str), Some("included"));
+        assert_eq!(body.get("milestoneIndex").and_then(Value::as_u64), Some(42));
+        assert_eq!(
+            body.get("milestoneId").and_then(Value::as_str),
+            Some(milestone.message_id.to_string().as_str())
+        );
+        let proof = body.get("proof").expect("No inclusion proof!");
+        assert_eq!(
+            proof.get("merkleRoot").and_then(Value::as_str),
+            Some(hex::encode(merkle_root).as_str())
+        );
+        assert_eq!(proof.get("index").and_then(Value::as_u64), Some(1));
+        assert_eq!(proof.get("count").and_then(Value::as_u64), Some(3));
+        let path = proof
+            .get("path")
+            .and_then(Value::as_array)
+            .expect("No audit path!")
+            .iter()
+            .map(|hash| {
+                let mut bytes = [0; 32];
+                hex::decode_to_slice(hash.as_str().unwrap(), &mut bytes).unwrap();
+                bytes
+            })
+            .collect::<Vec<_>>();
+        // Hash the leaf of b up the audit path, which yields the milestone merkle proof
+        assert_eq!(path, vec![leaf(&a.message_id), leaf(&c.message_id)]);
+        assert_eq!(node(node(path[0], leaf(&b.message_id)), path[1]), merkle_root);
+
+        let response = message_inclusion(pending, fetch_milestone, fetch_message, fetch_metadata)
+            .await
+            .unwrap();
+        let body: Value = serde_json::to_value(&response).unwrap();
+        assert_eq!(body.get("state").and_then(Value::as_str), Some("unconfirmed"));
+        assert!(body.get("milestoneIndex").is_none());
+        assert!(body.get("proof").is_none());
+
+        // A conflicting message is confirmed, but it's not a leaf of the inclusion merkle tree
+        let conflicting = MessageMetadata {
+            ledger_inclusion_state: Some(LedgerInclusionState::Conflicting),
+            ..b
+        };
+        let response = message_inclusion(conflicting, fetch_milestone, fetch_message, fetch_metadata)
+            .await
+            .unwrap();
+        let body: Value = serde_json::to_value(&response).unwrap();
+        assert_eq!(body.get("state").and_then(Value::as_str), Some("conflicting"));
+        assert_eq!(body.get("milestoneIndex").and_then(Value::as_u64), Some(42));
+        assert!(body.get("proof").is_none());
+
+        // The proof is verified against the merkle proof of the milestone
+        let fetch_forged = |_| futures::future::ready(Ok(Milestone::new(forged.message_id, 0)));
+        let error = message_inclusion(b.clone(), fetch_forged, fetch_message, fetch_metadata)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("does not match"), "{}", error);
+
+        // A missing message of the cone, or at its border, fails the proof
+        for missing in vec![a.message_id, old.message_id] {
+            let fetch_metadata = |message_id: MessageId| {
+                futures::future::ready(
+                    messages
+                        .get(&message_id)
+                        .filter(|_| message_id != missing)
+                        .map(|(_, metadata)| metadata.clone())
+                        .ok_or(ListenerError::NoResults),
+                )
+            };
+            let error = message_inclusion(b.clone(), fetch_milestone, fetch_message, fetch_metadata)
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("is incomplete"), "{}", error);
+        }
+    }
+
+    #[rocket::async_test]
+    async fn computed_milestone_analytics_match_stored() {
+        use bee_message::prelude::{
+            MilestonePayload,
+            MilestonePayloadEssence,
+            MILESTONE_MERKLE_PROOF_LENGTH,
+        };
+
+        let transaction = |amount| {
+            Payload::Transaction(Box::new(transaction_payload(
+                vec![UtxoInput::new(TransactionId::new([0; 32]), 0).unwrap()],
+                vec![single_output(Ed25519Address::new([0; 32]), amount)],
+                None,
+            )))
+        };
+        let mut messages = HashMap::new();
+        let mut add_message =
+            |parents: Vec<MessageId>, payload: Option<Payload>, milestone_index, ledger_inclusion_state| {
+                let message = message(1, parents.clone(), payload);
+                let metadata = MessageMetadata {
+                    message_id: message.id().0,
+                    parent_message_ids: parents,
+                    is_solid: true,
+                    referenced_by_milestone_index: Some(milestone_index),
+                    ledger_inclusion_state: Some(ledger_inclusion_state),
+                    should_promote: None,
+                    should_reattach: None,
+                    conflict_reason: None,
+                };
+                messages.insert(metadata.message_id, (message, metadata.clone()));
+                metadata.message_id
+            };
+        // old is referenced by an older milestone, so it's out of the cone
+        let old = add_message(
+            vec![MessageId::new([0; 32])],
+            Some(transaction(5_000_000)),
+            41,
+            LedgerInclusionState::Included,
+        );
+        let a = add_message(
+            vec![old],
+            Some(transaction(1_000_000)),
+            42,
+            LedgerInclusionState::Included,
+        );
+        let b = add_message(
+            vec![a],
+            Some(transaction(2_000_000)),
+            42,
+            LedgerInclusionState::Conflicting,
+        );
+        let c = add_message(vec![a, old], None, 42, LedgerInclusionState::NoTransaction);
+        let essence = MilestonePayloadEssence::new(
+            MilestoneIndex(42),
+            0,
+            Parents::new(vec![b, c]).unwrap(),
+            [0; MILESTONE_MERKLE_PROOF_LENGTH],
+            0,
+            0,
+            vec![[0; 32]],
+            None,
+        )
+        .unwrap();
+        let milestone_payload = Box::new(MilestonePayload::new(essence, vec![Box::new([0; 64])]).unwrap());
+        let milestone = add_message(
+            vec![b, c],
+            Some(Payload::Milestone(milestone_payload.clone())),
+            42,
+            LedgerInclusionState::NoTransaction,
+        );
+        let fetch_message = |message_id: MessageId| {
+            futures::future::ready(
+                messages
+                    .get(&message_id)
+                    .map(|(message, _)| message.clone())
+                    .ok_or(ListenerError::NoResults),
+            )
+        };
+        let fetch_metadata = |message_id: MessageId| {
+            futures::future::ready(
+                messages
+                    .get(&message_id)
+                    .map(|(_, metadata)| metadata.clone())
+                    .ok_or(ListenerError::NoResults),
+            )
+        };
+
+        // The analytics the solidifier stores on ingestion, out of the collected milestone data
+        let stored: AnalyticData = MilestoneDataBuilder::new(42, CreatedBy::Incoming)
+            .with_milestone(milestone_payload)
+            .with_messages(
+                messages
+                    .values()
+                    .filter(|(_, metadata)| metadata.referenced_by_milestone_index == Some(42))
+                    .map(|(message, metadata)| FullMessage::new(message.clone(), metadata.clone())),
+            )
+            .build()
+            .unwrap()
+            .get_analytic_record()
+            .unwrap()
+            .into();
+        let computed = computed_milestone_analytics(42, milestone, fetch_message, fetch_metadata)
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&computed).unwrap(),
+            serde_json::to_value(&stored).unwrap()
+        );
+        assert_eq!(computed.message_count(), 4);
+        // A milestone which is not stored has no analytics
+        assert!(matches!(
+            computed_milestone_analytics(43, MessageId::new([9; 32]), fetch_message, fetch_metadata).await,
+            Err(ListenerError::NoResults)
+        ));
+        // An incomplete cone has no analytics, rather than the partial ones of the messages found
+        let complete_metadata = fetch_metadata;
+        let fetch_metadata = |message_id: MessageId| {
+            futures::future::ready(
+                messages
+                    .get(&message_id)
+                    .filter(|_| message_id != a)
+                    .map(|(_, metadata)| metadata.clone())
+                    .ok_or(ListenerError::NoResults),
+            )
+        };
+        let error = computed_milestone_analytics(42, milestone, fetch_message, fetch_metadata)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("is incomplete"), "{}", error);
+
+        // Nor is an incomplete cone backfilled, only the complete one is stored
+        let table = std::sync::Mutex::new(Vec::new());
+        let backfill = AnalyticsBackfill::new();
+        let hints = LedgerHints {
+            ledger_index: 42,
+            pruning_index: 41,
+        };
+        let store = |record: &chronicle_storage::access::AnalyticRecord| {
+            table.lock().unwrap().push(**record.milestone_index());
+            Ok(())
+        };
+        let incomplete = analytics_with_backfill(
+            "permanode",
+            42..43,
+            hints,
+            &backfill,
+            |_| futures::future::ready(Ok(AnalyticsData::default())),
+            |milestone_index| computed_milestone_record(milestone_index, milestone, fetch_message, fetch_metadata),
+            store,
+        )
+        .await
+        .unwrap();
+        assert!(incomplete.analytics.is_empty());
+        assert!(table.lock().unwrap().is_empty());
+        let complete = analytics_with_backfill(
+            "permanode",
+            42..43,
+            hints,
+            &backfill,
+            |_| futures::future::ready(Ok(AnalyticsData::default())),
+            |milestone_index| computed_milestone_record(milestone_index, milestone, fetch_message, complete_metadata),
+            store,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            complete
+                .analytics
+                .iter()
+                .map(|analytic_data| (analytic_data.range().clone(), analytic_data.message_count()))
+                .collect::<Vec<_>>(),
+            vec![(42..43, 4)]
+        );
+        assert_eq!(*table.lock().unwrap(), vec![42]);
+    }
+
+    #[rocket::async_test]
+    async fn milestone_confirmed_message_count() {
+        use chronicle_storage::access::{
+            AnalyticRecord,
+            MessageCount,
+            ReattachmentCount,
+            TransactionCount,
+            TransferredTokens,
+        };
+        let milestone = || futures::future::ready(Ok(Milestone::new(MessageId::new([1; 32]), 7)));
+        let analytics = AnalyticData::from(AnalyticRecord::new(
+            MilestoneIndex(42),
+            MessageCount(23),
+            TransactionCount(2),
+            TransferredTokens(100),
+            ReattachmentCount(0),
+        ));
+
+        let synced = || futures::future::ready(Err(ListenerError::NoResults));
+        let response = milestone_with_message_count(42, milestone(), futures::future::ready(Ok(analytics)), synced())
+            .await
+            .unwrap();
+        let body: Value = serde_json::to_value(&response).unwrap();
+        assert_eq!(body.get("index").and_then(Value::as_u64), Some(42));
+        assert_eq!(body.get("confirmedMessageCount").and_then(Value::as_u64), Some(23));
+        // a milestone without analytics is still returned
+        let response = milestone_with_message_count(
+            42,
+            milestone(),
+            futures::future::ready(Err(ListenerError::NoResults)),
+            synced(),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            response,
+            ListenerResponse::Milestone {
+                confirmed_message_count: None,
+                ..
+            }
+        ));
+    }
+
+    #[rocket::async_test]
+    async fn milestone_provenance() {
+        use chronicle_storage::access::SyncRecord;
+        // the sync table stub, seeded with the milestones created by each source, while the milestone 4 was synced
+        // before the source was recorded and the milestone 5 is not synced yet
+        let mut table = HashMap::new();
+        for (index, created_by) in vec![
+            (1, Some(CreatedBy::Incoming)),
+            (2, Some(CreatedBy::Expected)),
+            (3, Some(CreatedBy::Syncer)),
+            (4, None),
+        ] {
+            let synced =
+                SyncRecord::new(MilestoneIndex(index), Some(0), None).with_created_by(created_by.map(u8::from));
+            table.insert(index, synced);
+        }
+        let mut provenance = Vec::new();
+        for index in 1..=5 {
+            let synced = table.get(&index).cloned().ok_or(ListenerError::NoResults);
+            let response = milestone_with_message_count(
+                index,
+                futures::future::ready(Ok(Milestone::new(MessageId::new([1; 32]), 7))),
+                futures::future::ready(Err(ListenerError::NoResults)),
+                futures::future::ready(synced),
+            )
+            .await
+            .unwrap();
+            let body: Value = serde_json::to_value(&response).unwrap();
+            provenance.push(body.get("createdBy").and_then(Value::as_str).map(str::to_owned));
+        }
+        assert_eq!(
+            provenance,
+            vec![
+                Some("incoming".to_owned()),
+                Some("expected".to_owned()),
+                Some("syncer".to_owned()),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[rocket::async_test]
+    async fn analytics_series_grouping() {
+        use chronicle_storage::access::{
+            AnalyticRecord,
+            MessageCount,
+            ReattachmentCount,
+            TransactionCount,
+            TransferredTokens,
+        };
+        // The milestones 1..=10 confirm `index` messages and transfer `index * 100` tokens
+        let seeded = (1..=10)
+            .map(|index| {
+                let analytics = AnalyticData::from(AnalyticRecord::new(
+                    MilestoneIndex(index),
+                    MessageCount(index),
+                    TransactionCount(1),
+                    TransferredTokens(index as u64 * 100),
+                    ReattachmentCount(0),
+                ));
+                (index, analytics)
+            })
+            .collect::<Vec<_>>();
+        let in_flight = std::sync::atomic::AtomicUsize::new(0);
+        let max_in_flight = std::sync::atomic::AtomicUsize::new(0);
+        let fetch = |range: Range<u32>| {
+            let (in_flight, max_in_flight, seeded) = (&in_flight, &max_in_flight, &seeded);
+            async move {
+                let current = in_flight.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, std::sync::atomic::Ordering::SeqCst);
+                // the later buckets respond first
+                tokio::time::sleep(std::time::Duration::from_millis(20 - range.start as u64)).await;
+                in_flight.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(seeded
+                    .iter()
+                    .filter(|(index, _)| range.contains(index))
+                    .map(|(_, analytics)| analytics.clone())
+                    .collect())
+            }
+        };
+
+        let (buckets, truncated) = series_buckets(1, 11, 4).unwrap();
+        assert_eq!(buckets, vec![1..5, 5..9, 9..11]);
+        assert!(!truncated);
+        let points = analytics_series(buckets, fetch).await.unwrap();
+        let points = serde_json::to_value(&points).unwrap();
+        let field = |name: &str| {
+            points
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|point| point.get(name).and_then(Value::as_u64).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(field("start"), vec![1, 5, 9]);
+        assert_eq!(field("end"), vec![5, 9, 11]);
+        assert_eq!(field("message_count"), vec![10, 26, 19]);
+        assert_eq!(field("transaction_count"), vec![4, 4, 2]);
+        assert_eq!(field("transferred_tokens"), vec![1000, 2600, 1900]);
+
+        // the bucket fetches are bounded
+        let (buckets, _) = series_buckets(1, 11, 1).unwrap();
+        max_in_flight.store(0, std::sync::atomic::Ordering::SeqCst);
+        let points = analytics_series(buckets, fetch).await.unwrap();
+        assert_eq!(points.len(), 10);
+        assert_eq!(
+            max_in_flight.load(std::sync::atomic::Ordering::SeqCst),
+            SERIES_CONCURRENCY
+        );
+
+        let (buckets, truncated) = series_buckets(0, u32::MAX, 1).unwrap();
+        assert_eq!(buckets.len(), MAX_SERIES_POINTS);
+        assert!(truncated);
+        assert!(series_buckets(1, 11, 0).is_err());
+        assert!(series_buckets(11, 1, 4).is_err());
+    }
+
+    #[rocket::async_test]
+    async fn cold_analytics_backfill() {
+        use chronicle_storage::access::{
+            AnalyticRecord,
+            MessageCount,
+            ReattachmentCount,
+            TransactionCount,
+            TransferredTokens,
+        };
+        use std::collections::BTreeMap;
+        // the analytics table stub, which starts cold
+        let table = std::sync::Mutex::new(BTreeMap::<u32, AnalyticRecord>::new());
+        let computed = AtomicU64::new(0);
+        let backfill = AnalyticsBackfill::new();
+        // the milestones 11..=15 are stored
+        let hints = LedgerHints {
+            ledger_index: 15,
+            pruning_index: 10,
+        };
+        let fetch = |range: Range<u32>| {
+            let mut analytics_data = AnalyticsData::default();
+            analytics_data.merge(
+                table
+                    .lock()
+                    .unwrap()
+                    .range(range)
+                    .map(|(_, record)| record.clone())
+                    .collect::<Vec<_>>(),
+            );
+            futures::future::ready(Ok(analytics_data))
+        };
+        let compute = |milestone_index: u32| {
+            computed.fetch_add(1, Ordering::SeqCst);
+            async move {
+                // yield, so the concurrent query runs while the milestones are backfilled
+                tokio::task::yield_now().await;
+                Ok(AnalyticRecord::new(
+                    MilestoneIndex(milestone_index),
+                    MessageCount(milestone_index),
+                    TransactionCount(1),
+                    TransferredTokens(100),
+                    ReattachmentCount(0),
+                ))
+            }
+        };
+        let store = |record: &AnalyticRecord| {
+            table.lock().unwrap().insert(**record.milestone_index(), record.clone());
+            Ok(())
+        };
+        let range = |analytics_data: &AnalyticsData| {
+            analytics_data
+                .analytics
+                .iter()
+                .map(|analytic_data| (analytic_data.range().clone(), analytic_data.message_count()))
+                .collect::<Vec<_>>()
+        };
+
+        // the cold query computes the analytics of the stored milestones, and populates the table, while the
+        // concurrent cold query awaits them rather than computing them again (or leaving them out)
+        let (cold, concurrent) = futures::future::join(
+            analytics_with_backfill("permanode", 1..i32::MAX as u32, hints, &backfill, fetch, compute, store),
+            analytics_with_backfill("permanode", 1..i32::MAX as u32, hints, &backfill, fetch, compute, store),
+        )
+        .await;
+        assert_eq!(range(&cold.unwrap()), vec![(11..16, 65)]);
+        assert_eq!(range(&concurrent.unwrap()), vec![(11..16, 65)]);
+        assert_eq!(computed.load(Ordering::SeqCst), 5);
+        assert_eq!(
+            table.lock().unwrap().keys().cloned().collect::<Vec<_>>(),
+            vec![11, 12, 13, 14, 15]
+        );
+        // the following queries are served by the table
+        let warm = analytics_with_backfill("permanode", 12..14, hints, &backfill, fetch, compute, store)
+            .await
+            .unwrap();
+        assert_eq!(range(&warm), vec![(12..14, 25)]);
+        assert_eq!(computed.load(Ordering::SeqCst), 5);
+        // nothing is backfilled without stored milestones
+        let empty = analytics_with_backfill(
+            "permanode",
+            1..10,
+            LedgerHints::default(),
+            &backfill,
+            fetch,
+            compute,
+            store,
+        )
+        .await
+        .unwrap();
+        assert!(empty.analytics.is_empty());
+        assert_eq!(computed.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn configured_read_consistency() {
+        let settings = |read_consistency| {
+            QuerySettings::new(&ApiConfig {
+                read_consistency,
+                ..Default::default()
+            })
+        };
+        assert!(matches!(QuerySettings::default().consistency(), Consistency::One));
+        assert!(matches!(
+            settings(ReadConsistency::LocalQuorum).consistency(),
+            Consistency::LocalQuorum
+        ));
+        assert!(matches!(
+            settings(ReadConsistency::Quorum).consistency(),
+            Consistency::Quorum
+        ));
+    }
+
+    #[test]
+    fn configured_analytics_store() {
+        let mut config = Config::default();
+        config.storage_config.keyspaces[0].row_ttl_secs = Some(3600);
+        config.broker_config.sync_write_consistency = WriteConsistency::Quorum;
+        config.broker_config.retries_per_query = 7;
+        let keyspace_name = config.storage_config.keyspaces[0].name.clone();
+        let store_settings = AnalyticsStoreSettings::new(&config);
+        // the backfilled records expire with the rest of the keyspace rows
+        let keyspace = store_settings.keyspace(&keyspace_name);
+        assert_eq!(keyspace.row_ttl_secs(), Some(3600));
+        assert!(Insert::<Synckey, AnalyticRecord>::statement(&keyspace).ends_with(" USING TTL 3600"));
+        assert!(matches!(store_settings.consistency(), Consistency::Quorum));
+        assert_eq!(store_settings.retries_per_query, 7);
+    }
+
+    #[rocket::async_test]
+    async fn query_timeout() {
+        struct InFlight(std::sync::Arc<std::sync::atomic::AtomicBool>);
+        impl Drop for InFlight {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+        let request_id = RequestId::generate();
+        let cancelled = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let in_flight = InFlight(cancelled.clone());
+        let slow_storage = async move {
+            let _in_flight = in_flight;
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            Ok(())
+        };
+        let res = with_query_timeout(&request_id, 10, slow_storage).await;
+        assert!(matches!(res, Err(ListenerError::Timeout)));
+        assert_eq!(res.unwrap_err().status(), Status::GatewayTimeout);
+        assert!(cancelled.load(Ordering::SeqCst));
+
+        let fast_storage = async { Ok(1) };
+        assert_eq!(with_query_timeout(&request_id, 10, fast_storage).await.unwrap(), 1);
+    }
+
+    #[test]
+    fn query_timeout_aborts_on_the_node() {
+        let settings = |timeout_ms| {
+            QuerySettings::new(&ApiConfig {
+                query_timeout_ms: timeout_ms,
+                ..Default::default()
+            })
+        };
+        let keyspace = settings(10).keyspace(ChronicleKeyspace::new("permanode".to_owned()));
+        assert!(Select::<MessageId, Message>::statement(&keyspace).ends_with("message_id = ? USING TIMEOUT 10ms"));
+        let keyspace = settings(0).keyspace(ChronicleKeyspace::new("permanode".to_owned()));
+        assert!(Select::<MessageId, Message>::statement(&keyspace).ends_with("message_id = ?"));
+    }
+
+    #[rocket::async_test]
+    async fn request_id() {
+        let rocket = construct_rocket(rocket::ignite().manage(HashSet::<String>::new()));
+        let client = Client::tracked(rocket).await.expect("Invalid rocket instance!");
+
+        // a supplied request id is echoed
+        let res = client
+            .get("/api/info")
+            .header(Header::new(REQUEST_ID_HEADER, "test-request-id"))
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::Ok);
+        assert_eq!(res.headers().get_one(REQUEST_ID_HEADER), Some("test-request-id"));
+
+        // otherwise one is generated for every request
+        let res = client.get("/api/info").dispatch().await;
+        let first = res.headers().get_one(REQUEST_ID_HEADER).map(String::from);
+        assert!(first.as_ref().map(|id| !id.is_empty()).unwrap_or(false));
+        let res = client.get("/api/info").dispatch().await;
+        assert_ne!(res.headers().get_one(REQUEST_ID_HEADER).map(String::from), first);
+
+        // and included in the error responses
+        let res = client
+            .get("/api/permanode/milestones/1")
+            .header(Header::new(REQUEST_ID_HEADER, "test-error-id"))
+            .dispatch()
+            .await;
+        assert_eq!(res.status(), Status::NotFound);
+        assert_eq!(res.headers().get_one(REQUEST_ID_HEADER), Some("test-error-id"));
+        let body: Value = serde_json::from_str(&res.into_string().await.expect("No body returned!"))
+            .expect("Failed to deserialize response!");
+        assert_eq!(body.get("requestId").and_then(Value::as_str), Some("test-error-id"));
+    }
 }
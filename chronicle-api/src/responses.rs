@@ -12,7 +12,10 @@ use bee_rest_api::types::dtos::{
     OutputDto,
     PayloadDto,
 };
-use chronicle_broker::AnalyticData;
+use chronicle_broker::{
+    AnalyticData,
+    AnalyticDelta,
+};
 use chronicle_storage::access::{
     AddressRecord,
     IndexationRecord,
@@ -21,16 +24,18 @@ use chronicle_storage::access::{
     ParentRecord,
     Partitioned,
 };
+use schemars::JsonSchema;
 use serde::{
     Deserialize,
     Serialize,
 };
 use std::{
     borrow::Cow,
+    collections::BTreeMap,
     convert::TryFrom,
 };
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub(crate) enum ListenerResponse {
     /// Response of GET /info
@@ -60,9 +65,14 @@ pub(crate) enum ListenerResponse {
         network_id: String,
         #[serde(rename = "parentMessageIds")]
         parents: Vec<String>,
+        #[schemars(with = "Option<serde_json::Value>")]
         payload: Option<PayloadDto>,
         nonce: String,
     },
+    /// Response of POST /api/<keyspace>/messages, the messages by id (null if not found)
+    Messages {
+        messages: BTreeMap<String, Option<ListenerResponse>>,
+    },
     /// Response of GET /api/<keyspace>/messages/<message_id>/metadata
     MessageMetadata {
         #[serde(rename = "messageId")]
@@ -76,6 +86,7 @@ pub(crate) enum ListenerResponse {
         referenced_by_milestone_index: Option<u32>,
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(rename = "ledgerInclusionState")]
+        #[schemars(with = "Option<String>")]
         ledger_inclusion_state: Option<LedgerInclusionState>,
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(rename = "shouldPromote")]
@@ -83,6 +94,40 @@ pub(crate) enum ListenerResponse {
         #[serde(skip_serializing_if = "Option::is_none")]
         #[serde(rename = "shouldReattach")]
         should_reattach: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(rename = "conflictReason")]
+        conflict_reason: Option<String>,
+    },
+    /// Response of GET /api/<keyspace>/messages/<message_id>/milestone
+    MessageMilestone {
+        #[serde(rename = "messageId")]
+        message_id: String,
+        #[serde(rename = "milestoneIndex")]
+        milestone_index: u32,
+        #[serde(rename = "milestoneId")]
+        milestone_id: String,
+    },
+    /// Response of GET /api/<keyspace>/messages/<message_id>/inclusion
+    MessageInclusion {
+        #[serde(rename = "messageId")]
+        message_id: String,
+        state: InclusionState,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(rename = "milestoneIndex")]
+        milestone_index: Option<u32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(rename = "milestoneId")]
+        milestone_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        proof: Option<MerkleProof>,
+    },
+    /// Response of GET /api/<keyspace>/messages/<message_id>/parents
+    MessageParents {
+        #[serde(rename = "messageId")]
+        message_id: String,
+        count: usize,
+        #[serde(rename = "parentMessageIds")]
+        parent_message_ids: Vec<String>,
     },
     /// Response of GET /api/<keyspace>/messages/<message_id>/children
     MessageChildren {
@@ -90,10 +135,16 @@ pub(crate) enum ListenerResponse {
         message_id: String,
         #[serde(rename = "maxResults")]
         max_results: usize,
+        #[serde(rename = "pageSize")]
+        page_size: usize,
         count: usize,
         #[serde(rename = "childrenMessageIds")]
         children_message_ids: Vec<String>,
+        // Whether children past the max children were truncated
+        #[serde(rename = "hasMore")]
+        has_more: bool,
         state: Option<String>,
+        next: Option<String>,
     },
     /// Response of GET /api/<keyspace>/messages/<message_id>/children[?expanded=true]
     MessageChildrenExpanded {
@@ -101,26 +152,44 @@ pub(crate) enum ListenerResponse {
         message_id: String,
         #[serde(rename = "maxResults")]
         max_results: usize,
+        #[serde(rename = "pageSize")]
+        page_size: usize,
         count: usize,
         #[serde(rename = "childrenMessageIds")]
         children_message_ids: Vec<Record>,
+        // Whether children past the max children were truncated
+        #[serde(rename = "hasMore")]
+        has_more: bool,
         state: Option<String>,
+        next: Option<String>,
     },
     /// Response of GET /api/<keyspace>/messages?<index>
     MessagesForIndex {
         index: String,
         #[serde(rename = "maxResults")]
         max_results: usize,
+        #[serde(rename = "pageSize")]
+        page_size: usize,
         count: usize,
         #[serde(rename = "messageIds")]
         message_ids: Vec<String>,
         state: Option<String>,
     },
+    /// Response of GET /api/<keyspace>/tags?<prefix>
+    Tags {
+        prefix: String,
+        #[serde(rename = "pageSize")]
+        page_size: usize,
+        count: usize,
+        tags: Vec<String>,
+    },
     /// Response of GET /api/<keyspace>/messages?<index>[&expanded=true]
     MessagesForIndexExpanded {
         index: String,
         #[serde(rename = "maxResults")]
         max_results: usize,
+        #[serde(rename = "pageSize")]
+        page_size: usize,
         count: usize,
         #[serde(rename = "messageIds")]
         message_ids: Vec<Record>,
@@ -134,10 +203,15 @@ pub(crate) enum ListenerResponse {
         address: String,
         #[serde(rename = "maxResults")]
         max_results: usize,
+        #[serde(rename = "pageSize")]
+        page_size: usize,
         count: usize,
         #[serde(rename = "outputIds")]
+        #[schemars(with = "Vec<String>")]
         output_ids: Vec<OutputId>,
         state: Option<String>,
+        #[serde(flatten)]
+        hints: Option<LedgerHints>,
     },
     /// Response of GET /api/<keyspace>/addresses/<address>/outputs[?expanded=true]
     OutputsForAddressExpanded {
@@ -147,10 +221,31 @@ pub(crate) enum ListenerResponse {
         address: String,
         #[serde(rename = "maxResults")]
         max_results: usize,
+        #[serde(rename = "pageSize")]
+        page_size: usize,
         count: usize,
         #[serde(rename = "outputIds")]
         output_ids: Vec<Record>,
         state: Option<String>,
+        #[serde(flatten)]
+        hints: Option<LedgerHints>,
+    },
+    /// Response of GET /api/<keyspace>/addresses/ed25519/<address>/spent-outputs
+    SpentOutputsForAddress {
+        // The type of the address (1=Ed25519).
+        #[serde(rename = "addressType")]
+        address_type: u8,
+        address: String,
+        #[serde(rename = "maxResults")]
+        max_results: usize,
+        #[serde(rename = "pageSize")]
+        page_size: usize,
+        count: usize,
+        #[serde(rename = "spentOutputs")]
+        spent_outputs: Vec<SpentOutput>,
+        state: Option<String>,
+        #[serde(flatten)]
+        hints: Option<LedgerHints>,
     },
     /// Response of GET /api/<keyspace>/outputs/<output_id>
     Output {
@@ -162,7 +257,26 @@ pub(crate) enum ListenerResponse {
         output_index: u16,
         #[serde(rename = "isSpent")]
         is_spent: bool,
+        #[schemars(with = "serde_json::Value")]
         output: OutputDto,
+        #[serde(flatten)]
+        hints: Option<LedgerHints>,
+    },
+    /// Response of GET /api/<keyspace>/outputs/<output_id>/spending
+    OutputSpending {
+        #[serde(rename = "messageId")]
+        message_id: String,
+        #[serde(rename = "transactionId")]
+        transaction_id: String,
+        #[serde(rename = "milestoneIndex")]
+        milestone_index: Option<u32>,
+    },
+    /// Response of GET /api/<keyspace>/outputs/<output_id>/history
+    OutputHistory {
+        #[serde(rename = "outputId")]
+        output_id: String,
+        created: OutputTransaction,
+        spent: Option<OutputTransaction>,
     },
     /// Response of GET /api/<keyspace>/milestone/<index>
     Milestone {
@@ -171,9 +285,33 @@ pub(crate) enum ListenerResponse {
         #[serde(rename = "messageId")]
         message_id: String,
         timestamp: u64,
+        #[serde(rename = "confirmedMessageCount")]
+        confirmed_message_count: Option<u128>,
+        #[serde(rename = "createdBy")]
+        created_by: Option<String>,
     },
     /// Response of GET /api/<keyspace>/analytics[?start=<u32>&end=<u32>]
-    Analytics { ranges: Vec<AnalyticData> },
+    Analytics {
+        #[schemars(with = "Vec<serde_json::Value>")]
+        ranges: Vec<AnalyticData>,
+    },
+    /// Response of GET /api/<keyspace>/analytics/series?<from>&<to>&<bucket>
+    AnalyticsSeries {
+        bucket: u32,
+        #[schemars(with = "Vec<serde_json::Value>")]
+        points: Vec<AnalyticData>,
+        /// Whether the requested range got truncated to the max number of points
+        truncated: bool,
+    },
+    /// Response of GET /api/<keyspace>/milestones/diff?<a>&<b>
+    MilestoneDiff {
+        #[schemars(with = "serde_json::Value")]
+        a: AnalyticData,
+        #[schemars(with = "serde_json::Value")]
+        b: AnalyticData,
+        #[schemars(with = "serde_json::Value")]
+        delta: AnalyticDelta,
+    },
 }
 
 impl TryFrom<Message> for ListenerResponse {
@@ -203,13 +341,111 @@ impl From<MessageMetadata> for ListenerResponse {
             ledger_inclusion_state: metadata.ledger_inclusion_state,
             should_promote: metadata.should_promote,
             should_reattach: metadata.should_reattach,
+            conflict_reason: metadata
+                .conflict_reason
+                .filter(|&code| code != 0)
+                .map(|code| conflict_reason(code).to_string()),
         }
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Map a node conflict reason code to a readable description
+pub fn conflict_reason(code: u8) -> &'static str {
+    match code {
+        0 => "none",
+        1 => "input UTXO already spent",
+        2 => "input UTXO already spent in this milestone",
+        3 => "input UTXO not found",
+        4 => "inputs and outputs sum mismatch",
+        5 => "invalid signature",
+        6 => "invalid dust allowance",
+        255 => "semantic validation failed",
+        _ => "unknown",
+    }
+}
+
+/// The data horizon hints of the keyspace, as returned by the IOTA node apis
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct LedgerHints {
+    /// The latest stored milestone index
+    #[serde(rename = "ledgerIndex")]
+    pub ledger_index: u32,
+    /// The milestone index below the earliest stored one, the data up to it is not available
+    #[serde(rename = "pruningIndex")]
+    pub pruning_index: u32,
+}
+
+/// The inclusion state of a message, which is unconfirmed until a milestone references it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub(crate) enum InclusionState {
+    #[serde(rename = "unconfirmed")]
+    Unconfirmed,
+    #[serde(rename = "conflicting")]
+    Conflicting,
+    #[serde(rename = "included")]
+    Included,
+    #[serde(rename = "noTransaction")]
+    NoTransaction,
+}
+
+impl From<LedgerInclusionState> for InclusionState {
+    fn from(ledger_inclusion_state: LedgerInclusionState) -> Self {
+        match ledger_inclusion_state {
+            LedgerInclusionState::Conflicting => InclusionState::Conflicting,
+            LedgerInclusionState::Included => InclusionState::Included,
+            LedgerInclusionState::NoTransaction => InclusionState::NoTransaction,
+        }
+    }
+}
+
+/// The merkle audit path (RFC 6962) of an included message, which hashes up to the inclusion merkle proof of its
+/// milestone
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct MerkleProof {
+    /// The inclusion merkle proof of the milestone
+    #[serde(rename = "merkleRoot")]
+    pub merkle_root: String,
+    /// The position of the message within the included messages, in white flag order
+    pub index: usize,
+    /// The number of messages included by the milestone
+    pub count: usize,
+    /// The sibling hashes from the message leaf up to the merkle root
+    pub path: Vec<String>,
+}
+
+/// A transaction of the history of an output
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct OutputTransaction {
+    #[serde(rename = "messageId")]
+    pub message_id: String,
+    #[serde(rename = "transactionId")]
+    pub transaction_id: String,
+    /// The index of the milestone which confirmed the transaction
+    #[serde(rename = "milestoneIndex")]
+    pub milestone_index: Option<u32>,
+    /// The ids of the outputs produced by the transaction
+    pub outputs: Vec<String>,
+}
+
+/// A spent output, along with the transaction which spent it
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct SpentOutput {
+    #[serde(rename = "outputId")]
+    pub output_id: String,
+    /// The message of the spending transaction
+    #[serde(rename = "messageId")]
+    pub message_id: String,
+    #[serde(rename = "transactionId")]
+    pub transaction_id: String,
+    /// The index of the milestone which confirmed the spending transaction
+    #[serde(rename = "milestoneIndex")]
+    pub milestone_index: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub(crate) struct Record {
     pub id: String,
+    #[schemars(with = "Option<String>")]
     pub inclusion_state: Option<LedgerInclusionState>,
     pub milestone_index: u32,
 }
@@ -271,3 +507,10 @@ impl From<(Option<Vec<u8>>, Option<u16>, Option<u32>, Vec<(MilestoneIndex, u16)>
         }
     }
 }
+
+impl StateData {
+    /// Whether there are partitions left to page through
+    pub fn has_next(&self) -> bool {
+        !self.partition_ids.is_empty()
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use std::sync::{
+    Arc,
+    Mutex,
+    PoisonError,
+};
+
+/// The min interval of the analytics snapshots, so the dashboards don't get flooded
+pub const MIN_ANALYTICS_INTERVAL: Duration = Duration::from_secs(1);
+/// The interval of the analytics snapshots, till it's set by `SetAnalyticsInterval`
+pub const DEFAULT_ANALYTICS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The analytics of the milestones analyzed since the previous snapshot, which is emitted to the dashboards every
+/// analytics interval
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnalyticsSnapshot {
+    /// The number of analyzed milestones
+    pub milestone_count: u64,
+    /// The number of messages confirmed by the milestones
+    pub message_count: u64,
+    /// The number of included transactions
+    pub transaction_count: u64,
+    /// The amount of transferred tokens
+    pub transferred_tokens: u64,
+    /// The number of reattached transactions
+    pub reattachment_count: u64,
+}
+
+/// The analytics of the milestones analyzed by the solidifiers of a broker since its previous snapshot, which is
+/// shared through the broker handle
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BrokerAnalytics(Arc<Mutex<AnalyticsSnapshot>>);
+
+impl BrokerAnalytics {
+    /// Roll up the analytic record of a milestone, once it's stored, into the next snapshot
+    pub(crate) fn rollup(&self, analytic_record: &AnalyticRecord) {
+        let mut snapshot = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        snapshot.milestone_count += 1;
+        snapshot.message_count += **analytic_record.message_count() as u64;
+        snapshot.transaction_count += **analytic_record.transaction_count() as u64;
+        snapshot.transferred_tokens += **analytic_record.transferred_tokens();
+        snapshot.reattachment_count += **analytic_record.reattachment_count() as u64;
+    }
+    /// Take the analytics rolled up since the previous snapshot
+    pub(crate) fn take(&self) -> AnalyticsSnapshot {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// Schedules the analytics snapshots, the current instant is always provided so the clock can be mocked
+#[derive(Debug, Clone)]
+pub(crate) struct AnalyticsTicker {
+    interval: Duration,
+    next_snapshot: Instant,
+}
+
+impl AnalyticsTicker {
+    pub(crate) fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            next_snapshot: now + interval,
+        }
+    }
+    /// Set the interval of the snapshots, the next snapshot is due one interval from now
+    pub(crate) fn set_interval(&mut self, interval: Duration, now: Instant) -> anyhow::Result<()> {
+        if interval < MIN_ANALYTICS_INTERVAL {
+            bail!(
+                "The analytics interval {:?} is below the min interval {:?}",
+                interval,
+                MIN_ANALYTICS_INTERVAL
+            );
+        }
+        self.interval = interval;
+        self.next_snapshot = now + interval;
+        Ok(())
+    }
+    /// The instant the next snapshot is due
+    pub(crate) fn next_snapshot(&self) -> Instant {
+        self.next_snapshot
+    }
+    /// Take the snapshot of the accumulated analytics if it's due, and schedule the next one
+    pub(crate) fn poll_snapshot(&mut self, now: Instant, analytics: &BrokerAnalytics) -> Option<AnalyticsSnapshot> {
+        if now < self.next_snapshot {
+            return None;
+        }
+        self.next_snapshot = now + self.interval;
+        Some(analytics.take())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bee_message::milestone::MilestoneIndex;
+
+    #[test]
+    fn analytics_interval() {
+        let record = |message_count| {
+            AnalyticRecord::new(
+                MilestoneIndex(1),
+                MessageCount(message_count),
+                TransactionCount(2),
+                TransferredTokens(100),
+                ReattachmentCount(0),
+            )
+        };
+        // the dashboard stub collects the snapshots emitted at the mocked instants
+        let start = Instant::now();
+        let mut dashboard = Vec::new();
+        let mut ticker = AnalyticsTicker::new(DEFAULT_ANALYTICS_INTERVAL, start);
+        let analytics = BrokerAnalytics::default();
+        // the analytics of another broker are accumulated apart
+        let other_analytics = BrokerAnalytics::default();
+        other_analytics.rollup(&record(3));
+        let mut emit_at = |ticker: &mut AnalyticsTicker, secs| {
+            if let Some(snapshot) = ticker.poll_snapshot(start + Duration::from_secs(secs), &analytics) {
+                dashboard.push((secs, snapshot));
+            }
+        };
+        analytics.rollup(&record(10));
+        analytics.clone().rollup(&record(5));
+        emit_at(&mut ticker, 30);
+        emit_at(&mut ticker, 60);
+        emit_at(&mut ticker, 90);
+        // too short intervals are rejected and keep the current one
+        assert!(ticker.set_interval(Duration::from_millis(100), start).is_err());
+        assert_eq!(ticker.next_snapshot(), start + Duration::from_secs(120));
+        // a shorter interval takes effect from the instant it's set
+        ticker
+            .set_interval(Duration::from_secs(10), start + Duration::from_secs(95))
+            .unwrap();
+        analytics.rollup(&record(7));
+        for secs in 96..=125 {
+            emit_at(&mut ticker, secs);
+        }
+        let emitted = dashboard.iter().map(|(secs, _)| *secs).collect::<Vec<_>>();
+        assert_eq!(emitted, vec![60, 105, 115, 125]);
+        assert_eq!(
+            dashboard[0].1,
+            AnalyticsSnapshot {
+                milestone_count: 2,
+                message_count: 15,
+                transaction_count: 4,
+                transferred_tokens: 200,
+                reattachment_count: 0,
+            }
+        );
+        assert_eq!(dashboard[1].1.message_count, 7);
+        assert_eq!(dashboard[2].1, AnalyticsSnapshot::default());
+        assert_eq!(other_analytics.take().message_count, 3);
+    }
+}
@@ -2,7 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use chronicle_common::get_history_mut;
+use chronicle_common::{
+    get_history_mut,
+    jobs::{
+        Jobs,
+        JOBS,
+    },
+};
 use futures::SinkExt;
 
 #[async_trait]
@@ -11,12 +17,36 @@ impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
         status?;
         if let Some(ref mut supervisor) = supervisor {
             self.service.update_status(ServiceStatus::Running);
-            while let Some(event) = self.inbox.recv().await {
+            loop {
+                let event = tokio::select! {
+                    event = self.inbox.recv() => event,
+                    _ = tokio::time::sleep_until(self.analytics_ticker.next_snapshot().into()) => {
+                        self.emit_analytics_snapshot(Instant::now()).await;
+                        continue;
+                    }
+                };
+                let event = match event {
+                    Some(event) => event,
+                    None => break,
+                };
                 match event {
                     BrokerEvent::Importer(importer_session) => {
+                        JOBS.update(|jobs| track_import_job(jobs, &importer_session));
                         let socket_msg = BrokerSocketMsg::ChronicleBroker(importer_session);
                         self.response_to_sockets(&socket_msg).await;
                     }
+                    BrokerEvent::Pruner(pruner_session) => {
+                        let socket_msg = BrokerSocketMsg::ChronicleBroker(pruner_session);
+                        self.response_to_sockets(&socket_msg).await;
+                    }
+                    BrokerEvent::Syncer(syncer_session) => {
+                        let socket_msg = BrokerSocketMsg::ChronicleBroker(syncer_session);
+                        self.response_to_sockets(&socket_msg).await;
+                    }
+                    BrokerEvent::Feed(feed_status) => {
+                        let socket_msg = BrokerSocketMsg::ChronicleBroker(feed_status);
+                        self.response_to_sockets(&socket_msg).await;
+                    }
                     BrokerEvent::Passthrough(passthrough_events) => {
                         match passthrough_events.try_get_my_event() {
                             Ok(my_event) => match my_event {
@@ -36,23 +66,54 @@ impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
                                     }
                                     match topology {
                                         BrokerTopology::AddMqttMessages(url) => {
-                                            if let Some(mqtt) = self.add_mqtt(Messages, MqttType::Messages, url) {
+                                            if let Some(mqtt) = self.add_mqtt(Messages, MqttType::Messages, url, None) {
                                                 tokio::spawn(mqtt.start(self.handle.clone()));
                                             }
                                         }
                                         BrokerTopology::AddMqttMessagesReferenced(url) => {
+                                            if let Some(mqtt) = self.add_mqtt(
+                                                MessagesReferenced,
+                                                MqttType::MessagesReferenced,
+                                                url,
+                                                None,
+                                            ) {
+                                                tokio::spawn(mqtt.start(self.handle.clone()));
+                                            }
+                                        }
+                                        BrokerTopology::AddMqttMessagesWithQos(url, qos) => {
                                             if let Some(mqtt) =
-                                                self.add_mqtt(MessagesReferenced, MqttType::MessagesReferenced, url)
+                                                self.add_mqtt(Messages, MqttType::Messages, url, Some(qos))
                                             {
                                                 tokio::spawn(mqtt.start(self.handle.clone()));
                                             }
                                         }
+                                        BrokerTopology::AddMqttMessagesReferencedWithQos(url, qos) => {
+                                            if let Some(mqtt) = self.add_mqtt(
+                                                MessagesReferenced,
+                                                MqttType::MessagesReferenced,
+                                                url,
+                                                Some(qos),
+                                            ) {
+                                                tokio::spawn(mqtt.start(self.handle.clone()));
+                                            }
+                                        }
                                         BrokerTopology::RemoveMqttMessagesReferenced(url) => {
                                             self.remove_mqtt::<MessagesReferenced>(MqttType::MessagesReferenced, url)
                                         }
                                         BrokerTopology::RemoveMqttMessages(url) => {
                                             self.remove_mqtt::<Messages>(MqttType::Messages, url)
                                         }
+                                        BrokerTopology::UpdateFeedTopics { ref url, ref topics } => {
+                                            if let Err(e) = self.update_feed_topics(url, topics) {
+                                                error!("{}", e);
+                                                let socket_msg =
+                                                    BrokerSocketMsg::ChronicleBroker(Err(topology.clone()));
+                                                self.response_to_sockets::<Result<BrokerTopology, BrokerTopology>>(
+                                                    &socket_msg,
+                                                )
+                                                .await;
+                                            }
+                                        }
                                         BrokerTopology::Import { .. } => {
                                             self.handle_import(topology).await;
                                             self.try_close_importer_session().await;
@@ -97,6 +158,30 @@ impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
                                 ChronicleBrokerThrough::ExitProgram => {
                                     supervisor.exit_program(false);
                                 }
+                                ChronicleBrokerThrough::Prune { below, dry_run } => {
+                                    self.handle_prune(below, dry_run);
+                                }
+                                ChronicleBrokerThrough::CancelImport { path } => {
+                                    self.handle_cancel_import(path).await;
+                                    self.try_close_importer_session().await;
+                                }
+                                ChronicleBrokerThrough::RefreshSync => {
+                                    if let Some(syncer_handle) = self.syncer_handle.as_ref() {
+                                        info!("Refreshing the syncer sync data");
+                                        let _ = syncer_handle.send(SyncerEvent::Refresh);
+                                    } else {
+                                        warn!("Unable to refresh the sync data, the syncer is not running");
+                                    }
+                                }
+                                ChronicleBrokerThrough::SwitchKeyspace(keyspace) => {
+                                    self.handle_switch_keyspace(keyspace);
+                                }
+                                ChronicleBrokerThrough::SetAnalyticsInterval(interval) => {
+                                    match self.analytics_ticker.set_interval(interval, Instant::now()) {
+                                        Ok(()) => info!("Emitting the analytics snapshots every {:?}", interval),
+                                        Err(e) => warn!("Unable to set the analytics interval: {}", e),
+                                    }
+                                }
                             },
                             Err(other_app_event) => {
                                 supervisor.passthrough(other_app_event, self.get_name());
@@ -215,18 +300,24 @@ impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
                                             let topic = name.next().unwrap();
                                             let url = Url::parse(name.next().unwrap()).unwrap();
                                             let restart_after = std::time::Duration::from_secs(5);
+                                            let qos = self.mqtt_qos.get(&microservice_name).copied();
                                             warn!("Restarting Mqtt: {}, after: {:?}", microservice_name, restart_after);
                                             match Topics::try_from(topic).unwrap() {
                                                 Topics::Messages => {
                                                     let new_mqtt =
-                                                        self.add_mqtt(Messages, MqttType::Messages, url).unwrap();
+                                                        self.add_mqtt(Messages, MqttType::Messages, url, qos).unwrap();
                                                     tokio::spawn(
                                                         new_mqtt.start_after(restart_after, self.handle.clone()),
                                                     );
                                                 }
                                                 Topics::MessagesReferenced => {
                                                     let new_mqtt = self
-                                                        .add_mqtt(MessagesReferenced, MqttType::MessagesReferenced, url)
+                                                        .add_mqtt(
+                                                            MessagesReferenced,
+                                                            MqttType::MessagesReferenced,
+                                                            url,
+                                                            qos,
+                                                        )
                                                         .unwrap();
                                                     tokio::spawn(
                                                         new_mqtt.start_after(restart_after, self.handle.clone()),
@@ -273,11 +364,44 @@ impl<H: ChronicleBrokerScope> EventLoop<H> for ChronicleBroker<H> {
 }
 
 impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
+    pub(crate) fn update_feed_topics(&mut self, url: &Url, topics: &[String]) -> anyhow::Result<()> {
+        let feed_name = |topic: &str| format!("{}@{}", topic, url.as_str());
+        let subscribed = [Messages::name(), MessagesReferenced::name()]
+            .iter()
+            .copied()
+            .filter(|topic| self.service.microservices.contains_key(&feed_name(topic)))
+            .collect::<HashSet<_>>();
+        let (subscribe, unsubscribe) = reconcile_topics(&subscribed, topics)
+            .map_err(|e| anyhow!("Unable to update the topics of the feed source {}: {}", url, e))?;
+        // the new topics keep the qos of the connected ones
+        let qos = subscribed
+            .iter()
+            .find_map(|topic| self.mqtt_qos.get(&feed_name(topic)).copied());
+        for topic in subscribe {
+            if topic == Messages::name() {
+                if let Some(mqtt) = self.add_mqtt(Messages, MqttType::Messages, url.clone(), qos) {
+                    tokio::spawn(mqtt.start(self.handle.clone()));
+                }
+            } else if let Some(mqtt) = self.add_mqtt(MessagesReferenced, MqttType::MessagesReferenced, url.clone(), qos)
+            {
+                tokio::spawn(mqtt.start(self.handle.clone()));
+            }
+        }
+        for topic in unsubscribe {
+            if topic == Messages::name() {
+                self.remove_mqtt::<Messages>(MqttType::Messages, url.clone());
+            } else {
+                self.remove_mqtt::<MessagesReferenced>(MqttType::MessagesReferenced, url.clone());
+            }
+        }
+        Ok(())
+    }
     pub(crate) fn remove_mqtt<T: Topic>(&mut self, mqtt_type: MqttType, url: Url) {
         let microservice_name = format!("{}@{}", T::name(), url.as_str());
         if let Some(service) = self.service.microservices.get(&microservice_name) {
             // add it to asked_to_shutdown hashmap
             self.asked_to_shutdown.insert(microservice_name.clone(), ());
+            self.mqtt_qos.remove(&microservice_name);
             if let Some(mqtt_handle) = self.mqtt_handles.remove(&microservice_name) {
                 mqtt_handle.shutdown();
                 let config = get_config();
@@ -306,17 +430,53 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
             // Maybe TODO response with something?;
         };
     }
-    pub(crate) fn add_mqtt<T: Topic>(&mut self, topic: T, mqtt_type: MqttType, url: Url) -> Option<Mqtt<T>> {
+    /// Get the dead-letter log of the configured path, whose writer is spawned on first use (or once the path changes)
+    fn dead_letter_log(&mut self, path: Option<&String>) -> Option<DeadLetterLog> {
+        let path = path?;
+        if self
+            .dead_letters
+            .as_ref()
+            .map_or(true, |dead_letters| dead_letters.path() != path)
+        {
+            let (dead_letters, writer) = DeadLetterLog::new(path.clone(), MAX_DEAD_LETTER_LOG_SIZE);
+            tokio::spawn(writer.run());
+            self.dead_letters.replace(dead_letters);
+        }
+        self.dead_letters.clone()
+    }
+    pub(crate) fn add_mqtt<T: Topic>(
+        &mut self,
+        topic: T,
+        mqtt_type: MqttType,
+        url: Url,
+        qos: Option<u8>,
+    ) -> Option<Mqtt<T>> {
         let config = get_config();
-        let mqtt = MqttBuilder::new()
+        // the feed qos overrides the configured one
+        let qos = qos.unwrap_or(config.broker_config.mqtt_qos);
+        if qos > 1 {
+            error!(
+                "The Mqtt: {}@{} qos: {} is not supported, it must be either 0 or 1",
+                T::name(),
+                url,
+                qos
+            );
+            return None;
+        }
+        let mut mqtt_builder = MqttBuilder::new()
             .collectors_handles(self.collector_handles.clone())
             .topic(topic)
             .url(url.clone())
             .stream_capacity(config.broker_config.mqtt_stream_capacity)
-            .build();
+            .qos(qos as i32);
+        if let Some(dead_letters) = self.dead_letter_log(config.broker_config.dead_letter_path.as_ref()) {
+            mqtt_builder = mqtt_builder.dead_letters(dead_letters);
+        }
+        let mqtt = mqtt_builder.build();
         let microservice = mqtt.clone_service();
         let microservice_name = microservice.get_name();
         if let None = self.service.microservices.get(&microservice_name) {
+            self.mqtt_qos.insert(microservice_name.clone(), qos);
             self.service.update_microservice(microservice_name, microservice);
             let mut new_config = config.clone();
             if let Some(list) = new_config.broker_config.mqtt_brokers.get_mut(&mqtt_type) {
@@ -342,41 +502,210 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
             resume,
             ref import_range,
             import_type,
+            verify_checksum,
+            insert_concurrency,
+            batch_size,
+            strict,
+            force,
         } = import_topology
         {
             // don't do anything if the service is shutting down
             if self.service.is_stopping() {
                 return ();
             }
+            // reject runaway imports before any work begins
+            if let Err(e) = get_config()
+                .broker_config
+                .verify_import_range(import_range.as_ref(), force)
+            {
+                let event = ImporterSession::PathError {
+                    path: path.clone(),
+                    msg: e.to_string(),
+                };
+                let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
+                self.response_to_sockets(&socket_msg).await;
+                return ();
+            }
             // check if we have enough parallelism points
             if self.parallelism_points == 0 {
                 // add it to pending list
                 self.pending_imports.push(import_topology);
                 return ();
             }
-            if path.is_file() {
+            if LogFile::remote_url(path).is_some() {
                 // build importer
                 self.spawn_importer(
                     path.clone(),
                     resume,
                     import_range.clone(),
                     import_type,
+                    verify_checksum,
+                    insert_concurrency,
+                    batch_size,
+                    strict,
                     self.parallelism_points,
                 )
                 .await;
-            } else if path.is_dir() {
-                self.spawn_importers(path.clone(), resume, import_range.clone(), import_type)
-                    .await;
             } else {
-                let event = ImporterSession::PathError {
-                    path: path.clone(),
-                    msg: "Invalid path".into(),
-                };
-                let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
-                self.response_to_sockets(&socket_msg).await;
+                match LogFile::import_paths(path).await {
+                    Ok(import_files) => {
+                        self.spawn_importers(
+                            path.clone(),
+                            import_files,
+                            resume,
+                            import_range.clone(),
+                            import_type,
+                            verify_checksum,
+                            insert_concurrency,
+                            batch_size,
+                            strict,
+                        )
+                        .await
+                    }
+                    Err(e) => {
+                        let event = ImporterSession::PathError {
+                            path: path.clone(),
+                            msg: e.to_string(),
+                        };
+                        let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
+                        self.response_to_sockets(&socket_msg).await;
+                    }
+                }
+            }
+        }
+    }
+    async fn handle_cancel_import(&mut self, path: PathBuf) {
+        // drop the pending imports of the path, ie the remaining LogFiles of a dir import
+        let mut cancelled = Vec::new();
+        self.pending_imports.retain(|topology| match topology {
+            BrokerTopology::Import { path: pending_path, .. } if pending_path.starts_with(&path) => {
+                cancelled.push(pending_path.clone());
+                false
+            }
+            _ => true,
+        });
+        for pending_path in cancelled {
+            let event = ImporterSession::PathError {
+                path: pending_path,
+                msg: "Import cancelled before it started".into(),
+            };
+            let socket_msg = BrokerSocketMsg::ChronicleBroker(event);
+            self.response_to_sockets(&socket_msg).await;
+        }
+        // the in progress importers stop at the next milestone boundary and finish as cancelled
+        for (importer_name, importer_handle) in self.importer_handles.iter() {
+            if std::path::Path::new(importer_name).starts_with(&path) {
+                info!("Cancelling importer: {}", importer_name);
+                importer_handle.send(ImporterEvent::Cancel).ok();
             }
         }
     }
+    /// Emit the analytics rolled up since the previous snapshot to the dashboards, if the snapshot is due
+    async fn emit_analytics_snapshot(&mut self, now: Instant) {
+        if let Some(snapshot) = self.analytics_ticker.poll_snapshot(now, &self.analytics) {
+            let socket_msg = BrokerSocketMsg::ChronicleBroker(snapshot);
+            self.response_to_sockets(&socket_msg).await;
+        }
+    }
+    fn handle_switch_keyspace(&mut self, keyspace: String) {
+        if !get_config()
+            .storage_config
+            .keyspaces
+            .iter()
+            .any(|keyspace_config| keyspace_config.name == keyspace)
+        {
+            warn!("Unable to switch to keyspace: {}, it is not configured", keyspace);
+            return;
+        }
+        if self.default_keyspace.name() == &keyspace {
+            info!("Already writing to keyspace: {}", keyspace);
+            return;
+        }
+        info!(
+            "Switching from keyspace: {} to: {}",
+            self.default_keyspace.name(),
+            keyspace
+        );
+        self.default_keyspace = ChronicleKeyspace::configured(keyspace, &get_config().storage_config);
+        // the restarted children and the new importers are built with the broker keyspace
+        for collector_handle in self.collector_handles.values() {
+            let event = CollectorEvent::Internal(Internal::SwitchKeyspace(self.default_keyspace.clone()));
+            collector_handle.send(event).ok();
+        }
+        for solidifier_handle in self.solidifier_handles.values() {
+            solidifier_handle
+                .send(SolidifierEvent::SwitchKeyspace(self.default_keyspace.clone()))
+                .ok();
+        }
+        if let Some(syncer_handle) = self.syncer_handle.as_ref() {
+            syncer_handle
+                .send(SyncerEvent::SwitchKeyspace(self.default_keyspace.clone()))
+                .ok();
+        }
+    }
+    fn handle_prune(&mut self, below: u32, dry_run: bool) {
+        // don't do anything if the service is shutting down
+        if self.service.is_stopping() {
+            return ();
+        }
+        let config = get_config();
+        let store = |keyspace: ChronicleKeyspace| {
+            ChroniclePruneStore::new(
+                keyspace,
+                config.storage_config.partition_config,
+                0, // TODO get it from config
+                config.broker_config.retries_per_query,
+                config.broker_config.sync_write_consistency,
+            )
+        };
+        let default_store = store(self.default_keyspace.clone());
+        // a dry run only counts the rows of the default keyspace, which the mirrors hold too
+        let mirror_stores: Vec<_> = if dry_run {
+            Vec::new()
+        } else {
+            config
+                .broker_config
+                .mirror_keyspaces
+                .iter()
+                .map(|keyspace| store(ChronicleKeyspace::configured(keyspace.clone(), &config.storage_config)))
+                .collect()
+        };
+        let handle = self.handle.clone().expect("Expected broker handle");
+        tokio::spawn(async move {
+            let pruned = async {
+                let stats = Pruner::new(default_store, below, dry_run)
+                    .prune(|session| {
+                        handle.send(BrokerEvent::Pruner(session)).ok();
+                    })
+                    .await?;
+                // the mirrors hold the same milestones, so only the progress of the default keyspace is reported
+                for mirror_store in mirror_stores {
+                    Pruner::new(mirror_store, below, false).prune(|_| ()).await?;
+                }
+                Ok::<_, anyhow::Error>(stats)
+            };
+            let msg = match pruned.await {
+                Ok(stats) if dry_run => {
+                    info!("Would prune milestones data below: {}, {:?}", below, stats);
+                    format!(
+                        "would prune {} milestones, {} messages and {} outputs",
+                        stats.milestones, stats.messages, stats.outputs
+                    )
+                }
+                Ok(stats) => {
+                    info!("Pruned milestones data below: {}, {:?}", below, stats);
+                    "done".into()
+                }
+                Err(e) => {
+                    error!("Unable to prune milestones data below: {}, error: {}", below, e);
+                    "failed".into()
+                }
+            };
+            handle
+                .send(BrokerEvent::Pruner(PrunerSession::Finish { below, msg }))
+                .ok();
+        });
+    }
     async fn try_close_importer_session(&mut self) {
         if self.in_progress_importers == 0 {
             let event = ImporterSession::Close;
@@ -389,17 +718,38 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
         file_path: PathBuf,
         resume: bool,
         import_range: Option<Range<u32>>,
+        verify_checksum: bool,
+        insert_concurrency: Option<usize>,
+        batch_size: Option<usize>,
+        strict: bool,
         parallelism: u8,
     ) {
         let mut importer_builder = ImporterBuilder::<T>::new();
         if let Some(import_range) = import_range {
             importer_builder = importer_builder.import_range(import_range);
         };
+        if let Some(insert_concurrency) = insert_concurrency {
+            importer_builder = importer_builder.insert_concurrency(insert_concurrency);
+        };
+        if let Some(batch_size) = batch_size {
+            importer_builder = importer_builder.batch_size(batch_size);
+        };
         let importer = importer_builder
             .file_path(file_path)
             .resume(resume)
+            .verify_checksum(verify_checksum)
+            .strict(strict)
             .parallelism(parallelism)
-            .retries_per_query(50) // TODO get it from config
+            .retries_per_query(get_config().broker_config.retries_per_query)
+            .sync_write_consistency(get_config().broker_config.sync_write_consistency)
+            .mirror_keyspaces(
+                get_config()
+                    .broker_config
+                    .mirror_keyspaces
+                    .iter()
+                    .map(|keyspace| ChronicleKeyspace::configured(keyspace.clone(), &get_config().storage_config))
+                    .collect(),
+            )
             .chronicle_id(0) // TODO get it from config
             .build();
         let handle = importer.clone_handle().expect("Expected existing importer handle");
@@ -414,6 +764,10 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
         resume: bool,
         import_range: Option<Range<u32>>,
         import_type: ImportType,
+        verify_checksum: bool,
+        insert_concurrency: Option<usize>,
+        batch_size: Option<usize>,
+        strict: bool,
         parallelism: u8,
     ) {
         // don't do anything if the service is shutting down
@@ -426,10 +780,28 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
             }
             match import_type {
                 ImportType::All => {
-                    self.build_and_start_importer::<All>(file_path, resume, import_range, parallelism);
+                    self.build_and_start_importer::<All>(
+                        file_path,
+                        resume,
+                        import_range,
+                        verify_checksum,
+                        insert_concurrency,
+                        batch_size,
+                        strict,
+                        parallelism,
+                    );
                 }
                 ImportType::Analytics => {
-                    self.build_and_start_importer::<Analytics>(file_path, resume, import_range, parallelism);
+                    self.build_and_start_importer::<Analytics>(
+                        file_path,
+                        resume,
+                        import_range,
+                        verify_checksum,
+                        insert_concurrency,
+                        batch_size,
+                        strict,
+                        parallelism,
+                    );
                 }
             }
             self.in_progress_importers += 1;
@@ -444,22 +816,20 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
             self.response_to_sockets(&socket_msg).await;
         }
     }
+    /// Spawn the importers of the ordered LogFiles of the import path, the ones which don't fit in the parallelism
+    /// points are imported in order once the points are returned
     async fn spawn_importers(
         &mut self,
         path: PathBuf,
+        mut import_files: Vec<PathBuf>,
         resume: bool,
         import_range: Option<Range<u32>>,
         import_type: ImportType,
+        verify_checksum: bool,
+        insert_concurrency: Option<usize>,
+        batch_size: Option<usize>,
+        strict: bool,
     ) {
-        let mut import_files = Vec::new();
-        if let Ok(mut dir_entry) = tokio::fs::read_dir(&path).await {
-            while let Ok(Some(p)) = dir_entry.next_entry().await {
-                let file_path = p.path();
-                if file_path.is_file() {
-                    import_files.push(file_path);
-                }
-            }
-        };
         let import_files_len = import_files.len();
         if import_files_len == 0 {
             let event = ImporterSession::PathError {
@@ -473,27 +843,46 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
         if self.parallelism_points as usize > import_files_len {
             let parallelism = (self.parallelism_points as usize / import_files_len) as u8;
             for file_path in import_files {
-                self.spawn_importer(file_path, resume, import_range.clone(), import_type, parallelism)
-                    .await
+                self.spawn_importer(
+                    file_path,
+                    resume,
+                    import_range.clone(),
+                    import_type,
+                    verify_checksum,
+                    insert_concurrency,
+                    batch_size,
+                    strict,
+                    parallelism,
+                )
+                .await
             }
         } else {
-            // unwrap is safe
-            let file_path = import_files.pop().expect("Expected import file");
+            let file_path = import_files.remove(0);
             self.spawn_importer(
                 file_path,
                 resume,
                 import_range.clone(),
                 import_type,
+                verify_checksum,
+                insert_concurrency,
+                batch_size,
+                strict,
                 self.parallelism_points,
             )
             .await;
-            // convert any remaining into pending_imports
-            for file_path in import_files {
+            // convert any remaining into pending_imports, which are popped, so the lowest is pushed last
+            for file_path in import_files.into_iter().rev() {
                 let topology = BrokerTopology::Import {
                     path: file_path,
                     resume,
                     import_range: import_range.clone(),
                     import_type,
+                    verify_checksum,
+                    insert_concurrency,
+                    batch_size,
+                    strict,
+                    // the import range was already verified
+                    force: true,
                 };
                 self.pending_imports.push(topology);
             }
@@ -556,3 +945,27 @@ impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
         }
     }
 }
+
+/// Keep the active import jobs up to date with the importer session events
+fn track_import_job(jobs: &mut Jobs, importer_session: &ImporterSession) {
+    match importer_session {
+        ImporterSession::ProgressBar {
+            log_file_size,
+            from_ms,
+            to_ms,
+            ms_bytes_size,
+            milestone_index,
+            skipped,
+        } => jobs.import_progress(
+            *from_ms,
+            *to_ms,
+            *log_file_size,
+            *ms_bytes_size,
+            *milestone_index,
+            *skipped,
+        ),
+        ImporterSession::Finish { from_ms, to_ms, .. } => jobs.import_finished(*from_ms, *to_ms),
+        ImporterSession::Close => jobs.close_imports(),
+        ImporterSession::PathError { .. } => (),
+    }
+}
@@ -9,6 +9,7 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
         self.service.update_status(ServiceStatus::Initializing);
         if let Some(ref mut supervisor) = supervisor {
             let config = get_config_async().await;
+            self.configure(&config);
             supervisor.status_change(self.service.clone());
             // Query sync table
             self.query_sync_table().await?;
@@ -22,6 +23,7 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
             let (one, recv) = tokio::sync::oneshot::channel();
             let mut syncer_builder = SyncerBuilder::new()
                 .sync_data(self.sync_data.clone())
+                .keyspace(self.default_keyspace.clone())
                 .handle(syncer_handle.clone())
                 .first_ask(AskSyncer::FillGaps)
                 .oneshot(one)
@@ -35,6 +37,8 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                     .keyspace(self.default_keyspace.clone())
                     .solidifiers_count(self.collector_count)
                     .max_log_size(max_log_size)
+                    .retries_per_query(config.broker_config.retries_per_query)
+                    .sync_write_consistency(config.broker_config.sync_write_consistency)
                     .oneshot(recv)
                     .build();
                 archiver_handle = archiver.take_handle();
@@ -47,6 +51,12 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                 info!("Initializing Broker without Archiver");
                 archiver_handle = None;
             }
+            let mirror_keyspaces: Vec<ChronicleKeyspace> = config
+                .broker_config
+                .mirror_keyspaces
+                .iter()
+                .map(|keyspace| ChronicleKeyspace::configured(keyspace.clone(), &config.storage_config))
+                .collect();
             let mut collector_builders: Vec<CollectorBuilder> = Vec::new();
             let mut solidifier_builders: Vec<SolidifierBuilder> = Vec::new();
             let reqwest_client = reqwest::Client::builder()
@@ -64,7 +74,11 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                 }
                 // create collector_builder
                 let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-                let collector_handle = CollectorHandle { tx, requesters_senders };
+                let collector_handle = CollectorHandle {
+                    tx,
+                    requesters_senders,
+                    feed_limiter: FeedLimiter::new(&config.broker_config.feed_channel, self.default_keyspace.name()),
+                };
                 let collector_inbox = CollectorInbox { rx };
                 self.collector_handles.insert(partition_id, collector_handle.clone());
                 let collector_builder = CollectorBuilder::new()
@@ -74,9 +88,11 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                     .inbox(collector_inbox)
                     .api_endpoints(config.broker_config.api_endpoints.iter().cloned().collect())
                     .storage_config(config.storage_config.clone())
+                    .mirror_keyspaces(mirror_keyspaces.clone())
                     .reqwest_client(reqwest_client.clone())
                     .retries_per_query(config.broker_config.retries_per_query)
                     .retries_per_endpoint(config.broker_config.retries_per_endpoint)
+                    .feed_dedup_window(config.broker_config.feed_dedup_window)
                     .requesters_channels(requesters_channels)
                     .partition_id(partition_id);
 
@@ -94,7 +110,12 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                 }
                 solidifier_builder = solidifier_builder
                     .gap_start(gap_start)
+                    .retries(config.broker_config.retries_per_query as u16)
+                    .sync_write_consistency(config.broker_config.sync_write_consistency)
+                    .solidification_timeout(config.broker_config.solidification_timeout)
+                    .expected_timeout_secs(config.broker_config.expected_timeout_secs)
                     .keyspace(self.default_keyspace.clone())
+                    .mirror_keyspaces(mirror_keyspaces.clone())
                     .handle(solidifier_handle)
                     .inbox(solidifier_inbox)
                     .partition_id(partition_id);
@@ -107,6 +128,7 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                 .solidifier_handles(self.solidifier_handles.clone())
                 .sync_range(self.sync_range)
                 .parallelism(self.parallelism)
+                .concurrency(config.broker_config.syncer_concurrency)
                 .update_sync_data_every(self.complete_gaps_interval)
                 .build();
             tokio::spawn(syncer.start(self.handle.clone()));
@@ -119,7 +141,7 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                 .flat_map(|v| v.iter())
                 .cloned()
             {
-                if let Some(mqtt) = self.add_mqtt(Messages, MqttType::Messages, broker_url) {
+                if let Some(mqtt) = self.add_mqtt(Messages, MqttType::Messages, broker_url, None) {
                     tokio::spawn(mqtt.start(self.handle.clone()));
                 }
             }
@@ -131,7 +153,7 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
                 .flat_map(|v| v.iter())
                 .cloned()
             {
-                if let Some(mqtt) = self.add_mqtt(MessagesReferenced, MqttType::MessagesReferenced, broker_url) {
+                if let Some(mqtt) = self.add_mqtt(MessagesReferenced, MqttType::MessagesReferenced, broker_url, None) {
                     tokio::spawn(mqtt.start(self.handle.clone()));
                 }
             }
@@ -157,7 +179,7 @@ impl<H: ChronicleBrokerScope> Init<H> for ChronicleBroker<H> {
 
 impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
     pub(crate) async fn query_sync_table(&mut self) -> Result<(), Need> {
-        self.sync_data = SyncData::try_fetch(&self.default_keyspace, &self.sync_range, 10)
+        self.sync_data = fetch_sync_data(&self.default_keyspace, &self.sync_range)
             .await
             .map_err(|e| {
                 error!("{}", e);
@@ -7,23 +7,52 @@ use crate::{
     importer::*,
     listener::*,
     mqtt::*,
+    pruner::*,
     solidifier::*,
     syncer::*,
     websocket::*,
 };
 use async_trait::async_trait;
-use chronicle_common::config::BrokerConfig;
+use chronicle_common::config::{
+    BrokerConfig,
+    Config,
+    WriteConsistency,
+};
 use std::{
+    collections::HashSet,
     ops::Range,
     str::FromStr,
-    time::Duration,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
+mod analytics;
 mod event_loop;
 mod init;
 mod starter;
 mod terminating;
 
+pub use analytics::{
+    AnalyticsSnapshot,
+    DEFAULT_ANALYTICS_INTERVAL,
+    MIN_ANALYTICS_INTERVAL,
+};
+pub(crate) use analytics::{
+    AnalyticsTicker,
+    BrokerAnalytics,
+};
+
+/// Get the storage consistency of the configured write consistency
+pub(crate) fn write_consistency(consistency: WriteConsistency) -> Consistency {
+    match consistency {
+        WriteConsistency::One => Consistency::One,
+        WriteConsistency::LocalQuorum => Consistency::LocalQuorum,
+        WriteConsistency::Quorum => Consistency::Quorum,
+    }
+}
+
 /// Define the application scope trait
 pub trait ChronicleBrokerScope: LauncherSender<ChronicleBrokerBuilder<Self>> {}
 impl<H: LauncherSender<ChronicleBrokerBuilder<H>>> ChronicleBrokerScope for H {}
@@ -41,6 +70,8 @@ builder!(
 /// BrokerHandle to be passed to the children
 pub struct BrokerHandle<H: ChronicleBrokerScope> {
     tx: tokio::sync::mpsc::UnboundedSender<BrokerEvent<H::AppsEvents>>,
+    /// The analytics the solidifiers roll up into the broker snapshots
+    analytics: BrokerAnalytics,
 }
 /// BrokerInbox used to recv events
 pub struct BrokerInbox<H: ChronicleBrokerScope> {
@@ -49,7 +80,17 @@ pub struct BrokerInbox<H: ChronicleBrokerScope> {
 
 impl<H: ChronicleBrokerScope> Clone for BrokerHandle<H> {
     fn clone(&self) -> Self {
-        BrokerHandle::<H> { tx: self.tx.clone() }
+        BrokerHandle::<H> {
+            tx: self.tx.clone(),
+            analytics: self.analytics.clone(),
+        }
+    }
+}
+
+impl<H: ChronicleBrokerScope> BrokerHandle<H> {
+    /// The analytics accumulated for the next snapshot of the broker
+    pub(crate) fn analytics(&self) -> &BrokerAnalytics {
+        &self.analytics
     }
 }
 
@@ -59,6 +100,8 @@ pub struct ChronicleBroker<H: ChronicleBrokerScope> {
     websockets: HashMap<String, WsTx>,
     listener_handle: Option<ListenerHandle>,
     mqtt_handles: HashMap<String, MqttHandle>,
+    /// The QoS level of the added mqtts, so a restarted mqtt keeps it
+    mqtt_qos: HashMap<String, u8>,
     importer_handles: HashMap<String, ImporterHandle>,
     asked_to_shutdown: HashMap<String, ()>,
     parallelism: u8,
@@ -76,6 +119,10 @@ pub struct ChronicleBroker<H: ChronicleBrokerScope> {
     sync_range: SyncRange,
     sync_data: SyncData,
     syncer_handle: Option<SyncerHandle>,
+    analytics_ticker: AnalyticsTicker,
+    analytics: BrokerAnalytics,
+    /// The dead-letter log shared by the feed sources, if enabled
+    dead_letters: Option<DeadLetterLog>,
 }
 
 /// SubEvent type, indicates the children
@@ -102,6 +149,12 @@ pub enum BrokerChild {
 pub enum BrokerEvent<T> {
     /// Importer Session
     Importer(ImporterSession),
+    /// Pruner Session
+    Pruner(PrunerSession),
+    /// Syncer Session
+    Syncer(SyncerSession),
+    /// Feed connection state change
+    Feed(FeedStatus),
     /// It's the passthrough event, which the scylla application will receive from
     Passthrough(T),
     /// Used by broker children to push their service
@@ -123,45 +176,39 @@ impl<H: ChronicleBrokerScope> Builder for ChronicleBrokerBuilder<H> {
     type State = ChronicleBroker<H>;
     fn build(self) -> Self::State {
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        let handle = Some(BrokerHandle { tx });
+        let analytics = BrokerAnalytics::default();
+        let handle = Some(BrokerHandle {
+            tx,
+            analytics: analytics.clone(),
+        });
         let inbox = BrokerInbox { rx };
-        let config = get_config();
-        let default_keyspace = ChronicleKeyspace::new(
-            config
-                .storage_config
-                .keyspaces
-                .first()
-                .and_then(|keyspace| Some(keyspace.name.clone()))
-                .unwrap_or("permanode".to_owned()),
-        );
-        let sync_range = config
-            .broker_config
-            .sync_range
-            .and_then(|range| Some(range))
-            .unwrap_or(SyncRange::default());
+        // the configured keyspace, sync range and logs dir are set once the broker is initialized, as the build
+        // runs within the runtime and can't block on the config
+        let default_keyspace = ChronicleKeyspace::new("permanode".to_owned());
+        let sync_range = SyncRange::default();
         let sync_data = SyncData {
             completed: Vec::new(),
             synced_but_unlogged: Vec::new(),
             gaps: Vec::new(),
+            partial: false,
         };
-        let logs_dir_path;
-        if let Some(logs_dir) = config.broker_config.logs_dir {
-            logs_dir_path = Some(PathBuf::from_str(&logs_dir).expect("Failed to parse configured logs path!"));
-        } else {
-            logs_dir_path = None;
-        }
+        let logs_dir_path = None;
         let parallelism = self.parallelism.unwrap_or(25);
         ChronicleBroker::<H> {
             service: Service::new(),
             websockets: HashMap::new(),
             listener_handle: self.listener_handle,
             mqtt_handles: HashMap::new(),
+            mqtt_qos: HashMap::new(),
             importer_handles: HashMap::new(),
             asked_to_shutdown: HashMap::new(),
             collector_count: self.collector_count.unwrap_or(10),
             collector_handles: HashMap::new(),
             solidifier_handles: HashMap::new(),
             syncer_handle: None,
+            analytics_ticker: AnalyticsTicker::new(DEFAULT_ANALYTICS_INTERVAL, Instant::now()),
+            analytics,
+            dead_letters: None,
             parallelism,
             parallelism_points: parallelism,
             pending_imports: Vec::new(),
@@ -179,6 +226,31 @@ impl<H: ChronicleBrokerScope> Builder for ChronicleBrokerBuilder<H> {
 }
 
 /// implementation of passthrough functionality
+impl<H: ChronicleBrokerScope> ChronicleBroker<H> {
+    /// Set the configured keyspace, sync range and logs dir
+    pub(crate) fn configure(&mut self, config: &Config) {
+        self.default_keyspace = ChronicleKeyspace::configured(
+            config
+                .storage_config
+                .keyspaces
+                .first()
+                .and_then(|keyspace| Some(keyspace.name.clone()))
+                .unwrap_or("permanode".to_owned()),
+            &config.storage_config,
+        );
+        self.sync_range = config
+            .broker_config
+            .sync_range
+            .and_then(|range| Some(range))
+            .unwrap_or(SyncRange::default());
+        self.logs_dir_path = config
+            .broker_config
+            .logs_dir
+            .as_ref()
+            .map(|logs_dir| PathBuf::from_str(logs_dir).expect("Failed to parse configured logs path!"));
+    }
+}
+
 impl<H: ChronicleBrokerScope> Passthrough<ChronicleBrokerThrough> for BrokerHandle<H> {
     fn launcher_status_change(&mut self, _service: &Service) {}
     fn app_status_change(&mut self, service: &Service) {
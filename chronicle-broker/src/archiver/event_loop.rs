@@ -155,6 +155,7 @@ impl Archiver {
                     milestone_index,
                     &self.keyspace,
                     self.retries_per_query,
+                    self.sync_write_consistency,
                 )
                 .await?;
                 // check if now the log_file reached an upper limit to finish the file
@@ -222,6 +223,7 @@ impl Archiver {
             milestone_index,
             &self.keyspace,
             self.retries_per_query,
+            self.sync_write_consistency,
         )
         .await?;
         // check if we hit an upper_ms_limit, as this is possible when the log_file only needs 1 milestone data.
@@ -272,6 +274,7 @@ impl Archiver {
         ms_index: u32,
         keyspace: &ChronicleKeyspace,
         retries_per_query: usize,
+        sync_write_consistency: WriteConsistency,
     ) -> anyhow::Result<()> {
         log_file.append_line(&milestone_data_line).await?;
         // insert into the DB, without caring about the response
@@ -279,7 +282,7 @@ impl Archiver {
         let synced_record = SyncRecord::new(MilestoneIndex(ms_index), None, Some(0));
         keyspace
             .insert(&sync_key, &synced_record)
-            .consistency(Consistency::One)
+            .consistency(write_consistency(sync_write_consistency))
             .build()?
             .send_local(InsertWorker::boxed(
                 keyspace.clone(),
@@ -7,14 +7,10 @@ use super::*;
 impl<H: ChronicleBrokerScope> Init<BrokerHandle<H>> for Archiver {
     async fn init(&mut self, _status: Result<(), Need>, _supervisor: &mut Option<BrokerHandle<H>>) -> Result<(), Need> {
         self.service.update_status(ServiceStatus::Initializing);
-        // create directory first
-        if let Err(e) = tokio::fs::create_dir(self.dir_path.clone().into_boxed_path()).await {
-            if e.kind() == std::io::ErrorKind::AlreadyExists {
-                // do nothing
-            } else {
-                error!("Unable to create log directory, error: {}", e);
-                return Err(Need::Abort);
-            }
+        // create directory (and its missing parents) first
+        if let Err(e) = tokio::fs::create_dir_all(self.dir_path.clone().into_boxed_path()).await {
+            error!("Unable to create log directory, error: {}", e);
+            return Err(Need::Abort);
         };
         self.service.update_status(ServiceStatus::Initializing);
         let event = BrokerEvent::Children(BrokerChild::Archiver(self.service.clone(), Ok(())));
@@ -4,6 +4,7 @@
 use super::*;
 use crate::{
     application::{
+        write_consistency,
         BrokerChild,
         BrokerEvent,
         BrokerHandle,
@@ -15,7 +16,12 @@ use anyhow::{
     anyhow,
     bail,
 };
+use chronicle_common::config::WriteConsistency;
 use chronicle_storage::access::ChronicleKeyspace;
+use sha2::{
+    Digest,
+    Sha256,
+};
 use std::{
     collections::BinaryHeap,
     convert::TryFrom,
@@ -23,7 +29,10 @@ use std::{
         Deref,
         DerefMut,
     },
-    path::PathBuf,
+    path::{
+        Path,
+        PathBuf,
+    },
 };
 use tokio::{
     fs::{
@@ -32,6 +41,7 @@ use tokio::{
     },
     io::{
         AsyncBufReadExt,
+        AsyncReadExt,
         AsyncWriteExt,
         BufReader,
     },
@@ -39,8 +49,11 @@ use tokio::{
 };
 mod event_loop;
 mod init;
+mod remote;
 mod terminating;
 
+pub use remote::RemoteLogFile;
+
 /// The maximum bytes size for a given log file;
 pub const MAX_LOG_SIZE: u64 = u32::MAX as u64;
 
@@ -51,6 +64,7 @@ builder!(ArchiverBuilder {
     oneshot: Receiver<u32>,
     solidifiers_count: u8,
     retries_per_query: usize,
+    sync_write_consistency: WriteConsistency,
     dir_path: PathBuf
 });
 
@@ -95,6 +109,15 @@ pub enum ArchiverEvent {
     Close(u32),
 }
 
+#[derive(Debug)]
+/// The underlying source of the log file
+enum LogFileSource {
+    /// Local log file
+    Local(BufReader<File>),
+    /// Remote log file served over HTTP(S)
+    Remote(RemoteLogFile),
+}
+
 #[derive(Debug)]
 /// Write ahead file which stores ordered milestones data by milestone index.
 pub struct LogFile {
@@ -105,10 +128,14 @@ pub struct LogFile {
     /// NotIncluded (yet) milestone data
     to_ms_index: u32,
     upper_ms_limit: u32,
-    file: BufReader<File>,
+    file: LogFileSource,
     /// Identifier if it had io error
     maybe_corrupted: bool,
     finished: bool,
+    /// The LogFile format version, 0 for LogFiles without header
+    version: u32,
+    /// The first line of an unversioned LogFile, read while looking for the header
+    pending_line: Option<String>,
 }
 
 impl LogFile {
@@ -120,24 +147,159 @@ impl LogFile {
     ) -> anyhow::Result<LogFile> {
         let filename = format!("{}.part", milestone_index);
         let file_path = dir_path.join(&filename);
-        let file: File = OpenOptions::new()
+        let mut file: File = OpenOptions::new()
             .append(true)
             .create(true)
             .open(file_path)
             .await
             .map_err(|e| anyhow!("Unable to create log file: {}, error: {}", filename, e))?;
+        let mut len = 0;
+        // only a brand new file gets the header, as the header must be the first line
+        if file.metadata().await?.len() == 0 {
+            let header = LogFileHeader::default().to_line();
+            file.write_all(header.as_bytes())
+                .await
+                .map_err(|e| anyhow!("Unable to write log file header: {}, error: {}", filename, e))?;
+            len = header.len() as u64;
+        }
         Ok(Self {
-            len: 0,
+            len,
             filename,
             from_ms_index: milestone_index,
             to_ms_index: milestone_index,
             upper_ms_limit: opt_upper_limit.unwrap_or(u32::MAX),
-            file: BufReader::new(file),
+            file: LogFileSource::Local(BufReader::new(file)),
+            maybe_corrupted: false,
+            finished: false,
+            version: LOG_FILE_VERSION,
+            pending_line: None,
+        })
+    }
+
+    /// Open a remote log file from the given HTTP(S) url, the url path must end with a valid log filename
+    pub async fn from_url(url: Url) -> anyhow::Result<LogFile> {
+        let filename = url
+            .path_segments()
+            .and_then(|segments| segments.last())
+            .map(Path::new)
+            .and_then(|last| last.file_stem())
+            .and_then(|stem| stem.to_str())
+            .ok_or(anyhow!("Invalid url filename!"))?
+            .to_owned();
+        let (from_ms_index, to_ms_index) = Self::parse_filename(&filename)?;
+        let (remote, len) = RemoteLogFile::open(url).await?;
+        Ok(LogFile {
+            len,
+            filename,
+            from_ms_index,
+            to_ms_index,
+            upper_ms_limit: to_ms_index,
+            file: LogFileSource::Remote(remote),
             maybe_corrupted: false,
             finished: false,
+            version: 0,
+            pending_line: None,
         })
     }
 
+    /// Verify the log file against its sidecar checksum file (ie `{from}to{to}.log.sha256`), which is expected to
+    /// contain the hex encoded sha256 digest of the log file (in `sha256sum` output format)
+    pub async fn verify_checksum(file_path: &PathBuf) -> anyhow::Result<()> {
+        let mut checksum_path = file_path.clone().into_os_string();
+        checksum_path.push(".sha256");
+        let checksum_path = PathBuf::from(checksum_path);
+        let checksum = tokio::fs::read_to_string(&checksum_path)
+            .await
+            .map_err(|e| anyhow!("Unable to read checksum file: {:?}, error: {}", checksum_path, e))?;
+        let expected = checksum
+            .split_whitespace()
+            .next()
+            .ok_or_else(|| anyhow!("Empty checksum file: {:?}", checksum_path))?
+            .to_lowercase();
+        let mut file = File::open(file_path).await?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 1 << 16];
+        loop {
+            let n = file.read(&mut buffer).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buffer[..n]);
+        }
+        let actual = hex::encode(hasher.finalize());
+        anyhow::ensure!(
+            actual == expected,
+            "Checksum mismatch for LogFile: {:?}, expected: {}, actual: {}",
+            file_path,
+            expected,
+            actual
+        );
+        Ok(())
+    }
+
+    /// Returns the url if the given path is an HTTP(S) url which points to a remote log file
+    pub fn remote_url(path: &PathBuf) -> Option<Url> {
+        path.to_str()
+            .and_then(|s| Url::parse(s).ok())
+            .filter(|url| url.scheme() == "http" || url.scheme() == "https")
+    }
+
+    /// List the LogFiles of an import path, which is either a LogFile or a directory of LogFiles (recursing one
+    /// level), ordered by their milestone range. The unfinished (`.part`) files and the checksum files are skipped.
+    pub async fn import_paths(path: &PathBuf) -> anyhow::Result<Vec<PathBuf>> {
+        if path.is_file() {
+            return Ok(vec![path.clone()]);
+        }
+        anyhow::ensure!(path.is_dir(), "Invalid path");
+        let mut file_paths = Vec::new();
+        for entry_path in Self::read_dir(path).await? {
+            if entry_path.is_dir() {
+                file_paths.extend(Self::read_dir(&entry_path).await?.into_iter().filter(|p| p.is_file()));
+            } else if entry_path.is_file() {
+                file_paths.push(entry_path);
+            }
+        }
+        let mut log_files = file_paths
+            .into_iter()
+            .filter_map(|file_path| {
+                let range = file_path
+                    .extension()
+                    .filter(|extension| extension.to_str() == Some("log"))
+                    .and(file_path.file_stem())
+                    .and_then(|filename| filename.to_str())
+                    .and_then(|filename| Self::parse_filename(filename).ok())?;
+                Some((range, file_path))
+            })
+            .collect::<Vec<_>>();
+        log_files.sort_by_key(|(range, _)| *range);
+        Ok(log_files.into_iter().map(|(_, file_path)| file_path).collect())
+    }
+
+    async fn read_dir(dir_path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        let mut entries = tokio::fs::read_dir(dir_path)
+            .await
+            .map_err(|e| anyhow!("Unable to read the directory {}: {}", dir_path.display(), e))?;
+        let mut entry_paths = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            entry_paths.push(entry.path());
+        }
+        Ok(entry_paths)
+    }
+
+    /// Parse the milestone range out of a finished log filename (ie `{from}to{to}`)
+    fn parse_filename(filename: &str) -> anyhow::Result<(u32, u32)> {
+        let split = filename.split("to").collect::<Vec<_>>();
+        anyhow::ensure!(split.len() == 2, "Invalid filename!");
+        Ok((split[0].parse()?, split[1].parse()?))
+    }
+
+    fn local_file(&mut self) -> anyhow::Result<&mut BufReader<File>> {
+        match &mut self.file {
+            LogFileSource::Local(file) => Ok(file),
+            LogFileSource::Remote(_) => bail!("Remote log file: {} is read-only", self.filename),
+        }
+    }
+
     /// Complete a log file and save it to the given directory
     pub async fn finish(&mut self, dir_path: &PathBuf) -> anyhow::Result<()> {
         let new_file_name = format!("{}to{}.log", self.from_ms_index, self.to_ms_index);
@@ -147,7 +309,8 @@ impl LogFile {
             self.maybe_corrupted = true;
             bail!(e)
         };
-        if let Err(e) = self.file.get_mut().sync_all().await {
+        let file = self.local_file()?;
+        if let Err(e) = file.get_mut().sync_all().await {
             self.maybe_corrupted = true;
             bail!(e)
         };
@@ -157,7 +320,8 @@ impl LogFile {
     /// Append a new line to the log file
     pub async fn append_line(&mut self, line: &Vec<u8>) -> anyhow::Result<()> {
         // append to the file
-        if let Err(e) = self.file.write_all(line.as_ref()).await {
+        let file = self.local_file()?;
+        if let Err(e) = file.write_all(line.as_ref()).await {
             self.maybe_corrupted = true;
             bail!(
                 "Unable to append milestone data line into the log file: {}, error: {}",
@@ -170,6 +334,47 @@ impl LogFile {
         self.len += line.len() as u64;
         Ok(())
     }
+    /// Read the LogFile header (if any) and return the LogFile format version, a LogFile without header is
+    /// treated as version 0, and a LogFile with a version newer than `LOG_FILE_VERSION` is rejected.
+    /// Note: this must be invoked before fetching the first milestone data
+    pub async fn read_version(&mut self) -> anyhow::Result<u32> {
+        if self.len == 0 {
+            return Ok(self.version);
+        }
+        let mut first_line = String::new();
+        let n = match &mut self.file {
+            LogFileSource::Local(file) => file.read_line(&mut first_line).await,
+            LogFileSource::Remote(remote) => remote.read_line(&mut first_line).await,
+        }
+        .map_err(|e| {
+            self.maybe_corrupted = true;
+            anyhow!("Unable to read the header of LogFile: {}, error: {}", self.filename, e)
+        })?;
+        if let Some(header) = LogFileHeader::from_line(&first_line) {
+            anyhow::ensure!(
+                header.is_supported(),
+                "Unsupported LogFile: {} version: {}, the latest supported version is: {}",
+                self.filename,
+                header.log_file_version,
+                LOG_FILE_VERSION
+            );
+            self.len -= n as u64;
+            self.version = header.log_file_version;
+        } else {
+            // unversioned LogFile, keep the line as it's already the first milestone data
+            self.version = 0;
+            if n > 0 {
+                self.pending_line.replace(first_line);
+            }
+        }
+        Ok(self.version)
+    }
+
+    /// Get the LogFile format version
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
     /// Fetch the next milestone data from the log file.
     /// Note: this supposed to be used by importer
     pub async fn next(&mut self) -> Result<Option<MilestoneData>, std::io::Error> {
@@ -184,7 +389,16 @@ impl LogFile {
             return Ok(None);
         }
         let mut milestone_data_line: String = String::new();
-        match self.file.read_line(&mut milestone_data_line).await {
+        let read = if let Some(line) = self.pending_line.take() {
+            milestone_data_line = line;
+            Ok(milestone_data_line.len())
+        } else {
+            match &mut self.file {
+                LogFileSource::Local(file) => file.read_line(&mut milestone_data_line).await,
+                LogFileSource::Remote(remote) => remote.read_line(&mut milestone_data_line).await,
+            }
+        };
+        match read {
             Ok(n) => {
                 if n == 0 {
                     self.finished = true;
@@ -240,6 +454,8 @@ pub struct Archiver {
     oneshot: Option<tokio::sync::oneshot::Receiver<u32>>,
     keyspace: ChronicleKeyspace,
     retries_per_query: usize,
+    /// The consistency of the sync record writes
+    sync_write_consistency: WriteConsistency,
     solidifiers_count: u8,
     handle: Option<ArchiverHandle>,
     inbox: ArchiverInbox,
@@ -272,6 +488,7 @@ impl Builder for ArchiverBuilder {
             milestones_data: std::collections::BinaryHeap::new(),
             oneshot: self.oneshot,
             retries_per_query: self.retries_per_query.unwrap_or(10),
+            sync_write_consistency: self.sync_write_consistency.unwrap_or_default(),
             handle,
             inbox,
         }
@@ -306,9 +523,7 @@ impl TryFrom<PathBuf> for LogFile {
                 .to_str()
                 .ok_or(anyhow::anyhow!("Invalid filename!"))?
                 .to_owned();
-            let split = filename.split("to").collect::<Vec<_>>();
-            anyhow::ensure!(split.len() == 2, "Invalid filename!");
-            let (from_ms_index, to_ms_index) = (split[0].parse()?, split[1].parse()?);
+            let (from_ms_index, to_ms_index) = Self::parse_filename(&filename)?;
             let std_file = std::fs::OpenOptions::new().write(false).read(true).open(file_path)?;
             let len = std_file.metadata()?.len();
             let file = tokio::fs::File::from_std(std_file);
@@ -318,12 +533,121 @@ impl TryFrom<PathBuf> for LogFile {
                 from_ms_index,
                 to_ms_index,
                 upper_ms_limit: to_ms_index,
-                file: BufReader::new(file),
+                file: LogFileSource::Local(BufReader::new(file)),
                 maybe_corrupted: false,
                 finished: false,
+                version: 0,
+                pending_line: None,
             })
         } else {
             anyhow::bail!("File path does not point to a file!");
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn verify_checksum() {
+        let dir = std::env::temp_dir().join(format!("chronicle-checksum-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let file_path = dir.join("1to3.log");
+        let content = b"{\"milestone\":1}\n{\"milestone\":2}\n";
+        tokio::fs::write(&file_path, content).await.unwrap();
+        let digest = hex::encode(Sha256::digest(content));
+        tokio::fs::write(dir.join("1to3.log.sha256"), format!("{}  1to3.log\n", digest))
+            .await
+            .unwrap();
+        assert!(LogFile::verify_checksum(&file_path).await.is_ok());
+        // tamper the log file
+        tokio::fs::write(&file_path, b"{\"milestone\":1}\n{\"milestone\":3}\n")
+            .await
+            .unwrap();
+        assert!(LogFile::verify_checksum(&file_path).await.is_err());
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+
+    #[tokio::test]
+    async fn import_paths() {
+        let dir = std::env::temp_dir().join(format!("chronicle-import-paths-{}", std::process::id()));
+        tokio::fs::create_dir_all(dir.join("nested/deeper")).await.unwrap();
+        for file_path in [
+            "30to40.log",
+            "2to10.log",
+            "10to30.log",
+            "10to30.log.sha256",
+            "45.part",
+            "nested/40to45.log",
+            "nested/deeper/45to50.log",
+        ]
+        .iter()
+        {
+            tokio::fs::write(dir.join(file_path), b"").await.unwrap();
+        }
+        // a single file
+        let single = dir.join("30to40.log");
+        assert_eq!(LogFile::import_paths(&single).await.unwrap(), vec![single.clone()]);
+        // a directory, ordered by the milestone range rather than the file name
+        assert_eq!(
+            LogFile::import_paths(&dir).await.unwrap(),
+            vec![
+                dir.join("2to10.log"),
+                dir.join("10to30.log"),
+                dir.join("30to40.log"),
+                dir.join("nested/40to45.log"),
+            ]
+        );
+        assert!(LogFile::import_paths(&dir.join("missing")).await.is_err());
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+
+    fn milestone_data_line(milestone_index: u32) -> Vec<u8> {
+        let milestone_data = MilestoneData::new(milestone_index, CreatedBy::Syncer);
+        let mut line = serde_json::to_string(&milestone_data).unwrap();
+        line.push('\n');
+        line.into()
+    }
+
+    async fn import_all(file_path: PathBuf) -> anyhow::Result<(u32, Vec<u32>)> {
+        let mut log_file = LogFile::try_from(file_path)?;
+        let version = log_file.read_version().await?;
+        let mut imported = Vec::new();
+        while let Some(milestone_data) = log_file.next().await? {
+            imported.push(milestone_data.milestone_index());
+        }
+        Ok((version, imported))
+    }
+
+    #[tokio::test]
+    async fn log_file_versions() {
+        let dir = std::env::temp_dir().join(format!("chronicle-log-version-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        // unversioned LogFile
+        let mut v0 = milestone_data_line(1);
+        v0.extend(milestone_data_line(2));
+        tokio::fs::write(dir.join("1to3.log"), v0).await.unwrap();
+        assert_eq!(import_all(dir.join("1to3.log")).await.unwrap(), (0, vec![1, 2]));
+        // versioned LogFile, written by the archiver
+        let mut log_file = LogFile::create(&dir, 3, None).await.unwrap();
+        log_file.append_line(&milestone_data_line(3)).await.unwrap();
+        log_file.append_line(&milestone_data_line(4)).await.unwrap();
+        log_file.finish(&dir).await.unwrap();
+        assert_eq!(
+            import_all(dir.join("3to5.log")).await.unwrap(),
+            (LOG_FILE_VERSION, vec![3, 4])
+        );
+        // LogFile from the future
+        let mut future = LogFileHeader {
+            log_file_version: LOG_FILE_VERSION + 1,
+        }
+        .to_line()
+        .into_bytes();
+        future.extend(milestone_data_line(5));
+        tokio::fs::write(dir.join("5to6.log"), future).await.unwrap();
+        let err = import_all(dir.join("5to6.log")).await.unwrap_err();
+        assert!(err.to_string().contains("Unsupported LogFile"));
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use reqwest::{
+    header::RANGE,
+    Client,
+    Response,
+    StatusCode,
+};
+
+/// The number of times a remote log file stream is re-requested (using a range request) after it got interrupted
+pub const REMOTE_LOG_FILE_RETRIES: u8 = 5;
+
+#[derive(Debug)]
+/// Remote (HTTP/HTTPS) log file reader, which streams the log file lines without downloading it first.
+pub struct RemoteLogFile {
+    client: Client,
+    url: Url,
+    /// The active response stream
+    response: Option<Response>,
+    /// Bytes received but not consumed yet
+    buffer: Vec<u8>,
+    /// The total bytes received so far, used as offset by range requests
+    fetched: u64,
+    retries: u8,
+}
+
+impl RemoteLogFile {
+    /// Open a remote log file and return it along with its content length
+    pub async fn open(url: Url) -> anyhow::Result<(Self, u64)> {
+        let client = Client::new();
+        let response = client
+            .get(url.clone())
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| anyhow!("Unable to request remote log file: {}, error: {}", url, e))?;
+        let len = response
+            .content_length()
+            .ok_or_else(|| anyhow!("Remote log file: {} did not provide content length", url))?;
+        let remote = Self {
+            client,
+            url,
+            response: Some(response),
+            buffer: Vec::new(),
+            fetched: 0,
+            retries: REMOTE_LOG_FILE_RETRIES,
+        };
+        Ok((remote, len))
+    }
+    /// Read the next line (including the line feed) and append it to the provided buf.
+    /// Returns the number of bytes read, zero means the end of the remote file
+    pub async fn read_line(&mut self, buf: &mut String) -> Result<usize, std::io::Error> {
+        loop {
+            if let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+                let line = self.buffer.drain(..=pos).collect::<Vec<u8>>();
+                return Self::push_line(line, buf);
+            }
+            if let Some(chunk) = self.next_chunk().await? {
+                self.buffer.extend_from_slice(&chunk);
+            } else {
+                // the last line might not end with line feed
+                let line = std::mem::take(&mut self.buffer);
+                return Self::push_line(line, buf);
+            }
+        }
+    }
+    fn push_line(line: Vec<u8>, buf: &mut String) -> Result<usize, std::io::Error> {
+        let n = line.len();
+        let line = String::from_utf8(line).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        buf.push_str(&line);
+        Ok(n)
+    }
+    async fn next_chunk(&mut self) -> Result<Option<Vec<u8>>, std::io::Error> {
+        loop {
+            if self.response.is_none() {
+                let response = self.request_remaining().await.map_err(|e| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::Other,
+                        format!("Unable to resume remote log file: {}, error: {}", self.url, e),
+                    )
+                })?;
+                self.response.replace(response);
+            }
+            match self.response.as_mut().unwrap().chunk().await {
+                Ok(Some(chunk)) => {
+                    self.fetched += chunk.len() as u64;
+                    return Ok(Some(chunk.to_vec()));
+                }
+                Ok(None) => return Ok(None),
+                Err(e) => {
+                    self.response.take();
+                    if self.retries == 0 {
+                        return Err(std::io::Error::new(std::io::ErrorKind::Other, e));
+                    }
+                    self.retries -= 1;
+                    warn!(
+                        "Remote log file: {} stream interrupted at byte: {}, error: {}, resuming",
+                        self.url, self.fetched, e
+                    );
+                }
+            }
+        }
+    }
+    /// Request the remaining bytes of the remote log file using a range request
+    async fn request_remaining(&self) -> anyhow::Result<Response> {
+        let response = self
+            .client
+            .get(self.url.clone())
+            .header(RANGE, format!("bytes={}-", self.fetched))
+            .send()
+            .await?
+            .error_for_status()?;
+        ensure!(
+            response.status() == StatusCode::PARTIAL_CONTENT,
+            "Server does not support range requests, status: {}",
+            response.status()
+        );
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::AsyncReadExt,
+        net::TcpListener,
+    };
+
+    const BODY: &str = "{\"line\":1}\n{\"line\":2}\n{\"line\":3}\n";
+
+    /// Serve BODY, but cut the first response in the middle to force a range request
+    async fn serve(listener: TcpListener) {
+        let mut first = true;
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut request = vec![0u8; 1024];
+            let n = socket.read(&mut request).await.unwrap();
+            let request = String::from_utf8_lossy(&request[..n]).to_lowercase();
+            let offset = request
+                .lines()
+                .find_map(|l| l.strip_prefix("range: bytes="))
+                .and_then(|r| r.trim_end_matches('-').parse::<usize>().ok());
+            let response = match offset {
+                Some(offset) => format!(
+                    "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\n\r\n{}",
+                    BODY.len() - offset,
+                    &BODY[offset..]
+                ),
+                None if first => {
+                    first = false;
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                        BODY.len(),
+                        &BODY[..15]
+                    )
+                }
+                None => format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}", BODY.len(), BODY),
+            };
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.ok();
+        }
+    }
+
+    #[tokio::test]
+    async fn read_lines_and_resume() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener));
+        let url = Url::parse(&format!("http://{}/1to4.log", addr)).unwrap();
+        let (mut remote, len) = RemoteLogFile::open(url).await.unwrap();
+        assert_eq!(len, BODY.len() as u64);
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            if remote.read_line(&mut line).await.unwrap() == 0 {
+                break;
+            }
+            lines.push(line);
+        }
+        assert_eq!(lines.concat(), BODY);
+        assert_eq!(lines.len(), 3);
+    }
+}
@@ -86,8 +86,20 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Collector {
                     }
                 }
                 CollectorEvent::Message(message_id, mut message) => {
+                    self.feed_limiter.release();
+                    if self.drop_duplicate(FeedTopic::Messages, message_id) {
+                        continue;
+                    }
                     // check if msg already in lru cache(if so then it's already presisted)
                     if let None = self.lru_msg.get(&message_id) {
+                        #[cfg(feature = "otel")]
+                        let _span = chronicle_common::telemetry::start_span(
+                            "broker_ingest_message",
+                            vec![chronicle_common::telemetry::opentelemetry::KeyValue::new(
+                                "message_id",
+                                message_id.to_string(),
+                            )],
+                        );
                         // store message
                         self.insert_message(&message_id, &mut message).unwrap_or_else(|e| {
                             error!("{}", e);
@@ -97,10 +109,14 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Collector {
                     }
                 }
                 CollectorEvent::MessageReferenced(metadata) => {
+                    self.feed_limiter.release();
                     if metadata.referenced_by_milestone_index.is_none() {
                         // metadata is not referenced yet, so we discard it.
                         continue;
                     }
+                    if self.drop_duplicate(FeedTopic::MessagesReferenced, metadata.message_id) {
+                        continue;
+                    }
                     let ref_ms = metadata.referenced_by_milestone_index.as_ref().unwrap();
                     let _partition_id = (ref_ms % (self.collector_count as u32)) as u8;
                     let message_id = metadata.message_id;
@@ -273,9 +289,15 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Collector {
                                     .send(event);
                             }
                         }
+                        Internal::SwitchKeyspace(keyspace) => {
+                            info!("{} switched to keyspace: {}", self.get_name(), keyspace.name());
+                            self.default_keyspace = keyspace;
+                        }
                         Internal::Shutdown => {
                             // To shutdown the collector we simply drop the handle
                             self.handle.take();
+                            // unblock any feed source awaiting on the feed channel
+                            self.feed_limiter.close();
                             // drop heap
                             self.requester_handles.drain().for_each(|h| {
                                 h.shutdown();
@@ -290,6 +312,16 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Collector {
 }
 
 impl Collector {
+    /// Check whether the feed event is a duplicate within the dedup window, counting the dropped ones
+    fn drop_duplicate(&mut self, topic: FeedTopic, message_id: MessageId) -> bool {
+        let duplicate = self.feed_dedup.is_duplicate(topic, message_id);
+        if duplicate {
+            DUPLICATE_FEED_EVENTS
+                .with_label_values(&[self.default_keyspace.name().as_ref(), topic.as_str()])
+                .inc();
+        }
+        duplicate
+    }
     /// Send an error event to the solidifier for a given milestone index
     fn send_err_solidifiy(&self, try_ms_index: u32) {
         // inform solidifier
@@ -386,7 +418,10 @@ impl Collector {
     #[cfg(feature = "filter")]
     fn get_keyspace_for_message(&self, message: &mut Message) -> ChronicleKeyspace {
         let res = futures::executor::block_on(chronicle_filter::filter_messages(message));
-        ChronicleKeyspace::new(res.keyspace.into_owned())
+        ChronicleKeyspace::configured(
+            res.keyspace.into_owned(),
+            &chronicle_common::get_config().storage_config,
+        )
     }
     /// Get the Chronicle keyspace
     fn get_keyspace(&self) -> ChronicleKeyspace {
@@ -412,8 +447,13 @@ impl Collector {
             let milestone_index = MilestoneIndex(*meta.referenced_by_milestone_index.as_ref().unwrap());
             let solidifier_id = (*milestone_index % (self.collector_count as u32)) as u8;
             let solidifier_handle = self.solidifier_handles.get(&solidifier_id).unwrap().clone();
-            let inherent_worker =
-                AtomicWorker::new(solidifier_handle, *milestone_index, *message_id, self.retries_per_query);
+            let inherent_worker = AtomicWorker::new(
+                solidifier_handle,
+                *milestone_index,
+                *message_id,
+                &self.mirror_keyspaces,
+                self.retries_per_query,
+            );
             let message_tuple = (message.clone(), meta.clone());
             // store message and metadata
             self.insert(&inherent_worker, &keyspace, *message_id, message_tuple)?;
@@ -558,6 +598,21 @@ impl Collector {
         let partitioned = Partitioned::new(index.clone(), partition_id, milestone_index.0);
         let index_record = IndexationRecord::new(*message_id, inclusion_state);
         self.insert(inherent_worker, &self.get_keyspace(), partitioned, index_record)?;
+        // insert tag record and the hint of its bucket
+        let tag = Tag(index.0.clone());
+        let tag_hint = Hint::tag(tag.bucket().to_owned());
+        self.insert(
+            inherent_worker,
+            &self.get_keyspace(),
+            Partitioned::new(tag, partition_id, milestone_index.0),
+            (),
+        )?;
+        self.insert(
+            inherent_worker,
+            &self.get_keyspace(),
+            tag_hint,
+            Partition::new(partition_id, *milestone_index),
+        )?;
         // insert hint record
         let hint = Hint::index(index.0);
         let partition = Partition::new(partition_id, *milestone_index);
@@ -594,7 +649,13 @@ impl Collector {
         #[cfg(not(feature = "filter"))]
         let keyspace = self.get_keyspace();
         let solidifier_handle = self.clone_solidifier_handle(*self.ref_ms);
-        let inherent_worker = AtomicWorker::new(solidifier_handle, *self.ref_ms, message_id, self.retries_per_query);
+        let inherent_worker = AtomicWorker::new(
+            solidifier_handle,
+            *self.ref_ms,
+            message_id,
+            &self.mirror_keyspaces,
+            self.retries_per_query,
+        );
         // Insert parents/children
         self.insert_parents(
             &inherent_worker,
@@ -815,19 +876,46 @@ impl Collector {
             }
         }
     }
-    /// The low-level insert function to insert a key/value pair through an inherent worker
+    /// The low-level insert function to insert a key/value pair through an inherent worker, and to mirror it into the
+    /// mirror keyspaces
     fn insert<I, S, K, V>(&self, inherent_worker: &I, keyspace: &S, key: K, value: V) -> anyhow::Result<()>
     where
         I: Inherent,
         S: 'static + Insert<K, V>,
+        ChronicleKeyspace: Insert<K, V>,
         K: 'static + Send + Clone,
         V: 'static + Send + Clone,
     {
+        self.mirror_insert(inherent_worker, &key, &value);
         let insert_req = keyspace.insert(&key, &value).consistency(Consistency::One).build()?;
         let worker = inherent_worker.inherent_boxed(keyspace.clone(), key, value);
         insert_req.send_local(worker);
         Ok(())
     }
+    /// Insert a key/value pair into each mirror keyspace through the mirror worker of the inherent worker, which
+    /// reports the writes of the milestone messages per mirror keyspace, so a failed mirror write blocks neither the
+    /// solidification of the milestone nor the writes to the other keyspaces
+    fn mirror_insert<I, K, V>(&self, inherent_worker: &I, key: &K, value: &V)
+    where
+        I: Inherent,
+        ChronicleKeyspace: Insert<K, V>,
+        K: 'static + Send + Clone,
+        V: 'static + Send + Clone,
+    {
+        for keyspace in self.mirror_keyspaces.iter() {
+            match keyspace.insert(key, value).consistency(Consistency::One).build() {
+                Ok(insert_req) => {
+                    let worker = inherent_worker.mirror_boxed(keyspace.clone(), key.clone(), value.clone());
+                    insert_req.send_local(worker);
+                }
+                Err(e) => error!(
+                    "Unable to mirror the insert into the {} keyspace: {}",
+                    keyspace.name(),
+                    e
+                ),
+            }
+        }
+    }
     /// Delete the `Parents` of a given message id in the table
     fn delete_parents(
         &self,
@@ -921,13 +1009,26 @@ impl Collector {
         }
         Ok(())
     }
-    /// Delete the key in the `Chronicle` keyspace
+    /// Delete the key in the `Chronicle` keyspace, and in the mirror keyspaces
     fn delete<K, V>(&self, key: K) -> anyhow::Result<()>
     where
         ChronicleKeyspace: Delete<K, V>,
         K: 'static + Send + Clone,
         V: 'static + Send + Clone,
     {
+        for keyspace in self.mirror_keyspaces.iter() {
+            match keyspace.delete(&key).consistency(Consistency::One).build() {
+                Ok(delete_req) => {
+                    let worker = DeleteWorker::boxed(keyspace.clone(), key.clone(), self.retries_per_query);
+                    delete_req.send_local(worker);
+                }
+                Err(e) => error!(
+                    "Unable to mirror the delete into the {} keyspace: {}",
+                    keyspace.name(),
+                    e
+                ),
+            }
+        }
         let delete_req = self
             .default_keyspace
             .delete(&key)
@@ -943,18 +1044,44 @@ impl Collector {
 pub struct AtomicWorker {
     /// The arced atomic solidifier handle
     arc_handle: Arc<AtomicSolidifierHandle>,
+    /// The arced atomic solidifier handles of the mirror keyspaces, by keyspace name
+    mirror_handles: HashMap<String, Arc<AtomicSolidifierHandle>>,
     /// The number of retires
     retries: usize,
 }
 
 impl AtomicWorker {
-    /// Create a new atomic solidifier worker with a solidifier handle, an milestone index, a message id, and a number
-    /// of retries
-    fn new(solidifier_handle: SolidifierHandle, milestone_index: u32, message_id: MessageId, retries: usize) -> Self {
-        let any_error = std::sync::atomic::AtomicBool::new(false);
-        let atomic_handle = AtomicSolidifierHandle::new(solidifier_handle, milestone_index, message_id, any_error);
-        let arc_handle = std::sync::Arc::new(atomic_handle);
-        Self { arc_handle, retries }
+    /// Create a new atomic solidifier worker with a solidifier handle, an milestone index, a message id, the mirror
+    /// keyspaces, and a number of retries
+    fn new(
+        solidifier_handle: SolidifierHandle,
+        milestone_index: u32,
+        message_id: MessageId,
+        mirror_keyspaces: &[ChronicleKeyspace],
+        retries: usize,
+    ) -> Self {
+        let atomic_handle = |mirror: Option<String>| {
+            let any_error = std::sync::atomic::AtomicBool::new(false);
+            let atomic_handle =
+                AtomicSolidifierHandle::new(solidifier_handle.clone(), milestone_index, message_id, any_error);
+            Arc::new(match mirror {
+                Some(keyspace) => atomic_handle.mirrored(keyspace),
+                None => atomic_handle,
+            })
+        };
+        let arc_handle = atomic_handle(None);
+        let mirror_handles = mirror_keyspaces
+            .iter()
+            .map(|keyspace| {
+                let keyspace = keyspace.name().to_string();
+                (keyspace.clone(), atomic_handle(Some(keyspace)))
+            })
+            .collect();
+        Self {
+            arc_handle,
+            mirror_handles,
+            retries,
+        }
     }
 }
 
@@ -971,6 +1098,11 @@ trait Inherent {
         S: 'static + Insert<K, V>,
         K: 'static + Send + Clone,
         V: 'static + Send + Clone;
+    fn mirror_boxed<K, V>(&self, keyspace: ChronicleKeyspace, key: K, value: V) -> Box<dyn Worker>
+    where
+        ChronicleKeyspace: 'static + Insert<K, V>,
+        K: 'static + Send + Clone,
+        V: 'static + Send + Clone;
 }
 
 /// Implement the `Inherent` trait for the simple worker
@@ -983,6 +1115,14 @@ impl Inherent for SimpleWorker {
     {
         InsertWorker::boxed(keyspace, key, value, self.retries)
     }
+    fn mirror_boxed<K, V>(&self, keyspace: ChronicleKeyspace, key: K, value: V) -> Box<dyn Worker>
+    where
+        ChronicleKeyspace: 'static + Insert<K, V>,
+        K: 'static + Send + Clone,
+        V: 'static + Send + Clone,
+    {
+        InsertWorker::boxed(keyspace, key, value, self.retries)
+    }
 }
 
 /// Implement the `Inherent` trait for the atomic solidifier worker
@@ -995,4 +1135,18 @@ impl Inherent for AtomicWorker {
     {
         AtomicSolidifierWorker::boxed(self.arc_handle.clone(), keyspace, key, value, self.retries)
     }
+    fn mirror_boxed<K, V>(&self, keyspace: ChronicleKeyspace, key: K, value: V) -> Box<dyn Worker>
+    where
+        ChronicleKeyspace: 'static + Insert<K, V>,
+        K: 'static + Send + Clone,
+        V: 'static + Send + Clone,
+    {
+        // the writes are confirmed to the solidifier per mirror keyspace
+        match self.mirror_handles.get(keyspace.name().as_ref()) {
+            Some(mirror_handle) => {
+                AtomicSolidifierWorker::boxed(mirror_handle.clone(), keyspace, key, value, self.retries)
+            }
+            None => InsertWorker::boxed(keyspace, key, value, self.retries),
+        }
+    }
 }
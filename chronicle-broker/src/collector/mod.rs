@@ -20,15 +20,27 @@ use std::collections::{
     VecDeque,
 };
 
-use chronicle_common::config::{
-    PartitionConfig,
-    StorageConfig,
+use chronicle_common::{
+    config::{
+        FeedChannelConfig,
+        FeedOverflowPolicy,
+        PartitionConfig,
+        StorageConfig,
+    },
+    metrics::{
+        DROPPED_FEED_EVENTS,
+        DUPLICATE_FEED_EVENTS,
+    },
 };
 use lru::LruCache;
-use std::ops::{
-    Deref,
-    DerefMut,
+use std::{
+    ops::{
+        Deref,
+        DerefMut,
+    },
+    sync::Arc,
 };
+use tokio::sync::Semaphore;
 
 mod event_loop;
 mod init;
@@ -39,6 +51,7 @@ use url::Url;
 builder!(CollectorBuilder {
     partition_id: u8,
     lru_capacity: usize,
+    feed_dedup_window: usize,
     inbox: CollectorInbox,
     solidifier_handles: HashMap<u8, SolidifierHandle>,
     reqwest_client: Client,
@@ -49,7 +62,8 @@ builder!(CollectorBuilder {
     retries_per_endpoint: usize,
     requesters_channels: Vec<(RequesterSender, RequesterReceiver)>,
     handle: CollectorHandle,
-    storage_config: StorageConfig
+    storage_config: StorageConfig,
+    mirror_keyspaces: Vec<ChronicleKeyspace>
 });
 
 /// Collector events
@@ -71,6 +85,8 @@ pub enum Internal {
     Service(Service),
     /// Used by supervisor(Broker) to shutdown the collector
     Shutdown,
+    /// Used by supervisor(Broker) to switch the keyspace of the collector writes
+    SwitchKeyspace(ChronicleKeyspace),
 }
 /// Messages for asking the collector for missing data
 pub enum AskCollector {
@@ -85,9 +101,98 @@ pub enum AskCollector {
 pub struct CollectorHandle {
     pub(crate) tx: tokio::sync::mpsc::UnboundedSender<CollectorEvent>,
     pub(crate) requesters_senders: Vec<RequesterSender>,
+    pub(crate) feed_limiter: FeedLimiter,
+}
+
+/// Bounds the number of pending feed events in the collector inbox
+#[derive(Clone)]
+pub struct FeedLimiter {
+    permits: Arc<Semaphore>,
+    overflow_policy: FeedOverflowPolicy,
+    keyspace: String,
+}
+
+impl FeedLimiter {
+    /// Create a new feed limiter from the feed channel config, the dropped feed events are counted under the keyspace
+    pub fn new(config: &FeedChannelConfig, keyspace: &str) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(config.capacity)),
+            overflow_policy: config.overflow_policy,
+            keyspace: keyspace.to_owned(),
+        }
+    }
+    /// Reserve a slot for one feed event, it returns false if the feed event must be dropped
+    pub async fn reserve(&self) -> bool {
+        match self.overflow_policy {
+            FeedOverflowPolicy::Block => self.permits.acquire().await.map(|permit| permit.forget()).is_ok(),
+            FeedOverflowPolicy::Drop => {
+                if let Ok(permit) = self.permits.try_acquire() {
+                    permit.forget();
+                    true
+                } else {
+                    DROPPED_FEED_EVENTS.with_label_values(&[&self.keyspace]).inc();
+                    false
+                }
+            }
+        }
+    }
+    /// Release the slot of a processed feed event
+    pub fn release(&self) {
+        self.permits.add_permits(1);
+    }
+    /// Close the feed limiter, which unblocks the feed sources awaiting on a full channel
+    pub fn close(&self) {
+        self.permits.close();
+    }
+}
+
+/// The feed topic of a feed event
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum FeedTopic {
+    /// The `messages` topic
+    Messages,
+    /// The `messages/referenced` topic
+    MessagesReferenced,
+}
+
+impl FeedTopic {
+    /// The metric label of the topic
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            FeedTopic::Messages => "messages",
+            FeedTopic::MessagesReferenced => "messages/referenced",
+        }
+    }
+}
+
+/// Tracks the recently seen feed events, as every feed source delivers the same ones
+pub(crate) struct FeedDedup {
+    seen: Option<LruCache<(FeedTopic, MessageId), ()>>,
+}
+
+impl FeedDedup {
+    /// Create a feed dedup of the provided window, a zero window disables it
+    pub(crate) fn new(window: usize) -> Self {
+        Self {
+            seen: (window > 0).then(|| LruCache::new(window)),
+        }
+    }
+    /// Record the feed event, it returns true if it was already seen within the window
+    pub(crate) fn is_duplicate(&mut self, topic: FeedTopic, message_id: MessageId) -> bool {
+        match self.seen.as_mut() {
+            Some(seen) => seen.put((topic, message_id), ()).is_some(),
+            None => false,
+        }
+    }
 }
 
 impl CollectorHandle {
+    /// Send feed event to the collector, applying the feed overflow policy when the feed channel is full
+    pub(crate) async fn send_feed(&self, event: CollectorEvent) {
+        if self.feed_limiter.reserve().await && self.send(event).is_err() {
+            self.feed_limiter.release();
+        }
+    }
     pub(crate) fn send_requester_topology(&self, requester_topology: RequesterTopology) {
         self.requesters_senders.iter().for_each(|r| {
             let event = RequesterEvent::Topology(requester_topology.clone());
@@ -170,8 +275,12 @@ pub struct Collector {
     lru_msg: LruCache<MessageId, (MilestoneIndex, Message)>,
     /// The LRU cache from message id to message metadata
     lru_msg_ref: LruCache<MessageId, MessageMetadata>,
+    /// The recently seen feed events, used to drop the duplicates before processing them
+    feed_dedup: FeedDedup,
     /// The collector handle
     handle: Option<CollectorHandle>,
+    /// The feed limiter, used to release the slots of the processed feed events
+    feed_limiter: FeedLimiter,
     /// The collector inbox to receive collector events
     inbox: CollectorInbox,
     /// The hashmap from a partition id to the corresponding solidifier handle
@@ -194,6 +303,8 @@ pub struct Collector {
     partition_config: PartitionConfig,
     /// The `Chronicle` keyspace
     default_keyspace: ChronicleKeyspace,
+    /// The keyspaces the writes are mirrored into
+    mirror_keyspaces: Vec<ChronicleKeyspace>,
 }
 
 impl<H: ChronicleBrokerScope> ActorBuilder<BrokerHandle<H>> for CollectorBuilder {}
@@ -216,6 +327,12 @@ impl Builder for CollectorBuilder {
                         .and_then(|keyspace| Some(keyspace.name.clone()))
                 })
                 .unwrap_or("permanode".to_owned()),
+        )
+        .with_row_ttl(
+            self.storage_config
+                .as_ref()
+                .and_then(|config| config.keyspaces.first())
+                .and_then(|keyspace| keyspace.row_ttl_secs),
         );
         let partition_config = self
             .storage_config
@@ -226,6 +343,7 @@ impl Builder for CollectorBuilder {
             service: Service::new(),
             lru_msg: LruCache::new(lru_cap),
             lru_msg_ref: LruCache::new(lru_cap),
+            feed_dedup: FeedDedup::new(self.feed_dedup_window.unwrap_or(10000)),
             partition_id: self.partition_id.unwrap(),
             requester_handles: BinaryHeap::new(),
             est_ms: MilestoneIndex(0),
@@ -238,6 +356,11 @@ impl Builder for CollectorBuilder {
             requesters_channels: self
                 .requesters_channels
                 .expect("Collector expected requesters channels"),
+            feed_limiter: self
+                .handle
+                .as_ref()
+                .map(|handle| handle.feed_limiter.clone())
+                .expect("Collector expected handle"),
             handle: self.handle,
             inbox: self.inbox.unwrap(),
             pending_requests: HashMap::new(),
@@ -245,6 +368,7 @@ impl Builder for CollectorBuilder {
             reqwest_client: self.reqwest_client.unwrap(),
             partition_config,
             default_keyspace,
+            mirror_keyspaces: self.mirror_keyspaces.unwrap_or_default(),
         }
         .set_name()
     }
@@ -270,3 +394,67 @@ impl<H: ChronicleBrokerScope> AknShutdown<Collector> for BrokerHandle<H> {
         let _ = self.send(event);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn feed_dedup_window() {
+        let mut dedup = FeedDedup::new(2);
+        let message_id = MessageId::new([1; 32]);
+        assert!(!dedup.is_duplicate(FeedTopic::Messages, message_id));
+        // the referenced metadata of the message is a distinct feed event
+        assert!(!dedup.is_duplicate(FeedTopic::MessagesReferenced, message_id));
+        // within the window
+        assert!(dedup.is_duplicate(FeedTopic::Messages, message_id));
+        // outside the window, once two other feed events got seen since
+        assert!(!dedup.is_duplicate(FeedTopic::Messages, MessageId::new([2; 32])));
+        assert!(!dedup.is_duplicate(FeedTopic::Messages, MessageId::new([3; 32])));
+        assert!(!dedup.is_duplicate(FeedTopic::Messages, message_id));
+        // disabled
+        let mut dedup = FeedDedup::new(0);
+        assert!(!dedup.is_duplicate(FeedTopic::Messages, message_id));
+        assert!(!dedup.is_duplicate(FeedTopic::Messages, message_id));
+    }
+
+    #[tokio::test]
+    async fn feed_limiter_drop_policy() {
+        let limiter = FeedLimiter::new(
+            &FeedChannelConfig {
+                capacity: 2,
+                overflow_policy: FeedOverflowPolicy::Drop,
+            },
+            "permanode",
+        );
+        let dropped = DROPPED_FEED_EVENTS.with_label_values(&["permanode"]).get();
+        assert!(limiter.reserve().await);
+        assert!(limiter.reserve().await);
+        // the channel is full
+        assert!(!limiter.reserve().await);
+        assert!(DROPPED_FEED_EVENTS.with_label_values(&["permanode"]).get() > dropped);
+        limiter.release();
+        assert!(limiter.reserve().await);
+    }
+
+    #[tokio::test]
+    async fn feed_limiter_block_policy() {
+        let limiter = FeedLimiter::new(
+            &FeedChannelConfig {
+                capacity: 1,
+                overflow_policy: FeedOverflowPolicy::Block,
+            },
+            "permanode",
+        );
+        assert!(limiter.reserve().await);
+        // the channel is full, so reserve must await
+        assert!(tokio::time::timeout(Duration::from_millis(50), limiter.reserve())
+            .await
+            .is_err());
+        limiter.release();
+        assert!(tokio::time::timeout(Duration::from_millis(50), limiter.reserve())
+            .await
+            .unwrap());
+    }
+}
@@ -35,47 +35,50 @@ impl<H: ChronicleBrokerScope, T: ImportMode> EventLoop<BrokerHandle<H>> for Impo
                     ImporterEvent::CqlResult(result) => {
                         match result {
                             Ok(milestone_index) => {
-                                // remove it from in_progress
-                                let _ = self
-                                    .in_progress_milestones_data
-                                    .remove(&milestone_index)
-                                    .expect("Expected entry for a milestone data");
                                 info!("Imported milestone data for milestone index: {}", milestone_index);
-                                let ms_bytes_size = self
-                                    .in_progress_milestones_data_bytes_size
-                                    .remove(&milestone_index)
-                                    .expect("Expected size-entry for a milestone data");
-                                let skipped = false;
-                                Self::imported(
-                                    supervisor,
-                                    self.from_ms,
-                                    self.to_ms,
-                                    self.log_file_size,
-                                    milestone_index,
-                                    ms_bytes_size,
-                                    skipped,
-                                );
-                                // check if we should process more
-                                if !self.service.is_stopping() {
-                                    // process one more
-                                    if let Some(milestone_data) =
-                                        self.next_milestone_data(supervisor).await.map_err(|e| {
-                                            error!("Unable to fetch next milestone data. Error: {}", e);
-                                            Need::Abort
-                                        })?
-                                    {
-                                        T::handle_milestone_data(milestone_data, self).map_err(|e| {
-                                            error!("{}", e);
-                                            Need::Abort
-                                        })?;
-                                    } else {
+                                // emit the progress and process more only once a batch got imported
+                                if let Some((imported, fetch_count)) = self.milestone_imported(milestone_index) {
+                                    let skipped = false;
+                                    for (milestone_index, ms_bytes_size) in imported.iter() {
+                                        Self::imported(
+                                            supervisor,
+                                            self.from_ms,
+                                            self.to_ms,
+                                            self.log_file_size,
+                                            *milestone_index,
+                                            *ms_bytes_size,
+                                            skipped,
+                                        );
+                                    }
+                                    // check if we should process more
+                                    if !self.service.is_stopping() {
+                                        // process the next batch, unless the import got cancelled
+                                        for _ in 0..fetch_count {
+                                            if let Some(milestone_data) =
+                                                self.next_milestone_data(supervisor).await.map_err(|e| {
+                                                    error!("Unable to fetch next milestone data. Error: {}", e);
+                                                    Need::Abort
+                                                })?
+                                            {
+                                                T::handle_milestone_data(milestone_data, self).map_err(|e| {
+                                                    error!("{}", e);
+                                                    Need::Abort
+                                                })?;
+                                            } else {
+                                                break;
+                                            }
+                                        }
                                         // no more milestone data.
                                         if self.in_progress_milestones_data.is_empty() {
                                             // shut it down
-                                            info!("Imported the LogFile: {}", self.get_name());
+                                            if self.import_batch.is_cancelled() {
+                                                info!("Cancelled importing the LogFile: {}", self.get_name());
+                                            } else {
+                                                info!("Imported the LogFile: {}", self.get_name());
+                                            }
                                             return Ok(());
                                         }
-                                    };
+                                    }
                                 }
                             }
                             Err(_milestone_index) => {
@@ -90,7 +93,7 @@ impl<H: ChronicleBrokerScope, T: ImportMode> EventLoop<BrokerHandle<H>> for Impo
                             continue;
                         }
                         // extract the remaining milestone data iterator
-                        let (mut iter, analytic_record) = self
+                        let (mut iter, mut analytics) = self
                             .in_progress_milestones_data
                             .remove(&milestone_index)
                             .expect("Expected Entry for milestone data");
@@ -98,12 +101,14 @@ impl<H: ChronicleBrokerScope, T: ImportMode> EventLoop<BrokerHandle<H>> for Impo
                         let importer_handle = self.handle.clone().expect("Expected importer handle");
                         let keyspace = self.get_keyspace();
                         if !is_empty {
-                            self.insert_some_messages(milestone_index, &mut iter).map_err(|e| {
-                                error!("Unable to insert/import more message ,Error: {}", e);
-                                Need::Abort
-                            })?;
+                            self.insert_some_messages(milestone_index, &mut iter, &mut analytics)
+                                .map_err(|e| {
+                                    error!("Unable to insert/import more message ,Error: {}", e);
+                                    Need::Abort
+                                })?;
                         } else {
-                            // insert it into analytics and sync table
+                            // insert it into analytics and sync table, all the messages got accumulated by now
+                            let analytic_record = analytics.analytic_record(milestone_index);
                             let milestone_index = MilestoneIndex(milestone_index);
                             let synced_by = Some(self.chronicle_id);
                             let logged_by = Some(self.chronicle_id);
@@ -114,7 +119,9 @@ impl<H: ChronicleBrokerScope, T: ImportMode> EventLoop<BrokerHandle<H>> for Impo
                                 analytic_record.clone(),
                                 synced_record,
                                 self.retries_per_query,
-                            );
+                            )
+                            .with_sync_write_consistency(self.sync_write_consistency)
+                            .with_mirror_keyspaces(self.mirror_keyspaces.clone());
                             self.default_keyspace
                                 .insert_prepared(&Synckey, &analytic_record)
                                 .consistency(Consistency::One)
@@ -124,9 +131,20 @@ impl<H: ChronicleBrokerScope, T: ImportMode> EventLoop<BrokerHandle<H>> for Impo
                         }
                         // put it back
                         self.in_progress_milestones_data
-                            .insert(milestone_index, (iter, analytic_record));
+                            .insert(milestone_index, (iter, analytics));
                         // NOTE: we only delete it once we get Ok CqlResult
                     }
+                    ImporterEvent::Cancel => {
+                        if self.service.is_stopping() {
+                            continue;
+                        }
+                        info!("Cancelling the import of LogFile: {}", self.get_name());
+                        self.import_batch.cancel();
+                        // nothing in progress to wait for
+                        if self.in_progress_milestones_data.is_empty() {
+                            return Ok(());
+                        }
+                    }
                     ImporterEvent::Shutdown => {
                         self.service.update_status(ServiceStatus::Stopping);
                         self.handle.take();
@@ -180,8 +198,8 @@ impl<T: ImportMode> Importer<T> {
             )?;
         }
         let message_tuple = (message, metadata);
-        // store message and metadata
-        self.insert(inherent_worker, message_id, message_tuple)
+        // store message and metadata, unless a previous import did already, so a retried import never overwrites it
+        self.insert(inherent_worker, IfAbsent(message_id), message_tuple)
     }
 
     fn insert_parents<I: Inherent>(
@@ -265,6 +283,19 @@ impl<T: ImportMode> Importer<T> {
         let partitioned = Partitioned::new(index.clone(), partition_id, milestone_index.0);
         let index_record = IndexationRecord::new(*message_id, inclusion_state);
         self.insert(inherent_worker, partitioned, index_record)?;
+        // insert tag record and the hint of its bucket
+        let tag = Tag(index.0.clone());
+        let tag_hint = Hint::tag(tag.bucket().to_owned());
+        self.insert(
+            inherent_worker,
+            Partitioned::new(tag, partition_id, milestone_index.0),
+            (),
+        )?;
+        self.insert(
+            inherent_worker,
+            tag_hint,
+            Partition::new(partition_id, *milestone_index),
+        )?;
         // insert hint record
         let hint = Hint::index(index.0);
         let partition = Partition::new(partition_id, *milestone_index);
@@ -459,6 +490,8 @@ impl<T: ImportMode> Importer<T> {
         }
         Ok(())
     }
+    /// Insert the key/value pair into the default keyspace and into each mirror keyspace, the inserts share the
+    /// inherent worker so the milestone is only synced once they're all confirmed
     fn insert<I, K, V>(&self, inherent_worker: &I, key: K, value: V) -> anyhow::Result<()>
     where
         I: Inherent,
@@ -466,13 +499,15 @@ impl<T: ImportMode> Importer<T> {
         V: 'static + Send + Clone,
         ChronicleKeyspace: Insert<K, V>,
     {
-        let req = self
-            .default_keyspace
-            .insert(&key, &value)
-            .consistency(Consistency::One)
-            .build()?;
-        let worker = inherent_worker.inherent_boxed(key, value);
-        req.send_local(worker);
+        for keyspace in std::iter::once(&self.default_keyspace).chain(self.mirror_keyspaces.iter()) {
+            let req = keyspace.insert(&key, &value).consistency(Consistency::One).build()?;
+            let (inherent_worker, keyspace, key, value) =
+                (inherent_worker.clone(), keyspace.clone(), key.clone(), value.clone());
+            spawn_with_permit(self.insert_permits.clone(), move |permit| {
+                let worker = inherent_worker.inherent_boxed(keyspace, key, value, permit);
+                req.send_local(worker);
+            });
+        }
         Ok(())
     }
 }
@@ -15,15 +15,34 @@ impl<H: ChronicleBrokerScope, T: ImportMode> Init<BrokerHandle<H>> for Importer<
             self.service.update_status(ServiceStatus::Initializing);
             let event = BrokerEvent::Children(BrokerChild::Importer(self.service.clone(), Ok(()), self.parallelism));
             supervisor.send(event).ok();
-            let log_file = LogFile::try_from(self.file_path.clone()).map_err(|e| {
+            if self.verify_checksum {
+                if let Err(e) = self.verify_log_file_checksum().await {
+                    error!("{}", e);
+                    self.path_error.replace(e.to_string());
+                    return Err(Need::Abort);
+                }
+            }
+            let mut log_file = if let Some(url) = LogFile::remote_url(&self.file_path) {
+                LogFile::from_url(url).await
+            } else {
+                LogFile::try_from(self.file_path.clone())
+            }
+            .map_err(|e| {
                 error!("Unable to create LogFile. Error: {}", e);
                 Need::Abort
             })?;
+            if let Err(e) = log_file.read_version().await {
+                error!("{}", e);
+                self.path_error.replace(e.to_string());
+                return Err(Need::Abort);
+            }
             let from = log_file.from_ms_index();
             let to = log_file.to_ms_index();
             self.log_file_size = log_file.len();
             self.from_ms = from;
             self.to_ms = to;
+            self.milestone_gaps = MilestoneGaps::new(from);
+            self.job.replace(JOBS.track_import(from, to, self.log_file_size));
             let importer_session = ImporterSession::ProgressBar {
                 log_file_size: self.log_file_size,
                 from_ms: from,
@@ -56,8 +75,15 @@ impl<H: ChronicleBrokerScope, T: ImportMode> Init<BrokerHandle<H>> for Importer<
 }
 
 impl<T: ImportMode> Importer<T> {
+    async fn verify_log_file_checksum(&self) -> anyhow::Result<()> {
+        ensure!(
+            LogFile::remote_url(&self.file_path).is_none(),
+            "Checksum verification is not supported for remote LogFiles"
+        );
+        LogFile::verify_checksum(&self.file_path).await
+    }
     async fn init_importing<H: ChronicleBrokerScope>(&mut self, supervisor: &BrokerHandle<H>) -> anyhow::Result<()> {
-        for _ in 0..self.parallelism {
+        for _ in 0..self.import_batch.initial_fetch_count(self.parallelism) {
             if let Some(milestone_data) = self.next_milestone_data(supervisor).await? {
                 T::handle_milestone_data(milestone_data, self)?;
             } else {
@@ -78,11 +104,17 @@ impl<T: ImportMode> Importer<T> {
         let mut scan_budget: usize = 100;
         loop {
             let pre_len = log_file.len();
-            if let Some(milestone_data) = log_file.next().await? {
+            if let Some(mut milestone_data) = log_file.next().await? {
                 let milestone_index = milestone_data.milestone_index();
-                let not_in_import_range = !self.import_range.contains(&milestone_index);
-                let resume = self.resume && self.sync_data.completed.iter().any(|r| r.contains(&milestone_index));
-                if resume || not_in_import_range {
+                if let Some(gap) = self.milestone_gaps.observe(milestone_index) {
+                    warn!(
+                        "LogFile: {} skips the milestone indexes: {:?}",
+                        self.file_path.display(),
+                        gap
+                    );
+                    ensure!(!self.strict, "LogFile skips the milestone indexes: {:?}", gap);
+                }
+                if self.is_skipped(milestone_index) {
                     warn!(
                         "Skipping imported milestone data for milestone index: {}",
                         milestone_index
@@ -106,16 +138,41 @@ impl<T: ImportMode> Importer<T> {
                         tokio::task::yield_now().await;
                     }
                 } else {
+                    let duplicates = milestone_data.dedup();
+                    if duplicates > 0 {
+                        warn!(
+                            "Dropped {} duplicated messages of milestone data for milestone index: {}",
+                            duplicates, milestone_index
+                        );
+                    }
                     let ms_bytes_size = (pre_len - log_file.len()) as usize;
                     self.in_progress_milestones_data_bytes_size
                         .insert(milestone_index, ms_bytes_size);
                     return Ok(Some(milestone_data));
                 }
             } else {
+                if let Some(gap) = self.milestone_gaps.end(log_file.to_ms_index()) {
+                    warn!(
+                        "LogFile: {} misses the milestone indexes: {:?}",
+                        self.file_path.display(),
+                        gap
+                    );
+                    ensure!(!self.strict, "LogFile misses the milestone indexes: {:?}", gap);
+                }
                 return Ok(None);
             }
         }
     }
+    /// Whether the milestone data is skipped, as it's either out of the import range, already imported (ie by a
+    /// cancelled import which is resumed), or duplicated within the LogFile while its first copy is still in progress
+    pub(crate) fn is_skipped(&self, milestone_index: u32) -> bool {
+        let not_in_import_range = !self.import_range.contains(&milestone_index);
+        let resume = self.resume && self.sync_data.completed.iter().any(|r| r.contains(&milestone_index));
+        let in_progress = self
+            .in_progress_milestones_data_bytes_size
+            .contains_key(&milestone_index);
+        resume || not_in_import_range || in_progress
+    }
     pub(crate) fn imported<H: ChronicleBrokerScope>(
         supervisor: &BrokerHandle<H>,
         from_ms: u32,
@@ -139,6 +196,7 @@ impl<T: ImportMode> Importer<T> {
         &mut self,
         milestone_index: u32,
         milestone_data: &mut IntoIter<MessageId, FullMessage>,
+        analytics: &mut AnalyticsAccumulator,
     ) -> anyhow::Result<()> {
         let importer_handle = self
             .handle
@@ -148,7 +206,15 @@ impl<T: ImportMode> Importer<T> {
         let inherent_worker =
             MilestoneDataWorker::new(importer_handle, keyspace, milestone_index, self.retries_per_query);
         for _ in 0..self.parallelism {
-            if let Some((message_id, FullMessage(message, metadata))) = milestone_data.next() {
+            if let Some((message_id, full_message)) = milestone_data.next() {
+                analytics.add_message(&full_message).map_err(|e| {
+                    error!(
+                        "Unable to accumulate the analytics of message id: {}. Error: {}",
+                        message_id, e
+                    );
+                    e
+                })?;
+                let FullMessage(message, metadata) = full_message;
                 // Insert the message
                 self.insert_message_with_metadata(&inherent_worker, message_id, message, metadata)?;
             } else {
@@ -3,6 +3,7 @@
 use super::*;
 use crate::{
     application::{
+        write_consistency,
         BrokerChild,
         BrokerEvent,
         BrokerHandle,
@@ -18,7 +19,14 @@ use bee_message::{
     },
 };
 use chronicle_common::{
-    config::PartitionConfig,
+    config::{
+        PartitionConfig,
+        WriteConsistency,
+    },
+    jobs::{
+        ImportJobGuard,
+        JOBS,
+    },
     Synckey,
 };
 use chronicle_storage::access::SyncRecord;
@@ -34,7 +42,14 @@ use std::{
         Range,
     },
     path::PathBuf,
-    sync::atomic::Ordering,
+    sync::{
+        atomic::Ordering,
+        Arc,
+    },
+};
+use tokio::sync::{
+    OwnedSemaphorePermit,
+    Semaphore,
 };
 
 mod event_loop;
@@ -53,34 +68,42 @@ pub trait ImportMode: Sized + Send + 'static {
 }
 impl ImportMode for All {
     fn handle_milestone_data(milestone_data: MilestoneData, importer: &mut Importer<All>) -> anyhow::Result<()> {
-        let analytic_record = milestone_data.get_analytic_record().map_err(|e| {
-            error!("Unable to get analytic record for milestone data. Error: {}", e);
-            e
-        })?;
+        if !milestone_data.check_if_completed() {
+            bail!("Unable to import uncompleted milestone data");
+        }
         let milestone_index = milestone_data.milestone_index();
         let mut iterator = milestone_data.into_iter();
-        importer.insert_some_messages(milestone_index, &mut iterator)?;
+        // the analytics are accumulated as the messages get inserted
+        let mut analytics = AnalyticsAccumulator::default();
+        importer.insert_some_messages(milestone_index, &mut iterator, &mut analytics)?;
         importer
             .in_progress_milestones_data
-            .insert(milestone_index, (iterator, analytic_record));
+            .insert(milestone_index, (iterator, analytics));
         Ok(())
     }
 }
 
 impl ImportMode for Analytics {
     fn handle_milestone_data(milestone_data: MilestoneData, importer: &mut Importer<Analytics>) -> anyhow::Result<()> {
-        let analytic_record = milestone_data.get_analytic_record().map_err(|e| {
-            error!("Unable to get analytic record for milestone data. Error: {}", e);
-            e
-        })?;
+        if !milestone_data.check_if_completed() {
+            bail!("Unable to get analytic record for uncompleted milestone data");
+        }
         let milestone_index = milestone_data.milestone_index();
-        let iterator = milestone_data.into_iter();
-        importer.insert_analytic_record(&analytic_record)?;
-        // note: iterator is not needed to presist analytic record in Analytics mode,
-        // however we kept them for simplicty sake.
+        // the messages are dropped as they get accumulated, only the analytic record is persisted in Analytics mode
+        let mut analytics = AnalyticsAccumulator::default();
+        for (message_id, full_message) in milestone_data.into_iter() {
+            analytics.add_message(&full_message).map_err(|e| {
+                error!(
+                    "Unable to accumulate the analytics of message id: {}. Error: {}",
+                    message_id, e
+                );
+                e
+            })?;
+        }
+        importer.insert_analytic_record(&analytics.analytic_record(milestone_index))?;
         importer
             .in_progress_milestones_data
-            .insert(milestone_index, (iterator, analytic_record));
+            .insert(milestone_index, (HashMap::new().into_iter(), analytics));
         Ok(())
     }
 }
@@ -88,18 +111,154 @@ impl ImportMode for Analytics {
 builder!(ImporterBuilder<T> {
     file_path: PathBuf,
     retries_per_query: usize,
+    sync_write_consistency: WriteConsistency,
     resume: bool,
     import_range: Range<u32>,
+    verify_checksum: bool,
     parallelism: u8,
-    chronicle_id: u8
+    insert_concurrency: usize,
+    batch_size: usize,
+    strict: bool,
+    chronicle_id: u8,
+    mirror_keyspaces: Vec<ChronicleKeyspace>
 });
 
+/// The default max number of in-flight storage inserts of an importer
+pub const DEFAULT_INSERT_CONCURRENCY: usize = 1000;
+/// The upper bound of the in-flight storage inserts of an importer
+pub const MAX_INSERT_CONCURRENCY: usize = 10000;
+
+/// Get the effective insert concurrency, which is the requested one (or the default one if absent) clamped to
+/// `1..=MAX_INSERT_CONCURRENCY`
+pub fn insert_concurrency(requested: Option<usize>) -> usize {
+    requested
+        .unwrap_or(DEFAULT_INSERT_CONCURRENCY)
+        .clamp(1, MAX_INSERT_CONCURRENCY)
+}
+
+/// Spawn a task which waits for an insert permit before sending the insert, the permit is moved into the insert
+/// worker and released once the worker is done, so at most the semaphore permits inserts are in flight
+pub(crate) fn spawn_with_permit<F>(insert_permits: Arc<Semaphore>, send: F)
+where
+    F: 'static + Send + FnOnce(OwnedSemaphorePermit),
+{
+    tokio::spawn(async move {
+        if let Ok(permit) = insert_permits.acquire_owned().await {
+            send(permit);
+        }
+    });
+}
+
+/// The imported milestones whose progress is not emitted yet. Without a batch size every imported milestone is flushed
+/// on its own, and the importer fetches the next milestone, so `parallelism` milestones are in progress at any time.
+/// With a batch size the importer fetches a whole batch of milestones, inserts all of them, and only flushes the batch
+/// once every milestone of it got imported, before fetching the next batch
+pub(crate) struct ImportBatch {
+    batch_size: Option<usize>,
+    imported: Vec<(u32, usize)>,
+    cancelled: bool,
+}
+
+impl ImportBatch {
+    /// Create an import batch with the provided batch size (if any), which is at least one milestone
+    pub(crate) fn new(batch_size: Option<usize>) -> Self {
+        let batch_size = batch_size.map(|batch_size| batch_size.max(1));
+        Self {
+            batch_size,
+            imported: Vec::with_capacity(batch_size.unwrap_or(1)),
+            cancelled: false,
+        }
+    }
+    /// The number of milestones to fetch when the import starts, which is the batch size or the parallelism
+    pub(crate) fn initial_fetch_count(&self, parallelism: u8) -> usize {
+        self.batch_size.unwrap_or(parallelism as usize)
+    }
+    /// Cancel the import at the next milestone boundary, the in progress milestones are still imported (and synced,
+    /// so a resumed import skips them), but no more milestones are fetched
+    pub(crate) fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+    /// Whether the import got cancelled
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+    /// The number of milestones to fetch once a batch is flushed, which is none if the import got cancelled
+    pub(crate) fn fetch_count(&self, flushed: usize) -> usize {
+        if self.cancelled {
+            0
+        } else {
+            self.batch_size.unwrap_or(flushed)
+        }
+    }
+    /// Record an imported milestone index along with its bytes size, and take the batch once none of its milestones is
+    /// in progress anymore (right away without a batch size), so a trailing partial batch is flushed too
+    pub(crate) fn imported(
+        &mut self,
+        milestone_index: u32,
+        ms_bytes_size: usize,
+        in_progress_is_empty: bool,
+    ) -> Option<Vec<(u32, usize)>> {
+        self.imported.push((milestone_index, ms_bytes_size));
+        if self.batch_size.is_none() || in_progress_is_empty {
+            Some(std::mem::replace(
+                &mut self.imported,
+                Vec::with_capacity(self.batch_size.unwrap_or(1)),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// The milestone indexes skipped by a LogFile, which is expected to hold the contiguous milestones of its range
+pub(crate) struct MilestoneGaps {
+    next: u32,
+    gaps: Vec<Range<u32>>,
+}
+
+impl MilestoneGaps {
+    /// Create the milestone gaps of a LogFile which starts at the provided milestone index
+    pub(crate) fn new(from_ms: u32) -> Self {
+        Self {
+            next: from_ms,
+            gaps: Vec::new(),
+        }
+    }
+    /// Observe the next milestone index read from the LogFile, and get the gap preceding it (if any)
+    pub(crate) fn observe(&mut self, milestone_index: u32) -> Option<Range<u32>> {
+        let gap = self.gap_till(milestone_index);
+        self.next = self.next.max(milestone_index.saturating_add(1));
+        gap
+    }
+    /// Observe the end of the LogFile, whose range ends (exclusively) at `to_ms`, and get the trailing gap (if any)
+    pub(crate) fn end(&mut self, to_ms: u32) -> Option<Range<u32>> {
+        let gap = self.gap_till(to_ms);
+        self.next = self.next.max(to_ms);
+        gap
+    }
+    /// Get the skipped milestone ranges
+    pub(crate) fn gaps(&self) -> &[Range<u32>] {
+        &self.gaps
+    }
+    fn gap_till(&mut self, milestone_index: u32) -> Option<Range<u32>> {
+        if milestone_index > self.next {
+            let gap = self.next..milestone_index;
+            self.gaps.push(gap.clone());
+            Some(gap)
+        } else {
+            None
+        }
+    }
+}
+
 /// Importer events
 pub enum ImporterEvent {
     /// The result of an insert into the database
     CqlResult(Result<u32, u32>),
     /// Indicator to continue processing
     ProcessMore(u32),
+    /// Cancel the import at the next milestone boundary
+    Cancel,
     /// Shutdown the importer
     Shutdown,
 }
@@ -168,10 +327,14 @@ pub struct Importer<T> {
     to_ms: u32,
     /// The default Chronicle keyspace
     default_keyspace: ChronicleKeyspace,
+    /// The keyspaces the imported milestones are mirrored into
+    mirror_keyspaces: Vec<ChronicleKeyspace>,
     /// The partition configuration
     partition_config: PartitionConfig,
     /// The number of retires per query
     retries_per_query: usize,
+    /// The consistency of the sync record writes
+    sync_write_consistency: WriteConsistency,
     /// The chronicle id
     chronicle_id: u8,
     /// The number of parallelism
@@ -180,10 +343,24 @@ pub struct Importer<T> {
     resume: bool,
     /// The range of requested milestones to import
     import_range: Range<u32>,
+    /// Verify the LogFile checksum before importing
+    verify_checksum: bool,
+    /// Fail the import if the LogFile skips milestone indexes
+    strict: bool,
+    /// The milestone indexes skipped by the LogFile
+    milestone_gaps: MilestoneGaps,
+    /// The active import job, which is finished once the importer is dropped
+    job: Option<ImportJobGuard>,
+    /// The permits bounding the in-flight storage inserts
+    insert_permits: Arc<Semaphore>,
+    /// The imported milestones which are not flushed yet
+    import_batch: ImportBatch,
+    /// The reason why the LogFile path got rejected
+    path_error: Option<String>,
     /// The database sync data
     sync_data: SyncData,
     /// In progress milestones data
-    in_progress_milestones_data: HashMap<u32, (IntoIter<MessageId, FullMessage>, AnalyticRecord)>,
+    in_progress_milestones_data: HashMap<u32, (IntoIter<MessageId, FullMessage>, AnalyticsAccumulator)>,
     in_progress_milestones_data_bytes_size: HashMap<u32, usize>,
     /// The importer handle
     handle: Option<ImporterHandle>,
@@ -205,13 +382,14 @@ impl<T: ImportMode> Builder for ImporterBuilder<T> {
         // In order to use multiple keyspaces, the user must
         // use filters to determine where records go
         let config = chronicle_common::get_config();
-        let default_keyspace = ChronicleKeyspace::new(
+        let default_keyspace = ChronicleKeyspace::configured(
             config
                 .storage_config
                 .keyspaces
                 .first()
                 .and_then(|keyspace| Some(keyspace.name.clone()))
                 .unwrap_or("permanode".to_owned()),
+            &config.storage_config,
         );
         let partition_config = config.storage_config.partition_config;
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
@@ -229,14 +407,23 @@ impl<T: ImportMode> Builder for ImporterBuilder<T> {
             from_ms: 0,
             to_ms: 0,
             default_keyspace,
+            mirror_keyspaces: self.mirror_keyspaces.unwrap_or_default(),
             partition_config,
             parallelism: self.parallelism.unwrap_or(10),
             chronicle_id: self.chronicle_id.unwrap(),
             in_progress_milestones_data: HashMap::new(),
             in_progress_milestones_data_bytes_size: HashMap::new(),
             retries_per_query: self.retries_per_query.unwrap_or(10),
+            sync_write_consistency: self.sync_write_consistency.unwrap_or_default(),
             resume: self.resume.unwrap_or(true),
             import_range,
+            verify_checksum: self.verify_checksum.unwrap_or(false),
+            strict: self.strict.unwrap_or(false),
+            milestone_gaps: MilestoneGaps::new(0),
+            job: None,
+            insert_permits: Arc::new(Semaphore::new(insert_concurrency(self.insert_concurrency))),
+            import_batch: ImportBatch::new(self.batch_size),
+            path_error: None,
             sync_data: SyncData::default(),
             handle,
             inbox,
@@ -251,6 +438,26 @@ impl<T> Importer<T> {
     pub(crate) fn clone_handle(&self) -> Option<ImporterHandle> {
         self.handle.clone()
     }
+    /// Remove the imported milestone from the in progress ones, and get the flushed batch of imported milestones (if
+    /// any) along with the number of milestones to fetch next
+    pub(crate) fn milestone_imported(&mut self, milestone_index: u32) -> Option<(Vec<(u32, usize)>, usize)> {
+        // remove it from in_progress
+        let _ = self
+            .in_progress_milestones_data
+            .remove(&milestone_index)
+            .expect("Expected entry for a milestone data");
+        let ms_bytes_size = self
+            .in_progress_milestones_data_bytes_size
+            .remove(&milestone_index)
+            .expect("Expected size-entry for a milestone data");
+        let in_progress_is_empty = self.in_progress_milestones_data.is_empty();
+        self.import_batch
+            .imported(milestone_index, ms_bytes_size, in_progress_is_empty)
+            .map(|imported| {
+                let fetch_count = self.import_batch.fetch_count(imported.len());
+                (imported, fetch_count)
+            })
+    }
 }
 /// Implement `Name` trait of the Importer
 impl<T> Name for Importer<T> {
@@ -272,7 +479,8 @@ impl Importer<Analytics> {
                 keyspace,
                 analytic_record.clone(),
                 self.retries_per_query,
-            );
+            )
+            .with_mirror_keyspaces(self.mirror_keyspaces.clone());
             self.default_keyspace
                 .insert_prepared(&Synckey, analytic_record)
                 .consistency(Consistency::One)
@@ -307,6 +515,8 @@ where
     key: K,
     value: V,
     retries: usize,
+    /// The insert permit, released once the worker (and its clones) are dropped
+    _permit: Arc<OwnedSemaphorePermit>,
 }
 
 /// An atomic importer handle
@@ -354,10 +564,15 @@ where
     K: 'static + Send,
     V: 'static + Send,
 {
-    /// Create a new atomic importer worker with an atomic importer handle, a keyspace, a key, a value, and a number of
-    /// retries
-    pub fn new(handle: std::sync::Arc<AtomicImporterHandle<S>>, key: K, value: V) -> Self {
-        let keyspace = handle.keyspace.clone();
+    /// Create a new atomic importer worker with an atomic importer handle, the keyspace of the insert (either the
+    /// handle's one or a mirror keyspace), a key, a value, and an insert permit
+    pub fn new(
+        handle: std::sync::Arc<AtomicImporterHandle<S>>,
+        keyspace: S,
+        key: K,
+        value: V,
+        permit: OwnedSemaphorePermit,
+    ) -> Self {
         let retries = handle.retries;
         Self {
             handle,
@@ -365,12 +580,19 @@ where
             key,
             value,
             retries,
+            _permit: Arc::new(permit),
         }
     }
-    /// Create a new boxed atomic importer worker with an atomic importer handle, a keyspace, a key, a value, and a
-    /// number of retries
-    pub fn boxed(handle: std::sync::Arc<AtomicImporterHandle<S>>, key: K, value: V) -> Box<Self> {
-        Box::new(Self::new(handle, key, value))
+    /// Create a new boxed atomic importer worker with an atomic importer handle, the keyspace of the insert, a key, a
+    /// value, and an insert permit
+    pub fn boxed(
+        handle: std::sync::Arc<AtomicImporterHandle<S>>,
+        keyspace: S,
+        key: K,
+        value: V,
+        permit: OwnedSemaphorePermit,
+    ) -> Box<Self> {
+        Box::new(Self::new(handle, keyspace, key, value, permit))
     }
 }
 
@@ -381,7 +603,12 @@ where
     V: 'static + Send + Clone,
 {
     fn handle_response(self: Box<Self>, giveload: Vec<u8>) -> anyhow::Result<()> {
-        Decoder::from(giveload.try_into()?).get_void()
+        let decoder = Decoder::from(giveload.try_into()?);
+        // the insert-if-absent responds with whether it got applied, either way the row is stored
+        if decoder.is_rows()? {
+            return Ok(());
+        }
+        decoder.get_void()
     }
     fn handle_error(
         mut self: Box<Self>,
@@ -444,6 +671,10 @@ where
     synced_record: SyncRecord,
     /// The number of retries
     retries: usize,
+    /// The consistency of the `sync` table row write
+    sync_write_consistency: WriteConsistency,
+    /// The mirror keyspaces the records are inserted into next, one keyspace after the other
+    mirror_keyspaces: Vec<S>,
 }
 
 impl<S> AnalyzeAndSyncWorker<S>
@@ -466,8 +697,20 @@ where
             synced_record,
             analyzed: false,
             retries,
+            sync_write_consistency: WriteConsistency::default(),
+            mirror_keyspaces: Vec::new(),
         }
     }
+    /// Set the consistency of the `sync` table row write
+    pub fn with_sync_write_consistency(mut self: Box<Self>, sync_write_consistency: WriteConsistency) -> Box<Self> {
+        self.sync_write_consistency = sync_write_consistency;
+        self
+    }
+    /// Set the mirror keyspaces the records are inserted into once they're synced in the keyspace
+    pub fn with_mirror_keyspaces(mut self: Box<Self>, mirror_keyspaces: Vec<S>) -> Box<Self> {
+        self.mirror_keyspaces = mirror_keyspaces;
+        self
+    }
     ///  Create a new boxed ync worker with an importer handle, a keyspace, a `sync` table row (`SyncRecord`), and a
     /// number of retries
     pub fn boxed(
@@ -489,7 +732,17 @@ where
     fn handle_response(mut self: Box<Self>, giveload: Vec<u8>) -> anyhow::Result<()> {
         Decoder::from(giveload.try_into()?).get_void()?;
         let milestone_index = *self.synced_record.milestone_index;
-        if self.analyzed {
+        if self.analyzed && !self.mirror_keyspaces.is_empty() {
+            // the milestone is synced in the keyspace, so its records are inserted into the next mirror keyspace
+            self.keyspace = self.mirror_keyspaces.remove(0);
+            self.analyzed = false;
+            let req = self
+                .keyspace
+                .insert_prepared(&Synckey, &self.analytic_record)
+                .consistency(Consistency::One)
+                .build()?;
+            req.send_local(self);
+        } else if self.analyzed {
             // tell importer
             self.handle.send(ImporterEvent::CqlResult(Ok(milestone_index))).ok();
         } else {
@@ -499,7 +752,7 @@ where
             let req = self
                 .keyspace
                 .insert_prepared(&Synckey, &self.synced_record)
-                .consistency(Consistency::One)
+                .consistency(write_consistency(self.sync_write_consistency))
                 .build()?;
             req.send_local(self);
         }
@@ -536,7 +789,7 @@ where
                 let req = self
                     .keyspace
                     .insert_query(&Synckey, &self.synced_record)
-                    .consistency(Consistency::One)
+                    .consistency(write_consistency(self.sync_write_consistency))
                     .build()?;
                 tokio::spawn(async { req.send_global(self) });
             } else {
@@ -559,6 +812,7 @@ where
 }
 
 /// A milestone data worker
+#[derive(Clone)]
 pub struct MilestoneDataWorker<S>
 where
     S: 'static + Insert<Synckey, SyncRecord>,
@@ -583,8 +837,14 @@ where
 }
 
 /// The inherent trait to return a boxed worker for a given key/value pair
-pub(crate) trait Inherent {
-    fn inherent_boxed<K, V>(&self, key: K, value: V) -> Box<dyn Worker>
+pub(crate) trait Inherent: 'static + Clone + Send {
+    fn inherent_boxed<K, V>(
+        &self,
+        keyspace: ChronicleKeyspace,
+        key: K,
+        value: V,
+        permit: OwnedSemaphorePermit,
+    ) -> Box<dyn Worker>
     where
         ChronicleKeyspace: 'static + Insert<K, V> + Insert<Synckey, SyncRecord>,
         K: 'static + Send + Clone,
@@ -594,13 +854,19 @@ pub(crate) trait Inherent {
 /// Implement the `Inherent` trait for the milestone data worker, so we can get the atomic importer worker
 /// which contains the atomic importer handle of the milestone data worker
 impl Inherent for MilestoneDataWorker<ChronicleKeyspace> {
-    fn inherent_boxed<K, V>(&self, key: K, value: V) -> Box<dyn Worker>
+    fn inherent_boxed<K, V>(
+        &self,
+        keyspace: ChronicleKeyspace,
+        key: K,
+        value: V,
+        permit: OwnedSemaphorePermit,
+    ) -> Box<dyn Worker>
     where
         ChronicleKeyspace: 'static + Insert<K, V> + Insert<Synckey, SyncRecord>,
         K: 'static + Send + Clone,
         V: 'static + Send + Clone,
     {
-        AtomicImporterWorker::boxed(self.arc_handle.clone(), key, value)
+        AtomicImporterWorker::boxed(self.arc_handle.clone(), keyspace, key, value, permit)
     }
 }
 
@@ -618,6 +884,8 @@ where
     analytic_record: AnalyticRecord,
     /// The number of retries
     retries: usize,
+    /// The mirror keyspaces the record is inserted into next, one keyspace after the other
+    mirror_keyspaces: Vec<S>,
 }
 
 impl<S> AnalyzeWorker<S>
@@ -632,6 +900,7 @@ where
             keyspace,
             analytic_record,
             retries,
+            mirror_keyspaces: Vec::new(),
         }
     }
     /// Create a new boxed AnalyzeWorker with an importer handle, a keyspace, a `analytics` table row
@@ -639,6 +908,11 @@ where
     pub fn boxed(handle: ImporterHandle, keyspace: S, analytic_record: AnalyticRecord, retries: usize) -> Box<Self> {
         Box::new(Self::new(handle, keyspace, analytic_record, retries))
     }
+    /// Set the mirror keyspaces the record is inserted into once it's inserted into the keyspace
+    pub fn with_mirror_keyspaces(mut self: Box<Self>, mirror_keyspaces: Vec<S>) -> Box<Self> {
+        self.mirror_keyspaces = mirror_keyspaces;
+        self
+    }
 }
 
 /// Implement the Scylla `Worker` trait
@@ -646,8 +920,19 @@ impl<S> Worker for AnalyzeWorker<S>
 where
     S: 'static + Insert<Synckey, AnalyticRecord>,
 {
-    fn handle_response(self: Box<Self>, giveload: Vec<u8>) -> anyhow::Result<()> {
+    fn handle_response(mut self: Box<Self>, giveload: Vec<u8>) -> anyhow::Result<()> {
         Decoder::from(giveload.try_into()?).get_void()?;
+        if !self.mirror_keyspaces.is_empty() {
+            // insert the record into the next mirror keyspace
+            self.keyspace = self.mirror_keyspaces.remove(0);
+            let req = self
+                .keyspace
+                .insert_prepared(&Synckey, &self.analytic_record)
+                .consistency(Consistency::One)
+                .build()?;
+            req.send_local(self);
+            return Ok(());
+        }
         let milestone_index = self.analytic_record.milestone_index();
         self.handle.send(ImporterEvent::CqlResult(Ok(**milestone_index))).ok();
         Ok(())
@@ -682,3 +967,355 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::AtomicUsize,
+        time::Duration,
+    };
+
+    #[test]
+    fn insert_concurrency_clamp() {
+        assert_eq!(insert_concurrency(None), DEFAULT_INSERT_CONCURRENCY);
+        assert_eq!(insert_concurrency(Some(0)), 1);
+        assert_eq!(insert_concurrency(Some(50)), 50);
+        assert_eq!(insert_concurrency(Some(usize::MAX)), MAX_INSERT_CONCURRENCY);
+    }
+
+    #[tokio::test]
+    async fn insert_permits_bound_in_flight_inserts() {
+        let concurrency = insert_concurrency(Some(3));
+        let insert_permits = Arc::new(Semaphore::new(concurrency));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let (done_tx, mut done_rx) = tokio::sync::mpsc::unbounded_channel();
+        for _ in 0..20 {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            let done_tx = done_tx.clone();
+            spawn_with_permit(insert_permits.clone(), move |permit| {
+                // A stub insert which responds later, and releases its permit once done
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    drop(permit);
+                    done_tx.send(()).ok();
+                });
+            });
+        }
+        for _ in 0..20 {
+            done_rx.recv().await.expect("Expected insert response");
+        }
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), concurrency);
+    }
+
+    fn importer(parallelism: u8, batch_size: Option<usize>) -> Importer<All> {
+        let mut builder = ImporterBuilder::<All>::new()
+            .file_path(PathBuf::from("1to11.log"))
+            .chronicle_id(0)
+            .parallelism(parallelism);
+        if let Some(batch_size) = batch_size {
+            builder = builder.batch_size(batch_size);
+        }
+        builder.build()
+    }
+
+    /// Fetch the next milestones into the in progress ones, as the LogFile would
+    fn fetch(importer: &mut Importer<All>, pending: &mut std::collections::VecDeque<u32>, count: usize) {
+        for milestone_index in pending.drain(..count.min(pending.len())) {
+            importer.in_progress_milestones_data.insert(
+                milestone_index,
+                (HashMap::new().into_iter(), AnalyticsAccumulator::default()),
+            );
+            importer
+                .in_progress_milestones_data_bytes_size
+                .insert(milestone_index, 1);
+        }
+    }
+
+    /// Import the in progress milestones in order, fetching the next ones as the importer asks for, and get the flushed
+    /// batches along with the max number of milestones which were in progress at once
+    fn import(importer: &mut Importer<All>, pending: &mut std::collections::VecDeque<u32>) -> (Vec<Vec<u32>>, usize) {
+        let initial_fetch_count = importer.import_batch.initial_fetch_count(importer.parallelism);
+        fetch(importer, pending, initial_fetch_count);
+        let mut max_in_progress = importer.in_progress_milestones_data.len();
+        let mut batches = Vec::new();
+        while let Some(milestone_index) = importer.in_progress_milestones_data.keys().min().cloned() {
+            if let Some((imported, fetch_count)) = importer.milestone_imported(milestone_index) {
+                batches.push(
+                    imported
+                        .into_iter()
+                        .map(|(milestone_index, _)| milestone_index)
+                        .collect(),
+                );
+                fetch(importer, pending, fetch_count);
+                max_in_progress = max_in_progress.max(importer.in_progress_milestones_data.len());
+            }
+        }
+        (batches, max_in_progress)
+    }
+
+    #[test]
+    fn trailing_partial_batch_is_flushed() {
+        // 7 milestones with a batch size of 3 is not a multiple of it
+        let mut importer = importer(4, Some(3));
+        let mut pending = (1..=7).collect();
+        let (batches, max_in_progress) = import(&mut importer, &mut pending);
+        assert!(pending.is_empty());
+        assert_eq!(batches, vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+        // a whole batch is inserted at once
+        assert_eq!(max_in_progress, 3);
+    }
+
+    #[test]
+    fn unbatched_import_flushes_every_milestone() {
+        let mut importer = importer(4, None);
+        let mut pending = (1..=7).collect();
+        let (batches, max_in_progress) = import(&mut importer, &mut pending);
+        assert!(pending.is_empty());
+        assert_eq!(
+            batches,
+            (1..=7).map(|milestone_index| vec![milestone_index]).collect::<Vec<_>>()
+        );
+        assert_eq!(max_in_progress, 4);
+    }
+
+    #[test]
+    fn cancel_stops_at_milestone_boundary() {
+        let mut importer = importer(3, Some(2));
+        let mut pending = (1..=10).collect();
+        // cancel mid-import, before the first batch is flushed
+        importer.import_batch.cancel();
+        let (batches, _) = import(&mut importer, &mut pending);
+        // the in progress milestones were all flushed, and nothing else got fetched, so the rest is resumable from the
+        // next milestone
+        assert_eq!(batches, vec![vec![1, 2]]);
+        assert_eq!(pending, (3..=10).collect::<std::collections::VecDeque<_>>());
+    }
+
+    /// Fetch the next milestones of the LogFile into the in progress ones, skipping them as the importer would
+    async fn fetch_from_log_file(importer: &mut Importer<All>, log_file: &mut LogFile, count: usize) {
+        let mut fetched = 0;
+        while fetched < count {
+            match log_file.next().await.unwrap() {
+                Some(milestone_data) => {
+                    let milestone_index = milestone_data.milestone_index();
+                    if !importer.is_skipped(milestone_index) {
+                        fetch(importer, &mut vec![milestone_index].into(), 1);
+                        fetched += 1;
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Import the LogFile till it's done, cancelling it once the first batch is in progress if asked to, and get the
+    /// imported milestones, which are synced as they get flushed
+    async fn import_log_file(importer: &mut Importer<All>, file_path: &std::path::Path, cancel: bool) -> Vec<u32> {
+        let mut log_file = LogFile::try_from(file_path.to_path_buf()).unwrap();
+        log_file.read_version().await.unwrap();
+        let initial_fetch_count = importer.import_batch.initial_fetch_count(importer.parallelism);
+        fetch_from_log_file(importer, &mut log_file, initial_fetch_count).await;
+        if cancel {
+            importer.import_batch.cancel();
+        }
+        let mut synced = Vec::new();
+        while let Some(milestone_index) = importer.in_progress_milestones_data.keys().min().cloned() {
+            if let Some((imported, fetch_count)) = importer.milestone_imported(milestone_index) {
+                synced.extend(imported.into_iter().map(|(milestone_index, _)| milestone_index));
+                fetch_from_log_file(importer, &mut log_file, fetch_count).await;
+            }
+        }
+        synced
+    }
+
+    #[tokio::test]
+    async fn resume_cancelled_import_from_checkpoint() {
+        let dir = std::env::temp_dir().join(format!("chronicle-resume-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let file_path = dir.join("1to11.log");
+        let mut lines = String::new();
+        for milestone_index in 1..11 {
+            lines.push_str(&serde_json::to_string(&MilestoneData::new(milestone_index, CreatedBy::Syncer)).unwrap());
+            lines.push('\n');
+        }
+        tokio::fs::write(&file_path, lines).await.unwrap();
+        // cancel mid-import, so only the first batch gets imported and synced
+        let mut cancelled = importer(3, Some(2));
+        let checkpoint = import_log_file(&mut cancelled, &file_path, true).await;
+        assert_eq!(checkpoint, vec![1, 2]);
+        // resume from the synced milestones of the cancelled import
+        let mut resumed = importer(3, Some(2));
+        resumed.sync_data = SyncData::from_ranges(
+            checkpoint
+                .iter()
+                .map(|milestone_index| *milestone_index..milestone_index + 1)
+                .collect(),
+            Vec::new(),
+            Vec::new(),
+        )
+        .unwrap();
+        let imported = import_log_file(&mut resumed, &file_path, false).await;
+        // the resumed import picks up right after the checkpoint, without importing the synced milestones again
+        assert_eq!(imported, (3..11).collect::<Vec<_>>());
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+
+    #[test]
+    fn gapped_log_file() {
+        // a 100to110.log LogFile which skips 101..103, holds a duplicate and misses its trailing milestones
+        let mut milestone_gaps = MilestoneGaps::new(100);
+        let gaps = [100, 103, 104, 104, 105, 108]
+            .iter()
+            .filter_map(|milestone_index| milestone_gaps.observe(*milestone_index))
+            .collect::<Vec<_>>();
+        assert_eq!(gaps, vec![101..103, 106..108]);
+        assert_eq!(milestone_gaps.end(110), Some(109..110));
+        assert_eq!(milestone_gaps.gaps(), &[101..103, 106..108, 109..110]);
+        // a contiguous LogFile has none
+        let mut milestone_gaps = MilestoneGaps::new(1);
+        assert!((1..10).all(|milestone_index| milestone_gaps.observe(milestone_index).is_none()));
+        assert!(milestone_gaps.end(10).is_none());
+        assert!(milestone_gaps.gaps().is_empty());
+    }
+
+    /// Records the rows the importer inserts, rather than sending them to the storage
+    #[derive(Clone, Default)]
+    struct RecordedInserts(Arc<std::sync::Mutex<Vec<(String, Option<String>)>>>);
+
+    impl Inherent for RecordedInserts {
+        fn inherent_boxed<K, V>(
+            &self,
+            keyspace: ChronicleKeyspace,
+            key: K,
+            _value: V,
+            permit: OwnedSemaphorePermit,
+        ) -> Box<dyn Worker>
+        where
+            ChronicleKeyspace: 'static + Insert<K, V> + Insert<Synckey, SyncRecord>,
+            K: 'static + Send + Clone,
+            V: 'static + Send + Clone,
+        {
+            let statement = <ChronicleKeyspace as Insert<K, V>>::statement(&keyspace);
+            let message_id = (&key as &dyn std::any::Any)
+                .downcast_ref::<IfAbsent<MessageId>>()
+                .map(|IfAbsent(message_id)| message_id.to_string());
+            self.0.lock().unwrap().push((statement.to_string(), message_id));
+            Box::new(DiscardedInsert { _permit: permit })
+        }
+    }
+
+    struct DiscardedInsert {
+        _permit: OwnedSemaphorePermit,
+    }
+
+    impl Worker for DiscardedInsert {
+        fn handle_response(self: Box<Self>, _giveload: Vec<u8>) -> anyhow::Result<()> {
+            Ok(())
+        }
+        fn handle_error(
+            self: Box<Self>,
+            _error: WorkerError,
+            _reporter: &Option<ReporterHandle>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn reimport_same_log_file() {
+        use crate::types::tests::message;
+        let dir = std::env::temp_dir().join(format!("chronicle-reimport-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let file_path = dir.join("1to2.log");
+        let mut milestone_data = MilestoneData::new(1, CreatedBy::Syncer);
+        let first = message(vec![MessageId::new([0; 32])], 1);
+        milestone_data.add_full_message(message(vec![*first.message_id()], 1));
+        milestone_data.add_full_message(first);
+        let mut line = serde_json::to_string(&milestone_data).unwrap();
+        line.push('\n');
+        tokio::fs::write(&file_path, line).await.unwrap();
+
+        let mut imports = Vec::new();
+        for _ in 0..2 {
+            let mut importer = ImporterBuilder::<All>::new()
+                .file_path(file_path.clone())
+                .chronicle_id(0)
+                .build();
+            let inserts = RecordedInserts::default();
+            let mut log_file = LogFile::try_from(file_path.clone()).unwrap();
+            log_file.read_version().await.unwrap();
+            while let Some(mut milestone_data) = log_file.next().await.unwrap() {
+                milestone_data.dedup();
+                for (message_id, FullMessage(message, metadata)) in milestone_data.messages().clone() {
+                    importer
+                        .insert_message_with_metadata(&inserts, message_id, message, metadata)
+                        .unwrap();
+                }
+            }
+            // let the spawned inserts record their rows
+            for _ in 0..10 {
+                tokio::task::yield_now().await;
+            }
+            let mut recorded = inserts.0.lock().unwrap().clone();
+            recorded.sort();
+            imports.push(recorded);
+        }
+        // the messages are only inserted if absent, so importing again never overwrites them
+        let message_inserts = imports[0]
+            .iter()
+            .filter(|(_, message_id)| message_id.is_some())
+            .collect::<Vec<_>>();
+        assert_eq!(message_inserts.len(), 2);
+        assert!(message_inserts
+            .iter()
+            .all(|(statement, _)| statement.contains("IF NOT EXISTS")));
+        // while the rest of the rows are upserts of the very same rows
+        assert_eq!(imports[0], imports[1]);
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+
+    #[tokio::test]
+    async fn mirrored_import() {
+        use crate::types::tests::message;
+        let mut importer = ImporterBuilder::<All>::new()
+            .file_path(std::env::temp_dir().join("1to2.log"))
+            .mirror_keyspaces(vec![ChronicleKeyspace::new("permanode_mirror".to_owned())])
+            .chronicle_id(0)
+            .build();
+        let inserts = RecordedInserts::default();
+        let full_message = message(vec![MessageId::new([0; 32])], 1);
+        let message_id = *full_message.message_id();
+        let FullMessage(message, metadata) = full_message;
+        importer
+            .insert_message_with_metadata(&inserts, message_id, message, metadata)
+            .unwrap();
+        // let the spawned inserts record their rows
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        let recorded = inserts.0.lock().unwrap().clone();
+        let keyspace_inserts = |keyspace: &str| {
+            recorded
+                .iter()
+                .filter(|(statement, message_id)| {
+                    message_id.is_some() && statement.contains(&format!(" {}.messages", keyspace))
+                })
+                .count()
+        };
+        // the message is inserted into both keyspaces, sharing the inherent worker of the milestone
+        assert_eq!(keyspace_inserts(&importer.default_keyspace.name()), 1);
+        assert_eq!(keyspace_inserts("permanode_mirror"), 1);
+        // as every other row
+        let mirrored = recorded
+            .iter()
+            .filter(|(statement, _)| statement.contains(" permanode_mirror."))
+            .count();
+        assert_eq!(mirrored * 2, recorded.len());
+    }
+}
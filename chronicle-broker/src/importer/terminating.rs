@@ -12,7 +12,9 @@ impl<H: ChronicleBrokerScope, T: Send + ImportMode> Terminating<BrokerHandle<H>>
     ) -> Result<(), Need> {
         info!("{} is terminating", self.get_name());
         let msg;
-        if status.is_ok() {
+        if status.is_ok() && self.import_batch.is_cancelled() {
+            msg = "cancelled".into();
+        } else if status.is_ok() {
             msg = "done".into();
         } else {
             msg = "failed".into();
@@ -22,13 +24,14 @@ impl<H: ChronicleBrokerScope, T: Send + ImportMode> Terminating<BrokerHandle<H>>
                 from_ms: log_file.from_ms_index(),
                 to_ms: log_file.to_ms_index(),
                 msg,
+                gaps: self.milestone_gaps.gaps().to_vec(),
             };
             let event = BrokerEvent::Importer(importer_session);
             supervisor.as_mut().expect("Expected BrokerHandle").send(event).ok();
         } else {
             let event = BrokerEvent::Importer(ImporterSession::PathError {
                 path: self.file_path.clone(),
-                msg: "Invalid LogFile path".into(),
+                msg: self.path_error.take().unwrap_or_else(|| "Invalid LogFile path".into()),
             });
             supervisor.as_mut().expect("Expected BrokerHandle").send(event).ok();
         }
@@ -21,6 +21,9 @@ pub mod listener;
 /// MQTT handler
 #[cfg(feature = "application")]
 pub mod mqtt;
+/// The pruner, which deletes old milestones data
+#[cfg(feature = "application")]
+pub mod pruner;
 /// Missing data requester
 #[cfg(feature = "application")]
 pub mod requester;
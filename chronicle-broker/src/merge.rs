@@ -1,7 +1,10 @@
 // Copyright 2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::MilestoneData;
+use crate::{
+    LogFileHeader,
+    MilestoneData,
+};
 use anyhow::{
     anyhow,
     bail,
@@ -53,6 +56,8 @@ enum LogFileError {
     InvalidRange { range: Range<u32>, path: PathBuf },
     #[error("File exceeds max file size of {max}: {path}")]
     TooBig { max: u64, path: PathBuf },
+    #[error("Unsupported LogFile version {version}: {path}")]
+    UnsupportedVersion { version: u32, path: PathBuf },
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -89,6 +94,10 @@ impl LogFileError {
                 "This file exceeds the requested maximum file size.
                 The merger will skip this file."
             }
+            LogFileError::UnsupportedVersion { .. } => {
+                "This file has a LogFile version which is newer than the supported one.
+                The merger will skip this file."
+            }
             LogFileError::Other(_) => "An unknown error occurred.",
         }
     }
@@ -213,7 +222,24 @@ impl LogFile {
                 let mut est_idx = self.start;
                 let mut lines = reader.lines();
                 let mut extra = 0;
+                let mut first_line = true;
                 while let Some(line) = lines.next_line().await.map_err(|e| anyhow!(e))? {
+                    // Skip the LogFile header, if any
+                    if std::mem::take(&mut first_line) {
+                        if let Some(header) = LogFileHeader::from_line(&line) {
+                            if !header.is_supported() {
+                                self.err = true;
+                                return Err(LogFileError::UnsupportedVersion {
+                                    version: header.log_file_version,
+                                    path,
+                                });
+                            }
+                            if let Some(pb) = progress_bar.as_mut() {
+                                pb.inc(line.as_bytes().len() as u64);
+                            }
+                            continue;
+                        }
+                    }
                     // If we've exceeded our claimed range, just add up the extras
                     if est_idx >= self.end {
                         extra += 1;
@@ -601,6 +627,7 @@ impl Merger {
         let mut line_buffer = String::new();
         let mut milestone_index = start;
         let mut total_read_bytes = 0;
+        let mut header_bytes = 0;
         if let Some(pb) = self.progress_bar.as_mut() {
             pb.set_position(0);
             pb.set_length(total_bytes);
@@ -617,6 +644,13 @@ impl Merger {
                         //}
                         tokio::fs::remove_file(&path).await?;
                         break;
+                    } else if total_read_bytes == bytes as u64 && LogFileHeader::from_line(&ms_line).is_some() {
+                        // Skip the header of the consumed file, the active file has its own one (if any)
+                        header_bytes = bytes as u64;
+                        if let Some(pb) = self.progress_bar.as_mut() {
+                            pb.inc(bytes as u64);
+                        }
+                        continue;
                     } else {
                         // Perform validation if JIT is enabled or we are looking at an overlapping milestone
                         if milestone_index < active.end || self.validation_level == ValidationLevel::JustInTime {
@@ -670,7 +704,7 @@ impl Merger {
                             //}
                             active.finalized = true;
                             // If we read more than just a single line from the file
-                            if total_read_bytes != bytes as u64 {
+                            if total_read_bytes - header_bytes != bytes as u64 {
                                 // Create a new file to funnel the remainder of the milestones to
                                 active = self.create_active(milestone_index).await?;
                                 // Add the line we just read
@@ -767,7 +801,7 @@ impl Merger {
 
     async fn create_active(&mut self, milestone_index: u32) -> anyhow::Result<LogFile> {
         let file_path = self.logs_dir.join(&format!("{}.log.active", milestone_index));
-        let file: File = OpenOptions::new()
+        let mut file: File = OpenOptions::new()
             .append(true)
             .create(true)
             .open(&file_path)
@@ -779,7 +813,12 @@ impl Merger {
                     e
                 )
             })?;
-        let len = file.metadata().await?.len();
+        let mut len = file.metadata().await?.len();
+        if len == 0 {
+            let header = LogFileHeader::default().to_line();
+            file.write_all(header.as_bytes()).await?;
+            len = header.len() as u64;
+        }
         Ok(LogFile::new(milestone_index, milestone_index, file_path, file, len))
     }
 }
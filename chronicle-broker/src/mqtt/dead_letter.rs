@@ -0,0 +1,236 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use chronicle_common::metrics::DEAD_LETTER_FEED_MESSAGES;
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+use tokio::{
+    fs::{
+        File,
+        OpenOptions,
+    },
+    io::AsyncWriteExt,
+    sync::mpsc::{
+        self,
+        error::TrySendError,
+    },
+};
+
+/// The size (in bytes) past which the dead-letter log is rotated into `<path>.1`, replacing the previous rotated log
+pub(crate) const MAX_DEAD_LETTER_LOG_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The max number of dead letters waiting to be written, past it they're dropped (but still counted)
+const DEAD_LETTER_BACKLOG: usize = 1000;
+
+/// A feed message which failed to deserialize
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DeadLetter {
+    /// The unix timestamp (in seconds) the message was received at
+    pub timestamp: u64,
+    /// The feed source of the message
+    pub url: String,
+    /// The topic of the message
+    pub topic: String,
+    /// The deserialization error
+    pub error: String,
+    /// The hex encoded raw payload of the message
+    pub payload: String,
+}
+
+/// The dead-letter log of the feed messages which fail to deserialize, one JSON line per message. The handle is
+/// shared by all the feed sources, while a single writer owns the file, so the mqtt event loops never block on it
+#[derive(Debug, Clone)]
+pub struct DeadLetterLog {
+    path: String,
+    tx: mpsc::Sender<DeadLetter>,
+}
+
+impl DeadLetterLog {
+    /// Create the dead-letter log of the path along with its writer, which is expected to be spawned
+    pub fn new(path: String, max_size: u64) -> (Self, DeadLetterWriter) {
+        let (tx, rx) = mpsc::channel(DEAD_LETTER_BACKLOG);
+        let writer = DeadLetterWriter {
+            path: path.clone(),
+            max_size,
+            rx,
+        };
+        (Self { path, tx }, writer)
+    }
+    /// The path of the dead-letter log
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    /// Queue the dead letter to be appended to the log, it's dropped if the writer lags behind
+    pub(crate) fn append(&self, dead_letter: DeadLetter) {
+        match self.tx.try_send(dead_letter) {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) => warn!("The dead-letter log: {} is lagging behind, dropping", self.path),
+            Err(TrySendError::Closed(_)) => error!("The dead-letter log: {} is closed", self.path),
+        }
+    }
+}
+
+/// The writer of the dead-letter log, which appends the queued dead letters to the file (creating it if it doesn't
+/// exist) and rotates it once it exceeds the max size
+pub struct DeadLetterWriter {
+    path: String,
+    max_size: u64,
+    rx: mpsc::Receiver<DeadLetter>,
+}
+
+impl DeadLetterWriter {
+    /// Write the queued dead letters until all the log handles are dropped
+    pub async fn run(mut self) {
+        let mut file = None;
+        let mut size = 0;
+        while let Some(dead_letter) = self.rx.recv().await {
+            if let Err(e) = self.write(&mut file, &mut size, &dead_letter).await {
+                error!("Unable to append to the dead-letter log: {}, error: {}", self.path, e);
+                // reopen the log on the next dead letter
+                file.take();
+            }
+        }
+    }
+    async fn write(&self, file: &mut Option<File>, size: &mut u64, dead_letter: &DeadLetter) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(dead_letter)?;
+        line.push(b'\n');
+        if file.is_none() {
+            let opened = self.open().await?;
+            *size = opened.metadata().await?.len();
+            file.replace(opened);
+        }
+        if *size > 0 && *size + line.len() as u64 > self.max_size {
+            file.take();
+            tokio::fs::rename(&self.path, format!("{}.1", self.path)).await?;
+            file.replace(self.open().await?);
+            *size = 0;
+        }
+        if let Some(file) = file.as_mut() {
+            file.write_all(&line).await?;
+            file.flush().await?;
+            *size += line.len() as u64;
+        }
+        Ok(())
+    }
+    async fn open(&self) -> std::io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(&self.path).await
+    }
+}
+
+/// Count the feed message which failed to deserialize, and append it to the dead-letter log (if enabled)
+pub(crate) fn dead_letter(
+    dead_letters: &Option<DeadLetterLog>,
+    url: &Url,
+    topic: &str,
+    payload: &[u8],
+    error: impl std::fmt::Display,
+) {
+    DEAD_LETTER_FEED_MESSAGES.with_label_values(&[topic]).inc();
+    warn!("Mqtt: {}@{}, unable to deserialize message: {}", topic, url, error);
+    if let Some(dead_letter_log) = dead_letters.as_ref() {
+        let dead_letter = DeadLetter {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            url: url.to_string(),
+            topic: topic.to_string(),
+            error: error.to_string(),
+            payload: hex::encode(payload),
+        };
+        dead_letter_log.append(dead_letter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_dead_letters(path: &str) -> Vec<DeadLetter> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<DeadLetter>(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn malformed_message_is_dead_lettered() {
+        let path = std::env::temp_dir().join(format!("chronicle-dead-letters-{}.log", std::process::id()));
+        let path = path.to_str().unwrap();
+        let url = Url::parse("tcp://localhost:1883").unwrap();
+        let payload = [1u8, 2, 3];
+        let error = Message::unpack(&mut &payload[..]).unwrap_err();
+        let count = DEAD_LETTER_FEED_MESSAGES.with_label_values(&["messages"]).get();
+
+        let (dead_letter_log, writer) = DeadLetterLog::new(path.to_string(), MAX_DEAD_LETTER_LOG_SIZE);
+        let writer = tokio::spawn(writer.run());
+        // the feed sources share the log
+        let dead_letters = Some(dead_letter_log.clone());
+        dead_letter(&dead_letters, &url, "messages", &payload, &error);
+        dead_letter(
+            &Some(dead_letter_log),
+            &url,
+            "messages/referenced",
+            b"{",
+            "EOF while parsing",
+        );
+        // disabled log, the message is still counted
+        dead_letter(&None, &url, "messages", &payload, &error);
+        drop(dead_letters);
+        writer.await.unwrap();
+
+        let dead_letters = read_dead_letters(path);
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(dead_letters.len(), 2);
+        assert_eq!(dead_letters[0].url, url.to_string());
+        assert_eq!(dead_letters[0].topic, "messages");
+        assert_eq!(dead_letters[0].error, error.to_string());
+        assert_eq!(dead_letters[0].payload, "010203");
+        assert_eq!(dead_letters[1].payload, "7b");
+        assert_eq!(
+            DEAD_LETTER_FEED_MESSAGES.with_label_values(&["messages"]).get(),
+            count + 2
+        );
+    }
+
+    #[tokio::test]
+    async fn dead_letter_log_rotation() {
+        let path = std::env::temp_dir().join(format!("chronicle-rotated-dead-letters-{}.log", std::process::id()));
+        let path = path.to_str().unwrap();
+        let rotated_path = format!("{}.1", path);
+        std::fs::remove_file(path).ok();
+        std::fs::remove_file(&rotated_path).ok();
+        let dead_letter = |payload: &str| DeadLetter {
+            timestamp: 0,
+            url: "tcp://localhost:1883".to_string(),
+            topic: "messages".to_string(),
+            error: "invalid".to_string(),
+            payload: payload.to_string(),
+        };
+        let line_size = serde_json::to_vec(&dead_letter("00")).unwrap().len() as u64 + 1;
+        // two dead letters fit in the log
+        let (dead_letter_log, writer) = DeadLetterLog::new(path.to_string(), 2 * line_size);
+        let writer = tokio::spawn(writer.run());
+        for payload in &["00", "01", "02", "03", "04"] {
+            dead_letter_log.append(dead_letter(payload));
+        }
+        drop(dead_letter_log);
+        writer.await.unwrap();
+
+        let payloads = |path: &str| {
+            read_dead_letters(path)
+                .into_iter()
+                .map(|dead_letter| dead_letter.payload)
+                .collect::<Vec<_>>()
+        };
+        // the log got rotated twice, so the first two dead letters are gone
+        assert_eq!(payloads(&rotated_path), vec!["02", "03"]);
+        assert_eq!(payloads(path), vec!["04"]);
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(rotated_path).unwrap();
+    }
+}
@@ -17,16 +17,24 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Mqtt<Messages> {
         let inbox = self.inbox.as_mut().unwrap();
         while let Some(msg_opt) = inbox.stream.next().await {
             if let Some(msg) = msg_opt {
-                if let Ok(msg) = Message::unpack(&mut msg.payload()) {
-                    let (message_id, _) = msg.id();
-                    // partitioning based on first byte of the message_id
-                    let collector_partition_id = self.partitioner.partition_id(&message_id);
-                    if let Some(collector_handle) = self.collectors_handles.get(&collector_partition_id) {
-                        let _ = collector_handle.send(CollectorEvent::Message(message_id, msg));
+                match Message::unpack(&mut msg.payload()) {
+                    Ok(msg) => {
+                        let (message_id, _) = msg.id();
+                        // partitioning based on first byte of the message_id
+                        let collector_partition_id = self.partitioner.partition_id(&message_id);
+                        if let Some(collector_handle) = self.collectors_handles.get(&collector_partition_id) {
+                            collector_handle
+                                .send_feed(CollectorEvent::Message(message_id, msg))
+                                .await;
+                        }
                     }
-                };
+                    Err(e) => dead_letter(&self.dead_letters, &self.url, Messages::name(), msg.payload(), e),
+                }
             } else {
                 warn!("Mqtt: {}, lost connection", self.get_name());
+                if let Some(feed_status) = self.feed_status(false) {
+                    let _ = supervisor.as_mut().unwrap().send(BrokerEvent::Feed(feed_status));
+                }
                 return Err(Need::Restart);
             }
         }
@@ -48,15 +56,29 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Mqtt<MessagesRefere
         let inbox = self.inbox.as_mut().unwrap();
         while let Some(msg_ref_opt) = inbox.stream.next().await {
             if let Some(msg_ref) = msg_ref_opt {
-                if let Ok(msg_ref) = serde_json::from_str::<MessageMetadata>(&msg_ref.payload_str()) {
-                    // partitioning based on first byte of the message_id
-                    let collector_partition_id = self.partitioner.partition_id(&msg_ref.message_id);
-                    if let Some(collector_handle) = self.collectors_handles.get(&collector_partition_id) {
-                        let _ = collector_handle.send(CollectorEvent::MessageReferenced(msg_ref));
+                match serde_json::from_str::<MessageMetadata>(&msg_ref.payload_str()) {
+                    Ok(metadata) => {
+                        // partitioning based on first byte of the message_id
+                        let collector_partition_id = self.partitioner.partition_id(&metadata.message_id);
+                        if let Some(collector_handle) = self.collectors_handles.get(&collector_partition_id) {
+                            collector_handle
+                                .send_feed(CollectorEvent::MessageReferenced(metadata))
+                                .await;
+                        }
                     }
-                };
+                    Err(e) => dead_letter(
+                        &self.dead_letters,
+                        &self.url,
+                        MessagesReferenced::name(),
+                        msg_ref.payload(),
+                        e,
+                    ),
+                }
             } else {
                 warn!("Mqtt: {}, lost connection", self.get_name());
+                if let Some(feed_status) = self.feed_status(false) {
+                    let _ = supervisor.as_mut().unwrap().send(BrokerEvent::Feed(feed_status));
+                }
                 return Err(Need::Restart);
             }
         }
@@ -41,8 +41,9 @@ impl<T: Topic, H: ChronicleBrokerScope> Init<BrokerHandle<H>> for Mqtt<T> {
             Need::Restart
         })?;
         info!("Connected AsyncClient: {}", &self.url.as_str());
-        // subscribe to T::name() topic with T::qos()
-        arced_client.subscribe(T::name(), T::qos()).await.map_err(|e| {
+        // subscribe to T::name() topic with the configured qos
+        let (topic, qos) = self.subscription();
+        arced_client.subscribe(topic, qos).await.map_err(|e| {
             error!(
                 "Unable to subscribe AsyncClient: {}, topic: {}, error: {}",
                 &self.url.as_str(),
@@ -53,7 +54,15 @@ impl<T: Topic, H: ChronicleBrokerScope> Init<BrokerHandle<H>> for Mqtt<T> {
         })?;
         let handle = MqttHandle { client: arc_client };
         self.handle.replace(handle);
-        info!("Subscribed AsyncClient: {}, topic: {}", &self.url.as_str(), T::name());
+        info!(
+            "Subscribed AsyncClient: {}, topic: {}, qos: {}",
+            &self.url.as_str(),
+            topic,
+            qos
+        );
+        if let Some(feed_status) = self.feed_status(true) {
+            let _ = supervisor.as_mut().unwrap().send(BrokerEvent::Feed(feed_status));
+        }
         let event = BrokerEvent::Children(BrokerChild::Mqtt(
             self.service.clone(),
             Some(self.handle.as_ref().unwrap().clone()),
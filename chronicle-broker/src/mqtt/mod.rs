@@ -11,20 +11,32 @@ use super::{
 };
 use futures::stream::StreamExt;
 use std::{
-    collections::HashMap,
+    collections::{
+        HashMap,
+        HashSet,
+    },
     time::Duration,
 };
 
+mod dead_letter;
 mod event_loop;
 mod init;
 mod terminating;
 
+use dead_letter::dead_letter;
+pub(crate) use dead_letter::{
+    DeadLetterLog,
+    MAX_DEAD_LETTER_LOG_SIZE,
+};
+
 // Mqtt builder
 builder!(MqttBuilder<T> {
     url: Url,
     topic: T,
     collectors_handles: HashMap<u8, CollectorHandle>,
-    stream_capacity: usize
+    stream_capacity: usize,
+    qos: i32,
+    dead_letters: DeadLetterLog
 });
 
 /// MqttHandle to be passed to the supervisor in order to shutdown
@@ -51,10 +63,16 @@ pub struct Mqtt<T> {
     service: Service,
     url: Url,
     stream_capacity: usize,
+    /// The MQTT QoS level of the topic subscription
+    qos: i32,
     collectors_handles: HashMap<u8, CollectorHandle>,
     partitioner: MessageIdPartitioner,
     handle: Option<MqttHandle>,
     inbox: Option<MqttInbox>,
+    /// Whether the feed is connected to the remote broker
+    connected: bool,
+    /// The dead-letter log of the messages which fail to deserialize, if enabled
+    dead_letters: Option<DeadLetterLog>,
     _topic: T,
 }
 
@@ -64,6 +82,25 @@ impl<T> Mqtt<T> {
     }
 }
 
+impl<T: Topic> Mqtt<T> {
+    /// The topic and QoS level to subscribe with
+    pub(crate) fn subscription(&self) -> (&'static str, i32) {
+        (T::name(), self.qos)
+    }
+    /// Record the feed connection state, and return the feed status to be emitted if the state changed
+    pub(crate) fn feed_status(&mut self, connected: bool) -> Option<FeedStatus> {
+        if self.connected == connected {
+            return None;
+        }
+        self.connected = connected;
+        Some(FeedStatus {
+            url: self.url.clone(),
+            topic_kind: T::name().to_string(),
+            connected,
+        })
+    }
+}
+
 /// MQTT topics
 pub enum Topics {
     /// Messages topic
@@ -83,6 +120,32 @@ impl TryFrom<&str> for Topics {
     }
 }
 
+/// Reconcile the topics subscribed from a feed source with the requested ones, returns the topics to subscribe and
+/// the ones to unsubscribe, it fails if the feed source isn't connected or a requested topic isn't supported
+pub(crate) fn reconcile_topics(
+    subscribed: &HashSet<&'static str>,
+    requested: &[String],
+) -> anyhow::Result<(Vec<&'static str>, Vec<&'static str>)> {
+    anyhow::ensure!(!subscribed.is_empty(), "The feed source is not connected");
+    anyhow::ensure!(
+        !requested.is_empty(),
+        "No topics requested, remove the feed source instead"
+    );
+    let mut requested_topics = HashSet::new();
+    for topic in requested {
+        let topic = match Topics::try_from(topic.as_str()).map_err(|e| anyhow::anyhow!(e))? {
+            Topics::Messages => Messages::name(),
+            Topics::MessagesReferenced => MessagesReferenced::name(),
+        };
+        requested_topics.insert(topic);
+    }
+    let mut subscribe = requested_topics.difference(subscribed).copied().collect::<Vec<_>>();
+    let mut unsubscribe = subscribed.difference(&requested_topics).copied().collect::<Vec<_>>();
+    subscribe.sort_unstable();
+    unsubscribe.sort_unstable();
+    Ok((subscribe, unsubscribe))
+}
+
 /// Trait to be implemented on the mqtt topics
 pub trait Topic: Send + 'static {
     /// MQTT Topic name
@@ -142,8 +205,11 @@ impl<T: Topic> Builder for MqttBuilder<T> {
             collectors_handles,
             partitioner: MessageIdPartitioner::new(collector_count),
             stream_capacity: self.stream_capacity.unwrap_or(10000),
+            qos: self.qos.unwrap_or_else(T::qos),
             handle: None,
             inbox: None,
+            connected: false,
+            dead_letters: self.dead_letters,
             _topic: self.topic.unwrap(),
         }
         .set_name()
@@ -165,8 +231,95 @@ impl<T: Topic> Name for Mqtt<T> {
 #[async_trait::async_trait]
 impl<T: Topic, H: ChronicleBrokerScope> AknShutdown<Mqtt<T>> for BrokerHandle<H> {
     async fn aknowledge_shutdown(self, mut _state: Mqtt<T>, status: Result<(), Need>) {
+        if let Some(feed_status) = _state.feed_status(false) {
+            let _ = self.send(BrokerEvent::Feed(feed_status));
+        }
         _state.service.update_status(ServiceStatus::Stopped);
         let event = BrokerEvent::Children(BrokerChild::Mqtt(_state.service.clone(), None, status));
         let _ = self.send(event);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockFeed;
+
+    impl Topic for MockFeed {
+        fn name() -> &'static str {
+            "mock"
+        }
+        fn qos() -> i32 {
+            0
+        }
+    }
+
+    #[test]
+    fn feed_status_on_state_change() {
+        let url = Url::parse("tcp://localhost:1883").unwrap();
+        let mut mqtt = MqttBuilder::<MockFeed>::new()
+            .url(url.clone())
+            .topic(MockFeed)
+            .collectors_handles(HashMap::new())
+            .build();
+        // it starts disconnected
+        assert_eq!(mqtt.feed_status(false), None);
+        assert_eq!(
+            mqtt.feed_status(true),
+            Some(FeedStatus {
+                url: url.clone(),
+                topic_kind: "mock".to_string(),
+                connected: true,
+            })
+        );
+        assert_eq!(mqtt.feed_status(true), None);
+        assert_eq!(
+            mqtt.feed_status(false).map(|feed_status| feed_status.connected),
+            Some(false)
+        );
+        assert_eq!(mqtt.feed_status(false), None);
+    }
+
+    #[test]
+    fn feed_topics_reconciliation() {
+        let topics = |topics: &[&str]| topics.iter().map(|topic| topic.to_string()).collect::<Vec<_>>();
+        let mock_feed = vec![Messages::name()].into_iter().collect::<HashSet<_>>();
+        assert_eq!(
+            reconcile_topics(&mock_feed, &topics(&["messages", "messages/referenced"])).unwrap(),
+            (vec![MessagesReferenced::name()], vec![])
+        );
+        assert_eq!(
+            reconcile_topics(&mock_feed, &topics(&["messages/referenced"])).unwrap(),
+            (vec![MessagesReferenced::name()], vec![Messages::name()])
+        );
+        // the already subscribed topics stay untouched
+        assert_eq!(
+            reconcile_topics(&mock_feed, &topics(&["messages", "messages"])).unwrap(),
+            (vec![], vec![])
+        );
+        assert!(reconcile_topics(&mock_feed, &topics(&["milestones/latest"])).is_err());
+        assert!(reconcile_topics(&mock_feed, &[]).is_err());
+        // a feed source which isn't connected can't be updated
+        assert!(reconcile_topics(&HashSet::new(), &topics(&["messages"])).is_err());
+    }
+
+    #[test]
+    fn subscription_qos() {
+        let url = Url::parse("tcp://localhost:1883").unwrap();
+        let mqtt = MqttBuilder::<MockFeed>::new()
+            .url(url.clone())
+            .topic(MockFeed)
+            .collectors_handles(HashMap::new())
+            .build();
+        // it defaults to the topic QoS
+        assert_eq!(mqtt.subscription(), ("mock", MockFeed::qos()));
+        let mqtt = MqttBuilder::<MockFeed>::new()
+            .url(url)
+            .topic(MockFeed)
+            .collectors_handles(HashMap::new())
+            .qos(1)
+            .build();
+        assert_eq!(mqtt.subscription(), ("mock", 1));
+    }
+}
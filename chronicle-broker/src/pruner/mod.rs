@@ -0,0 +1,662 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+use super::*;
+use crate::application::write_consistency;
+use bee_message::{
+    address::Address,
+    milestone::Milestone,
+    output::Output,
+    payload::{
+        transaction::Essence,
+        Payload,
+    },
+    prelude::{
+        MilestoneIndex,
+        TransactionId,
+    },
+};
+use chronicle_common::{
+    config::{
+        PartitionConfig,
+        WriteConsistency,
+    },
+    Synckey,
+};
+use scylla_rs::prelude::stage::ReporterHandle;
+use std::{
+    collections::HashSet,
+    ops::Range,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        Arc,
+    },
+};
+use tokio::sync::oneshot;
+
+/// The max number of sync records written at once while marking the pruned ranges
+const MARK_PRUNED_BATCH: u32 = 1000;
+
+/// The storage operations used by the pruner
+#[async_trait::async_trait]
+pub trait PruneStore: Send + Sync {
+    /// Fetch the sync data for the provided sync range
+    async fn sync_data(&self, sync_range: &SyncRange) -> anyhow::Result<SyncData>;
+    /// Fetch the message id of the milestone with the provided index
+    async fn milestone_message_id(&self, milestone_index: u32) -> anyhow::Result<Option<MessageId>>;
+    /// Fetch the message and its metadata
+    async fn full_message(&self, message_id: &MessageId) -> anyhow::Result<Option<FullMessage>>;
+    /// Delete the milestone row along with the rows of its cone messages (messages, parents, indexes, addresses and
+    /// transactions), returns once all the deletes succeeded
+    async fn delete_milestone(&self, milestone_index: u32, cone: &[FullMessage]) -> anyhow::Result<()>;
+    /// Mark the milestone indexes as completed in the sync table, so they're no longer reported as gap or unlogged,
+    /// returns once all the inserts succeeded
+    async fn mark_pruned(&self, milestone_indexes: Range<u32>) -> anyhow::Result<()>;
+}
+
+/// The pruner stats
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruneStats {
+    /// The number of pruned milestones
+    pub milestones: usize,
+    /// The number of pruned messages
+    pub messages: usize,
+    /// The number of pruned transaction outputs
+    pub outputs: usize,
+}
+
+/// Pruner which deletes the milestones data below a milestone index.
+///
+/// Only the rows which can be located through the milestone cone are deleted, that is the messages
+/// rows and their parents, indexes, addresses and transactions rows.
+pub struct Pruner<S> {
+    store: S,
+    below: u32,
+    /// Only count the affected rows without deleting anything
+    dry_run: bool,
+}
+
+impl<S: PruneStore> Pruner<S> {
+    /// Create a new pruner for the milestones data below the provided milestone index
+    pub fn new(store: S, below: u32, dry_run: bool) -> Self {
+        Self { store, below, dry_run }
+    }
+    /// Prune the milestones data, and report the progress using the provided reporter
+    pub async fn prune<R: FnMut(PrunerSession)>(&self, mut report: R) -> anyhow::Result<PruneStats> {
+        ensure!(self.below > 1, "Nothing to prune below milestone index: {}", self.below);
+        let sync_range = SyncRange {
+            from: 1,
+            to: self.below,
+        };
+        let sync_data = self.store.sync_data(&sync_range).await?;
+        ensure!(!sync_data.partial, "Unable to prune using partial sync data");
+        let mut stats = PruneStats::default();
+        // only the synced milestones have data to be deleted
+        for range in sync_data.completed.iter().chain(sync_data.synced_but_unlogged.iter()) {
+            for milestone_index in range.clone() {
+                let (messages, outputs) = self.prune_milestone(milestone_index).await?;
+                stats.milestones += 1;
+                stats.messages += messages;
+                stats.outputs += outputs;
+                if self.dry_run {
+                    report(PrunerSession::WouldPrune {
+                        below: self.below,
+                        milestone_index,
+                        messages,
+                    });
+                } else {
+                    report(PrunerSession::ProgressBar {
+                        below: self.below,
+                        milestone_index,
+                        messages,
+                    });
+                }
+            }
+        }
+        if self.dry_run {
+            report(PrunerSession::DryRun {
+                below: self.below,
+                milestones: stats.milestones,
+                messages: stats.messages,
+                outputs: stats.outputs,
+            });
+            return Ok(stats);
+        }
+        // mark everything else as completed, otherwise it will be reported as gap or unlogged
+        for range in sync_data.gaps.iter().chain(sync_data.synced_but_unlogged.iter()) {
+            let mut from = range.start;
+            while from < range.end {
+                let to = range.end.min(from.saturating_add(MARK_PRUNED_BATCH));
+                self.store.mark_pruned(from..to).await?;
+                from = to;
+            }
+        }
+        Ok(stats)
+    }
+    /// Walk the milestone cone and delete its messages, returns the number of deleted messages and outputs
+    async fn prune_milestone(&self, milestone_index: u32) -> anyhow::Result<(usize, usize)> {
+        let mut cone = Vec::new();
+        if let Some(milestone_message_id) = self.store.milestone_message_id(milestone_index).await? {
+            let mut pending = vec![milestone_message_id];
+            let mut visited = HashSet::new();
+            while let Some(message_id) = pending.pop() {
+                if !visited.insert(message_id) {
+                    continue;
+                }
+                if let Some(full_message) = self.store.full_message(&message_id).await? {
+                    // the cone stops at the messages referenced by older milestones
+                    if full_message.ref_ms() == Some(milestone_index) {
+                        pending.extend(full_message.message().parents().iter().copied());
+                        cone.push(full_message);
+                    }
+                }
+            }
+        }
+        let outputs = cone
+            .iter()
+            .map(|full_message| Self::outputs_count(full_message.message()))
+            .sum();
+        if !self.dry_run {
+            self.store.delete_milestone(milestone_index, &cone).await?;
+        }
+        Ok((cone.len(), outputs))
+    }
+    fn outputs_count(message: &Message) -> usize {
+        if let Some(Payload::Transaction(transaction_payload)) = message.payload() {
+            let Essence::Regular(regular) = transaction_payload.essence();
+            regular.outputs().len()
+        } else {
+            0
+        }
+    }
+}
+
+/// A group of prune writes, which reports whether all of them succeeded once the last of their workers is dropped
+struct PruneWrites {
+    any_error: AtomicBool,
+    outcome: Option<oneshot::Sender<bool>>,
+}
+
+impl PruneWrites {
+    fn new() -> (Arc<Self>, oneshot::Receiver<bool>) {
+        let (outcome, outcome_rx) = oneshot::channel();
+        let writes = Self {
+            any_error: AtomicBool::new(false),
+            outcome: Some(outcome),
+        };
+        (Arc::new(writes), outcome_rx)
+    }
+    /// Wait for the group writes, once the writes are sent
+    async fn done(self: Arc<Self>, outcome: oneshot::Receiver<bool>) -> anyhow::Result<()> {
+        drop(self);
+        ensure!(outcome.await?, "Unable to complete the prune writes");
+        Ok(())
+    }
+}
+
+impl Drop for PruneWrites {
+    fn drop(&mut self) {
+        if let Some(outcome) = self.outcome.take() {
+            outcome.send(!self.any_error.load(Ordering::Acquire)).ok();
+        }
+    }
+}
+
+/// The sender of a prune write request, which is resent through it on retries
+type PruneSend = Arc<dyn Fn(Box<PruneWorker>) + Send + Sync>;
+
+/// Scylla worker of a prune write, which flags its group on failure
+struct PruneWorker {
+    writes: Arc<PruneWrites>,
+    retries: usize,
+    send: PruneSend,
+}
+
+impl PruneWorker {
+    /// Send a new write request of the group
+    fn send(writes: &Arc<PruneWrites>, retries: usize, send: PruneSend) {
+        let worker = Box::new(Self {
+            writes: writes.clone(),
+            retries,
+            send: send.clone(),
+        });
+        send(worker);
+    }
+    fn fail(self: Box<Self>, error: impl std::fmt::Display) {
+        error!("Unable to complete the prune write: {}", error);
+        self.writes.any_error.store(true, Ordering::Release);
+    }
+}
+
+impl Worker for PruneWorker {
+    fn handle_response(self: Box<Self>, giveload: Vec<u8>) -> anyhow::Result<()> {
+        let void = (|| -> anyhow::Result<()> { Decoder::from(giveload.try_into()?).get_void() })();
+        if let Err(e) = void {
+            self.fail(e);
+        }
+        Ok(())
+    }
+    fn handle_error(mut self: Box<Self>, error: WorkerError, _reporter: &Option<ReporterHandle>) -> anyhow::Result<()> {
+        if self.retries > 0 {
+            self.retries -= 1;
+            let send = self.send.clone();
+            tokio::spawn(async move { send(self) });
+        } else {
+            self.fail(error);
+        }
+        Ok(())
+    }
+}
+
+/// The scylla backed prune store
+pub struct ChroniclePruneStore {
+    keyspace: ChronicleKeyspace,
+    partition_config: PartitionConfig,
+    chronicle_id: u8,
+    retries: usize,
+    /// The consistency of the sync record writes, which mark the milestones as pruned
+    sync_write_consistency: WriteConsistency,
+}
+
+impl ChroniclePruneStore {
+    /// Create a new prune store for the provided keyspace
+    pub fn new(
+        keyspace: ChronicleKeyspace,
+        partition_config: PartitionConfig,
+        chronicle_id: u8,
+        retries: usize,
+        sync_write_consistency: WriteConsistency,
+    ) -> Self {
+        Self {
+            keyspace,
+            partition_config,
+            chronicle_id,
+            retries,
+            sync_write_consistency,
+        }
+    }
+    async fn select<K, V>(&self, key: K) -> anyhow::Result<Option<V>>
+    where
+        ChronicleKeyspace: Select<K, V>,
+        K: 'static + Send + Clone,
+        V: 'static + Send + Clone,
+    {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        self.keyspace
+            .select(&key)
+            .consistency(Consistency::One)
+            .build()?
+            .send_local(ValueWorker::boxed(
+                tx,
+                self.keyspace.clone(),
+                key,
+                self.retries,
+                std::marker::PhantomData,
+            ));
+        Ok(rx
+            .recv()
+            .await
+            .ok_or_else(|| anyhow!("Expected Rx inbox to receive the select response"))??)
+    }
+    fn delete<K, V>(&self, writes: &Arc<PruneWrites>, key: K)
+    where
+        ChronicleKeyspace: Delete<K, V>,
+        K: 'static + Send + Sync + Clone,
+        V: 'static + Send + Clone,
+    {
+        let keyspace = self.keyspace.clone();
+        let send: PruneSend =
+            Arc::new(
+                move |worker| match keyspace.delete_query::<V>(&key).consistency(Consistency::One).build() {
+                    Ok(delete_req) => {
+                        delete_req.send_global(worker);
+                    }
+                    Err(e) => worker.fail(e),
+                },
+            );
+        PruneWorker::send(writes, self.retries, send);
+    }
+    /// Delete the rows which are located through the message (parents, indexes, addresses and transactions)
+    fn delete_message_rows(&self, writes: &Arc<PruneWrites>, full_message: &FullMessage) -> anyhow::Result<()> {
+        let message_id = *full_message.message_id();
+        let milestone_index = MilestoneIndex(
+            full_message
+                .ref_ms()
+                .ok_or_else(|| anyhow!("Expected referenced message: {}", message_id))?,
+        );
+        let partition_id = self.partition_config.partition_id(milestone_index.0);
+        let message = full_message.message();
+        for parent_id in message.parents().iter() {
+            self.delete(
+                writes,
+                ParentPK::new(*parent_id, partition_id, milestone_index, message_id),
+            );
+        }
+        match message.payload() {
+            Some(Payload::Indexation(indexation)) => {
+                self.delete_index(writes, indexation.index(), partition_id, milestone_index, message_id);
+            }
+            Some(Payload::Transaction(transaction_payload)) => {
+                let transaction_id = transaction_payload.id();
+                let Essence::Regular(regular) = transaction_payload.essence();
+                if let Some(Payload::Indexation(indexation)) = regular.payload() {
+                    self.delete_index(writes, indexation.index(), partition_id, milestone_index, message_id);
+                }
+                for (output_index, output) in regular.outputs().iter().enumerate() {
+                    self.delete_address(
+                        writes,
+                        output,
+                        &transaction_id,
+                        output_index as u16,
+                        partition_id,
+                        milestone_index,
+                    );
+                }
+                self.delete::<TransactionId, TransactionRecord>(writes, transaction_id);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    /// Delete the index row of the message, along with its tag row unless a later milestone of the partition holds it
+    fn delete_index(
+        &self,
+        writes: &Arc<PruneWrites>,
+        index: &[u8],
+        partition_id: u16,
+        milestone_index: MilestoneIndex,
+        message_id: MessageId,
+    ) {
+        let index = hex::encode(index);
+        self.delete::<Partitioned<Tag>, ()>(
+            writes,
+            Partitioned::new(Tag(index.clone()), partition_id, milestone_index.0),
+        );
+        self.delete(
+            writes,
+            IndexationPK::new(Indexation(index), partition_id, milestone_index, message_id),
+        );
+    }
+    fn delete_address(
+        &self,
+        writes: &Arc<PruneWrites>,
+        output: &Output,
+        transaction_id: &TransactionId,
+        index: u16,
+        partition_id: u16,
+        milestone_index: MilestoneIndex,
+    ) {
+        let address = match output {
+            Output::SignatureLockedSingle(sls) => sls.address(),
+            Output::SignatureLockedDustAllowance(slda) => slda.address(),
+            _ => return,
+        };
+        let Address::Ed25519(ed_address) = address;
+        let address_pk = Ed25519AddressPK::new(
+            *ed_address,
+            partition_id,
+            milestone_index,
+            output.kind(),
+            *transaction_id,
+            index,
+        );
+        self.delete(writes, address_pk)
+    }
+}
+
+#[async_trait::async_trait]
+impl PruneStore for ChroniclePruneStore {
+    async fn sync_data(&self, sync_range: &SyncRange) -> anyhow::Result<SyncData> {
+        SyncData::try_fetch(&self.keyspace, sync_range, self.retries).await
+    }
+    async fn milestone_message_id(&self, milestone_index: u32) -> anyhow::Result<Option<MessageId>> {
+        let milestone: Option<Milestone> = self.select(MilestoneIndex(milestone_index)).await?;
+        Ok(milestone.map(|milestone| *milestone.message_id()))
+    }
+    async fn full_message(&self, message_id: &MessageId) -> anyhow::Result<Option<FullMessage>> {
+        let message_and_metadata: Option<(Option<Message>, Option<MessageMetadata>)> = self.select(*message_id).await?;
+        if let Some((Some(message), Some(metadata))) = message_and_metadata {
+            Ok(Some(FullMessage::new(message, metadata)))
+        } else {
+            Ok(None)
+        }
+    }
+    async fn delete_milestone(&self, milestone_index: u32, cone: &[FullMessage]) -> anyhow::Result<()> {
+        // the dependent rows are deleted first, so the cone can still be walked if any of them fails
+        let (writes, outcome) = PruneWrites::new();
+        for full_message in cone {
+            self.delete_message_rows(&writes, full_message)?;
+        }
+        writes.done(outcome).await?;
+        let (writes, outcome) = PruneWrites::new();
+        for full_message in cone {
+            self.delete::<MessageId, Message>(&writes, *full_message.message_id());
+        }
+        self.delete::<MilestoneIndex, Milestone>(&writes, MilestoneIndex(milestone_index));
+        writes.done(outcome).await
+    }
+    async fn mark_pruned(&self, milestone_indexes: Range<u32>) -> anyhow::Result<()> {
+        let (writes, outcome) = PruneWrites::new();
+        for milestone_index in milestone_indexes {
+            let keyspace = self.keyspace.clone();
+            let consistency = write_consistency(self.sync_write_consistency);
+            let synced_record = SyncRecord::new(
+                MilestoneIndex(milestone_index),
+                Some(self.chronicle_id),
+                Some(self.chronicle_id),
+            );
+            let send: PruneSend = Arc::new(move |worker| {
+                match keyspace
+                    .insert_query(&Synckey, &synced_record)
+                    .consistency(consistency)
+                    .build()
+                {
+                    Ok(insert_req) => {
+                        insert_req.send_global(worker);
+                    }
+                    Err(e) => worker.fail(e),
+                }
+            });
+            PruneWorker::send(&writes, self.retries, send);
+        }
+        writes.done(outcome).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bee_message::{
+        parents::Parents,
+        MessageBuilder,
+    };
+    use bee_pow::providers::miner::Miner;
+    use std::{
+        collections::BTreeMap,
+        ops::Range,
+        sync::Mutex,
+    };
+
+    /// In memory prune store, seeded with a chain of milestones where each milestone cone has two messages
+    #[derive(Default)]
+    struct MockStore {
+        milestones: Mutex<HashMap<u32, MessageId>>,
+        messages: Mutex<HashMap<MessageId, FullMessage>>,
+        sync: Mutex<BTreeMap<u32, SyncRecord>>,
+        /// The milestone index whose delete fails
+        fail_delete: Option<u32>,
+    }
+
+    impl MockStore {
+        fn seed(range: Range<u32>) -> Self {
+            let store = Self::default();
+            let mut parent = MessageId::new([0; 32]);
+            for milestone_index in range {
+                let message_id = store.insert_message(milestone_index, parent);
+                let milestone_message_id = store.insert_message(milestone_index, message_id);
+                store
+                    .milestones
+                    .lock()
+                    .unwrap()
+                    .insert(milestone_index, milestone_message_id);
+                let record = SyncRecord::new(MilestoneIndex(milestone_index), Some(0), Some(0));
+                store.sync.lock().unwrap().insert(milestone_index, record);
+                parent = milestone_message_id;
+            }
+            store
+        }
+        fn insert_message(&self, milestone_index: u32, parent: MessageId) -> MessageId {
+            let message = MessageBuilder::<Miner>::new()
+                .with_network_id(milestone_index as u64)
+                .with_parents(Parents::new(vec![parent]).unwrap())
+                .finish()
+                .unwrap();
+            let message_id = message.id().0;
+            let metadata = MessageMetadata {
+                message_id,
+                parent_message_ids: vec![parent],
+                is_solid: true,
+                referenced_by_milestone_index: Some(milestone_index),
+                ledger_inclusion_state: Some(LedgerInclusionState::NoTransaction),
+                should_promote: None,
+                should_reattach: None,
+                conflict_reason: None,
+            };
+            self.messages
+                .lock()
+                .unwrap()
+                .insert(message_id, FullMessage::new(message, metadata));
+            message_id
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl PruneStore for MockStore {
+        async fn sync_data(&self, sync_range: &SyncRange) -> anyhow::Result<SyncData> {
+            let sync = self.sync.lock().unwrap();
+            let rows = sync
+                .range(sync_range.from..sync_range.to)
+                .rev()
+                .map(|(_, record)| Ok(*record));
+            Ok(SyncData::from_rows(sync_range, rows))
+        }
+        async fn milestone_message_id(&self, milestone_index: u32) -> anyhow::Result<Option<MessageId>> {
+            Ok(self.milestones.lock().unwrap().get(&milestone_index).copied())
+        }
+        async fn full_message(&self, message_id: &MessageId) -> anyhow::Result<Option<FullMessage>> {
+            Ok(self.messages.lock().unwrap().get(message_id).cloned())
+        }
+        async fn delete_milestone(&self, milestone_index: u32, cone: &[FullMessage]) -> anyhow::Result<()> {
+            ensure!(
+                self.fail_delete != Some(milestone_index),
+                "Unable to delete milestone: {}",
+                milestone_index
+            );
+            let mut messages = self.messages.lock().unwrap();
+            for full_message in cone {
+                messages.remove(full_message.message_id());
+            }
+            self.milestones.lock().unwrap().remove(&milestone_index);
+            Ok(())
+        }
+        async fn mark_pruned(&self, milestone_indexes: Range<u32>) -> anyhow::Result<()> {
+            let mut sync = self.sync.lock().unwrap();
+            for milestone_index in milestone_indexes {
+                let record = SyncRecord::new(MilestoneIndex(milestone_index), Some(0), Some(0));
+                sync.insert(milestone_index, record);
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn prune_below() {
+        // milestones 1..5 are missing, 5..20 are synced
+        let pruner = Pruner::new(MockStore::seed(5..20), 10, false);
+        let mut progress = Vec::new();
+        let stats = pruner
+            .prune(|session| {
+                if let PrunerSession::ProgressBar { milestone_index, .. } = session {
+                    progress.push(milestone_index);
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            PruneStats {
+                milestones: 5,
+                messages: 10,
+                outputs: 0,
+            }
+        );
+        assert_eq!(progress, (5..10).collect::<Vec<u32>>());
+        let store = &pruner.store;
+        assert!((5..10).all(|ms| !store.milestones.lock().unwrap().contains_key(&ms)));
+        assert!((10..20).all(|ms| store.milestones.lock().unwrap().contains_key(&ms)));
+        assert_eq!(store.messages.lock().unwrap().len(), 20);
+        let sync_range = SyncRange { from: 1, to: 20 };
+        let sync_data = store.sync_data(&sync_range).await.unwrap();
+        assert!(sync_data.gaps.is_empty());
+        assert!(sync_data.synced_but_unlogged.is_empty());
+        assert_eq!(sync_data.completed, vec![1..20]);
+    }
+
+    #[tokio::test]
+    async fn prune_stops_on_failed_delete() {
+        let mut store = MockStore::seed(5..20);
+        store.fail_delete = Some(7);
+        let pruner = Pruner::new(store, 10, false);
+        let mut progress = Vec::new();
+        let result = pruner
+            .prune(|session| {
+                if let PrunerSession::ProgressBar { milestone_index, .. } = session {
+                    progress.push(milestone_index);
+                }
+            })
+            .await;
+        assert!(result.is_err());
+        // the failed milestone is not reported as pruned, and the gaps are left as they are
+        assert_eq!(progress, vec![5, 6]);
+        let store = &pruner.store;
+        assert!(store.milestones.lock().unwrap().contains_key(&7));
+        let sync_range = SyncRange { from: 1, to: 20 };
+        let sync_data = store.sync_data(&sync_range).await.unwrap();
+        assert_eq!(sync_data.gaps, vec![1..5]);
+    }
+
+    #[tokio::test]
+    async fn prune_dry_run() {
+        let pruner = Pruner::new(MockStore::seed(5..20), 10, true);
+        let mut reported = None;
+        let mut would_prune = Vec::new();
+        let stats = pruner
+            .prune(|session| match session {
+                PrunerSession::DryRun {
+                    milestones,
+                    messages,
+                    outputs,
+                    ..
+                } => {
+                    reported.replace((milestones, messages, outputs));
+                }
+                PrunerSession::WouldPrune { milestone_index, .. } => would_prune.push(milestone_index),
+                session => panic!("Unexpected dry run session: {:?}", session),
+            })
+            .await
+            .unwrap();
+        assert_eq!(reported, Some((stats.milestones, stats.messages, stats.outputs)));
+        assert_eq!(would_prune, (5..10).collect::<Vec<u32>>());
+        assert_eq!(stats.milestones, 5);
+        assert_eq!(stats.messages, 10);
+        // nothing got deleted
+        let store = &pruner.store;
+        assert_eq!(store.milestones.lock().unwrap().len(), 15);
+        assert_eq!(store.messages.lock().unwrap().len(), 30);
+        let sync_range = SyncRange { from: 1, to: 20 };
+        let sync_data = store.sync_data(&sync_range).await.unwrap();
+        assert_eq!(sync_data.gaps, vec![1..5]);
+    }
+}
@@ -61,6 +61,9 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Solidifier {
                                 CqlResult::AnalyzedMilestone(milestone_index) => {
                                     if let Some(in_database) = self.in_database.get_mut(&milestone_index) {
                                         in_database.set_analyzed(true);
+                                        if let Some(analytic_record) = in_database.take_analytic_record() {
+                                            self.analytics.rollup(&analytic_record);
+                                        }
                                         info!("Analyzed this milestone {}", milestone_index);
                                         if in_database.check_if_all_in_database() {
                                             // Insert record into sync table
@@ -72,8 +75,48 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Solidifier {
                                         error!("Analyzed Milestone should have in_database entry");
                                     }
                                 }
+                                CqlResult::MirroredMsg(keyspace, message_id, milestone_index) => {
+                                    if self.mirror_persisted(keyspace.clone(), message_id, milestone_index) {
+                                        self.handle_mirror_in_database(keyspace, milestone_index)
+                                            .unwrap_or_else(|e| {
+                                                error!("{}", e);
+                                            });
+                                    }
+                                }
+                                CqlResult::MirroredSyncedMilestone(keyspace, milestone_index) => {
+                                    info!("Synced this milestone {} in the {} keyspace", milestone_index, keyspace);
+                                }
+                                CqlResult::MirroredAnalyzedMilestone(keyspace, milestone_index) => {
+                                    if self.mirror_analyzed(keyspace.clone(), milestone_index) {
+                                        self.handle_mirror_in_database(keyspace, milestone_index)
+                                            .unwrap_or_else(|e| {
+                                                error!("{}", e);
+                                            });
+                                    }
+                                }
                             }
                         }
+                        // a failed mirror write leaves the milestone unsynced in the mirror keyspace only
+                        Err(CqlResult::MirroredMsg(keyspace, message_id, milestone_index)) => {
+                            error!(
+                                "Unable to mirror message with id: {}, referenced by milestone index: {}, into the {} keyspace",
+                                message_id, milestone_index, keyspace
+                            );
+                            self.mirror_failed(keyspace, milestone_index);
+                        }
+                        Err(CqlResult::MirroredSyncedMilestone(keyspace, milestone_index)) => {
+                            error!(
+                                "Unable to update sync table for milestone index: {}, in the {} keyspace",
+                                milestone_index, keyspace
+                            );
+                        }
+                        Err(CqlResult::MirroredAnalyzedMilestone(keyspace, milestone_index)) => {
+                            error!(
+                                "Unable to update analytics table for milestone index: {}, in the {} keyspace",
+                                milestone_index, keyspace
+                            );
+                            self.mirror_failed(keyspace, milestone_index);
+                        }
                         Err(cql_result) => {
                             match cql_result {
                                 CqlResult::PersistedMsg(message_id, milestone_index) => {
@@ -91,6 +134,7 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Solidifier {
                                         milestone_index,
                                     );
                                 }
+                                _ => unreachable!("The mirror write results are handled apart"),
                             }
                             error!("Scylla cluster is likely having a complete outage, so we are shutting down broker for meantime.");
                             // Abort solidifier in order to let broker app reschedule itself after few mins
@@ -123,6 +167,9 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Solidifier {
                         }
                     }
                 }
+                SolidifierEvent::SolidificationTimeout => self.handle_solidification_timeout(Instant::now()),
+                SolidifierEvent::ExpectedTimeout => self.handle_expected_timeout(Instant::now()),
+                SolidifierEvent::SwitchKeyspace(keyspace) => self.switch_keyspace(keyspace),
                 SolidifierEvent::Shutdown => break,
             }
         }
@@ -131,6 +178,16 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Solidifier {
 }
 
 impl Solidifier {
+    /// Switch the keyspace of the next writes, the in-flight workers keep (and retry in) the keyspace they were
+    /// created with
+    pub(crate) fn switch_keyspace(&mut self, keyspace: ChronicleKeyspace) {
+        info!(
+            "Solidifier id: {} switched to keyspace: {}",
+            self.partition_id,
+            keyspace.name()
+        );
+        self.keyspace = keyspace;
+    }
     fn handle_solidify_failure(&mut self, milestone_index: u32) {
         error!(
             "Solidifier id: {}. was unable to solidify milestone_index: {}",
@@ -143,6 +200,7 @@ impl Solidifier {
                 // move it out lru_in_database (if any)
                 self.lru_in_database.pop(&milestone_index);
                 self.in_database.remove(&milestone_index);
+                self.forget_mirrors_in_database(milestone_index);
                 // move to unreachable atm
                 self.unreachable.put(milestone_index, ());
                 // ensure it's created by syncer
@@ -164,6 +222,80 @@ impl Solidifier {
             }
         }
     }
+    /// Apply the solidification timeout policy to the milestone data which did not solidify in time
+    pub(crate) fn handle_solidification_timeout(&mut self, now: Instant) {
+        let timeout = match self.solidification_timeout.timeout() {
+            Some(timeout) => timeout,
+            None => return,
+        };
+        let timed_out = self
+            .milestones_data
+            .values()
+            .filter(|ms_data| !ms_data.check_if_completed() && now.duration_since(ms_data.created_at) >= timeout)
+            .map(|ms_data| ms_data.milestone_index())
+            .collect::<Vec<_>>();
+        for milestone_index in timed_out {
+            let ms_data = self
+                .milestones_data
+                .remove(&milestone_index)
+                .expect("Expected milestone data for milestone_index");
+            SOLIDIFICATION_TIMEOUTS
+                .with_label_values(&[self.keyspace.name().as_ref()])
+                .inc();
+            warn!(
+                "Solidifier id: {}, milestone data for index: {} created by: {:?} did not solidify within {}s, pending: {}, messages: {}, applying the {:?} policy",
+                self.partition_id,
+                milestone_index,
+                ms_data.created_by(),
+                timeout.as_secs(),
+                ms_data.pending().len(),
+                ms_data.messages().len(),
+                self.solidification_timeout.policy,
+            );
+            self.lru_in_database.pop(&milestone_index);
+            self.in_database.remove(&milestone_index);
+            self.forget_mirrors_in_database(milestone_index);
+            self.unreachable.put(milestone_index, ());
+            if ms_data.created_by.eq(&CreatedBy::Syncer) {
+                // the syncer archives what it fills (within its upper limit), so it's only told to retry it later
+                let _ = self.syncer_handle.send(SyncerEvent::Unreachable(milestone_index));
+            } else if let SolidificationTimeoutPolicy::Persist = self.solidification_timeout.policy {
+                if let Some(archiver_handle) = self.archiver_handle.as_ref() {
+                    // the archived milestone data records its pending set, while it's left unsynced for the syncer
+                    let _ = archiver_handle.send(ArchiverEvent::MilestoneData(ms_data, None));
+                }
+            }
+        }
+    }
+    /// Request the expected milestones which did not arrive within the expected timeout, as the feed may never
+    /// deliver them. The request is repeated on every check till the milestone arrives or the solidification timeout
+    /// gives it up
+    pub(crate) fn handle_expected_timeout(&mut self, now: Instant) {
+        let timeout = match self.expected_timeout {
+            Some(timeout) => timeout,
+            None => return,
+        };
+        let mut timed_out = self
+            .milestones_data
+            .values()
+            .filter(|ms_data| {
+                ms_data.created_by == CreatedBy::Expected
+                    && !ms_data.milestone_exist()
+                    && now.duration_since(ms_data.created_at) >= timeout
+            })
+            .map(|ms_data| ms_data.milestone_index())
+            .collect::<Vec<_>>();
+        timed_out.sort_unstable();
+        for milestone_index in timed_out {
+            warn!(
+                "Solidifier id: {}, expected milestone index: {} did not arrive within {}s, requesting it",
+                self.partition_id,
+                milestone_index,
+                timeout.as_secs(),
+            );
+            Self::request_milestone_message(&self.collector_handles, self.partition_id, milestone_index);
+        }
+    }
     fn handle_solidify(&mut self, milestone_index: u32) {
         // open solidify requests only for less than the expected
         if milestone_index >= self.expected {
@@ -199,6 +331,7 @@ impl Solidifier {
                 self.in_database
                     .entry(milestone_index)
                     .or_insert_with(|| InDatabase::new(milestone_index));
+                self.open_mirrors_in_database(milestone_index);
             } else {
                 // tell syncer to skip this atm
                 let _ = self.syncer_handle.send(SyncerEvent::Unreachable(milestone_index));
@@ -217,6 +350,7 @@ impl Solidifier {
             self.in_database
                 .entry(milestone_index)
                 .or_insert_with(|| InDatabase::new(milestone_index));
+            self.open_mirrors_in_database(milestone_index);
         }
     }
     fn close_message_id(&mut self, milestone_index: u32, message_id: &MessageId) -> anyhow::Result<()> {
@@ -242,24 +376,40 @@ impl Solidifier {
         }
         Ok(())
     }
+    /// Record the time the completed milestone data took to solidify
+    pub(crate) fn record_solidification_latency(&self, milestone_data: &MilestoneData, now: Instant) {
+        SOLIDIFICATION_LATENCY
+            .with_label_values(&[
+                self.keyspace.name().as_ref(),
+                milestone_data.created_by().to_string().as_str(),
+            ])
+            .observe(now.duration_since(milestone_data.created_at).as_secs_f64());
+    }
     fn push_to_logger(&mut self, milestone_index: u32) -> anyhow::Result<()> {
         // Remove milestoneData from self state and pass it to archiver
         let milestone_data = self
             .milestones_data
             .remove(&milestone_index)
             .expect("Expected milestone data for milestone_index");
+        self.record_solidification_latency(&milestone_data, Instant::now());
         let analytic_record = milestone_data.get_analytic_record()?;
-        self.insert_analytic(milestone_index, analytic_record)?;
+        self.insert_analytic(milestone_index, analytic_record.clone())?;
         // Update in_database
         let in_database = self
             .in_database
             .entry(milestone_index)
             .or_insert_with(|| InDatabase::from(&milestone_data));
         in_database.set_messages_len(milestone_data.messages().len());
+        in_database.set_created_by(*milestone_data.created_by());
+        // rolled up into the broker analytics once the insert succeeds
+        in_database.set_analytic_record(analytic_record);
         if in_database.check_if_all_in_database() {
             // Insert record into sync table
             self.handle_in_database(milestone_index)?;
         }
+        for keyspace in self.mirror_messages_len(&milestone_data) {
+            self.handle_mirror_in_database(keyspace, milestone_index)?;
+        }
         if let Some(archiver_handle) = self.archiver_handle.as_ref() {
             info!(
                 "solidifier_id: {}, is pushing the milestone data for index: {}, to Logger",
@@ -280,46 +430,226 @@ impl Solidifier {
             .milestones_data
             .remove(&milestone_index)
             .expect("Expected milestone data for milestone_index");
+        self.record_solidification_latency(&milestone_data, Instant::now());
         let analytic_record = milestone_data.get_analytic_record()?;
-        self.insert_analytic(milestone_index, analytic_record)?;
+        self.insert_analytic(milestone_index, analytic_record.clone())?;
         // Update in_database
         let in_database = self
             .in_database
             .entry(milestone_index)
             .or_insert_with(|| InDatabase::from(&milestone_data));
         in_database.set_messages_len(milestone_data.messages().len());
+        in_database.set_created_by(*milestone_data.created_by());
+        // rolled up into the broker analytics once the insert succeeds
+        in_database.set_analytic_record(analytic_record);
         if in_database.check_if_all_in_database() {
             // Insert record into sync table
             self.handle_in_database(milestone_index)?;
         }
+        for keyspace in self.mirror_messages_len(&milestone_data) {
+            self.handle_mirror_in_database(keyspace, milestone_index)?;
+        }
         let syncer_event = SyncerEvent::MilestoneData(milestone_data);
         let _ = self.syncer_handle.send(syncer_event);
         Ok(())
     }
     fn handle_in_database(&mut self, milestone_index: u32) -> anyhow::Result<()> {
-        self.in_database.remove(&milestone_index);
+        let created_by = self
+            .in_database
+            .remove(&milestone_index)
+            .and_then(|in_database| in_database.created_by);
         self.lru_in_database.put(milestone_index, ());
         let sync_key = Synckey;
         let synced_by = Some(self.chronicle_id);
-        let synced_record = SyncRecord::new(MilestoneIndex(milestone_index), synced_by, None);
-        let request = self
-            .keyspace
-            .insert(&sync_key, &synced_record)
-            .consistency(Consistency::One)
+        let synced_record =
+            SyncRecord::new(MilestoneIndex(milestone_index), synced_by, None).with_created_by(created_by.map(u8::from));
+        let request = self.sync_record_request(&sync_key, &synced_record)?;
+        let worker = self.synced_milestone_worker(milestone_index, synced_record);
+        request.send_local(worker);
+        Ok(())
+    }
+    /// Create the insert request of the sync record, with the configured sync write consistency
+    pub(crate) fn sync_record_request(
+        &self,
+        sync_key: &Synckey,
+        synced_record: &SyncRecord,
+    ) -> anyhow::Result<InsertRequest<ChronicleKeyspace, Synckey, SyncRecord>> {
+        self.keyspace
+            .insert(sync_key, synced_record)
+            .consistency(write_consistency(self.sync_write_consistency))
+            .build()
+    }
+    /// Track the message of the milestone persisted in the mirror keyspace, returns whether all the writes of the
+    /// milestone are confirmed there
+    pub(crate) fn mirror_persisted(&mut self, keyspace: String, message_id: MessageId, milestone_index: u32) -> bool {
+        let key = (keyspace, milestone_index);
+        if let Some(in_database) = self.mirror_in_database.get_mut(&key) {
+            in_database.add_message_id(message_id);
+            in_database.check_if_all_in_database()
+        } else {
+            // like in the default keyspace, a reinserted message doesn't reopen a synced or unreachable milestone
+            if self.lru_mirror_in_database.get(&key).is_none() && self.unreachable.get(&milestone_index).is_none() {
+                let mut in_database = InDatabase::new(milestone_index);
+                in_database.add_message_id(message_id);
+                self.mirror_in_database.insert(key, in_database);
+            }
+            false
+        }
+    }
+    /// Track the analytic record of the milestone mirrored into the mirror keyspace, returns whether all the writes
+    /// of the milestone are confirmed there
+    pub(crate) fn mirror_analyzed(&mut self, keyspace: String, milestone_index: u32) -> bool {
+        match self.mirror_in_database.get_mut(&(keyspace, milestone_index)) {
+            Some(in_database) => {
+                in_database.set_analyzed(true);
+                in_database.check_if_all_in_database()
+            }
+            None => false,
+        }
+    }
+    /// Track the messages of the completed milestone data in every mirror keyspace, returns the mirror keyspaces all
+    /// the writes of the milestone are confirmed in
+    pub(crate) fn mirror_messages_len(&mut self, milestone_data: &MilestoneData) -> Vec<String> {
+        let milestone_index = milestone_data.milestone_index();
+        let mut completed = Vec::new();
+        for keyspace in self.mirror_keyspaces.iter() {
+            let key = (keyspace.name().to_string(), milestone_index);
+            if self.lru_mirror_in_database.get(&key).is_some() {
+                continue;
+            }
+            let in_database = self
+                .mirror_in_database
+                .entry(key)
+                .or_insert_with(|| InDatabase::from(milestone_data));
+            in_database.set_messages_len(milestone_data.messages().len());
+            in_database.set_created_by(*milestone_data.created_by());
+            if in_database.check_if_all_in_database() {
+                completed.push(keyspace.name().to_string());
+            }
+        }
+        completed
+    }
+    /// Stop tracking the milestone in the mirror keyspace whose write failed, so it's never synced there
+    pub(crate) fn mirror_failed(&mut self, keyspace: String, milestone_index: u32) {
+        let key = (keyspace, milestone_index);
+        self.mirror_in_database.remove(&key);
+        self.lru_mirror_in_database.put(key, ());
+    }
+    /// Insert empty entries of the milestone for the mirror keyspaces, which the syncer asked to solidify
+    fn open_mirrors_in_database(&mut self, milestone_index: u32) {
+        for keyspace in self.mirror_keyspaces.iter() {
+            let key = (keyspace.name().to_string(), milestone_index);
+            self.lru_mirror_in_database.pop(&key);
+            self.mirror_in_database
+                .entry(key)
+                .or_insert_with(|| InDatabase::new(milestone_index));
+        }
+    }
+    /// Forget the milestone in the mirror keyspaces, as it's unreachable
+    fn forget_mirrors_in_database(&mut self, milestone_index: u32) {
+        self.mirror_in_database
+            .retain(|(_, index), _| *index != milestone_index);
+        for keyspace in self.mirror_keyspaces.iter() {
+            self.lru_mirror_in_database
+                .pop(&(keyspace.name().to_string(), milestone_index));
+        }
+    }
+    /// Insert the sync record of the milestone into the mirror keyspace, once all its writes are confirmed there
+    fn handle_mirror_in_database(&mut self, keyspace: String, milestone_index: u32) -> anyhow::Result<()> {
+        let key = (keyspace, milestone_index);
+        let created_by = self
+            .mirror_in_database
+            .remove(&key)
+            .and_then(|in_database| in_database.created_by);
+        let (request, worker) = self.mirror_sync_record_request(&key.0, milestone_index, created_by)?;
+        self.lru_mirror_in_database.put(key, ());
+        request.send_local(worker);
+        Ok(())
+    }
+    /// Create the insert request of the sync record in the mirror keyspace, with the configured sync write
+    /// consistency, along with its worker which reports the write as the mirror's one
+    pub(crate) fn mirror_sync_record_request(
+        &self,
+        keyspace: &str,
+        milestone_index: u32,
+        created_by: Option<CreatedBy>,
+    ) -> anyhow::Result<(
+        InsertRequest<ChronicleKeyspace, Synckey, SyncRecord>,
+        Box<SyncedMilestoneWorker<ChronicleKeyspace, Synckey, SyncRecord>>,
+    )> {
+        let mirror = self
+            .mirror_keyspaces
+            .iter()
+            .find(|mirror| mirror.name() == keyspace)
+            .ok_or_else(|| anyhow!("The {} keyspace is not a mirror keyspace", keyspace))?;
+        let synced_record = SyncRecord::new(MilestoneIndex(milestone_index), Some(self.chronicle_id), None)
+            .with_created_by(created_by.map(u8::from));
+        let request = mirror
+            .insert(&Synckey, &synced_record)
+            .consistency(write_consistency(self.sync_write_consistency))
             .build()?;
         let worker = SyncedMilestoneWorker::boxed(
+            self.handle.clone(),
+            milestone_index,
+            mirror.clone(),
+            Synckey,
+            synced_record,
+            self.retries,
+        )
+        .with_consistency(self.sync_write_consistency)
+        .with_mirror(keyspace.to_owned());
+        Ok((request, worker))
+    }
+    /// Create the worker which inserts the sync record of the milestone, retrying the failed writes
+    pub(crate) fn synced_milestone_worker(
+        &self,
+        milestone_index: u32,
+        synced_record: SyncRecord,
+    ) -> Box<SyncedMilestoneWorker<ChronicleKeyspace, Synckey, SyncRecord>> {
+        SyncedMilestoneWorker::boxed(
             self.handle.clone(),
             milestone_index,
             self.keyspace.clone(),
-            sync_key,
+            Synckey,
             synced_record,
             self.retries,
-        );
-        request.send_local(worker);
-        Ok(())
+        )
+        .with_consistency(self.sync_write_consistency)
     }
-    fn insert_analytic(&self, milestone_index: u32, analytic_record: AnalyticRecord) -> anyhow::Result<()> {
+    fn insert_analytic(&mut self, milestone_index: u32, analytic_record: AnalyticRecord) -> anyhow::Result<()> {
         let sync_key = Synckey;
+        let mut failed = Vec::new();
+        for keyspace in self.mirror_keyspaces.iter() {
+            match keyspace
+                .insert(&sync_key, &analytic_record)
+                .consistency(Consistency::One)
+                .build()
+            {
+                Ok(request) => {
+                    let worker = AnalyzedMilestoneWorker::boxed(
+                        self.handle.clone(),
+                        milestone_index,
+                        keyspace.clone(),
+                        sync_key,
+                        analytic_record.clone(),
+                        self.retries,
+                    )
+                    .with_mirror(keyspace.name().to_string());
+                    request.send_local(worker);
+                }
+                Err(e) => {
+                    error!(
+                        "Unable to mirror the analytics into the {} keyspace: {}",
+                        keyspace.name(),
+                        e
+                    );
+                    failed.push(keyspace.name().to_string());
+                }
+            }
+        }
+        for keyspace in failed {
+            self.mirror_failed(keyspace, milestone_index);
+        }
         let request = self
             .keyspace
             .insert(&sync_key, &analytic_record)
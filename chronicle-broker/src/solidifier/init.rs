@@ -7,12 +7,38 @@ use super::*;
 impl<H: ChronicleBrokerScope> Init<BrokerHandle<H>> for Solidifier {
     async fn init(&mut self, status: Result<(), Need>, _supervisor: &mut Option<BrokerHandle<H>>) -> Result<(), Need> {
         info!("{} is Initializing", self.get_name());
+        if let Some(supervisor) = _supervisor.as_ref() {
+            self.analytics = supervisor.analytics().clone();
+        }
         self.service.update_status(ServiceStatus::Initializing);
         let event = BrokerEvent::Children(BrokerChild::Solidifier(self.service.clone(), Ok(())));
         let _ = _supervisor
             .as_mut()
             .expect("Solidifier expected BrokerHandle")
             .send(event);
+        if let Some(timeout) = self.solidification_timeout.timeout() {
+            // The milestone data are checked every timeout, so a timed out one is handled within twice the timeout
+            let handle = self.handle.clone();
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(timeout).await;
+                    if handle.send(SolidifierEvent::SolidificationTimeout).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        if let Some(timeout) = self.expected_timeout {
+            let handle = self.handle.clone();
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(timeout).await;
+                    if handle.send(SolidifierEvent::ExpectedTimeout).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
         status
     }
 }
@@ -1,6 +1,10 @@
 // Copyright 2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 use super::{
+    application::{
+        write_consistency,
+        BrokerAnalytics,
+    },
     archiver::{
         ArchiverEvent,
         ArchiverHandle,
@@ -18,13 +22,27 @@ use super::{
     *,
 };
 use bee_message::prelude::MilestonePayload;
-
+use chronicle_common::{
+    config::{
+        SolidificationTimeoutConfig,
+        SolidificationTimeoutPolicy,
+        WriteConsistency,
+    },
+    metrics::{
+        SOLIDIFICATION_LATENCY,
+        SOLIDIFICATION_TIMEOUTS,
+    },
+};
 use std::{
     ops::{
         Deref,
         DerefMut,
     },
     sync::atomic::Ordering,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 
 mod event_loop;
@@ -35,6 +53,7 @@ mod terminating;
 builder!(SolidifierBuilder {
     chronicle_id: u8,
     keyspace: ChronicleKeyspace,
+    mirror_keyspaces: Vec<ChronicleKeyspace>,
     partition_id: u8,
     lru_capacity: usize,
     syncer_handle: SyncerHandle,
@@ -43,8 +62,11 @@ builder!(SolidifierBuilder {
     inbox: SolidifierInbox,
     gap_start: u32,
     retries: u16,
+    sync_write_consistency: WriteConsistency,
     collector_handles: HashMap<u8, CollectorHandle>,
-    collector_count: u8
+    collector_count: u8,
+    solidification_timeout: SolidificationTimeoutConfig,
+    expected_timeout_secs: u64
 });
 
 /// A milestone message payload
@@ -65,6 +87,10 @@ struct InDatabase {
     #[allow(unused)]
     milestone_index: u32,
     analyzed: bool,
+    /// The analytic record of the milestone, till its insert succeeds
+    analytic_record: Option<AnalyticRecord>,
+    /// The source which created the milestone data, persisted with the sync record
+    created_by: Option<CreatedBy>,
     messages_len: usize,
     in_database: HashMap<MessageId, ()>,
 }
@@ -74,6 +100,8 @@ impl InDatabase {
         Self {
             milestone_index,
             analyzed: false,
+            analytic_record: None,
+            created_by: None,
             messages_len: usize::MAX,
             in_database: HashMap::new(),
         }
@@ -87,6 +115,15 @@ impl InDatabase {
     fn set_analyzed(&mut self, analyzed: bool) {
         self.analyzed = analyzed;
     }
+    fn set_analytic_record(&mut self, analytic_record: AnalyticRecord) {
+        self.analytic_record.replace(analytic_record);
+    }
+    fn take_analytic_record(&mut self) -> Option<AnalyticRecord> {
+        self.analytic_record.take()
+    }
+    fn set_created_by(&mut self, created_by: CreatedBy) {
+        self.created_by.replace(created_by);
+    }
     fn check_if_all_in_database(&self) -> bool {
         self.messages_len == self.in_database.len() && self.analyzed
     }
@@ -96,6 +133,7 @@ impl From<&MilestoneData> for InDatabase {
     fn from(milestone_data: &MilestoneData) -> Self {
         let mut in_database = Self::new(milestone_data.milestone_index());
         in_database.set_messages_len(milestone_data.messages().len());
+        in_database.set_created_by(*milestone_data.created_by());
         in_database
     }
 }
@@ -113,6 +151,12 @@ pub enum SolidifierEvent {
     Solidify(Result<u32, u32>),
     /// CqlResult from scylla worker;
     CqlResult(Result<CqlResult, CqlResult>),
+    /// Check the milestone data which did not solidify within the solidification timeout
+    SolidificationTimeout,
+    /// Check the expected milestones which did not arrive within the expected timeout
+    ExpectedTimeout,
+    /// Switch the keyspace of the solidifier writes
+    SwitchKeyspace(ChronicleKeyspace),
     /// Shutdown the solidifier
     Shutdown,
 }
@@ -125,6 +169,30 @@ pub enum CqlResult {
     SyncedMilestone(u32),
     /// Analyzed MilestoneData or not
     AnalyzedMilestone(u32),
+    /// Message was persisted in the mirror keyspace or not
+    MirroredMsg(String, MessageId, u32),
+    /// Milestone was synced in the mirror keyspace or not
+    MirroredSyncedMilestone(String, u32),
+    /// Analyzed MilestoneData was mirrored into the mirror keyspace or not
+    MirroredAnalyzedMilestone(String, u32),
+}
+
+impl CqlResult {
+    /// The result of the same write into the mirror keyspace, if any
+    fn mirrored(self, mirror: Option<String>) -> Self {
+        match (self, mirror) {
+            (CqlResult::PersistedMsg(message_id, milestone_index), Some(keyspace)) => {
+                CqlResult::MirroredMsg(keyspace, message_id, milestone_index)
+            }
+            (CqlResult::SyncedMilestone(milestone_index), Some(keyspace)) => {
+                CqlResult::MirroredSyncedMilestone(keyspace, milestone_index)
+            }
+            (CqlResult::AnalyzedMilestone(milestone_index), Some(keyspace)) => {
+                CqlResult::MirroredAnalyzedMilestone(keyspace, milestone_index)
+            }
+            (cql_result, _) => cql_result,
+        }
+    }
 }
 
 /// SolidifierHandle
@@ -180,10 +248,15 @@ pub struct Solidifier {
     /// It's the chronicle id.
     chronicle_id: u8,
     keyspace: ChronicleKeyspace,
+    /// The keyspaces the sync and analytics records are mirrored into
+    mirror_keyspaces: Vec<ChronicleKeyspace>,
     partition_id: u8,
     milestones_data: HashMap<u32, MilestoneData>,
     in_database: HashMap<u32, InDatabase>,
     lru_in_database: lru::LruCache<u32, ()>,
+    /// The milestones tracked in each mirror keyspace, till all their writes are confirmed there
+    mirror_in_database: HashMap<(String, u32), InDatabase>,
+    lru_mirror_in_database: lru::LruCache<(String, u32), ()>,
     unreachable: lru::LruCache<u32, ()>,
     collector_handles: HashMap<u8, CollectorHandle>,
     collector_count: u8,
@@ -194,6 +267,13 @@ pub struct Solidifier {
     gap_start: u32,
     expected: u32,
     retries: u16,
+    /// The consistency of the sync record writes
+    sync_write_consistency: WriteConsistency,
+    solidification_timeout: SolidificationTimeoutConfig,
+    /// The time a missing expected milestone is waited for before it's requested
+    expected_timeout: Option<Duration>,
+    /// The analytics of the broker, which the stored analytic records are rolled up into
+    analytics: BrokerAnalytics,
     handle: SolidifierHandle,
     inbox: SolidifierInbox,
 }
@@ -209,9 +289,12 @@ impl Builder for SolidifierBuilder {
             service: Service::new(),
             partition_id: self.partition_id.unwrap(),
             keyspace: self.keyspace.unwrap(),
+            mirror_keyspaces: self.mirror_keyspaces.unwrap_or_default(),
             chronicle_id: self.chronicle_id.unwrap_or(0),
             in_database: HashMap::new(),
             lru_in_database: lru::LruCache::new(100),
+            mirror_in_database: HashMap::new(),
+            lru_mirror_in_database: lru::LruCache::new(100),
             unreachable: lru::LruCache::new(100),
             milestones_data: HashMap::new(),
             collector_handles: self.collector_handles.unwrap(),
@@ -223,6 +306,14 @@ impl Builder for SolidifierBuilder {
             gap_start: self.gap_start.unwrap(),
             expected: 0,
             retries: self.retries.unwrap_or(100),
+            sync_write_consistency: self.sync_write_consistency.unwrap_or_default(),
+            solidification_timeout: self.solidification_timeout.unwrap_or_default(),
+            expected_timeout: self
+                .expected_timeout_secs
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs),
+            // the broker analytics are shared through the broker handle once the solidifier is initialized
+            analytics: BrokerAnalytics::default(),
             handle: self.handle.unwrap(),
             inbox: self.inbox.unwrap(),
         }
@@ -272,6 +363,8 @@ pub struct AtomicSolidifierHandle {
     pub(crate) milestone_index: u32,
     pub(crate) message_id: MessageId,
     pub(crate) any_error: std::sync::atomic::AtomicBool,
+    /// The mirror keyspace of the writes, if any
+    pub(crate) mirror: Option<String>,
 }
 impl AtomicSolidifierHandle {
     /// Create a new Atomic solidifier handle
@@ -286,8 +379,14 @@ impl AtomicSolidifierHandle {
             milestone_index,
             message_id,
             any_error,
+            mirror: None,
         }
     }
+    /// Report the writes as the ones of the mirror keyspace
+    pub fn mirrored(mut self, keyspace: String) -> Self {
+        self.mirror.replace(keyspace);
+        self
+    }
 }
 impl<S: Insert<K, V>, K, V> AtomicSolidifierWorker<S, K, V>
 where
@@ -363,7 +462,7 @@ where
 
 impl Drop for AtomicSolidifierHandle {
     fn drop(&mut self) {
-        let cql_result = CqlResult::PersistedMsg(self.message_id, self.milestone_index);
+        let cql_result = CqlResult::PersistedMsg(self.message_id, self.milestone_index).mirrored(self.mirror.take());
         let any_error = self.any_error.load(Ordering::Relaxed);
         if any_error {
             self.handle.send(SolidifierEvent::CqlResult(Err(cql_result))).ok();
@@ -388,6 +487,10 @@ where
     key: K,
     value: V,
     retries: u16,
+    /// The consistency of the write
+    consistency: WriteConsistency,
+    /// The mirror keyspace of the write, if any
+    mirror: Option<String>,
 }
 
 impl<S: Insert<K, V>, K, V> SyncedMilestoneWorker<S, K, V>
@@ -405,8 +508,20 @@ where
             key,
             value,
             retries,
+            consistency: WriteConsistency::default(),
+            mirror: None,
         }
     }
+    /// Set the consistency of the write
+    pub fn with_consistency(mut self: Box<Self>, consistency: WriteConsistency) -> Box<Self> {
+        self.consistency = consistency;
+        self
+    }
+    /// Report the write as the one of the mirror keyspace
+    pub fn with_mirror(mut self: Box<Self>, keyspace: String) -> Box<Self> {
+        self.mirror.replace(keyspace);
+        self
+    }
     /// Create a new boxed solidifier worker with a handle and retries
     pub fn boxed(
         handle: SolidifierHandle,
@@ -428,7 +543,7 @@ where
 {
     fn handle_response(self: Box<Self>, giveload: Vec<u8>) -> anyhow::Result<()> {
         Decoder::try_from(giveload).and_then(|decoder| decoder.get_void())?;
-        let synced_ms = CqlResult::SyncedMilestone(self.milestone_index);
+        let synced_ms = CqlResult::SyncedMilestone(self.milestone_index).mirrored(self.mirror);
         let _ = self.handle.send(SolidifierEvent::CqlResult(Ok(synced_ms)));
         Ok(())
     }
@@ -453,7 +568,7 @@ where
             match self
                 .keyspace
                 .insert_query(&self.key, &self.value)
-                .consistency(Consistency::One)
+                .consistency(write_consistency(self.consistency))
                 .build()
             {
                 Ok(req) => {
@@ -466,7 +581,7 @@ where
         } else {
             // no more retries
             // respond with error
-            let synced_ms = CqlResult::SyncedMilestone(self.milestone_index);
+            let synced_ms = CqlResult::SyncedMilestone(self.milestone_index).mirrored(self.mirror.take());
             let _ = self.handle.send(SolidifierEvent::CqlResult(Err(synced_ms)));
         }
         Ok(())
@@ -487,6 +602,8 @@ where
     key: K,
     value: V,
     retries: u16,
+    /// The mirror keyspace of the write, if any
+    mirror: Option<String>,
 }
 
 impl<S: Insert<K, V>, K, V> AnalyzedMilestoneWorker<S, K, V>
@@ -504,6 +621,7 @@ where
             key,
             value,
             retries,
+            mirror: None,
         }
     }
     /// Create a new boxed solidifier worker with a handle and retries
@@ -517,6 +635,11 @@ where
     ) -> Box<Self> {
         Box::new(Self::new(handle, milestone_index, keyspace, key, value, retries))
     }
+    /// Report the write as the one of the mirror keyspace
+    pub fn with_mirror(mut self: Box<Self>, keyspace: String) -> Box<Self> {
+        self.mirror.replace(keyspace);
+        self
+    }
 }
 
 impl<S, K, V> Worker for AnalyzedMilestoneWorker<S, K, V>
@@ -527,7 +650,7 @@ where
 {
     fn handle_response(self: Box<Self>, giveload: Vec<u8>) -> anyhow::Result<()> {
         Decoder::try_from(giveload).and_then(|decoder| decoder.get_void())?;
-        let analyzed_ms = CqlResult::AnalyzedMilestone(self.milestone_index);
+        let analyzed_ms = CqlResult::AnalyzedMilestone(self.milestone_index).mirrored(self.mirror);
         let _ = self.handle.send(SolidifierEvent::CqlResult(Ok(analyzed_ms)));
         Ok(())
     }
@@ -565,9 +688,323 @@ where
         } else {
             // no more retries
             // respond with error
-            let analyzed_ms = CqlResult::AnalyzedMilestone(self.milestone_index);
+            let analyzed_ms = CqlResult::AnalyzedMilestone(self.milestone_index).mirrored(self.mirror.take());
             let _ = self.handle.send(SolidifierEvent::CqlResult(Err(analyzed_ms)));
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collector::FeedLimiter;
+    use bee_message::prelude::MilestoneIndex;
+    use chronicle_common::{
+        config::{
+            BrokerConfig,
+            WriteConsistency,
+        },
+        Synckey,
+    };
+    use scylla_rs::prelude::Request;
+
+    #[test]
+    fn retries_per_query_reach_sync_worker() {
+        let broker_config = BrokerConfig {
+            retries_per_query: 7,
+            ..Default::default()
+        };
+        let (syncer_tx, _syncer_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let solidifier = SolidifierBuilder::new()
+            .collector_count(1)
+            .collector_handles(HashMap::new())
+            .syncer_handle(SyncerHandle { tx: syncer_tx })
+            .gap_start(1)
+            .retries(broker_config.retries_per_query as u16)
+            .keyspace(ChronicleKeyspace::new("permanode".to_string()))
+            .handle(SolidifierHandle { tx })
+            .inbox(SolidifierInbox { rx })
+            .partition_id(0)
+            .build();
+        let synced_record = SyncRecord::new(MilestoneIndex(1), Some(0), None);
+        let worker = solidifier.synced_milestone_worker(1, synced_record);
+        assert_eq!(worker.retries, 7);
+    }
+
+    #[test]
+    fn sync_record_write_consistency() {
+        let keyspace = ChronicleKeyspace::new("permanode".to_string());
+        let synced_record = SyncRecord::new(MilestoneIndex(1), Some(0), None);
+        for (consistency, expected) in vec![
+            (WriteConsistency::LocalQuorum, Consistency::LocalQuorum),
+            (WriteConsistency::Quorum, Consistency::Quorum),
+            (WriteConsistency::One, Consistency::One),
+        ] {
+            let (syncer_tx, _syncer_rx) = tokio::sync::mpsc::unbounded_channel();
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            let solidifier = SolidifierBuilder::new()
+                .collector_count(1)
+                .collector_handles(HashMap::new())
+                .syncer_handle(SyncerHandle { tx: syncer_tx })
+                .gap_start(1)
+                .sync_write_consistency(consistency)
+                .keyspace(keyspace.clone())
+                .handle(SolidifierHandle { tx })
+                .inbox(SolidifierInbox { rx })
+                .partition_id(0)
+                .build();
+            let request = solidifier.sync_record_request(&Synckey, &synced_record).unwrap();
+            let expected_request = keyspace
+                .insert(&Synckey, &synced_record)
+                .consistency(expected)
+                .build()
+                .unwrap();
+            assert_eq!(request.payload(), expected_request.payload());
+            // the retried writes keep the consistency
+            let worker = solidifier.synced_milestone_worker(1, synced_record.clone());
+            assert_eq!(worker.consistency, consistency);
+        }
+    }
+
+    #[test]
+    fn sync_record_row_ttl() {
+        let keyspace = ChronicleKeyspace::new("permanode".to_string());
+        let synced_record = SyncRecord::new(MilestoneIndex(1), Some(0), None);
+        let (syncer_tx, _syncer_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let solidifier = SolidifierBuilder::new()
+            .collector_count(1)
+            .collector_handles(HashMap::new())
+            .syncer_handle(SyncerHandle { tx: syncer_tx })
+            .gap_start(1)
+            .keyspace(keyspace.clone().with_row_ttl(Some(3600)))
+            .handle(SolidifierHandle { tx })
+            .inbox(SolidifierInbox { rx })
+            .partition_id(0)
+            .build();
+        let request = solidifier.sync_record_request(&Synckey, &synced_record).unwrap();
+        let build = |keyspace: &ChronicleKeyspace| {
+            keyspace
+                .insert(&Synckey, &synced_record)
+                .consistency(Consistency::One)
+                .build()
+                .unwrap()
+        };
+        // the sync record expires along with the milestone data
+        assert_eq!(
+            request.payload(),
+            build(&keyspace.clone().with_row_ttl(Some(3600))).payload()
+        );
+        assert_ne!(request.payload(), build(&keyspace).payload());
+    }
+
+    #[test]
+    fn switched_keyspace_writes() {
+        let (syncer_tx, _syncer_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut solidifier = SolidifierBuilder::new()
+            .collector_count(1)
+            .collector_handles(HashMap::new())
+            .syncer_handle(SyncerHandle { tx: syncer_tx })
+            .gap_start(1)
+            .keyspace(ChronicleKeyspace::new("permanode".to_string()))
+            .handle(SolidifierHandle { tx })
+            .inbox(SolidifierInbox { rx })
+            .partition_id(0)
+            .build();
+        let in_flight = solidifier.synced_milestone_worker(1, SyncRecord::new(MilestoneIndex(1), Some(0), None));
+        solidifier.switch_keyspace(ChronicleKeyspace::new("permanode_v2".to_string()));
+        let worker = solidifier.synced_milestone_worker(2, SyncRecord::new(MilestoneIndex(2), Some(0), None));
+        assert_eq!(worker.keyspace.name(), "permanode_v2");
+        // the in-flight write completes in the previous keyspace
+        assert_eq!(in_flight.keyspace.name(), "permanode");
+    }
+
+    #[test]
+    fn mirrored_milestone_writes() {
+        use crate::types::tests::message;
+        let (syncer_tx, _syncer_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut solidifier = SolidifierBuilder::new()
+            .collector_count(1)
+            .collector_handles(HashMap::new())
+            .syncer_handle(SyncerHandle { tx: syncer_tx })
+            .gap_start(1)
+            .keyspace(ChronicleKeyspace::new("permanode".to_string()))
+            .mirror_keyspaces(vec![ChronicleKeyspace::new("permanode_mirror".to_string())])
+            .handle(SolidifierHandle { tx })
+            .inbox(SolidifierInbox { rx })
+            .partition_id(0)
+            .build();
+        let mirror = || "permanode_mirror".to_string();
+        let first = message(vec![MessageId::new([0; 32])], 0);
+        let second = message(vec![*first.message_id()], 0);
+        let (first_id, second_id) = (*first.message_id(), *second.message_id());
+        let mut milestone_data = MilestoneData::new(1, CreatedBy::Incoming);
+        milestone_data.add_full_message(first);
+        milestone_data.add_full_message(second);
+        // the default keyspace confirms all the writes of the milestone
+        let mut in_database = InDatabase::from(&milestone_data);
+        // the source of the milestone data is persisted with the sync record
+        assert_eq!(in_database.created_by, Some(CreatedBy::Incoming));
+        in_database.add_message_id(first_id);
+        in_database.add_message_id(second_id);
+        in_database.set_analyzed(true);
+        assert!(in_database.check_if_all_in_database());
+        solidifier.in_database.insert(1, in_database);
+        // while the mirror keyspace confirmed a single message so far
+        assert!(!solidifier.mirror_persisted(mirror(), first_id, 1));
+        assert!(solidifier.mirror_messages_len(&milestone_data).is_empty());
+        assert!(!solidifier.mirror_analyzed(mirror(), 1));
+        // the milestone reaches the mirror keyspace once its last write is confirmed there
+        assert!(solidifier.mirror_persisted(mirror(), second_id, 1));
+        assert!(solidifier
+            .mirror_sync_record_request(&mirror(), 1, Some(CreatedBy::Incoming))
+            .is_ok());
+        assert!(solidifier.mirror_sync_record_request("permanode", 1, None).is_err());
+
+        // a failed mirror write stops tracking the milestone in the mirror keyspace only
+        assert!(!solidifier.mirror_persisted(mirror(), first_id, 2));
+        solidifier.mirror_failed(mirror(), 2);
+        assert!(!solidifier.mirror_persisted(mirror(), second_id, 2));
+        assert!(!solidifier.mirror_in_database.contains_key(&(mirror(), 2)));
+        let mut milestone_data = MilestoneData::new(2, CreatedBy::Incoming);
+        milestone_data.add_full_message(message(vec![MessageId::new([0; 32])], 0));
+        assert!(solidifier.mirror_messages_len(&milestone_data).is_empty());
+        assert!(!solidifier.mirror_in_database.contains_key(&(mirror(), 2)));
+        assert!(solidifier.in_database.contains_key(&1));
+    }
+
+    #[test]
+    fn solidification_latency_sample() {
+        let (syncer_tx, _syncer_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let solidifier = SolidifierBuilder::new()
+            .collector_count(1)
+            .collector_handles(HashMap::new())
+            .syncer_handle(SyncerHandle { tx: syncer_tx })
+            .gap_start(1)
+            .keyspace(ChronicleKeyspace::new("solidification_latency".to_string()))
+            .handle(SolidifierHandle { tx })
+            .inbox(SolidifierInbox { rx })
+            .partition_id(0)
+            .build();
+        let histogram = SOLIDIFICATION_LATENCY.with_label_values(&["solidification_latency", "incoming"]);
+        let ms_data = MilestoneData::new(5, CreatedBy::Incoming);
+        // the milestone data solidified 5 seconds after it was first seen
+        solidifier.record_solidification_latency(&ms_data, ms_data.created_at + Duration::from_secs(5));
+        assert_eq!(histogram.get_sample_count(), 1);
+        assert_eq!(histogram.get_sample_sum(), 5.0);
+        assert_eq!(
+            SOLIDIFICATION_LATENCY
+                .with_label_values(&["solidification_latency", "syncer"])
+                .get_sample_count(),
+            0
+        );
+    }
+
+    #[test]
+    fn solidification_timeout_policy() {
+        let solidifier = |policy| {
+            let (syncer_tx, syncer_rx) = tokio::sync::mpsc::unbounded_channel();
+            let (archiver_tx, archiver_rx) = tokio::sync::mpsc::unbounded_channel();
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            let solidifier = SolidifierBuilder::new()
+                .collector_count(1)
+                .collector_handles(HashMap::new())
+                .syncer_handle(SyncerHandle { tx: syncer_tx })
+                .archiver_handle(ArchiverHandle { tx: archiver_tx })
+                .gap_start(1)
+                .solidification_timeout(SolidificationTimeoutConfig {
+                    timeout_secs: 60,
+                    policy,
+                })
+                .keyspace(ChronicleKeyspace::new("permanode".to_string()))
+                .handle(SolidifierHandle { tx })
+                .inbox(SolidifierInbox { rx })
+                .partition_id(0)
+                .build();
+            (solidifier, syncer_rx, archiver_rx)
+        };
+        let partial = |milestone_index, created_by| {
+            let mut ms_data = MilestoneData::new(milestone_index, created_by);
+            ms_data.pending.insert(MessageId::new([1; 32]), ());
+            ms_data
+        };
+
+        let (mut solidifier, _syncer_rx, mut archiver_rx) = solidifier(SolidificationTimeoutPolicy::Persist);
+        let ms_data = partial(5, CreatedBy::Incoming);
+        let created_at = ms_data.created_at;
+        solidifier.milestones_data.insert(5, ms_data);
+        // not yet timed out
+        solidifier.handle_solidification_timeout(created_at + Duration::from_secs(30));
+        assert!(solidifier.milestones_data.contains_key(&5));
+        assert!(archiver_rx.try_recv().is_err());
+        let timeouts = SOLIDIFICATION_TIMEOUTS.with_label_values(&["permanode"]).get();
+        solidifier.handle_solidification_timeout(created_at + Duration::from_secs(60));
+        assert!(solidifier.milestones_data.is_empty());
+        assert!(SOLIDIFICATION_TIMEOUTS.with_label_values(&["permanode"]).get() > timeouts);
+        match archiver_rx.try_recv() {
+            Ok(ArchiverEvent::MilestoneData(ms_data, None)) => {
+                assert_eq!(ms_data.milestone_index(), 5);
+                assert!(ms_data.pending().contains_key(&MessageId::new([1; 32])));
+            }
+            _ => panic!("Expected the partial milestone data to be archived"),
+        }
+
+        let (mut solidifier, mut syncer_rx, mut archiver_rx) = solidifier(SolidificationTimeoutPolicy::Drop);
+        solidifier.milestones_data.insert(6, partial(6, CreatedBy::Incoming));
+        solidifier.milestones_data.insert(7, partial(7, CreatedBy::Syncer));
+        solidifier.handle_solidification_timeout(Instant::now() + Duration::from_secs(60));
+        assert!(solidifier.milestones_data.is_empty());
+        assert!(archiver_rx.try_recv().is_err());
+        assert!(matches!(syncer_rx.try_recv(), Ok(SyncerEvent::Unreachable(7))));
+        assert!(syncer_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn expected_timeout_requests_the_milestone() {
+        let (syncer_tx, _syncer_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (collector_tx, mut collector_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let collector_handle = CollectorHandle {
+            tx: collector_tx,
+            requesters_senders: Vec::new(),
+            feed_limiter: FeedLimiter::new(&Default::default(), "permanode"),
+        };
+        let mut solidifier = SolidifierBuilder::new()
+            .collector_count(1)
+            .collector_handles(vec![(0, collector_handle)].into_iter().collect())
+            .syncer_handle(SyncerHandle { tx: syncer_tx })
+            .gap_start(1)
+            .expected_timeout_secs(30)
+            .keyspace(ChronicleKeyspace::new("permanode".to_string()))
+            .handle(SolidifierHandle { tx })
+            .inbox(SolidifierInbox { rx })
+            .partition_id(0)
+            .build();
+        let ms_data = MilestoneData::new(5, CreatedBy::Expected);
+        let created_at = ms_data.created_at;
+        solidifier.milestones_data.insert(5, ms_data);
+        // the incoming milestone data are left to the feed
+        solidifier
+            .milestones_data
+            .insert(6, MilestoneData::new(6, CreatedBy::Incoming));
+
+        // not yet timed out
+        solidifier.handle_expected_timeout(created_at + Duration::from_secs(10));
+        assert!(collector_rx.try_recv().is_err());
+        // the expected milestone did not arrive, so it's requested, while its milestone data is kept
+        solidifier.handle_expected_timeout(created_at + Duration::from_secs(30));
+        assert!(matches!(
+            collector_rx.try_recv(),
+            Ok(CollectorEvent::Ask(AskCollector::MilestoneMessage(5)))
+        ));
+        assert!(collector_rx.try_recv().is_err());
+        assert_eq!(solidifier.milestones_data[&5].created_by(), &CreatedBy::Expected);
+        // the solidification timeout still bounds it, which leaves it as a gap for the syncer
+        assert!(solidifier.milestones_data.contains_key(&5));
+    }
+}
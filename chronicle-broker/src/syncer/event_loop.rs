@@ -16,7 +16,7 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Syncer {
             match event {
                 SyncerEvent::Ask(ask) => {
                     // Don't accept ask events when there is something already in progress.
-                    if let None = self.active {
+                    if self.workers.is_empty() {
                         match ask {
                             AskSyncer::Complete => {
                                 if !self.highest.eq(&0) {
@@ -40,19 +40,39 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Syncer {
                     } else {
                         error!(
                             "Cannot accept Ask request: {:?}, while processing: {:?}",
-                            &ask, self.active
+                            &ask,
+                            self.active()
                         );
                     }
                 }
                 SyncerEvent::MilestoneData(milestone_data) => {
+                    let eof = self.eof;
                     self.handle_milestone_data(milestone_data).await;
+                    self.report_session(eof, _supervisor);
                 }
                 SyncerEvent::Unreachable(milestone_index) => {
-                    self.pending -= 1;
+                    let eof = self.eof;
                     // This happens when all the peers don't have the requested milestone_index
                     error!("Syncer unable to reach milestone_index: {}", milestone_index);
-                    self.handle_skip();
+                    if let Some(worker) = self.workers.iter_mut().find(|worker| worker.contains(milestone_index)) {
+                        worker.pending -= 1;
+                        worker.handle_skip();
+                    } else if self.highest.eq(&0) {
+                        self.pending -= 1;
+                    }
                     self.trigger_process_more();
+                    self.report_session(eof, _supervisor);
+                }
+                SyncerEvent::Refresh => {
+                    if let Some(sync_range) = self.sync_range {
+                        let keyspace = self.keyspace.clone();
+                        self.refresh_sync(|| async move { fetch_sync_data(&keyspace, &sync_range).await })
+                            .await;
+                    }
+                }
+                SyncerEvent::SwitchKeyspace(keyspace) => {
+                    self.keyspace = keyspace;
+                    let _ = self.handle.send(SyncerEvent::Refresh);
                 }
                 SyncerEvent::Shutdown => break,
             }
@@ -62,11 +82,22 @@ impl<H: ChronicleBrokerScope> EventLoop<BrokerHandle<H>> for Syncer {
 }
 
 impl Syncer {
+    /// Keep the broker up to date with the sync progress
+    fn report_session<H: ChronicleBrokerScope>(&self, was_eof: bool, supervisor: &Option<BrokerHandle<H>>) {
+        let session = if !was_eof && self.eof {
+            Some(SyncerSession::Finish)
+        } else {
+            self.session()
+        };
+        if let (Some(session), Some(supervisor)) = (session, supervisor.as_ref()) {
+            supervisor.send(BrokerEvent::Syncer(session)).ok();
+        }
+    }
     async fn update_sync(&mut self) {
         if self.eof {
             if let Some(sync_range) = self.sync_range.as_ref() {
                 // try to fetch and update sync_data
-                if let Ok(sync_data) = SyncData::try_fetch(&self.keyspace, sync_range, 10).await {
+                if let Ok(sync_data) = fetch_sync_data(&self.keyspace, sync_range).await {
                     info!("Updated the sync data");
                     self.sync_data = sync_data;
                     self.eof = false;
@@ -78,9 +109,77 @@ impl Syncer {
         }
     }
 
+    /// Replace the sync data with the fetched one and reschedule the work from it. While ranges are active, their
+    /// milestones would be requested twice, so the refresh is deferred till they are done.
+    pub(crate) async fn refresh_sync<F, Fut>(&mut self, fetch: F)
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<SyncData>>,
+    {
+        if !self.workers.is_empty() {
+            info!(
+                "Deferring the sync data refresh till the active ranges: {:?} are done",
+                self.active()
+            );
+            self.refresh_requested = true;
+            return;
+        }
+        self.refresh_requested = false;
+        match fetch().await {
+            Ok(sync_data) => {
+                info!("Refreshed the sync data");
+                self.sync_data = sync_data;
+                // before the first milestones data the first ask is still awaiting, and it will use the fresh one
+                if !self.highest.eq(&0) {
+                    self.eof = false;
+                    self.complete_or_fillgaps();
+                }
+            }
+            Err(e) => {
+                error!("Unable to refresh the sync data: {}", e);
+            }
+        }
+    }
+
     pub(crate) async fn handle_milestone_data(&mut self, milestone_data: MilestoneData) {
-        self.pending -= 1;
-        self.milestones_data.push(Ascending::new(milestone_data));
+        if self.highest.eq(&0) {
+            self.pending -= 1;
+            self.milestones_data.push(Ascending::new(milestone_data));
+        } else {
+            let milestone_index = milestone_data.milestone_index();
+            if let Some(worker) = self.workers.iter_mut().find(|worker| worker.contains(milestone_index)) {
+                worker.pending -= 1;
+                worker.synced += 1;
+                worker.milestones_data.push(Ascending::new(milestone_data));
+                if !worker.skip {
+                    worker.schedule(1, &self.solidifier_handles);
+                    let upper_ms_limit = Some(worker.end);
+                    // check if we could send the next expected milestone_index
+                    while let Some(ms_data) = worker.milestones_data.pop() {
+                        let ms_index = ms_data.milestone_index();
+                        if worker.next.eq(&ms_index) {
+                            // push it to archiver
+                            if let Some(archiver_handle) = self.archiver_handle.as_ref() {
+                                let _ = archiver_handle
+                                    .send(ArchiverEvent::MilestoneData(ms_data.into_inner(), upper_ms_limit));
+                            }
+                            worker.next += 1;
+                        } else {
+                            // put it back and then break
+                            worker.milestones_data.push(ms_data);
+                            break;
+                        }
+                    }
+                } else {
+                    worker.handle_skip();
+                }
+            } else {
+                warn!(
+                    "Dropping milestone data: {}, which doesn't belong to any active range",
+                    milestone_index
+                );
+            }
+        }
         if self.highest.eq(&0) && self.pending.eq(&0) {
             // these are the first milestones data, which we didn't even request it.
             let milestone_data = self.milestones_data.pop().unwrap().into_inner();
@@ -115,55 +214,9 @@ impl Syncer {
             let _ = self.try_send_to_archiver(ArchiverEvent::Close(next));
             // set the first ask request
             self.complete_or_fillgaps();
-        } else if !self.highest.eq(&0) && !self.skip {
-            self.try_solidify_one_more();
-            let upper_ms_limit = Some(self.initial_gap_end);
-            // check if we could send the next expected milestone_index
-            while let Some(ms_data) = self.milestones_data.pop() {
-                let ms_index = ms_data.milestone_index();
-                if self.next.eq(&ms_index) {
-                    // push it to archiver
-                    let _ =
-                        self.try_send_to_archiver(ArchiverEvent::MilestoneData(ms_data.into_inner(), upper_ms_limit));
-                    self.next += 1;
-                } else {
-                    // put it back and then break
-                    self.milestones_data.push(ms_data);
-                    break;
-                }
-            }
-        } else if self.skip {
-            self.handle_skip();
-            // close the current file
         }
-        // check if pending is zero which is an indicator that all milestones_data
-        // has been processed, in order to move further
         self.trigger_process_more();
     }
-    pub(crate) fn handle_skip(&mut self) {
-        self.skip = true;
-        // we should skip/drop the current active slot but only when pending == 0
-        if self.pending.eq(&0) {
-            while let Some(d) = self.milestones_data.pop() {
-                let d = d.into_inner();
-                error!("We got milestone data for index: {}, but we're skipping it due to previous unreachable indexex within the same gap range", d.milestone_index());
-            }
-            match self.active.as_mut().unwrap() {
-                Active::Complete(ref mut range) => {
-                    error!("Complete: Skipping the remaining gap range: {:?}", range);
-                    // we just consume the range in order for the trigger_process_more to move further
-                    while let Some(_) = range.next() {}
-                }
-                Active::FillGaps(ref mut range) => {
-                    error!("FillGaps: Skipping the remaining gap range: {:?}", range);
-                    // we just consume the range in order for the trigger_process_more to move further
-                    while let Some(_) = range.next() {}
-                }
-            };
-            // reset skip back to false
-            self.skip = false;
-        }
-    }
     fn complete_or_fillgaps(&mut self) {
         match self.first_ask.as_ref() {
             Some(AskSyncer::Complete) => {
@@ -178,84 +231,58 @@ impl Syncer {
             let _ = archiver_handle.send(archiver_event);
         }
     }
-    fn close_log_file(&mut self) {
-        let created_log_file = self.initial_gap_start != self.next;
-        if self.prev_closed_log_filename != self.initial_gap_start && created_log_file {
+    fn close_log_file(&mut self, worker: &GapWorker) {
+        let created_log_file = worker.start != worker.next;
+        if self.prev_closed_log_filename != worker.start && created_log_file {
             if let Some(archiver_handle) = self.archiver_handle.as_ref() {
                 info!(
                     "Informing Archiver to close {}.part, and should be renamed to: {}to{}.log",
-                    self.initial_gap_start, self.initial_gap_start, self.next
+                    worker.start, worker.start, worker.next
                 );
-                // We should close any part file related to the current gap
-                let _ = archiver_handle.send(ArchiverEvent::Close(self.next));
+                // We should close any part file related to the gap
+                let _ = archiver_handle.send(ArchiverEvent::Close(worker.next));
             };
-            self.prev_closed_log_filename = self.initial_gap_start;
+            self.prev_closed_log_filename = worker.start;
         } else {
             self.prev_closed_log_filename = 0;
         }
     }
-    fn try_solidify_one_more(&mut self) {
-        match self.active.as_mut().unwrap() {
-            Active::Complete(ref mut range) => {
-                if let Some(milestone_index) = range.next() {
-                    Self::request_solidify(self.solidifier_count, &self.solidifier_handles, milestone_index);
-                    self.pending += 1;
-                }
-            }
-            Active::FillGaps(ref mut range) => {
-                if let Some(milestone_index) = range.next() {
-                    Self::request_solidify(self.solidifier_count, &self.solidifier_handles, milestone_index);
-                    self.pending += 1;
-                }
-            }
-        };
-    }
     pub(crate) fn process_more(&mut self) {
-        if let Some(ref mut active) = self.active {
-            match active {
-                Active::Complete(range) => {
-                    for _ in 0..self.parallelism {
-                        if let Some(milestone_index) = range.next() {
-                            Self::request_solidify(self.solidifier_count, &self.solidifier_handles, milestone_index);
-                            // update pending
-                            self.pending += 1;
-                        } else {
-                            // move to next gap (only if pending is zero)
-                            if self.pending.eq(&0) {
-                                // We should close any part file related to the current(above finished range) gap
-                                self.close_log_file();
-                                // Finished the current active range, therefore we drop it
-                                self.active.take();
-                                self.complete();
-                            }
-                            break;
-                        }
-                    }
+        let mut refill = None;
+        let mut i = 0;
+        while i < self.workers.len() {
+            let worker = &mut self.workers[i];
+            // move further only once the in flight requests of the range are processed
+            if worker.pending.eq(&0) {
+                let exhausted = worker.schedule(self.parallelism as u32, &self.solidifier_handles);
+                if exhausted && worker.pending.eq(&0) {
+                    // Finished the active range, therefore we drop it
+                    let worker = self.workers.remove(i);
+                    // We should close any part file related to the finished range
+                    self.close_log_file(&worker);
+                    refill = Some(match worker.active {
+                        Active::Complete(_) => AskSyncer::Complete,
+                        Active::FillGaps(_) => AskSyncer::FillGaps,
+                    });
+                    continue;
                 }
-                Active::FillGaps(range) => {
-                    for _ in 0..self.parallelism {
-                        if let Some(milestone_index) = range.next() {
-                            Self::request_solidify(self.solidifier_count, &self.solidifier_handles, milestone_index);
-                            // update pending
-                            self.pending += 1;
-                        } else {
-                            // move to next gap (only if pending is zero)
-                            if self.pending.eq(&0) {
-                                // We should close any part file related to the current(above finished range) gap
-                                self.close_log_file();
-                                // Finished the current active range, therefore we drop it
-                                self.active.take();
-                                self.fill_gaps();
-                            }
-                            break;
-                        }
-                    }
+            }
+            i += 1;
+        }
+        // move to the next gaps
+        match refill {
+            Some(AskSyncer::Complete) => self.complete(),
+            Some(_) => self.fill_gaps(),
+            None if self.workers.is_empty() => {
+                self.eof = true;
+                info!("SyncData reached EOF");
+                if self.refresh_requested {
+                    let _ = self.handle.send(SyncerEvent::Refresh);
+                } else {
+                    self.schedule_update_sync_data();
                 }
             }
-        } else {
-            self.eof = true;
-            info!("SyncData reached EOF");
-            self.schedule_update_sync_data();
+            None => (),
         }
     }
     fn schedule_update_sync_data(&self) {
@@ -269,93 +296,63 @@ impl Syncer {
         };
         tokio::spawn(update_sync);
     }
-    fn request_solidify(
-        solidifier_count: u8,
-        solidifier_handles: &HashMap<u8, SolidifierHandle>,
-        milestone_index: u32,
-    ) {
-        let solidifier_id = (milestone_index % (solidifier_count as u32)) as u8;
-        let solidifier_handle = solidifier_handles.get(&solidifier_id).unwrap();
-        let solidify_event = SolidifierEvent::Solidify(Ok(milestone_index));
-        let _ = solidifier_handle.send(solidify_event);
-    }
     fn trigger_process_more(&mut self) {
-        // move to next range (only if pending is zero)
+        // move further only once the first observed milestones data are processed
         if self.pending.eq(&0) {
             // start processing it
             self.process_more();
         }
     }
     pub(crate) fn complete(&mut self) {
-        // start from the lowest uncomplete
-        if let Some(mut gap) = self.sync_data.take_lowest_uncomplete() {
-            // ensure gap.end != i32::MAX
-            if !gap.end.eq(&(i32::MAX as u32)) {
-                info!("Completing the gap {:?}", gap);
-                // set next to be the start
-                self.next = gap.start;
-                self.initial_gap_start = self.next;
-                self.initial_gap_end = gap.end;
-                self.active.replace(Active::Complete(gap));
-                self.trigger_process_more();
-            } else {
-                // fill this with the gap.start up to self.highest
-                // this is the last gap in our sync data
-                // First we ensure highest is larger than gap.start
-                if self.highest > gap.start {
-                    // set next to be the start
-                    self.next = gap.start;
-                    self.initial_gap_start = self.next;
-                    // update the end of the gap
+        // start from the lowest uncomplete ranges, up to the concurrency
+        while self.workers.len() < self.concurrency as usize {
+            if let Some(mut gap) = self.sync_data.take_lowest_uncomplete() {
+                // ensure gap.end != i32::MAX
+                if !gap.end.eq(&(i32::MAX as u32)) {
+                    info!("Completing the gap {:?}", gap);
+                    self.activate(Active::Complete(gap));
+                } else if self.highest > gap.start {
+                    // fill this with the gap.start up to self.highest
+                    // this is the last gap in our sync data
                     gap.end = self.highest;
-                    self.initial_gap_end = gap.end;
                     info!("Completing the last gap {:?}", gap);
-                    self.active.replace(Active::Complete(gap));
-                    self.trigger_process_more();
+                    self.activate(Active::Complete(gap));
                 } else {
-                    info!("There are no more gaps neither unlogged in the current sync data");
-                    self.trigger_process_more();
+                    break;
                 }
+            } else {
+                break;
             }
-        } else {
+        }
+        if self.workers.is_empty() {
             info!("There are no more gaps neither unlogged in the current sync data");
-            self.trigger_process_more();
         }
+        self.trigger_process_more();
     }
     pub(crate) fn fill_gaps(&mut self) {
-        // start from the lowest gap
-        if let Some(mut gap) = self.sync_data.take_lowest_gap() {
-            // ensure gap.end != i32::MAX
-            if !gap.end.eq(&(i32::MAX as u32)) {
-                info!("Filling the gap {:?}", gap);
-                // set next to be the start
-                self.next = gap.start;
-                self.initial_gap_start = self.next;
-                self.initial_gap_end = gap.end;
-                self.active.replace(Active::FillGaps(gap));
-                self.trigger_process_more();
-            } else {
-                // fill this with the gap.start up to self.highest
-                // this is the last gap in our sync data
-                // First we ensure highest is larger than gap.start
-                if self.highest > gap.start {
-                    info!("Filling the last gap {:?}", gap);
-                    // set next to be the start
-                    self.next = gap.start;
-                    self.initial_gap_start = self.next;
-                    // update the end of the gap
+        // start from the lowest gaps, up to the concurrency
+        while self.workers.len() < self.concurrency as usize {
+            if let Some(mut gap) = self.sync_data.take_lowest_gap() {
+                // ensure gap.end != i32::MAX
+                if !gap.end.eq(&(i32::MAX as u32)) {
+                    info!("Filling the gap {:?}", gap);
+                    self.activate(Active::FillGaps(gap));
+                } else if self.highest > gap.start {
+                    // fill this with the gap.start up to self.highest
+                    // this is the last gap in our sync data
                     gap.end = self.highest;
-                    self.initial_gap_end = gap.end;
-                    self.active.replace(Active::FillGaps(gap));
-                    self.trigger_process_more();
+                    info!("Filling the last gap {:?}", gap);
+                    self.activate(Active::FillGaps(gap));
                 } else {
-                    info!("There are no more gaps in the current sync data");
-                    self.trigger_process_more();
+                    break;
                 }
+            } else {
+                break;
             }
-        } else {
+        }
+        if self.workers.is_empty() {
             info!("There are no more gaps in the current sync data");
-            self.trigger_process_more();
         }
+        self.trigger_process_more();
     }
 }
@@ -6,7 +6,10 @@ use super::*;
 #[async_trait::async_trait]
 impl<H: ChronicleBrokerScope> Init<BrokerHandle<H>> for Syncer {
     async fn init(&mut self, status: Result<(), Need>, _supervisor: &mut Option<BrokerHandle<H>>) -> Result<(), Need> {
-        info!("Syncer is initializing with parallelism: {}", self.parallelism);
+        info!(
+            "Syncer is initializing with parallelism: {}, concurrency: {}",
+            self.parallelism, self.concurrency
+        );
         self.service.update_status(ServiceStatus::Initializing);
         let event = BrokerEvent::Children(BrokerChild::Syncer(self.service.clone(), Ok(())));
         let _ = _supervisor.as_mut().expect("Syncer expected BrokerHandle").send(event);
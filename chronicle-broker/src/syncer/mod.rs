@@ -14,24 +14,44 @@ use super::{
 use chronicle_common::Wrapper;
 use chronicle_storage::keyspaces::ChronicleKeyspace;
 use std::{
+    future::Future,
     ops::{
         Deref,
         DerefMut,
     },
-    time::Duration,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 use tokio::sync::oneshot::Sender;
 mod event_loop;
 mod init;
 mod terminating;
 
+/// Fetch the sync data of the sync range, completing it if its rows got truncated. The sync data which is still
+/// partial leaves the milestones below its lowest processed row unknown (neither synced nor gaps) till the next fetch
+pub(crate) async fn fetch_sync_data(keyspace: &ChronicleKeyspace, sync_range: &SyncRange) -> anyhow::Result<SyncData> {
+    let mut sync_data = SyncData::try_fetch(keyspace, sync_range, 10).await?;
+    if let Err(e) = sync_data.try_complete(keyspace, sync_range, 10).await {
+        warn!(
+            "Unable to complete the partial sync data, the milestones: {:?} are unknown till the next fetch, error: {}",
+            sync_data.unknown(sync_range),
+            e
+        );
+    }
+    Ok(sync_data)
+}
+
 // Syncer builder
 builder!(SyncerBuilder {
     sync_data: SyncData,
     update_sync_data_every: Duration,
     sync_range: SyncRange,
+    keyspace: ChronicleKeyspace,
     solidifier_handles: HashMap<u8, SolidifierHandle>,
     parallelism: u8,
+    concurrency: u8,
     archiver_handle: ArchiverHandle,
     first_ask: AskSyncer,
     oneshot: Sender<u32>,
@@ -47,6 +67,10 @@ pub enum SyncerEvent {
     MilestoneData(MilestoneData),
     /// Notify of an unreachable cluster
     Unreachable(u32),
+    /// Re-read the sync table right away and reschedule the work from the fresh sync data
+    Refresh,
+    /// Switch to the keyspace, whose sync table is then refreshed
+    SwitchKeyspace(ChronicleKeyspace),
     /// Shutdown the syncer
     Shutdown,
 }
@@ -121,20 +145,22 @@ pub struct Syncer {
     keyspace: ChronicleKeyspace,
     sync_range: Option<SyncRange>,
     solidifier_handles: HashMap<u8, SolidifierHandle>,
-    solidifier_count: u8,
     parallelism: u8,
-    active: Option<Active>,
+    /// The max number of ranges which are synced in parallel
+    concurrency: u8,
+    /// The ranges being synced, in ascending order
+    workers: Vec<GapWorker>,
     first_ask: Option<AskSyncer>,
     archiver_handle: Option<ArchiverHandle>,
+    /// The first observed milestones data
     milestones_data: std::collections::BinaryHeap<Ascending<MilestoneData>>,
     highest: u32,
+    /// The pending first observed milestones data
     pending: u32,
     eof: bool,
-    next: u32,
-    skip: bool,
-    initial_gap_start: u32,
-    initial_gap_end: u32,
     prev_closed_log_filename: u32,
+    /// A refresh was requested while ranges were active, so it's done once they are
+    refresh_requested: bool,
     oneshot: Option<Sender<u32>>,
     handle: SyncerHandle,
     inbox: SyncerInbox,
@@ -149,38 +175,27 @@ impl Builder for SyncerBuilder {
         let solidifier_handles = self.solidifier_handles.unwrap();
         let solidifier_count = solidifier_handles.len() as u8;
         let sync_data = self.sync_data.unwrap();
-        let config = chronicle_common::get_config();
-        let keyspace = ChronicleKeyspace::new(
-            config
-                .storage_config
-                .keyspaces
-                .first()
-                .and_then(|keyspace| Some(keyspace.name.clone()))
-                .unwrap_or("permanode".to_owned()),
-        );
+        let keyspace = self.keyspace.expect("Expected the syncer keyspace");
         Self::State {
             service: Service::new(),
             sync_data,
             solidifier_handles,
-            solidifier_count,
             sync_range: self.sync_range,
             keyspace,
             update_sync_data_every: self
                 .update_sync_data_every
                 .unwrap_or(std::time::Duration::from_secs(60 * 60)),
             parallelism: self.parallelism.unwrap_or(solidifier_count),
-            active: None,
+            concurrency: self.concurrency.unwrap_or(1).max(1),
+            workers: Vec::new(),
             first_ask: self.first_ask,
             archiver_handle: self.archiver_handle,
             milestones_data: std::collections::BinaryHeap::new(),
             highest: 0,
             pending: solidifier_count as u32,
-            next: 0,
             eof: false,
-            skip: false,
-            initial_gap_start: 0,
-            initial_gap_end: 0,
             prev_closed_log_filename: 0,
+            refresh_requested: false,
             oneshot: self.oneshot,
             handle: self.handle.unwrap(),
             inbox: self.inbox.unwrap(),
@@ -188,11 +203,160 @@ impl Builder for SyncerBuilder {
         .set_name()
     }
 }
+/// Requests the milestone data of a given milestone index from the network
+pub trait MilestoneRequester {
+    /// Request the milestone data of the given milestone index
+    fn request_milestone(&self, milestone_index: u32);
+}
+
+/// The solidifiers request the milestone (and its messages) from the collectors,
+/// which fetch them using the requesters' api endpoints and feed them back to the solidifiers
+impl MilestoneRequester for HashMap<u8, SolidifierHandle> {
+    fn request_milestone(&self, milestone_index: u32) {
+        let solidifier_id = (milestone_index % (self.len() as u32)) as u8;
+        if let Some(solidifier_handle) = self.get(&solidifier_id) {
+            let solidify_event = SolidifierEvent::Solidify(Ok(milestone_index));
+            let _ = solidifier_handle.send(solidify_event);
+        }
+    }
+}
+
+/// Request the next milestones of the gap range, without exceeding the max in-flight requests.
+/// Returns true once the gap range is exhausted.
+pub fn schedule_milestone_requests<R: MilestoneRequester>(
+    gap: &mut std::ops::Range<u32>,
+    in_flight: &mut u32,
+    max_in_flight: u32,
+    requester: &R,
+) -> bool {
+    while *in_flight < max_in_flight {
+        if let Some(milestone_index) = gap.next() {
+            requester.request_milestone(milestone_index);
+            *in_flight += 1;
+        } else {
+            return true;
+        }
+    }
+    gap.is_empty()
+}
+
 #[derive(Debug)]
 enum Active {
     Complete(std::ops::Range<u32>),
     FillGaps(std::ops::Range<u32>),
 }
+
+/// A range being synced, whose milestones data are archived in order into its own log file
+struct GapWorker {
+    /// The remaining milestones of the range to be requested
+    active: Active,
+    /// The bounds of the range
+    start: u32,
+    end: u32,
+    /// The next milestone index to be archived
+    next: u32,
+    /// The number of requested milestones which are not synced yet
+    pending: u32,
+    /// An unreachable milestone was hit, so the remaining range is skipped
+    skip: bool,
+    /// The synced milestones data, which await the lower ones to be archived
+    milestones_data: std::collections::BinaryHeap<Ascending<MilestoneData>>,
+    /// When the range started to be synced
+    since: Instant,
+    /// The number of synced milestones within the range
+    synced: u32,
+}
+
+impl GapWorker {
+    fn new(active: Active) -> Self {
+        let (start, end) = match &active {
+            Active::Complete(range) | Active::FillGaps(range) => (range.start, range.end),
+        };
+        Self {
+            active,
+            start,
+            end,
+            next: start,
+            pending: 0,
+            skip: false,
+            milestones_data: std::collections::BinaryHeap::new(),
+            since: Instant::now(),
+            synced: 0,
+        }
+    }
+    /// Check whether the milestone index belongs to the range
+    fn contains(&self, milestone_index: u32) -> bool {
+        (self.start..self.end).contains(&milestone_index)
+    }
+    /// Request the next milestones of the range, up to `parallelism` in flight.
+    /// Returns true once the range is exhausted.
+    fn schedule<R: MilestoneRequester>(&mut self, parallelism: u32, requester: &R) -> bool {
+        let max_in_flight = self.pending + parallelism;
+        match self.active {
+            Active::Complete(ref mut range) | Active::FillGaps(ref mut range) => {
+                schedule_milestone_requests(range, &mut self.pending, max_in_flight, requester)
+            }
+        }
+    }
+    fn handle_skip(&mut self) {
+        self.skip = true;
+        // we should skip/drop the remaining range but only when pending == 0
+        if self.pending.eq(&0) {
+            while let Some(d) = self.milestones_data.pop() {
+                let d = d.into_inner();
+                error!("We got milestone data for index: {}, but we're skipping it due to previous unreachable indexex within the same gap range", d.milestone_index());
+            }
+            match self.active {
+                Active::Complete(ref mut range) => {
+                    error!("Complete: Skipping the remaining gap range: {:?}", range);
+                    // we just consume the range in order for the process_more to move further
+                    while let Some(_) = range.next() {}
+                }
+                Active::FillGaps(ref mut range) => {
+                    error!("FillGaps: Skipping the remaining gap range: {:?}", range);
+                    // we just consume the range in order for the process_more to move further
+                    while let Some(_) = range.next() {}
+                }
+            };
+            // reset skip back to false
+            self.skip = false;
+        }
+    }
+}
+
+impl Syncer {
+    /// Activate the range to be synced
+    fn activate(&mut self, active: Active) {
+        self.workers.push(GapWorker::new(active));
+    }
+    /// The ranges being synced
+    fn active(&self) -> Vec<&Active> {
+        self.workers.iter().map(|worker| &worker.active).collect()
+    }
+    /// Build the syncer session of the lowest active range (if any), the other active ranges are counted as
+    /// remaining
+    fn session(&self) -> Option<SyncerSession> {
+        let worker = self.workers.first()?;
+        let remaining_gaps = self.workers.len() - 1
+            + match worker.active {
+                Active::Complete(_) => self.sync_data.gaps.len() + self.sync_data.synced_but_unlogged.len(),
+                Active::FillGaps(_) => self.sync_data.gaps.len(),
+            };
+        let elapsed = worker.since.elapsed().as_secs_f64();
+        let milestones_per_sec = if elapsed > 0.0 {
+            worker.synced as f64 / elapsed
+        } else {
+            0.0
+        };
+        Some(SyncerSession::Progress {
+            range: worker.start..worker.end,
+            synced: worker.synced,
+            milestones_per_sec,
+            remaining_gaps,
+        })
+    }
+}
+
 /// impl name of the Syncer
 impl Name for Syncer {
     fn set_name(mut self) -> Self {
@@ -260,3 +424,209 @@ impl std::cmp::PartialEq for Ascending<MilestoneData> {
     }
 }
 impl std::cmp::Eq for Ascending<MilestoneData> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct MockRequester {
+        requested: RefCell<Vec<u32>>,
+    }
+
+    impl MilestoneRequester for MockRequester {
+        fn request_milestone(&self, milestone_index: u32) {
+            self.requested.borrow_mut().push(milestone_index);
+        }
+    }
+
+    #[test]
+    fn gap_produces_bounded_milestone_requests() {
+        let mut sync_data = SyncData {
+            completed: vec![20..30, 1..10],
+            synced_but_unlogged: Vec::new(),
+            gaps: vec![30..i32::MAX as u32, 10..20],
+            partial: false,
+        };
+        let mut gap = sync_data.take_lowest_gap().unwrap();
+        let requester = MockRequester::default();
+        let mut in_flight = 0;
+        // only 4 requests are allowed to be in flight
+        assert!(!schedule_milestone_requests(&mut gap, &mut in_flight, 4, &requester));
+        assert_eq!(*requester.requested.borrow(), vec![10, 11, 12, 13]);
+        // two responses came back
+        in_flight -= 2;
+        assert!(!schedule_milestone_requests(&mut gap, &mut in_flight, 4, &requester));
+        assert_eq!(in_flight, 4);
+        // drain the remaining gap
+        in_flight = 0;
+        assert!(schedule_milestone_requests(&mut gap, &mut in_flight, 100, &requester));
+        assert_eq!(*requester.requested.borrow(), (10..20).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn refresh_reschedules_from_fresh_sync_data() {
+        let (solidifier_tx, mut solidifier_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut solidifier_handles = HashMap::new();
+        solidifier_handles.insert(0, SolidifierHandle { tx: solidifier_tx });
+        let mut syncer = SyncerBuilder::new()
+            .sync_data(SyncData::default())
+            .keyspace(ChronicleKeyspace::new("permanode".to_string()))
+            .solidifier_handles(solidifier_handles)
+            .parallelism(2)
+            .first_ask(AskSyncer::FillGaps)
+            .handle(SyncerHandle { tx })
+            .inbox(SyncerInbox { rx })
+            .build();
+        // the syncer went through its sync data and awaits the next update
+        syncer.highest = 100;
+        syncer.pending = 0;
+        syncer.eof = true;
+
+        let fetches = std::cell::Cell::new(0);
+        let sync_table = || {
+            fetches.set(fetches.get() + 1);
+            async {
+                Ok(SyncData {
+                    completed: vec![13..100],
+                    synced_but_unlogged: Vec::new(),
+                    gaps: vec![10..13],
+                    partial: false,
+                })
+            }
+        };
+        syncer.refresh_sync(sync_table).await;
+        assert_eq!(fetches.get(), 1);
+        assert!(!syncer.eof);
+        assert!(syncer.sync_data.gaps.is_empty());
+        assert!(matches!(syncer.active().first(), Some(Active::FillGaps(_))));
+        // the fresh gap is requested, bounded by the parallelism
+        for expected in 10..12 {
+            match solidifier_rx.try_recv() {
+                Ok(SolidifierEvent::Solidify(Ok(milestone_index))) => assert_eq!(milestone_index, expected),
+                _ => panic!("Expected a solidify request for milestone index: {}", expected),
+            }
+        }
+        assert!(solidifier_rx.try_recv().is_err());
+
+        // a refresh while the gap is active is deferred
+        syncer.refresh_sync(sync_table).await;
+        assert_eq!(fetches.get(), 1);
+        assert!(syncer.refresh_requested);
+    }
+
+    #[tokio::test]
+    async fn concurrent_workers_cover_every_gap() {
+        let (solidifier_tx, mut solidifier_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (archiver_tx, mut archiver_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut solidifier_handles = HashMap::new();
+        solidifier_handles.insert(0, SolidifierHandle { tx: solidifier_tx });
+        let gaps = vec![10..13, 20..25, 30..31, 40..44, 50..52];
+        let mut syncer = SyncerBuilder::new()
+            .sync_data(SyncData {
+                completed: vec![52..100, 44..50, 31..40, 25..30, 13..20, 1..10],
+                synced_but_unlogged: Vec::new(),
+                gaps: gaps.iter().rev().cloned().collect(),
+                partial: false,
+            })
+            .keyspace(ChronicleKeyspace::new("permanode".to_string()))
+            .solidifier_handles(solidifier_handles)
+            .archiver_handle(ArchiverHandle { tx: archiver_tx })
+            .parallelism(2)
+            .concurrency(3)
+            .first_ask(AskSyncer::FillGaps)
+            .handle(SyncerHandle { tx })
+            .inbox(SyncerInbox { rx })
+            .build();
+        syncer.highest = 100;
+        syncer.pending = 0;
+
+        syncer.fill_gaps();
+        assert_eq!(
+            syncer
+                .workers
+                .iter()
+                .map(|worker| worker.start..worker.end)
+                .collect::<Vec<_>>(),
+            vec![10..13, 20..25, 30..31]
+        );
+        // the solidifiers respond to the requests in order
+        let mut requested = Vec::new();
+        while let Ok(SolidifierEvent::Solidify(Ok(milestone_index))) = solidifier_rx.try_recv() {
+            requested.push(milestone_index);
+            syncer
+                .handle_milestone_data(MilestoneData::new(milestone_index, CreatedBy::Syncer))
+                .await;
+            assert!(syncer.workers.len() <= 3);
+        }
+        assert!(syncer.workers.is_empty());
+        assert!(syncer.eof);
+        // every milestone of the gaps is requested once
+        requested.sort_unstable();
+        assert_eq!(requested, gaps.iter().cloned().flatten().collect::<Vec<_>>());
+
+        let mut archived = Vec::new();
+        let mut closed = Vec::new();
+        while let Ok(event) = archiver_rx.try_recv() {
+            match event {
+                ArchiverEvent::MilestoneData(milestone_data, upper_limit) => {
+                    archived.push((milestone_data.milestone_index(), upper_limit))
+                }
+                ArchiverEvent::Close(milestone_index) => closed.push(milestone_index),
+            }
+        }
+        // every gap is archived in order, bounded by its own end, without overlapping the others
+        assert_eq!(archived.len(), requested.len());
+        for gap in gaps.iter() {
+            let archived_gap = archived
+                .iter()
+                .filter(|(milestone_index, _)| gap.contains(milestone_index))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                archived_gap
+                    .iter()
+                    .map(|(milestone_index, _)| *milestone_index)
+                    .collect::<Vec<_>>(),
+                gap.clone().collect::<Vec<_>>()
+            );
+            assert!(archived_gap
+                .iter()
+                .all(|(_, upper_limit)| *upper_limit == Some(gap.end)));
+        }
+        closed.sort_unstable();
+        assert_eq!(closed, gaps.iter().map(|gap| gap.end).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn serialize_syncer_session() {
+        let progress = SyncerSession::Progress {
+            range: 10..20,
+            synced: 4,
+            milestones_per_sec: 2.0,
+            remaining_gaps: 3,
+        };
+        let socket_msg = BrokerSocketMsg::ChronicleBroker(progress.clone());
+        let json = serde_json::to_value(&socket_msg).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "ChronicleBroker": {
+                    "Progress": {
+                        "range": { "start": 10, "end": 20 },
+                        "synced": 4,
+                        "milestones_per_sec": 2.0,
+                        "remaining_gaps": 3
+                    }
+                }
+            })
+        );
+        let deserialized: BrokerSocketMsg<SyncerSession> = serde_json::from_value(json).unwrap();
+        let BrokerSocketMsg::ChronicleBroker(deserialized) = deserialized;
+        assert_eq!(deserialized, progress);
+        let finish = serde_json::to_string(&SyncerSession::Finish).unwrap();
+        assert_eq!(finish, "\"Finish\"");
+    }
+}
@@ -3,10 +3,12 @@
 
 use bee_message::{
     prelude::{
+        Address,
         Essence,
         MilestonePayload,
         Output,
         Payload,
+        TransactionId,
     },
     Message,
     MessageId,
@@ -16,19 +18,33 @@ use chronicle_storage::access::{
     LedgerInclusionState,
     MessageCount,
     MessageMetadata,
+    ReattachmentCount,
     TransactionCount,
     TransferredTokens,
 };
+use crypto::hashes::{
+    blake2b::Blake2b256,
+    Digest,
+};
 #[cfg(feature = "scylla-rs")]
 use scylla_rs::cql::Rows;
 use serde::{
     Deserialize,
     Serialize,
+    Serializer,
 };
 use std::{
-    collections::HashMap,
+    collections::{
+        HashMap,
+        HashSet,
+    },
     ops::Range,
     path::PathBuf,
+    str::FromStr,
+    time::{
+        Duration,
+        Instant,
+    },
 };
 use url::Url;
 
@@ -48,6 +64,28 @@ pub enum ChronicleBrokerThrough {
     Topology(BrokerTopology),
     /// Exit the broker app
     ExitProgram,
+    /// Prune the milestones data (and their messages) below the given milestone index
+    Prune {
+        /// The (exclusive) upper bound of the pruned milestone indexes
+        below: u32,
+        /// Only count the affected milestones, messages and outputs without deleting anything
+        #[serde(default)]
+        dry_run: bool,
+    },
+    /// Cancel the in progress (and pending) imports of the given file or dir path at the next milestone boundary,
+    /// the imported milestones are synced, so importing it again with `resume` continues where it stopped
+    CancelImport {
+        /// The file or dir path of the import
+        path: PathBuf,
+    },
+    /// Make the syncer re-read the sync table right away (ie after manual repairs), instead of at its next interval
+    RefreshSync,
+    /// Switch the keyspace the broker writes to (ie after a schema migration) to the given configured keyspace, the
+    /// in-flight writes still complete in the previous keyspace
+    SwitchKeyspace(String),
+    /// Set how often the analytics snapshots of the analyzed milestones are emitted to the dashboards, the interval
+    /// must be at least `MIN_ANALYTICS_INTERVAL`
+    SetAnalyticsInterval(Duration),
 }
 
 /// Topology event
@@ -57,13 +95,25 @@ pub enum BrokerTopology {
     AddMqttMessages(Url),
     /// Add new MQTT Messages Referenced feed source
     AddMqttMessagesReferenced(Url),
+    /// Add new MQTT Messages feed source, subscribed with the given QoS level (0 or 1)
+    AddMqttMessagesWithQos(Url, u8),
+    /// Add new MQTT Messages Referenced feed source, subscribed with the given QoS level (0 or 1)
+    AddMqttMessagesReferencedWithQos(Url, u8),
     /// Remove a MQTT Messages feed source
     RemoveMqttMessages(Url),
     /// Remove a MQTT Messages Referenced feed source
     RemoveMqttMessagesReferenced(Url),
+    /// Reconcile the topics subscribed from a connected feed source, every topic runs over its own connection, so
+    /// the missing topics get connected and the unlisted ones disconnected, while the kept ones stay untouched
+    UpdateFeedTopics {
+        /// The url of the connected feed source
+        url: Url,
+        /// The topics to be subscribed, either `messages` or `messages/referenced`
+        topics: Vec<String>,
+    },
     /// Import a log file using the given url
     Import {
-        /// File or dir path which supposed to contain LogFiles
+        /// File or dir path which supposed to contain LogFiles, or an HTTP(S) url of a single LogFile
         path: PathBuf,
         /// Resume the importing process
         resume: bool,
@@ -71,11 +121,154 @@ pub enum BrokerTopology {
         import_range: Option<Range<u32>>,
         /// The type of import requested
         import_type: ImportType,
+        /// Verify the LogFiles against their sidecar `.sha256` checksum files before importing
+        #[serde(default)]
+        verify_checksum: bool,
+        /// The max number of in-flight storage inserts per importer, clamped to a sane max
+        #[serde(default)]
+        insert_concurrency: Option<usize>,
+        /// The number of milestones whose inserts are awaited together before emitting their progress and fetching the
+        /// next ones, rather than fetching the next milestone as soon as one got imported
+        #[serde(default)]
+        batch_size: Option<usize>,
+        /// Fail the import if the LogFiles skip milestone indexes
+        #[serde(default)]
+        strict: bool,
+        /// Proceed even if the import range exceeds the configured max import range
+        #[serde(default)]
+        force: bool,
     },
     /// Add Endpoint
     Requesters(RequesterTopology),
 }
 
+/// Parse a topology out of its flat (CLI style) form, the JSON form remains the canonical wire form:
+/// - `add-mqtt-messages <url> [--qos <0|1>]` and `add-mqtt-messages-referenced <url> [--qos <0|1>]`
+/// - `remove-mqtt-messages <url>` and `remove-mqtt-messages-referenced <url>`
+/// - `update-feed-topics <url> --topics <topic>[,<topic>]`
+/// - `import <path> [--resume] [--from <index>] [--to <index>] [--analytics] [--verify-checksum] [--insert-concurrency
+///   <n>] [--batch-size <n>] [--strict] [--force]`
+/// - `add-endpoint <url>` and `remove-endpoint <url>`
+impl FromStr for BrokerTopology {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut args = s.split_whitespace();
+        let command = args
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Missing the topology command"))?;
+        let target = args
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Missing the url or path of the {} command", command))?;
+        let mut flags = TopologyFlags::parse(args)?;
+        let url = || Url::parse(target).map_err(|e| anyhow::anyhow!("Invalid url {}: {}", target, e));
+        let topology = match command {
+            "add-mqtt-messages" => match flags.value("qos")? {
+                Some(qos) => BrokerTopology::AddMqttMessagesWithQos(url()?, qos),
+                None => BrokerTopology::AddMqttMessages(url()?),
+            },
+            "add-mqtt-messages-referenced" => match flags.value("qos")? {
+                Some(qos) => BrokerTopology::AddMqttMessagesReferencedWithQos(url()?, qos),
+                None => BrokerTopology::AddMqttMessagesReferenced(url()?),
+            },
+            "remove-mqtt-messages" => BrokerTopology::RemoveMqttMessages(url()?),
+            "remove-mqtt-messages-referenced" => BrokerTopology::RemoveMqttMessagesReferenced(url()?),
+            "update-feed-topics" => BrokerTopology::UpdateFeedTopics {
+                url: url()?,
+                topics: flags
+                    .value::<String>("topics")?
+                    .ok_or_else(|| anyhow::anyhow!("Missing the --topics of the {} command", command))?
+                    .split(',')
+                    .map(str::to_owned)
+                    .collect(),
+            },
+            "import" => {
+                let from = flags.value("from")?;
+                let to = flags.value("to")?;
+                // like the cli, an unbounded side of the range defaults to the whole range
+                let import_range =
+                    (from.is_some() || to.is_some()).then(|| from.unwrap_or(1)..to.unwrap_or(i32::MAX as u32));
+                if let Some(range) = import_range.as_ref() {
+                    anyhow::ensure!(range.start < range.end, "Invalid import range: {:?}", range);
+                }
+                BrokerTopology::Import {
+                    path: PathBuf::from(target),
+                    resume: flags.is_set("resume"),
+                    import_range,
+                    import_type: if flags.is_set("analytics") {
+                        ImportType::Analytics
+                    } else {
+                        ImportType::All
+                    },
+                    verify_checksum: flags.is_set("verify-checksum"),
+                    insert_concurrency: flags.value("insert-concurrency")?,
+                    batch_size: flags.value("batch-size")?,
+                    strict: flags.is_set("strict"),
+                    force: flags.is_set("force"),
+                }
+            }
+            "add-endpoint" => BrokerTopology::Requesters(RequesterTopology::AddEndpoint(url()?)),
+            "remove-endpoint" => BrokerTopology::Requesters(RequesterTopology::RemoveEndpoint(url()?)),
+            _ => anyhow::bail!("Unknown topology command: {}", command),
+        };
+        flags.ensure_consumed(command)?;
+        Ok(topology)
+    }
+}
+
+/// The `--flag [value]` arguments of a flat topology command
+struct TopologyFlags<'a>(HashMap<&'a str, Option<&'a str>>);
+
+impl<'a> TopologyFlags<'a> {
+    /// The flags which take a value
+    const VALUE_FLAGS: [&'static str; 6] = ["qos", "from", "to", "insert-concurrency", "batch-size", "topics"];
+
+    fn parse(mut args: impl Iterator<Item = &'a str>) -> anyhow::Result<Self> {
+        let mut flags = HashMap::new();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .ok_or_else(|| anyhow::anyhow!("Unexpected argument: {}", arg))?;
+            let value = if Self::VALUE_FLAGS.contains(&name) {
+                Some(
+                    args.next()
+                        .ok_or_else(|| anyhow::anyhow!("Missing the value of --{}", name))?,
+                )
+            } else {
+                None
+            };
+            anyhow::ensure!(flags.insert(name, value).is_none(), "Duplicate flag --{}", name);
+        }
+        Ok(Self(flags))
+    }
+    /// Take the parsed value of the flag, if provided
+    fn value<T: FromStr>(&mut self, name: &str) -> anyhow::Result<Option<T>>
+    where
+        T::Err: std::fmt::Display,
+    {
+        self.0
+            .remove(name)
+            .flatten()
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid value {} of --{}: {}", value, name, e))
+            })
+            .transpose()
+    }
+    /// Take the switch flag, returns whether it was provided
+    fn is_set(&mut self, name: &str) -> bool {
+        self.0.remove(name).is_some()
+    }
+    /// Ensure every provided flag applies to the command
+    fn ensure_consumed(self, command: &str) -> anyhow::Result<()> {
+        if let Some(name) = self.0.keys().next() {
+            anyhow::bail!("Unexpected flag --{} of the {} command", name, command);
+        }
+        Ok(())
+    }
+}
+
 /// Import types
 #[derive(Deserialize, Serialize, Debug, Copy, Clone)]
 pub enum ImportType {
@@ -94,14 +287,117 @@ pub enum RequesterTopology {
     RemoveEndpoint(Url),
 }
 
+/// The LogFile format version, which is written as the header line of the newly created LogFiles
+pub const LOG_FILE_VERSION: u32 = 1;
+
+/// The header line of a versioned LogFile, LogFiles without header are treated as version 0
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LogFileHeader {
+    /// The LogFile format version
+    pub log_file_version: u32,
+}
+
+impl Default for LogFileHeader {
+    fn default() -> Self {
+        Self {
+            log_file_version: LOG_FILE_VERSION,
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl LogFileHeader {
+    /// Try to parse the header out of the first line of a LogFile, returns None if the line is not a header
+    pub fn from_line(line: &str) -> Option<Self> {
+        serde_json::from_str(line.trim_end()).ok()
+    }
+    /// Serialize the header into a LogFile line (including the trailing newline)
+    pub fn to_line(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap();
+        line.push('\n');
+        line
+    }
+    /// Check whether this header version is supported by this LogFile reader
+    pub fn is_supported(&self) -> bool {
+        self.log_file_version <= LOG_FILE_VERSION
+    }
+}
+
+fn sorted_messages(messages: &HashMap<MessageId, FullMessage>) -> Vec<(&MessageId, &FullMessage)> {
+    let mut messages = messages.iter().collect::<Vec<_>>();
+    messages.sort_unstable_by_key(|(message_id, _)| *message_id);
+    messages
+}
+
+/// Serialize the messages sorted by message id, so the exported LogFiles are byte-stable
+fn serialize_sorted_messages<S: Serializer>(
+    messages: &HashMap<MessageId, FullMessage>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(sorted_messages(messages))
+}
+
+/// Accumulates the analytics of a milestone message by message, so they're available without buffering the whole
+/// milestone data. `MilestoneData::get_analytic_record` feeds it with all the messages of the milestone.
+#[derive(Debug, Default)]
+pub struct AnalyticsAccumulator {
+    message_ids: HashSet<MessageId>,
+    transaction_ids: HashSet<TransactionId>,
+    transaction_count: u32,
+    transferred_tokens: u64,
+    reattachment_count: u32,
+}
+
+impl AnalyticsAccumulator {
+    /// Accumulate the analytics of the message, a message which is added again is ignored
+    pub fn add_message(&mut self, FullMessage(message, metadata): &FullMessage) -> anyhow::Result<()> {
+        if !self.message_ids.insert(metadata.message_id) {
+            return Ok(());
+        }
+        if let Some(Payload::Transaction(payload)) = message.payload() {
+            if !self.transaction_ids.insert(payload.id()) {
+                self.reattachment_count += 1;
+            }
+            if let Some(LedgerInclusionState::Included) = metadata.ledger_inclusion_state {
+                self.transaction_count += 1;
+                let Essence::Regular(regular_essence) = payload.essence();
+                for output in regular_essence.outputs() {
+                    match output {
+                        Output::SignatureLockedSingle(output) => self.transferred_tokens += output.amount(),
+                        Output::SignatureLockedDustAllowance(output) => self.transferred_tokens += output.amount(),
+                        // Note that the transaction payload don't have Treasury
+                        _ => anyhow::bail!("Unexpected Output variant in transaction payload"),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Get the analytic record of the messages accumulated so far
+    pub fn analytic_record(&self, milestone_index: u32) -> AnalyticRecord {
+        AnalyticRecord::new(
+            bee_message::milestone::MilestoneIndex(milestone_index),
+            MessageCount(self.message_ids.len() as u32),
+            TransactionCount(self.transaction_count),
+            TransferredTokens(self.transferred_tokens),
+            ReattachmentCount(self.reattachment_count),
+        )
+    }
+}
+
 /// Milestone data
 #[derive(Deserialize, Serialize)]
 pub struct MilestoneData {
     pub(crate) milestone_index: u32,
     pub(crate) milestone: Option<Box<MilestonePayload>>,
+    #[serde(serialize_with = "serialize_sorted_messages")]
     pub(crate) messages: HashMap<MessageId, FullMessage>,
     pub(crate) pending: HashMap<MessageId, ()>,
     pub(crate) created_by: CreatedBy,
+    /// When the solidification began, which bounds the time it may take
+    #[serde(skip, default = "Instant::now")]
+    pub(crate) created_at: Instant,
 }
 
 impl MilestoneData {
@@ -112,6 +408,7 @@ impl MilestoneData {
             messages: HashMap::new(),
             pending: HashMap::new(),
             created_by,
+            created_at: Instant::now(),
         }
     }
     /// Get the milestone index from this milestone data
@@ -123,48 +420,118 @@ impl MilestoneData {
         if !self.check_if_completed() {
             anyhow::bail!("cannot get analytics for uncompleted milestone data")
         }
-        // The accumulators
-        let mut transaction_count: u32 = 0;
-        let mut message_count: u32 = 0;
-        let mut transferred_tokens: u64 = 0;
-
-        // Iterate the messages to calculate analytics
+        self.analytic_record()
+    }
+    /// Compute the analytics of the collected messages
+    fn analytic_record(&self) -> anyhow::Result<AnalyticRecord> {
+        let mut analytics = AnalyticsAccumulator::default();
+        for full_message in self.messages.values() {
+            analytics.add_message(full_message)?;
+        }
+        Ok(analytics.analytic_record(self.milestone_index()))
+    }
+    /// Get the transferred tokens received by each address within the confirmed(included) transactions
+    pub fn token_flows(&self) -> HashMap<Address, u128> {
+        let mut token_flows = HashMap::new();
         for (_, FullMessage(message, metadata)) in &self.messages {
-            // Accumulate the message count
-            message_count += 1;
-            // Accumulate confirmed(included) transaction value
             if let Some(LedgerInclusionState::Included) = metadata.ledger_inclusion_state {
                 if let Some(Payload::Transaction(payload)) = message.payload() {
-                    // Accumulate the transaction count
-                    transaction_count += 1;
                     let Essence::Regular(regular_essence) = payload.essence();
-                    {
-                        for output in regular_essence.outputs() {
-                            match output {
-                                // Accumulate the transferred token amount
-                                Output::SignatureLockedSingle(output) => transferred_tokens += output.amount(),
-                                Output::SignatureLockedDustAllowance(output) => transferred_tokens += output.amount(),
-                                // Note that the transaction payload don't have Treasury
-                                _ => anyhow::bail!("Unexpected Output variant in transaction payload"),
-                            }
-                        }
+                    for output in regular_essence.outputs() {
+                        let (address, amount) = match output {
+                            Output::SignatureLockedSingle(output) => (output.address(), output.amount()),
+                            Output::SignatureLockedDustAllowance(output) => (output.address(), output.amount()),
+                            // Note that the transaction payload don't have Treasury
+                            _ => continue,
+                        };
+                        *token_flows.entry(*address).or_insert(0) += amount as u128;
                     }
                 }
             }
         }
-        let milestone_index = self.milestone_index();
-        let analytic_record = AnalyticRecord::new(
-            bee_message::milestone::MilestoneIndex(milestone_index),
-            MessageCount(message_count),
-            TransactionCount(transaction_count),
-            TransferredTokens(transferred_tokens),
-        );
-        // Return the analytic record
-        Ok(analytic_record)
+        token_flows
+    }
+    /// Validate that the included messages match the inclusion merkle proof of the milestone payload
+    pub fn validate_inclusion(&self) -> Result<(), String> {
+        let milestone = self.milestone.as_ref().ok_or_else(|| {
+            format!(
+                "Missing milestone payload for milestone index: {}",
+                self.milestone_index
+            )
+        })?;
+        self.verify_inclusion(milestone.essence().parents(), milestone.essence().merkle_proof())
+    }
+    /// Verify the merkle root of the included messages, within the past cone of the provided parents, against the
+    /// merkle proof
+    fn verify_inclusion(&self, parents: &[MessageId], merkle_proof: &[u8]) -> Result<(), String> {
+        let included = self.included_message_ids(parents);
+        let merkle_root = merkle_root(&included);
+        if merkle_root.as_ref() != merkle_proof {
+            return Err(format!(
+                "Inclusion merkle root mismatch for milestone index: {}, computed: {} from {} included messages, expected: {}",
+                self.milestone_index,
+                hex::encode(merkle_root),
+                included.len(),
+                hex::encode(merkle_proof)
+            ));
+        }
+        Ok(())
+    }
+    /// Get the merkle inclusion proof of the message, which is absent if the milestone payload is missing or the
+    /// message is not included by this milestone
+    pub fn inclusion_proof(&self, message_id: &MessageId) -> Option<InclusionProof> {
+        let milestone = self.milestone.as_ref()?;
+        let included = self.included_message_ids(milestone.essence().parents());
+        let index = included.iter().position(|included_id| included_id == message_id)?;
+        Some(InclusionProof {
+            index,
+            count: included.len(),
+            path: merkle_path(&included, index),
+        })
+    }
+    /// Get the ids of the included messages in white flag order, which is the post-order depth first traversal of the
+    /// messages referenced by this milestone, starting from the milestone parents
+    fn included_message_ids(&self, parents: &[MessageId]) -> Vec<MessageId> {
+        let mut included = Vec::new();
+        let mut visited = HashSet::new();
+        let mut stack: Vec<(MessageId, bool)> = parents.iter().rev().map(|parent| (*parent, false)).collect();
+        while let Some((message_id, children_visited)) = stack.pop() {
+            if children_visited {
+                if let Some(FullMessage(_, metadata)) = self.messages.get(&message_id) {
+                    if let Some(LedgerInclusionState::Included) = metadata.ledger_inclusion_state {
+                        included.push(message_id);
+                    }
+                }
+                continue;
+            }
+            if !visited.insert(message_id) {
+                continue;
+            }
+            // Messages referenced by older milestones (or missing ones) are out of the traversal
+            if let Some(FullMessage(message, metadata)) = self.messages.get(&message_id) {
+                if metadata.referenced_by_milestone_index == Some(self.milestone_index) {
+                    stack.push((message_id, true));
+                    stack.extend(message.parents().iter().rev().map(|parent| (*parent, false)));
+                }
+            }
+        }
+        included
+    }
+    /// Dedup the messages before inserting them, by dropping the messages which are not keyed by their own message
+    /// id, as they would be inserted twice under different ids. Returns the number of dropped messages
+    pub(crate) fn dedup(&mut self) -> usize {
+        let pre_len = self.messages.len();
+        self.messages
+            .retain(|message_id, full_message| full_message.message_id() == message_id);
+        pre_len - self.messages.len()
     }
     pub(crate) fn set_milestone(&mut self, boxed_milestone_payload: Box<MilestonePayload>) {
         self.milestone.replace(boxed_milestone_payload);
     }
+    /// Get the milestone payload, if any
+    pub fn milestone(&self) -> Option<&MilestonePayload> {
+        self.milestone.as_deref()
+    }
     /// Check if the milestone exists
     pub fn milestone_exist(&self) -> bool {
         self.milestone.is_some()
@@ -179,6 +546,26 @@ impl MilestoneData {
     pub fn messages(&self) -> &HashMap<MessageId, FullMessage> {
         &self.messages
     }
+    /// Get the milestone's messages sorted by message id
+    pub fn messages_sorted(&self) -> Vec<(&MessageId, &FullMessage)> {
+        sorted_messages(&self.messages)
+    }
+    /// Get the BLAKE2b-256 hash of the milestone id and the message ids (in sorted order, so it doesn't depend on
+    /// the insertion order), used to detect the divergence of the milestone data between replicas
+    pub fn content_hash(&self) -> [u8; 32] {
+        let mut hasher = Blake2b256::new();
+        match self.milestone.as_ref() {
+            Some(milestone) => {
+                hasher.update([1u8]);
+                hasher.update(milestone.id());
+            }
+            None => hasher.update([0u8]),
+        }
+        for (message_id, _) in self.messages_sorted() {
+            hasher.update(message_id);
+        }
+        hasher.finalize().into()
+    }
     /// Get the pending messages
     pub fn pending(&self) -> &HashMap<MessageId, ()> {
         &self.pending
@@ -208,6 +595,152 @@ impl std::iter::IntoIterator for MilestoneData {
     }
 }
 
+/// Builds a milestone data out of its milestone payload and messages, ie for the integration tests and tools which
+/// don't go through the solidifier
+pub struct MilestoneDataBuilder {
+    milestone_data: MilestoneData,
+}
+
+impl MilestoneDataBuilder {
+    /// Start building the milestone data of the milestone index
+    pub fn new(milestone_index: u32, created_by: CreatedBy) -> Self {
+        Self {
+            milestone_data: MilestoneData::new(milestone_index, created_by),
+        }
+    }
+    /// Set the milestone payload, whose index must match the milestone data one
+    pub fn with_milestone(mut self, milestone: Box<MilestonePayload>) -> Self {
+        self.milestone_data.set_milestone(milestone);
+        self
+    }
+    /// Add a message to the milestone data
+    pub fn with_message(mut self, full_message: FullMessage) -> Self {
+        self.milestone_data.add_full_message(full_message);
+        self
+    }
+    /// Add the messages to the milestone data
+    pub fn with_messages<I: IntoIterator<Item = FullMessage>>(mut self, full_messages: I) -> Self {
+        for full_message in full_messages {
+            self.milestone_data.add_full_message(full_message);
+        }
+        self
+    }
+    /// Build the milestone data, which fails if the milestone payload belongs to another milestone index
+    pub fn build(self) -> anyhow::Result<MilestoneData> {
+        if let Some(milestone) = self.milestone_data.milestone.as_ref() {
+            let index = milestone.essence().index().0;
+            anyhow::ensure!(
+                index == self.milestone_data.milestone_index,
+                "The milestone payload index {} does not match the milestone data index {}",
+                index,
+                self.milestone_data.milestone_index
+            );
+        }
+        Ok(self.milestone_data)
+    }
+}
+
+/// The merkle audit path (RFC 6962) of an included message, within the inclusion merkle tree of its milestone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InclusionProof {
+    /// The position of the message within the included messages, in white flag order
+    pub index: usize,
+    /// The number of messages included by the milestone
+    pub count: usize,
+    /// The sibling hashes from the message leaf up to the merkle root
+    pub path: Vec<[u8; 32]>,
+}
+
+impl InclusionProof {
+    /// Compute the merkle root of the message along the audit path
+    pub fn merkle_root(&self, message_id: &MessageId) -> Option<[u8; 32]> {
+        if self.index >= self.count {
+            return None;
+        }
+        merkle_root_from_path(message_id, self.index, self.count, &self.path)
+    }
+    /// Verify the audit path of the message against the inclusion merkle proof of the milestone payload
+    pub fn verify(&self, message_id: &MessageId, merkle_proof: &[u8]) -> bool {
+        self.merkle_root(message_id)
+            .map_or(false, |merkle_root| merkle_root.as_ref() == merkle_proof)
+    }
+}
+
+/// Get the largest power of two smaller than the number of leaves, where the merkle tree is split
+fn merkle_split(len: usize) -> usize {
+    let mut split = 1;
+    while split * 2 < len {
+        split *= 2;
+    }
+    split
+}
+
+/// Compute the merkle tree hash (RFC 6962) of the message ids, using BLAKE2b-256
+fn merkle_root(message_ids: &[MessageId]) -> [u8; 32] {
+    let mut hasher = Blake2b256::new();
+    match message_ids {
+        [] => (),
+        [message_id] => {
+            hasher.update([0u8]);
+            hasher.update(message_id.as_ref());
+        }
+        _ => {
+            let split = merkle_split(message_ids.len());
+            hasher.update([1u8]);
+            hasher.update(merkle_root(&message_ids[..split]));
+            hasher.update(merkle_root(&message_ids[split..]));
+        }
+    }
+    hasher.finalize().into()
+}
+
+/// Compute the merkle audit path (RFC 6962) of the message id at the index, from the leaf up to the root
+fn merkle_path(message_ids: &[MessageId], index: usize) -> Vec<[u8; 32]> {
+    if message_ids.len() <= 1 {
+        return Vec::new();
+    }
+    let split = merkle_split(message_ids.len());
+    let (mut path, sibling) = if index < split {
+        (
+            merkle_path(&message_ids[..split], index),
+            merkle_root(&message_ids[split..]),
+        )
+    } else {
+        (
+            merkle_path(&message_ids[split..], index - split),
+            merkle_root(&message_ids[..split]),
+        )
+    };
+    path.push(sibling);
+    path
+}
+
+/// Compute the merkle tree hash of the count leaves along the audit path of the message id at the index
+fn merkle_root_from_path(message_id: &MessageId, index: usize, count: usize, path: &[[u8; 32]]) -> Option<[u8; 32]> {
+    if count == 1 {
+        return if path.is_empty() {
+            Some(merkle_root(std::slice::from_ref(message_id)))
+        } else {
+            None
+        };
+    }
+    let (sibling, path) = path.split_last()?;
+    let split = merkle_split(count);
+    let (left, right) = if index < split {
+        (merkle_root_from_path(message_id, index, split, path)?, *sibling)
+    } else {
+        (
+            *sibling,
+            merkle_root_from_path(message_id, index - split, count - split, path)?,
+        )
+    };
+    let mut hasher = Blake2b256::new();
+    hasher.update([1u8]);
+    hasher.update(left);
+    hasher.update(right);
+    Some(hasher.finalize().into())
+}
+
 /// Created by sources
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[repr(u8)]
@@ -226,8 +759,30 @@ impl From<CreatedBy> for u8 {
     }
 }
 
+impl std::convert::TryFrom<u8> for CreatedBy {
+    type Error = anyhow::Error;
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(CreatedBy::Incoming),
+            1 => Ok(CreatedBy::Expected),
+            2 => Ok(CreatedBy::Syncer),
+            _ => anyhow::bail!("Unknown milestone data source: {}", value),
+        }
+    }
+}
+
+impl std::fmt::Display for CreatedBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreatedBy::Incoming => write!(f, "incoming"),
+            CreatedBy::Expected => write!(f, "expected"),
+            CreatedBy::Syncer => write!(f, "syncer"),
+        }
+    }
+}
+
 /// A "full" message payload, including both message and metadata
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct FullMessage(pub Message, pub MessageMetadata);
 
 impl FullMessage {
@@ -279,6 +834,9 @@ pub enum ImporterSession {
         to_ms: u32,
         /// Finish the progress bar using this msg.
         msg: String,
+        /// The milestone ranges skipped by the LogFile, which are missing from the archive
+        #[serde(default)]
+        gaps: Vec<Range<u32>>,
     },
     /// Return error
     PathError {
@@ -291,6 +849,76 @@ pub enum ImporterSession {
     Close,
 }
 
+/// Enum used by syncer to keep the sockets up to date with most recent progress.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum SyncerSession {
+    /// Update the sync progress state
+    Progress {
+        /// The range which is being synced
+        range: Range<u32>,
+        /// The number of synced milestones within the range
+        synced: u32,
+        /// The syncing speed in milestones per second
+        milestones_per_sec: f64,
+        /// The number of the remaining ranges to be synced after the current one
+        remaining_gaps: usize,
+    },
+    /// The syncer processed all the ranges in its sync data
+    Finish,
+}
+
+/// Enum used by pruner to keep the sockets up to date with most recent progress.
+#[derive(Deserialize, Serialize, Debug)]
+pub enum PrunerSession {
+    /// Create/update progress bar state
+    ProgressBar {
+        /// The (exclusive) upper bound of the pruned milestone indexes
+        below: u32,
+        /// The pruned milestone index
+        milestone_index: u32,
+        /// The number of pruned messages which belong to the milestone index
+        messages: usize,
+    },
+    /// Update the dry run progress with a milestone which would be pruned
+    WouldPrune {
+        /// The (exclusive) upper bound of the milestone indexes which would be pruned
+        below: u32,
+        /// The milestone index which would be pruned
+        milestone_index: u32,
+        /// The number of messages which belong to the milestone index and would be pruned
+        messages: usize,
+    },
+    /// The dry run result, which estimates what would be pruned
+    DryRun {
+        /// The (exclusive) upper bound of the pruned milestone indexes
+        below: u32,
+        /// The number of affected milestones
+        milestones: usize,
+        /// The number of affected messages
+        messages: usize,
+        /// The number of affected transaction outputs
+        outputs: usize,
+    },
+    /// Finish the progress bar with message
+    Finish {
+        /// The (exclusive) upper bound of the pruned milestone indexes
+        below: u32,
+        /// Finish the progress bar using this msg.
+        msg: String,
+    },
+}
+
+/// Event used by the mqtt feeds to keep the sockets up to date with their connection state.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FeedStatus {
+    /// The url of the feed source
+    pub url: Url,
+    /// The mqtt topic of the feed, ie `messages` or `messages/referenced`
+    pub topic_kind: String,
+    /// Whether the feed got connected or disconnected
+    pub connected: bool,
+}
+
 #[cfg(feature = "sync")]
 pub use sync::*;
 #[cfg(feature = "sync")]
@@ -303,7 +931,9 @@ mod sync {
         GetSelectRequest,
         Iter,
         Select,
+        SelectRequest,
         ValueWorker,
+        WorkerError,
     };
     use std::ops::Range;
 
@@ -316,6 +946,9 @@ mod sync {
         pub(crate) synced_but_unlogged: Vec<Range<u32>>,
         /// Gaps/missings milestones data
         pub(crate) gaps: Vec<Range<u32>>,
+        /// Identify whether the sync rows got truncated (ie storage timeout),
+        /// therefore anything below the lowest processed range is unknown
+        pub partial: bool,
     }
 
     impl SyncData {
@@ -325,59 +958,291 @@ mod sync {
             sync_range: &SyncRange,
             retries: usize,
         ) -> anyhow::Result<SyncData> {
+            Self::try_fetch_with_consistency(keyspace, sync_range, retries, Consistency::One).await
+        }
+        /// Try to fetch the sync data from the sync table for the provided keyspace and sync range,
+        /// using the provided consistency level (ie LocalQuorum in multi-DC deployments).
+        /// The sync rows are read in pages of the configured `sync_page_size`, and stitched across the page
+        /// boundaries as if they were read by a single select.
+        /// Note: if the select fails after some pages were already processed (ie storage timeout),
+        /// it returns the processed sync data flagged as partial instead of an error.
+        pub async fn try_fetch_with_consistency<S: 'static + Select<SyncRange, Iter<SyncRecord>>>(
+            keyspace: &S,
+            sync_range: &SyncRange,
+            retries: usize,
+            consistency: Consistency,
+        ) -> anyhow::Result<SyncData> {
+            let page_size = chronicle_common::get_config_async()
+                .await
+                .storage_config
+                .sync_page_size
+                .min(i32::MAX as u32) as i32;
             let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
-            let _ = keyspace
+            Self::query_sync_table(keyspace, sync_range, retries, consistency, page_size, tx.clone(), None)?;
+            let mut sync_data = SyncData::default();
+            let mut pre_record = None;
+            loop {
+                let select_response = rx
+                    .recv()
+                    .await
+                    .ok_or_else(|| anyhow::anyhow!("Expected Rx inbox to receive the sync data response"))?;
+                match select_response {
+                    Ok(Some(mut sync_rows)) => {
+                        let has_more_pages = sync_rows.has_more_pages();
+                        if has_more_pages {
+                            // request the next page, value worker will pass it to us through rx
+                            let paging_state = sync_rows.take_paging_state();
+                            Self::query_sync_table(
+                                keyspace,
+                                sync_range,
+                                retries,
+                                consistency,
+                                page_size,
+                                tx.clone(),
+                                paging_state,
+                            )?;
+                        }
+                        sync_data.process_page(sync_range, &mut pre_record, &mut sync_rows);
+                        if !has_more_pages {
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        if pre_record.is_none() {
+                            // nothing processed so far
+                            return Err(e.into());
+                        }
+                        sync_data.partial = true;
+                        sync_data.compact();
+                        return Ok(sync_data);
+                    }
+                }
+            }
+            sync_data.process_tail(sync_range, pre_record);
+            sync_data.compact();
+            Ok(sync_data)
+        }
+        fn query_sync_table<S: 'static + Select<SyncRange, Iter<SyncRecord>>>(
+            keyspace: &S,
+            sync_range: &SyncRange,
+            retries: usize,
+            consistency: Consistency,
+            page_size: i32,
+            tx: tokio::sync::mpsc::UnboundedSender<Result<Option<Iter<SyncRecord>>, WorkerError>>,
+            paging_state: Option<Vec<u8>>,
+        ) -> anyhow::Result<()> {
+            let req = Self::select_request(keyspace, sync_range, consistency, page_size, &paging_state)?;
+            let worker = ValueWorker::new(
+                tx,
+                keyspace.clone(),
+                sync_range.clone(),
+                retries,
+                std::marker::PhantomData,
+            )
+            .with_paging(page_size, paging_state);
+            req.send_local(Box::new(worker));
+            Ok(())
+        }
+        /// Build the select request of the sync rows within the provided sync range
+        fn select_request<S: 'static + Select<SyncRange, Iter<SyncRecord>>>(
+            keyspace: &S,
+            sync_range: &SyncRange,
+            consistency: Consistency,
+            page_size: i32,
+            paging_state: &Option<Vec<u8>>,
+        ) -> anyhow::Result<SelectRequest<S, SyncRange, Iter<SyncRecord>>> {
+            keyspace
                 .select(sync_range)
-                .consistency(Consistency::One)
-                .build()?
-                .send_local(ValueWorker::boxed(
-                    tx,
-                    keyspace.clone(),
-                    sync_range.clone(),
-                    retries,
-                    std::marker::PhantomData,
-                ));
-            let select_response = rx
-                .recv()
-                .await
-                .ok_or_else(|| anyhow::anyhow!("Expected Rx inbox to receive the sync data response"))??;
+                .consistency(consistency)
+                .page_size(page_size)
+                .paging_state(paging_state)
+                .build()
+        }
+        /// Create the sync data from the provided ranges, each list gets sorted and coalesced
+        /// into the normalized form (descending order without overlaps).
+        /// Returns an error if the completed and gaps ranges overlap.
+        pub fn from_ranges(
+            completed: Vec<Range<u32>>,
+            synced_but_unlogged: Vec<Range<u32>>,
+            gaps: Vec<Range<u32>>,
+        ) -> anyhow::Result<SyncData> {
+            let completed = Self::normalize(completed);
+            let synced_but_unlogged = Self::normalize(synced_but_unlogged);
+            let gaps = Self::normalize(gaps);
+            for c in completed.iter() {
+                if let Some(g) = gaps.iter().find(|g| c.start < g.end && g.start < c.end) {
+                    anyhow::bail!("Completed range: {:?} overlaps with gap: {:?}", c, g);
+                }
+            }
+            Ok(SyncData {
+                completed,
+                synced_but_unlogged,
+                gaps,
+                partial: false,
+            })
+        }
+        /// Returns the range below the lowest processed row of the partial sync data, which is neither known as
+        /// synced nor as gap
+        pub fn unknown(&self, sync_range: &SyncRange) -> Option<Range<u32>> {
+            if !self.partial {
+                return None;
+            }
+            let lowest = self
+                .completed
+                .iter()
+                .chain(self.synced_but_unlogged.iter())
+                .chain(self.gaps.iter())
+                .map(|r| r.start)
+                .min()
+                .unwrap_or(sync_range.to);
+            Some(sync_range.from..lowest).filter(|r| r.start < r.end)
+        }
+        /// Merge the sync data fetched for the unknown range below the partial sync data
+        pub fn merge_unknown(&mut self, unknown: SyncData) {
+            self.completed.extend(unknown.completed);
+            self.synced_but_unlogged.extend(unknown.synced_but_unlogged);
+            self.gaps.extend(unknown.gaps);
+            self.partial = unknown.partial;
+            self.compact();
+        }
+        /// Fetch the unknown range below the partial sync data till it's complete. On failure the sync data is left
+        /// partial, with the fetched rows merged
+        pub async fn try_complete<S: 'static + Select<SyncRange, Iter<SyncRecord>>>(
+            &mut self,
+            keyspace: &S,
+            sync_range: &SyncRange,
+            retries: usize,
+        ) -> anyhow::Result<()> {
+            // every partial fetch processed at least one row, so the unknown range shrinks till it's gone
+            while let Some(unknown) = self.unknown(sync_range) {
+                let unknown_range = SyncRange {
+                    from: unknown.start,
+                    to: unknown.end,
+                };
+                let unknown_sync_data = Self::try_fetch(keyspace, &unknown_range, retries).await?;
+                self.merge_unknown(unknown_sync_data);
+            }
+            self.partial = false;
+            Ok(())
+        }
+        /// Returns the earliest and the latest stored (synced) milestone indexes, if any
+        pub fn stored_bounds(&self) -> Option<(u32, u32)> {
+            let stored = self.completed.iter().chain(self.synced_but_unlogged.iter());
+            let earliest = stored.clone().map(|r| r.start).min()?;
+            let latest = stored.map(|r| r.end - 1).max()?;
+            Some((earliest, latest))
+        }
+        /// Returns the gaps (in descending order) within the sync range which are not covered by the completed ranges
+        pub fn gaps_within(sync_range: &SyncRange, completed: &[Range<u32>]) -> Vec<Range<u32>> {
+            let mut gaps = Vec::new();
+            let mut start = sync_range.from;
+            // normalized ranges are in descending order, therefore we walk them in reverse
+            for range in Self::normalize(completed.to_vec()).into_iter().rev() {
+                if range.end <= start {
+                    continue;
+                }
+                if range.start >= sync_range.to {
+                    break;
+                }
+                if range.start > start {
+                    gaps.push(start..range.start);
+                }
+                start = range.end;
+            }
+            if start < sync_range.to {
+                gaps.push(start..sync_range.to);
+            }
+            gaps.reverse();
+            gaps
+        }
+        /// Compact each of the range lists into the normalized form (descending order without overlaps), merging
+        /// the adjacent (`end == start`) and overlapping ranges into single spans, so the sync data neither fragments
+        /// nor depends on the order in which the sync rows were processed
+        pub fn compact(&mut self) {
+            self.completed = Self::normalize(std::mem::take(&mut self.completed));
+            self.synced_but_unlogged = Self::normalize(std::mem::take(&mut self.synced_but_unlogged));
+            self.gaps = Self::normalize(std::mem::take(&mut self.gaps));
+        }
+        /// Sort and coalesce the ranges in descending order
+        fn normalize(mut ranges: Vec<Range<u32>>) -> Vec<Range<u32>> {
+            ranges.retain(|r| r.start < r.end);
+            ranges.sort_by_key(|r| r.start);
+            let mut normalized: Vec<Range<u32>> = Vec::with_capacity(ranges.len());
+            for range in ranges {
+                match normalized.last_mut() {
+                    Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                    _ => normalized.push(range),
+                }
+            }
+            normalized.reverse();
+            normalized
+        }
+        /// Build the sync data for the provided sync range out of the sync rows,
+        /// note: the rows must be ordered by milestone_index in descending order (as stored in the sync table).
+        /// The sync range is half-open (`from` inclusive, `to` exclusive) like the resulting ranges, so a range
+        /// starting at milestone 0 is valid.
+        /// The row stream might get truncated by an error, in such case the sync data is flagged as partial.
+        pub fn from_rows<I: Iterator<Item = anyhow::Result<SyncRecord>>>(
+            sync_range: &SyncRange,
+            sync_rows: I,
+        ) -> SyncData {
             let mut sync_data = SyncData::default();
-            if let Some(mut sync_rows) = select_response {
-                // Get the first row, note: the first row is always with the largest milestone_index
-                let SyncRecord {
-                    milestone_index,
-                    logged_by,
-                    ..
-                } = sync_rows.next().unwrap();
-                // push missing row/gap (if any)
-                sync_data.process_gaps(sync_range.to, *milestone_index);
-                sync_data.process_rest(&logged_by, *milestone_index, &None);
-                let mut pre_ms = milestone_index;
-                let mut pre_lb = logged_by;
-                // Generate and identify missing gaps in order to fill them
-                while let Some(SyncRecord {
-                    milestone_index,
-                    logged_by,
-                    ..
-                }) = sync_rows.next()
-                {
-                    // check if there are any missings
-                    sync_data.process_gaps(*pre_ms, *milestone_index);
-                    sync_data.process_rest(&logged_by, *milestone_index, &pre_lb);
-                    pre_ms = milestone_index;
-                    pre_lb = logged_by;
+            let mut pre_record = None;
+            for sync_record in sync_rows {
+                match sync_record {
+                    Ok(sync_record) => sync_data.process_row(sync_range, &mut pre_record, sync_record),
+                    Err(_) => {
+                        sync_data.partial = true;
+                        return sync_data;
+                    }
                 }
-                // pre_ms is the most recent milestone we processed
+            }
+            sync_data.process_tail(sync_range, pre_record);
+            sync_data
+        }
+        /// Process the sync row, pre_record is the previously processed row (which has a larger milestone index)
+        fn process_row(
+            &mut self,
+            sync_range: &SyncRange,
+            pre_record: &mut Option<SyncRecord>,
+            sync_record: SyncRecord,
+        ) {
+            let milestone_index = *sync_record.milestone_index;
+            if let Some(pre_record) = pre_record.as_ref() {
+                // check if there are any missings
+                self.process_gaps(*pre_record.milestone_index, milestone_index);
+                self.process_rest(&sync_record.logged_by, milestone_index, &pre_record.logged_by);
+            } else {
+                // push missing row/gap (if any)
+                self.process_gaps(sync_range.to, milestone_index);
+                self.process_rest(&sync_record.logged_by, milestone_index, &None);
+            }
+            pre_record.replace(sync_record);
+        }
+        /// Process a page of the sync rows, pre_record carries the lowest processed row across the page boundaries
+        fn process_page<I: Iterator<Item = SyncRecord>>(
+            &mut self,
+            sync_range: &SyncRange,
+            pre_record: &mut Option<SyncRecord>,
+            sync_rows: I,
+        ) {
+            for sync_record in sync_rows {
+                self.process_row(sync_range, pre_record, sync_record);
+            }
+        }
+        /// Process what is left below the lowest processed row
+        fn process_tail(&mut self, sync_range: &SyncRange, pre_record: Option<SyncRecord>) {
+            if let Some(pre_record) = pre_record {
+                // pre_record is the most recent row we processed
                 // it's also the lowest milestone index in the select response
-                // so anything < pre_ms && anything >= (self.sync_range.from - 1)
+                // so anything < pre_ms && anything >= self.sync_range.from
                 // (lower provided sync bound) are missing
                 // push missing row/gap (if any)
-                sync_data.process_gaps(*pre_ms, sync_range.from - 1);
-                Ok(sync_data)
+                self.push_gap(sync_range.from, *pre_record.milestone_index);
             } else {
                 // Everything is missing as gaps
-                sync_data.process_gaps(sync_range.to, sync_range.from - 1);
-                Ok(sync_data)
+                self.push_gap(sync_range.from, sync_range.to);
             }
         }
         /// Takes the lowest gap from the sync_data
@@ -451,14 +1316,12 @@ mod sync {
             }
         }
         fn process_gaps(&mut self, pre_ms: u32, milestone_index: u32) {
-            let gap_start = milestone_index + 1;
-            if gap_start != pre_ms {
-                // create missing gap
-                let gap = Range {
-                    start: gap_start,
-                    end: pre_ms,
-                };
-                self.gaps.push(gap);
+            self.push_gap(milestone_index + 1, pre_ms);
+        }
+        /// Push the half-open gap `start..end`, if it's not empty
+        fn push_gap(&mut self, start: u32, end: u32) {
+            if start < end {
+                self.gaps.push(Range { start, end });
             }
         }
         fn proceed(ranges: &mut Vec<Range<u32>>, milestone_index: u32, check: bool) {
@@ -482,6 +1345,263 @@ mod sync {
             };
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use bee_message::prelude::MilestoneIndex;
+        use chronicle_storage::keyspaces::ChronicleKeyspace;
+        use scylla_rs::prelude::Request;
+
+        #[test]
+        fn sync_request_consistency() {
+            let keyspace = ChronicleKeyspace::new("permanode".to_owned());
+            let sync_range = SyncRange::default();
+            let statement = Select::<SyncRange, Iter<SyncRecord>>::statement(&keyspace);
+            // query frame: header(9 bytes) + statement long string(4 + len bytes), followed by the consistency
+            let offset = 9 + 4 + statement.len();
+            // the consistency is followed by the flags (1 byte) and the values: their count (2 bytes), the key
+            // (4 + 9 bytes) and the range bounds (2 * (4 + 4) bytes), followed by the page size
+            let page_size_offset = offset + 2 + 1 + 2 + 4 + "permanode".len() + 2 * (4 + 4);
+            for (consistency, expected) in vec![(Consistency::One, [0, 1]), (Consistency::LocalQuorum, [0, 6])] {
+                for page_size in vec![5000, 100] {
+                    let request =
+                        SyncData::select_request(&keyspace, &sync_range, consistency, page_size, &None).unwrap();
+                    let payload = request.payload();
+                    assert_eq!(&payload[offset..offset + 2], &expected);
+                    // the page size flag is set
+                    assert_eq!(payload[offset + 2] & 0x04, 0x04);
+                    assert_eq!(
+                        &payload[page_size_offset..page_size_offset + 4],
+                        &page_size.to_be_bytes()
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn from_ranges_coalesces() {
+            let sync_data = SyncData::from_ranges(
+                vec![1..5, 20..25, 3..10, 10..12, 30..30],
+                vec![12..15, 14..20],
+                vec![25..40, 40..50, 60..70],
+            )
+            .unwrap();
+            assert_eq!(sync_data.completed, vec![20..25, 1..12]);
+            assert_eq!(sync_data.synced_but_unlogged, vec![12..20]);
+            assert_eq!(sync_data.gaps, vec![60..70, 25..50]);
+            assert!(!sync_data.partial);
+        }
+
+        #[test]
+        fn compact_unsorted_ranges() {
+            let mut sync_data = SyncData {
+                completed: vec![1..5, 30..40, 5..8, 20..25, 3..6],
+                synced_but_unlogged: vec![8..10, 45..50, 10..12, 40..45],
+                gaps: vec![12..15, 60..70, 25..30, 15..20, 55..55],
+                partial: true,
+            };
+            sync_data.compact();
+            assert_eq!(sync_data.completed, vec![30..40, 20..25, 1..8]);
+            assert_eq!(sync_data.synced_but_unlogged, vec![40..50, 8..12]);
+            assert_eq!(sync_data.gaps, vec![60..70, 25..30, 12..20]);
+            assert!(sync_data.partial);
+            // the canonical form is left as is
+            let canonical = sync_data.clone();
+            sync_data.compact();
+            assert_eq!(sync_data.completed, canonical.completed);
+            assert_eq!(sync_data.synced_but_unlogged, canonical.synced_but_unlogged);
+            assert_eq!(sync_data.gaps, canonical.gaps);
+        }
+
+        #[test]
+        fn compact_adjacent_single_milestones() {
+            let mut sync_data = SyncData {
+                completed: (1..1000).map(|i| i..i + 1).collect(),
+                ..Default::default()
+            };
+            sync_data.compact();
+            assert_eq!(sync_data.completed, vec![1..1000]);
+            // the sync data fetched in pieces merges into single spans too
+            let mut fetched = SyncData {
+                completed: vec![500..1000],
+                partial: true,
+                ..Default::default()
+            };
+            fetched.merge_unknown(SyncData {
+                completed: (1..500).map(|i| i..i + 1).collect(),
+                ..Default::default()
+            });
+            assert_eq!(fetched.completed, vec![1..1000]);
+            assert!(!fetched.partial);
+        }
+
+        #[test]
+        fn stored_bounds() {
+            let sync_data = SyncData::from_ranges(vec![10..20, 30..40], vec![40..45], vec![20..30]).unwrap();
+            assert_eq!(sync_data.stored_bounds(), Some((10, 44)));
+            let sync_data = SyncData::from_ranges(Vec::new(), Vec::new(), vec![1..100]).unwrap();
+            assert_eq!(sync_data.stored_bounds(), None);
+        }
+
+        #[test]
+        fn from_ranges_rejects_overlap() {
+            assert!(SyncData::from_ranges(vec![1..10], Vec::new(), vec![9..20]).is_err());
+            assert!(SyncData::from_ranges(vec![1..10], Vec::new(), vec![10..20]).is_ok());
+        }
+
+        #[test]
+        fn gaps_within_sync_range() {
+            let sync_range = SyncRange { from: 10, to: 100 };
+            // fully complete
+            assert!(SyncData::gaps_within(&sync_range, &[1..50, 50..200]).is_empty());
+            // fully empty
+            assert_eq!(SyncData::gaps_within(&sync_range, &[]), vec![10..100]);
+            assert_eq!(SyncData::gaps_within(&sync_range, &[1..5, 100..120]), vec![10..100]);
+            // interior gaps
+            assert_eq!(
+                SyncData::gaps_within(&sync_range, &[60..70, 5..20, 30..40]),
+                vec![70..100, 40..60, 20..30]
+            );
+        }
+
+        #[test]
+        fn truncated_sync_rows() {
+            let sync_range = SyncRange { from: 1, to: 100 };
+            let record =
+                |milestone_index, logged_by| Ok(SyncRecord::new(MilestoneIndex(milestone_index), Some(0), logged_by));
+            let rows = vec![
+                record(60, Some(0)),
+                record(59, Some(0)),
+                record(58, None),
+                record(50, Some(0)),
+                Err(anyhow::anyhow!("read timeout")),
+                record(10, Some(0)),
+            ];
+            let sync_data = SyncData::from_rows(&sync_range, rows.into_iter());
+            assert!(sync_data.partial);
+            assert_eq!(sync_data.completed, vec![59..61, 50..51]);
+            assert_eq!(sync_data.synced_but_unlogged, vec![58..59]);
+            // nothing is known below the lowest processed row
+            assert_eq!(sync_data.gaps, vec![61..100, 51..58]);
+            assert_eq!(sync_data.unknown(&sync_range), Some(1..50));
+        }
+
+        #[test]
+        fn complete_truncated_sync_rows() {
+            let sync_range = SyncRange { from: 1, to: 100 };
+            let record =
+                |milestone_index, logged_by| Ok(SyncRecord::new(MilestoneIndex(milestone_index), Some(0), logged_by));
+            let rows = || {
+                vec![
+                    record(60, Some(0)),
+                    record(59, Some(0)),
+                    record(50, Some(0)),
+                    record(49, None),
+                    record(10, Some(0)),
+                ]
+                .into_iter()
+            };
+            let mut full = SyncData::from_rows(&sync_range, rows());
+            full.compact();
+            // the first read gets truncated below milestone 50
+            let mut sync_data = SyncData::from_rows(
+                &sync_range,
+                rows()
+                    .take(3)
+                    .chain(std::iter::once(Err(anyhow::anyhow!("read timeout")))),
+            );
+            let unknown = sync_data.unknown(&sync_range).unwrap();
+            assert_eq!(unknown, 1..50);
+            // the unknown range is read again, and gets truncated below milestone 49
+            let unknown_range = SyncRange {
+                from: unknown.start,
+                to: unknown.end,
+            };
+            let truncated = rows()
+                .skip(3)
+                .take(1)
+                .chain(std::iter::once(Err(anyhow::anyhow!("read timeout"))));
+            sync_data.merge_unknown(SyncData::from_rows(&unknown_range, truncated));
+            assert!(sync_data.partial);
+            let unknown = sync_data.unknown(&sync_range).unwrap();
+            assert_eq!(unknown, 1..49);
+            let unknown_range = SyncRange {
+                from: unknown.start,
+                to: unknown.end,
+            };
+            sync_data.merge_unknown(SyncData::from_rows(&unknown_range, rows().skip(4)));
+            assert!(!sync_data.partial);
+            assert_eq!(sync_data.unknown(&sync_range), None);
+            assert_eq!(sync_data.completed, full.completed);
+            assert_eq!(sync_data.synced_but_unlogged, full.synced_but_unlogged);
+            assert_eq!(sync_data.gaps, full.gaps);
+        }
+
+        #[test]
+        fn paged_sync_rows() {
+            let sync_range = SyncRange { from: 1, to: 100 };
+            let record =
+                |milestone_index, logged_by| SyncRecord::new(MilestoneIndex(milestone_index), Some(0), logged_by);
+            let rows = vec![
+                record(95, Some(0)),
+                record(94, Some(0)),
+                record(93, None),
+                record(92, None),
+                record(80, Some(0)),
+                record(79, Some(0)),
+                record(78, Some(0)),
+                record(60, None),
+                record(50, Some(0)),
+                record(49, Some(0)),
+                record(10, None),
+            ];
+            let mut single = SyncData::from_rows(&sync_range, rows.iter().cloned().map(Ok));
+            single.compact();
+            // every page size, including the ones which split the ranges and the gaps across the page boundaries
+            for page_size in 1..=rows.len() + 1 {
+                let mut paged = SyncData::default();
+                let mut pre_record = None;
+                for page in rows.chunks(page_size) {
+                    paged.process_page(&sync_range, &mut pre_record, page.iter().cloned());
+                }
+                paged.process_tail(&sync_range, pre_record);
+                paged.compact();
+                assert_eq!(paged.completed, single.completed, "page size {}", page_size);
+                assert_eq!(
+                    paged.synced_but_unlogged, single.synced_but_unlogged,
+                    "page size {}",
+                    page_size
+                );
+                assert_eq!(paged.gaps, single.gaps, "page size {}", page_size);
+            }
+            assert_eq!(single.completed, vec![94..96, 78..81, 49..51]);
+            assert_eq!(single.synced_but_unlogged, vec![92..94, 60..61, 10..11]);
+            assert_eq!(single.gaps, vec![96..100, 81..92, 61..78, 51..60, 11..49, 1..10]);
+        }
+
+        #[test]
+        fn sync_range_from_zero() {
+            let sync_range = SyncRange { from: 0, to: 100 };
+            let record =
+                |milestone_index, logged_by| Ok(SyncRecord::new(MilestoneIndex(milestone_index), Some(0), logged_by));
+            let rows = vec![record(60, Some(0)), record(5, Some(0))];
+            let sync_data = SyncData::from_rows(&sync_range, rows.into_iter());
+            assert_eq!(sync_data.completed, vec![60..61, 5..6]);
+            // the lower boundary gap starts at the (inclusive) milestone 0
+            assert_eq!(sync_data.gaps, vec![61..100, 6..60, 0..5]);
+
+            // the milestone 0 itself is synced, so there is no gap below it
+            let rows = vec![record(1, Some(0)), record(0, Some(0))];
+            let sync_data = SyncData::from_rows(&sync_range, rows.into_iter());
+            assert_eq!(sync_data.completed, vec![0..2]);
+            assert_eq!(sync_data.gaps, vec![2..100]);
+
+            // nothing is synced, so the whole range is a gap
+            let sync_data = SyncData::from_rows(&sync_range, std::iter::empty());
+            assert_eq!(sync_data.gaps, vec![0..100]);
+        }
+    }
 }
 
 #[cfg(feature = "analytic")]
@@ -515,6 +1635,21 @@ mod analytic {
         message_count: u128,
         transaction_count: u128,
         transferred_tokens: u128,
+        #[serde(default)]
+        reattachment_count: u128,
+    }
+
+    /// The per field deltas between two AnalyticData
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct AnalyticDelta {
+        /// The message count delta
+        pub message_count: i128,
+        /// The transaction count delta
+        pub transaction_count: i128,
+        /// The transferred tokens delta
+        pub transferred_tokens: i128,
+        /// The reattachment count delta
+        pub reattachment_count: i128,
     }
     impl From<AnalyticRecord> for AnalyticData {
         fn from(record: AnalyticRecord) -> Self {
@@ -523,11 +1658,18 @@ mod analytic {
             let message_count = **record.message_count() as u128;
             let transaction_count = **record.transaction_count() as u128;
             let transferred_tokens = **record.transferred_tokens() as u128;
+            let reattachment_count = **record.reattachment_count() as u128;
             let range = Range {
                 start: milestone_index,
                 end: milestone_index + 1,
             };
-            AnalyticData::new(range, message_count, transaction_count, transferred_tokens)
+            AnalyticData::new(
+                range,
+                message_count,
+                transaction_count,
+                transferred_tokens,
+                reattachment_count,
+            )
         }
     }
     impl AnalyticData {
@@ -536,12 +1678,14 @@ mod analytic {
             message_count: u128,
             transaction_count: u128,
             transferred_tokens: u128,
+            reattachment_count: u128,
         ) -> Self {
             Self {
                 range,
                 message_count,
                 transaction_count,
                 transferred_tokens,
+                reattachment_count,
             }
         }
         async fn process(mut self, analytics_data: &mut AnalyticsData, records: &mut Iter<AnalyticRecord>) {
@@ -564,11 +1708,41 @@ mod analytic {
         fn start(&self) -> u32 {
             self.range.start
         }
+        /// Get the milestone range of this analytic data
+        pub fn range(&self) -> &Range<u32> {
+            &self.range
+        }
+        /// Get the number of messages confirmed by the milestones of this analytic data
+        pub fn message_count(&self) -> u128 {
+            self.message_count
+        }
+        /// Sum the provided analytic data, which are within the range, into a single analytic data of the range
+        pub fn sum(range: Range<u32>, analytics: impl IntoIterator<Item = AnalyticData>) -> Self {
+            analytics
+                .into_iter()
+                .fold(AnalyticData::new(range, 0, 0, 0, 0), |mut sum, analytic_data| {
+                    sum.message_count += analytic_data.message_count;
+                    sum.transaction_count += analytic_data.transaction_count;
+                    sum.transferred_tokens += analytic_data.transferred_tokens;
+                    sum.reattachment_count += analytic_data.reattachment_count;
+                    sum
+                })
+        }
+        /// Compute the deltas from this analytic data to the other one
+        pub fn delta(&self, other: &AnalyticData) -> AnalyticDelta {
+            AnalyticDelta {
+                message_count: other.message_count as i128 - self.message_count as i128,
+                transaction_count: other.transaction_count as i128 - self.transaction_count as i128,
+                transferred_tokens: other.transferred_tokens as i128 - self.transferred_tokens as i128,
+                reattachment_count: other.reattachment_count as i128 - self.reattachment_count as i128,
+            }
+        }
         fn acc(&mut self, record: AnalyticRecord) {
             self.range.start -= 1;
             self.message_count += **record.message_count() as u128;
             self.transaction_count += **record.transaction_count() as u128;
             self.transferred_tokens += **record.transferred_tokens() as u128;
+            self.reattachment_count += **record.reattachment_count() as u128;
         }
     }
 
@@ -644,6 +1818,28 @@ mod analytic {
             req.send_local(Box::new(worker));
             Ok(())
         }
+        /// Merge the analytic records (ie computed on demand) into the analytics data, coalescing the continuous
+        /// ranges, which are kept in descending order as fetched from the analytics table
+        pub fn merge(&mut self, records: impl IntoIterator<Item = AnalyticRecord>) {
+            let mut analytics = self
+                .analytics
+                .drain(..)
+                .chain(records.into_iter().map(AnalyticData::from))
+                .collect::<Vec<_>>();
+            analytics.sort_by(|a, b| b.range.start.cmp(&a.range.start));
+            for analytic_data in analytics {
+                match self.analytics.last_mut() {
+                    Some(last) if last.range.start == analytic_data.range.end => {
+                        last.range.start = analytic_data.range.start;
+                        last.message_count += analytic_data.message_count;
+                        last.transaction_count += analytic_data.transaction_count;
+                        last.transferred_tokens += analytic_data.transferred_tokens;
+                        last.reattachment_count += analytic_data.reattachment_count;
+                    }
+                    _ => self.add_analytic_data(analytic_data),
+                }
+            }
+        }
         fn try_pop_recent_analytic_data(&mut self) -> Option<AnalyticData> {
             self.analytics.pop()
         }
@@ -651,4 +1847,591 @@ mod analytic {
             self.analytics.push(analytic_data);
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn analytic_data(milestone_index: u32, messages: u32, transactions: u32, tokens: u64) -> AnalyticData {
+            AnalyticRecord::new(
+                bee_message::milestone::MilestoneIndex(milestone_index),
+                MessageCount(messages),
+                TransactionCount(transactions),
+                TransferredTokens(tokens),
+                ReattachmentCount(0),
+            )
+            .into()
+        }
+
+        #[test]
+        fn analytic_delta() {
+            let a = analytic_data(1, 10, 4, 5_000_000);
+            let b = analytic_data(2, 7, 6, 1_000_000);
+            assert_eq!(
+                a.delta(&b),
+                AnalyticDelta {
+                    message_count: -3,
+                    transaction_count: 2,
+                    transferred_tokens: -4_000_000,
+                    reattachment_count: 0,
+                }
+            );
+            assert_eq!(b.delta(&b), AnalyticDelta::default());
+        }
+
+        #[test]
+        fn merged_analytic_records() {
+            let record = |milestone_index| {
+                AnalyticRecord::new(
+                    bee_message::milestone::MilestoneIndex(milestone_index),
+                    MessageCount(10),
+                    TransactionCount(1),
+                    TransferredTokens(100),
+                    ReattachmentCount(0),
+                )
+            };
+            let mut analytics_data = AnalyticsData::default();
+            analytics_data.merge(vec![record(5), record(6), record(9), record(3)]);
+            let ranges = analytics_data
+                .analytics
+                .iter()
+                .map(|analytic_data| (analytic_data.range.clone(), analytic_data.message_count))
+                .collect::<Vec<_>>();
+            assert_eq!(ranges, vec![(9..10, 10), (5..7, 20), (3..4, 10)]);
+            // the computed records fill the gaps between the stored ranges
+            analytics_data.merge(vec![record(4), record(7), record(8)]);
+            assert_eq!(analytics_data.analytics.len(), 1);
+            assert_eq!(analytics_data.analytics[0].range, 3..10);
+            assert_eq!(analytics_data.analytics[0].transferred_tokens, 700);
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use bee_message::{
+        parents::Parents,
+        prelude::{
+            Ed25519Address,
+            Ed25519Signature,
+            Input,
+            RegularEssence,
+            SignatureLockedSingleOutput,
+            SignatureUnlock,
+            TransactionId,
+            TransactionPayload,
+            UnlockBlock,
+            UnlockBlocks,
+            UtxoInput,
+        },
+        MessageBuilder,
+    };
+    use bee_pow::providers::miner::Miner;
+
+    fn transaction_message(outputs: Vec<(Address, u64)>, inclusion_state: LedgerInclusionState) -> FullMessage {
+        let essence = RegularEssence::builder()
+            .with_inputs(vec![Input::Utxo(
+                UtxoInput::new(TransactionId::new([0; 32]), 0).unwrap(),
+            )])
+            .with_outputs(
+                outputs
+                    .into_iter()
+                    .map(|(address, amount)| {
+                        Output::SignatureLockedSingle(SignatureLockedSingleOutput::new(address, amount).unwrap())
+                    })
+                    .collect(),
+            )
+            .finish()
+            .unwrap();
+        let transaction = TransactionPayload::builder()
+            .with_essence(Essence::Regular(essence))
+            .with_unlock_blocks(
+                UnlockBlocks::new(vec![UnlockBlock::Signature(SignatureUnlock::Ed25519(
+                    Ed25519Signature::new([0; 32], Box::new([0; 64])),
+                ))])
+                .unwrap(),
+            )
+            .finish()
+            .unwrap();
+        let message = MessageBuilder::<Miner>::new()
+            .with_network_id(0)
+            .with_parents(Parents::new(vec![MessageId::new([0; 32])]).unwrap())
+            .with_payload(Payload::Transaction(Box::new(transaction)))
+            .finish()
+            .unwrap();
+        let metadata = MessageMetadata {
+            message_id: message.id().0,
+            parent_message_ids: vec![MessageId::new([0; 32])],
+            is_solid: true,
+            referenced_by_milestone_index: Some(1),
+            ledger_inclusion_state: Some(inclusion_state),
+            should_promote: None,
+            should_reattach: None,
+            conflict_reason: None,
+        };
+        FullMessage::new(message, metadata)
+    }
+
+    pub(crate) fn message(parents: Vec<MessageId>, network_id: u64) -> FullMessage {
+        let message = MessageBuilder::<Miner>::new()
+            .with_network_id(network_id)
+            .with_parents(Parents::new(parents.clone()).unwrap())
+            .finish()
+            .unwrap();
+        let metadata = MessageMetadata {
+            message_id: message.id().0,
+            parent_message_ids: parents,
+            is_solid: true,
+            referenced_by_milestone_index: Some(1),
+            ledger_inclusion_state: Some(LedgerInclusionState::Included),
+            should_promote: None,
+            should_reattach: None,
+            conflict_reason: None,
+        };
+        FullMessage::new(message, metadata)
+    }
+
+    fn leaf(message_id: &MessageId) -> [u8; 32] {
+        Blake2b256::new()
+            .chain([0u8])
+            .chain(message_id.as_ref())
+            .finalize()
+            .into()
+    }
+
+    fn node(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+        Blake2b256::new()
+            .chain([1u8])
+            .chain(left)
+            .chain(right)
+            .finalize()
+            .into()
+    }
+
+    #[test]
+    fn validate_inclusion() {
+        let a = message(vec![MessageId::new([0; 32])], 1);
+        let b = message(vec![*a.message_id()], 2);
+        let c = message(vec![*a.message_id()], 3);
+        let parents = vec![*b.message_id(), *c.message_id()];
+        // White flag order is a, b, c
+        let merkle_proof = node(node(leaf(a.message_id()), leaf(b.message_id())), leaf(c.message_id()));
+        let mut milestone_data = MilestoneData::new(1, CreatedBy::Incoming);
+        assert!(milestone_data.validate_inclusion().is_err());
+        for full_message in vec![a, b.clone(), c] {
+            milestone_data.add_full_message(full_message);
+        }
+        assert!(milestone_data.verify_inclusion(&parents, &merkle_proof).is_ok());
+        // A lost message changes the root
+        milestone_data.messages.remove(b.message_id());
+        assert!(milestone_data.verify_inclusion(&parents, &merkle_proof).is_err());
+        // So does a tampered inclusion state
+        let mut b = b;
+        b.1.ledger_inclusion_state = Some(LedgerInclusionState::Conflicting);
+        milestone_data.add_full_message(b);
+        assert!(milestone_data.verify_inclusion(&parents, &merkle_proof).is_err());
+    }
+
+    #[test]
+    fn inclusion_proof() {
+        let a = message(vec![MessageId::new([0; 32])], 1);
+        let b = message(vec![*a.message_id()], 2);
+        let c = message(vec![*a.message_id()], 3);
+        let d = message(vec![*b.message_id(), *c.message_id()], 4);
+        let mut milestone_data = MilestoneData::new(1, CreatedBy::Incoming);
+        for full_message in vec![a.clone(), b.clone(), c.clone(), d.clone()] {
+            milestone_data.add_full_message(full_message);
+        }
+        // Missing milestone payload
+        assert!(milestone_data.inclusion_proof(d.message_id()).is_none());
+        milestone_data.set_milestone(milestone_with_parents(1, vec![*d.message_id()]));
+        let included = milestone_data.included_message_ids(&[*d.message_id()]);
+        assert_eq!(included.len(), 4);
+        let merkle_proof = merkle_root(&included);
+        for (index, message_id) in included.iter().enumerate() {
+            let proof = milestone_data.inclusion_proof(message_id).unwrap();
+            assert_eq!((proof.index, proof.count), (index, included.len()));
+            assert!(proof.verify(message_id, &merkle_proof));
+            // The proof does not hold for another message, or another position
+            assert!(!proof.verify(&MessageId::new([7; 32]), &merkle_proof));
+            let moved = InclusionProof {
+                index: (index + 1) % included.len(),
+                ..proof.clone()
+            };
+            assert!(!moved.verify(message_id, &merkle_proof));
+        }
+        assert!(milestone_data.inclusion_proof(&MessageId::new([7; 32])).is_none());
+        // A three leaves tree matches the hand computed root
+        let three = vec![*a.message_id(), *b.message_id(), *c.message_id()];
+        let proof = InclusionProof {
+            index: 2,
+            count: 3,
+            path: merkle_path(&three, 2),
+        };
+        assert_eq!(proof.path, vec![node(leaf(a.message_id()), leaf(b.message_id()))]);
+        assert_eq!(
+            proof.merkle_root(c.message_id()),
+            Some(node(
+                node(leaf(a.message_id()), leaf(b.message_id())),
+                leaf(c.message_id())
+            ))
+        );
+    }
+
+    #[test]
+    fn content_hash_is_order_independent() {
+        let full_messages = (0..16)
+            .map(|network_id| message(vec![MessageId::new([0; 32])], network_id))
+            .collect::<Vec<_>>();
+        let mut milestone_data = MilestoneData::new(1, CreatedBy::Incoming);
+        let mut reversed_milestone_data = MilestoneData::new(1, CreatedBy::Syncer);
+        for full_message in full_messages.iter() {
+            milestone_data.add_full_message(full_message.clone());
+        }
+        for full_message in full_messages.iter().rev() {
+            reversed_milestone_data.add_full_message(full_message.clone());
+        }
+        assert_eq!(milestone_data.content_hash(), reversed_milestone_data.content_hash());
+        // A diverged message set changes the hash
+        reversed_milestone_data.messages.remove(full_messages[0].message_id());
+        assert_ne!(milestone_data.content_hash(), reversed_milestone_data.content_hash());
+    }
+
+    #[test]
+    fn dedup_is_idempotent() {
+        let full_message = message(vec![MessageId::new([0; 32])], 1);
+        let mut milestone_data = MilestoneData::new(1, CreatedBy::Incoming);
+        milestone_data.add_full_message(full_message.clone());
+        // The same message under another key, ie from a retried import
+        milestone_data
+            .messages
+            .insert(MessageId::new([9; 32]), full_message.clone());
+        assert_eq!(milestone_data.dedup(), 1);
+        let first_import = milestone_data.messages.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(first_import, vec![*full_message.message_id()]);
+        // Deduping again, ie importing the same LogFile twice, yields the same messages
+        assert_eq!(milestone_data.dedup(), 0);
+        let second_import = milestone_data.messages.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(first_import, second_import);
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn sorted_export_is_stable() {
+        let full_messages = (0..16)
+            .map(|network_id| message(vec![MessageId::new([0; 32])], network_id))
+            .collect::<Vec<_>>();
+        let mut milestone_data = MilestoneData::new(1, CreatedBy::Incoming);
+        let mut reversed_milestone_data = MilestoneData::new(1, CreatedBy::Incoming);
+        for full_message in full_messages.iter() {
+            milestone_data.add_full_message(full_message.clone());
+        }
+        for full_message in full_messages.iter().rev() {
+            reversed_milestone_data.add_full_message(full_message.clone());
+        }
+        let sorted = milestone_data
+            .messages_sorted()
+            .into_iter()
+            .map(|(message_id, _)| *message_id)
+            .collect::<Vec<_>>();
+        assert!(sorted.windows(2).all(|pair| pair[0] < pair[1]));
+        let export = serde_json::to_string(&milestone_data).unwrap();
+        assert_eq!(export, serde_json::to_string(&reversed_milestone_data).unwrap());
+        assert_eq!(export, serde_json::to_string(&milestone_data).unwrap());
+    }
+
+    #[test]
+    fn created_by_names() {
+        assert_eq!(CreatedBy::Incoming.to_string(), "incoming");
+        assert_eq!(CreatedBy::Expected.to_string(), "expected");
+        assert_eq!(CreatedBy::Syncer.to_string(), "syncer");
+        // the sources round trip through their stored representation
+        for created_by in vec![CreatedBy::Incoming, CreatedBy::Expected, CreatedBy::Syncer] {
+            assert_eq!(
+                <CreatedBy as std::convert::TryFrom<u8>>::try_from(u8::from(created_by)).unwrap(),
+                created_by
+            );
+        }
+        assert!(<CreatedBy as std::convert::TryFrom<u8>>::try_from(3).is_err());
+    }
+
+    #[test]
+    fn token_flows_per_address() {
+        let alice = Address::Ed25519(Ed25519Address::new([1; 32]));
+        let bob = Address::Ed25519(Ed25519Address::new([2; 32]));
+        let mut milestone_data = MilestoneData::new(1, CreatedBy::Incoming);
+        milestone_data.add_full_message(transaction_message(
+            vec![(alice, 1_000_000), (bob, 2_000_000)],
+            LedgerInclusionState::Included,
+        ));
+        milestone_data.add_full_message(transaction_message(
+            vec![(alice, 3_000_000)],
+            LedgerInclusionState::Included,
+        ));
+        // Conflicting transactions did not transfer any token
+        milestone_data.add_full_message(transaction_message(
+            vec![(bob, 5_000_000)],
+            LedgerInclusionState::Conflicting,
+        ));
+        let token_flows = milestone_data.token_flows();
+        assert_eq!(token_flows.len(), 2);
+        assert_eq!(token_flows[&alice], 4_000_000);
+        assert_eq!(token_flows[&bob], 2_000_000);
+    }
+
+    #[test]
+    fn reattachments_are_counted() {
+        let alice = Address::Ed25519(Ed25519Address::new([1; 32]));
+        let attachment = transaction_message(vec![(alice, 1_000_000)], LedgerInclusionState::Included);
+        // The same transaction payload attached twice more, under other parents
+        let reattach = |parent| {
+            let message = MessageBuilder::<Miner>::new()
+                .with_network_id(0)
+                .with_parents(Parents::new(vec![MessageId::new([parent; 32])]).unwrap())
+                .with_payload(attachment.message().payload().clone().unwrap())
+                .finish()
+                .unwrap();
+            let metadata = MessageMetadata {
+                message_id: message.id().0,
+                parent_message_ids: vec![MessageId::new([parent; 32])],
+                ledger_inclusion_state: Some(LedgerInclusionState::Conflicting),
+                ..attachment.metadata().clone()
+            };
+            FullMessage::new(message, metadata)
+        };
+        let mut milestone_data = MilestoneData::new(1, CreatedBy::Incoming);
+        milestone_data.add_full_message(attachment.clone());
+        assert_eq!(**milestone_data.analytic_record().unwrap().reattachment_count(), 0);
+        milestone_data.add_full_message(reattach(1));
+        milestone_data.add_full_message(reattach(2));
+        // An unrelated transaction is not a reattachment
+        milestone_data.add_full_message(transaction_message(
+            vec![(alice, 2_000_000)],
+            LedgerInclusionState::Included,
+        ));
+        milestone_data.add_full_message(message(vec![MessageId::new([0; 32])], 1));
+        assert_eq!(milestone_data.messages.len(), 5);
+        assert_eq!(**milestone_data.analytic_record().unwrap().reattachment_count(), 2);
+    }
+
+    fn milestone(milestone_index: u32) -> Box<MilestonePayload> {
+        milestone_with_parents(milestone_index, vec![MessageId::new([0; 32])])
+    }
+
+    fn milestone_with_parents(milestone_index: u32, parents: Vec<MessageId>) -> Box<MilestonePayload> {
+        use bee_message::{
+            milestone::MilestoneIndex,
+            prelude::{
+                MilestonePayloadEssence,
+                MILESTONE_MERKLE_PROOF_LENGTH,
+            },
+        };
+        let essence = MilestonePayloadEssence::new(
+            MilestoneIndex(milestone_index),
+            0,
+            Parents::new(parents).unwrap(),
+            [0; MILESTONE_MERKLE_PROOF_LENGTH],
+            0,
+            0,
+            vec![[0; 32]],
+            None,
+        )
+        .unwrap();
+        Box::new(MilestonePayload::new(essence, vec![Box::new([0; 64])]).unwrap())
+    }
+
+    #[test]
+    fn milestone_data_builder() {
+        let alice = Address::Ed25519(Ed25519Address::new([1; 32]));
+        let milestone_data = MilestoneDataBuilder::new(1, CreatedBy::Syncer)
+            .with_milestone(milestone(1))
+            .with_message(transaction_message(
+                vec![(alice, 1_000_000)],
+                LedgerInclusionState::Included,
+            ))
+            .with_messages(vec![
+                transaction_message(vec![(alice, 2_000_000)], LedgerInclusionState::Conflicting),
+                message(vec![MessageId::new([0; 32])], 1),
+            ])
+            .build()
+            .unwrap();
+        assert_eq!(milestone_data.milestone_index(), 1);
+        assert_eq!(milestone_data.created_by(), &CreatedBy::Syncer);
+        assert!(milestone_data.check_if_completed());
+        let analytics = milestone_data.get_analytic_record().unwrap();
+        assert_eq!(**analytics.message_count(), 3);
+        assert_eq!(**analytics.transaction_count(), 1);
+        assert_eq!(**analytics.transferred_tokens(), 1_000_000);
+
+        // The milestone payload of another milestone index is rejected
+        assert!(MilestoneDataBuilder::new(2, CreatedBy::Incoming)
+            .with_milestone(milestone(1))
+            .build()
+            .is_err());
+        // Without the milestone payload the analytics are not available yet
+        let milestone_data = MilestoneDataBuilder::new(1, CreatedBy::Incoming).build().unwrap();
+        assert!(milestone_data.get_analytic_record().is_err());
+    }
+
+    #[test]
+    fn incremental_analytics_match_batch() {
+        let alice = Address::Ed25519(Ed25519Address::new([1; 32]));
+        let bob = Address::Ed25519(Ed25519Address::new([2; 32]));
+        let attachment = transaction_message(
+            vec![(alice, 1_000_000), (bob, 2_000_000)],
+            LedgerInclusionState::Included,
+        );
+        let reattachment = {
+            let message = MessageBuilder::<Miner>::new()
+                .with_network_id(0)
+                .with_parents(Parents::new(vec![MessageId::new([1; 32])]).unwrap())
+                .with_payload(attachment.message().payload().clone().unwrap())
+                .finish()
+                .unwrap();
+            let metadata = MessageMetadata {
+                message_id: message.id().0,
+                parent_message_ids: vec![MessageId::new([1; 32])],
+                ledger_inclusion_state: Some(LedgerInclusionState::Conflicting),
+                ..attachment.metadata().clone()
+            };
+            FullMessage::new(message, metadata)
+        };
+        let messages = vec![
+            attachment.clone(),
+            reattachment,
+            transaction_message(vec![(bob, 3_000_000)], LedgerInclusionState::Conflicting),
+            transaction_message(vec![(alice, 4_000_000)], LedgerInclusionState::Included),
+            message(vec![MessageId::new([0; 32])], 1),
+            // streamed twice, ie by a retried import
+            attachment,
+        ];
+        let mut milestone_data = MilestoneData::new(1, CreatedBy::Incoming);
+        let mut accumulator = AnalyticsAccumulator::default();
+        for full_message in messages {
+            accumulator.add_message(&full_message).unwrap();
+            milestone_data.add_full_message(full_message);
+        }
+        let batch = milestone_data.analytic_record().unwrap();
+        let incremental = accumulator.analytic_record(1);
+        assert_eq!(**incremental.message_count(), 5);
+        assert_eq!(**incremental.transaction_count(), 2);
+        assert_eq!(**incremental.transferred_tokens(), 7_000_000);
+        assert_eq!(**incremental.reattachment_count(), 1);
+        assert_eq!(**incremental.milestone_index(), **batch.milestone_index());
+        assert_eq!(**incremental.message_count(), **batch.message_count());
+        assert_eq!(**incremental.transaction_count(), **batch.transaction_count());
+        assert_eq!(**incremental.transferred_tokens(), **batch.transferred_tokens());
+        assert_eq!(**incremental.reattachment_count(), **batch.reattachment_count());
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn feed_status_socket_msg() {
+        let feed_status = FeedStatus {
+            url: Url::parse("tcp://api.hornet-0.testnet.chrysalis2.com:1883").unwrap(),
+            topic_kind: "messages".to_string(),
+            connected: true,
+        };
+        let json = serde_json::to_string(&BrokerSocketMsg::ChronicleBroker(feed_status.clone())).unwrap();
+        assert_eq!(
+            json,
+            r#"{"ChronicleBroker":{"url":"tcp://api.hornet-0.testnet.chrysalis2.com:1883","topic_kind":"messages","connected":true}}"#
+        );
+        match serde_json::from_str::<BrokerSocketMsg<FeedStatus>>(&json).unwrap() {
+            BrokerSocketMsg::ChronicleBroker(deserialized) => assert_eq!(deserialized, feed_status),
+        }
+    }
+
+    #[test]
+    fn parse_flat_topology() {
+        let url = Url::parse("tcp://api.hornet-0.testnet.chrysalis2.com:1883").unwrap();
+        match "add-mqtt-messages tcp://api.hornet-0.testnet.chrysalis2.com:1883".parse() {
+            Ok(BrokerTopology::AddMqttMessages(parsed)) => assert_eq!(parsed, url),
+            _ => panic!("Expected add mqtt messages"),
+        }
+        match "add-mqtt-messages-referenced tcp://api.hornet-0.testnet.chrysalis2.com:1883 --qos 1".parse() {
+            Ok(BrokerTopology::AddMqttMessagesReferencedWithQos(parsed, 1)) => assert_eq!(parsed, url),
+            _ => panic!("Expected add mqtt messages referenced with qos"),
+        }
+        match "remove-mqtt-messages-referenced tcp://api.hornet-0.testnet.chrysalis2.com:1883".parse() {
+            Ok(BrokerTopology::RemoveMqttMessagesReferenced(parsed)) => assert_eq!(parsed, url),
+            _ => panic!("Expected remove mqtt messages referenced"),
+        }
+        match "update-feed-topics tcp://api.hornet-0.testnet.chrysalis2.com:1883 --topics messages,messages/referenced"
+            .parse()
+        {
+            Ok(BrokerTopology::UpdateFeedTopics { url: parsed, topics }) => {
+                assert_eq!(parsed, url);
+                assert_eq!(topics, vec!["messages", "messages/referenced"]);
+            }
+            _ => panic!("Expected update feed topics"),
+        }
+        match "add-endpoint https://api.hornet-0.testnet.chrysalis2.com/api/v1".parse() {
+            Ok(BrokerTopology::Requesters(RequesterTopology::AddEndpoint(parsed))) => {
+                assert_eq!(parsed.as_str(), "https://api.hornet-0.testnet.chrysalis2.com/api/v1")
+            }
+            _ => panic!("Expected add endpoint"),
+        }
+        match "import /logs/ --resume --from 100 --to 200 --batch-size 10".parse() {
+            Ok(BrokerTopology::Import {
+                path,
+                resume,
+                import_range,
+                import_type,
+                verify_checksum,
+                insert_concurrency,
+                batch_size,
+                strict,
+                force,
+            }) => {
+                assert_eq!(path, PathBuf::from("/logs/"));
+                assert!(resume);
+                assert_eq!(import_range, Some(100..200));
+                assert!(matches!(import_type, ImportType::All));
+                assert!(!verify_checksum && !strict && !force);
+                assert_eq!(insert_concurrency, None);
+                assert_eq!(batch_size, Some(10));
+            }
+            _ => panic!("Expected import"),
+        }
+        match "import /logs/ --analytics --from 100 --force".parse() {
+            Ok(BrokerTopology::Import {
+                import_range,
+                import_type,
+                resume,
+                force,
+                ..
+            }) => {
+                assert_eq!(import_range, Some(100..i32::MAX as u32));
+                assert!(matches!(import_type, ImportType::Analytics));
+                assert!(!resume);
+                assert!(force);
+            }
+            _ => panic!("Expected import"),
+        }
+        match "import /logs/".parse() {
+            Ok(BrokerTopology::Import { import_range, .. }) => assert_eq!(import_range, None),
+            _ => panic!("Expected import"),
+        }
+    }
+
+    #[test]
+    fn reject_malformed_flat_topology() {
+        for command in vec![
+            "",
+            "add-mqtt-messages",
+            "add-mqtt-messages not-a-url",
+            "add-mqtt-messages tcp://localhost:1883 --resume",
+            "remove-mqtt-messages tcp://localhost:1883 --qos 1",
+            "import /logs/ --from",
+            "import /logs/ --from abc",
+            "import /logs/ --from 200 --to 100",
+            "import /logs/ --resume --resume",
+            "import /logs/ 100",
+            "prune 100",
+        ] {
+            assert!(command.parse::<BrokerTopology>().is_err(), "{}", command);
+        }
+    }
 }
@@ -304,6 +304,15 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                 }
             }
             let resume = subcommand.is_present("resume");
+            let verify_checksum = subcommand.is_present("verify-checksum");
+            let insert_concurrency = subcommand
+                .value_of("insert-concurrency")
+                .map(|s| s.parse::<usize>())
+                .transpose()?;
+            let batch_size = subcommand
+                .value_of("batch-size")
+                .map(|s| s.parse::<usize>())
+                .transpose()?;
             let (is_url, is_file) = Url::parse(dir)
                 .map(|url| (true, Path::new(url.path()).extension().is_some()))
                 .unwrap_or_else(|_| (false, path.extension().is_some()));
@@ -319,8 +328,13 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                         Ok(start..end)
                     })?
                 }
-                _ => 1..(i32::MAX as u32),
+                _ => {
+                    let sync_range = config.broker_config.sync_range.unwrap_or_default();
+                    sync_range.from..sync_range.to
+                }
             };
+            let force = subcommand.is_present("force");
+            let strict = subcommand.is_present("strict");
             println!(
                 "Path: {}, is_url: {}, is_file: {}, range: {:?}",
                 if is_url { dir.into() } else { path.to_string_lossy() },
@@ -329,7 +343,8 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                 range
             );
             if is_url {
-                panic!("URL imports are not currently supported!");
+                anyhow::ensure!(is_file, "URL imports must point to a single log file!");
+                path = PathBuf::from(dir);
             }
             let import_type = if subcommand.is_present("analytics") {
                 ImportType::Analytics
@@ -352,6 +367,11 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                         resume,
                         import_range: Some(range),
                         import_type,
+                        verify_checksum,
+                        insert_concurrency,
+                        batch_size,
+                        strict,
+                        force,
                     }),
                 ))?))
                 .await?;
@@ -404,8 +424,16 @@ async fn archive<'a>(matches: &ArgMatches<'a>) -> anyhow::Result<()> {
                                                         active_progress_bars.insert((from_ms, to_ms), ());
                                                     }
                                                 }
-                                                ImporterSession::Finish { from_ms, to_ms, msg } => {
-                                                    let m = format!("LogFile: {}to{}.log {}", from_ms, to_ms, msg);
+                                                ImporterSession::Finish {
+                                                    from_ms,
+                                                    to_ms,
+                                                    msg,
+                                                    gaps,
+                                                } => {
+                                                    let mut m = format!("LogFile: {}to{}.log {}", from_ms, to_ms, msg);
+                                                    if !gaps.is_empty() {
+                                                        m.push_str(&format!(", missing milestones: {:?}", gaps));
+                                                    }
                                                     if let Some(()) = active_progress_bars.remove(&(from_ms, to_ms)) {
                                                         pb.set_message(msg);
                                                         pb.println(m);
@@ -2,14 +2,310 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
+use rustls_pemfile::Item;
+use std::{
+    io::{
+        BufReader,
+        ErrorKind,
+    },
+    path::PathBuf,
+};
 /// Configuration for the Chronicle API
-#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq, Clone)]
-pub struct ApiConfig {}
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// The address the http listener binds
+    pub listen_address: SocketAddr,
+    /// The TLS certificate and private key of the listener, absent serves plain http
+    pub tls: Option<TlsConfig>,
+    /// The page size used by the paginated endpoints when the request does not provide one
+    pub default_page_size: usize,
+    /// The upper bound of the page size, larger requested page sizes are clamped to it
+    pub max_page_size: usize,
+    /// The max number of children returned for a message, regardless of the requested page size, zero disables it
+    pub max_children: usize,
+    /// The number of messages kept in the in-memory message cache, zero disables the cache
+    pub message_cache_capacity: usize,
+    /// The time in milliseconds an output id which was not found is remembered as missing, zero disables it
+    pub missing_output_ttl_ms: u64,
+    /// The time in milliseconds a storage query may take before the request fails with a gateway timeout, zero
+    /// disables it
+    pub query_timeout_ms: u64,
+    /// The max number of storage queries in flight at once, zero disables the limit
+    pub max_in_flight_queries: usize,
+    /// The time in milliseconds a query beyond the in-flight limit waits for a free slot before the request fails
+    /// with a service unavailable
+    pub query_queue_timeout_ms: u64,
+    /// The bech32 human readable part of the network addresses, empty defaults it from the first keyspace (or
+    /// `DEFAULT_BECH32_HRP` if the keyspace does not provide one)
+    pub bech32_hrp: String,
+    /// The consistency level of the storage reads served by the api
+    pub read_consistency: ReadConsistency,
+    /// The time in seconds an idle client connection is kept alive, zero disables keep-alive
+    pub keep_alive_secs: u32,
+    /// The max size in bytes of a serialized response, larger responses fail with a payload too large, zero
+    /// disables it
+    pub max_response_bytes: usize,
+}
+
+/// The TLS configuration of the api listener
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// The path of the PEM encoded certificate chain
+    pub cert_path: PathBuf,
+    /// The path of the PEM encoded (PKCS8 or RSA) private key
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    /// Verify that the certificate and the private key files exist and parse
+    pub fn verify(&self) -> anyhow::Result<()> {
+        let certs = Self::read_pem(&self.cert_path)?;
+        ensure!(
+            certs.iter().any(|item| matches!(item, Item::X509Certificate(_))),
+            "Error verifying tls certificate {}, no certificate found!",
+            self.cert_path.display()
+        );
+        let keys = Self::read_pem(&self.key_path)?;
+        ensure!(
+            keys.iter()
+                .any(|item| matches!(item, Item::PKCS8Key(_) | Item::RSAKey(_))),
+            "Error verifying tls private key {}, no private key found!",
+            self.key_path.display()
+        );
+        Ok(())
+    }
+
+    fn read_pem(path: &PathBuf) -> anyhow::Result<Vec<Item>> {
+        std::fs::File::open(path)
+            .and_then(|f| rustls_pemfile::read_all(&mut BufReader::new(f)))
+            .map_err(|e| anyhow!("Error reading tls file {}: {}", path.display(), e))
+    }
+}
+
+/// The consistency level of the api storage reads
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ReadConsistency {
+    /// A single replica responds
+    One,
+    /// A quorum of the replicas in the local datacenter responds
+    LocalQuorum,
+    /// A quorum of the replicas across all the datacenters responds
+    Quorum,
+}
+
+impl Default for ReadConsistency {
+    fn default() -> Self {
+        ReadConsistency::One
+    }
+}
+
+/// The bech32 human readable part of the mainnet addresses
+pub const DEFAULT_BECH32_HRP: &str = "iota";
+
+/// The upper bound of the listener keep-alive, in seconds
+pub const MAX_KEEP_ALIVE_SECS: u32 = 3600;
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            listen_address: ([127, 0, 0, 1], 8000).into(),
+            tls: None,
+            default_page_size: 1000,
+            max_page_size: 1000,
+            max_children: 1000,
+            message_cache_capacity: 10000,
+            missing_output_ttl_ms: 1000,
+            query_timeout_ms: 10000,
+            max_in_flight_queries: 512,
+            query_queue_timeout_ms: 100,
+            bech32_hrp: String::new(),
+            read_consistency: ReadConsistency::default(),
+            keep_alive_secs: 5,
+            max_response_bytes: 16 * 1024 * 1024,
+        }
+    }
+}
 
 impl ApiConfig {
     /// Verify that the api config is valid
     pub async fn verify(&mut self) -> anyhow::Result<()> {
-        // TODO
+        if self.max_page_size == 0 {
+            bail!("Error verifying max page size, zero provided!");
+        }
+        if self.default_page_size == 0 || self.default_page_size > self.max_page_size {
+            bail!(
+                "Error verifying default page size: {}, it must be within 1 and max page size: {}",
+                self.default_page_size,
+                self.max_page_size
+            );
+        }
+        if self.keep_alive_secs > MAX_KEEP_ALIVE_SECS {
+            bail!(
+                "Error verifying keep alive: {} secs, it must be at most {} secs!",
+                self.keep_alive_secs,
+                MAX_KEEP_ALIVE_SECS
+            );
+        }
+        if let Some(tls) = self.tls.as_ref() {
+            tls.verify()?;
+        }
         Ok(())
     }
+
+    /// The scheme the listener serves, which is https if tls is configured
+    pub fn scheme(&self) -> &'static str {
+        if self.tls.is_some() {
+            "https"
+        } else {
+            "http"
+        }
+    }
+
+    /// Verify that the listen address can be bound, which fails if it's already in use or not local to this host.
+    /// This is checked on the listener startup only, as the running listener holds the address.
+    pub fn verify_listen_address(&self) -> anyhow::Result<()> {
+        std::net::TcpListener::bind(self.listen_address)
+            .map(|_| ())
+            .map_err(|e| match e.kind() {
+                ErrorKind::AddrInUse => anyhow!(
+                    "Error binding api listen address {}, it is already in use by another process!",
+                    self.listen_address
+                ),
+                ErrorKind::AddrNotAvailable => anyhow!(
+                    "Error binding api listen address {}, it is not an address of this host!",
+                    self.listen_address
+                ),
+                _ => anyhow!("Error binding api listen address {}: {}", self.listen_address, e),
+            })
+    }
+
+    /// Get the effective bech32 human readable part, which is the configured one, or if it's not configured the
+    /// provided one (ie the storage keyspace one) or the mainnet one
+    pub fn effective_bech32_hrp(&self, network_hrp: Option<&String>) -> String {
+        if self.bech32_hrp.is_empty() {
+            network_hrp.cloned().unwrap_or_else(|| DEFAULT_BECH32_HRP.to_string())
+        } else {
+            self.bech32_hrp.clone()
+        }
+    }
+
+    /// Get the effective page size of a request, which is the requested page size (or the default one if absent)
+    /// clamped to the max page size
+    pub fn page_size(&self, requested: Option<usize>) -> usize {
+        requested
+            .unwrap_or(self.default_page_size)
+            .clamp(1, self.max_page_size.max(1))
+    }
+
+    /// Get the effective page size of a children request, which is the effective page size capped to the max
+    /// children
+    pub fn children_page_size(&self, requested: Option<usize>) -> usize {
+        match self.max_children {
+            0 => self.page_size(requested),
+            max_children => self.page_size(requested).min(max_children),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_size_default() {
+        let config = ApiConfig::default();
+        assert_eq!(config.page_size(None), config.default_page_size);
+        assert_eq!(config.page_size(Some(50)), 50);
+    }
+
+    #[test]
+    fn page_size_clamp() {
+        let config = ApiConfig {
+            default_page_size: 10,
+            max_page_size: 20,
+            ..Default::default()
+        };
+        assert_eq!(config.page_size(Some(1_000_000)), 20);
+        assert_eq!(config.page_size(Some(0)), 1);
+        assert_eq!(config.page_size(Some(20)), 20);
+    }
+
+    #[test]
+    fn listen_address_in_use() {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let config = ApiConfig {
+            listen_address: listener.local_addr().unwrap(),
+            ..Default::default()
+        };
+        let error = config.verify_listen_address().unwrap_err();
+        assert!(error.to_string().contains("already in use"), "{}", error);
+        drop(listener);
+        assert!(config.verify_listen_address().is_ok());
+    }
+
+    #[test]
+    fn tls_verification() {
+        let mut config = ApiConfig::default();
+        assert_eq!(config.scheme(), "http");
+        let tls = TlsConfig {
+            cert_path: "../fixtures/tls.cert.pem".into(),
+            key_path: "../fixtures/tls.key.pem".into(),
+        };
+        tls.verify().unwrap();
+        config.tls = Some(tls);
+        assert_eq!(config.scheme(), "https");
+        // swapped files do not parse as the expected items
+        let swapped = TlsConfig {
+            cert_path: "../fixtures/tls.key.pem".into(),
+            key_path: "../fixtures/tls.cert.pem".into(),
+        };
+        assert!(swapped.verify().is_err());
+        let missing = TlsConfig {
+            cert_path: "../fixtures/missing.cert.pem".into(),
+            key_path: "../fixtures/tls.key.pem".into(),
+        };
+        let error = missing.verify().unwrap_err();
+        assert!(error.to_string().contains("missing.cert.pem"), "{}", error);
+    }
+
+    #[test]
+    fn bech32_hrp_default() {
+        let config = ApiConfig::default();
+        assert_eq!(config.effective_bech32_hrp(None), DEFAULT_BECH32_HRP);
+        assert_eq!(config.effective_bech32_hrp(Some(&"atoi".to_string())), "atoi");
+        // a configured one is kept
+        let config = ApiConfig {
+            bech32_hrp: "atoi".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.effective_bech32_hrp(Some(&"iota".to_string())), "atoi");
+    }
+
+    #[test]
+    fn children_page_size_cap() {
+        let config = ApiConfig {
+            max_children: 50,
+            ..Default::default()
+        };
+        assert_eq!(config.children_page_size(None), 50);
+        assert_eq!(config.children_page_size(Some(20)), 20);
+        let config = ApiConfig {
+            max_children: 0,
+            ..Default::default()
+        };
+        assert_eq!(config.children_page_size(Some(500)), 500);
+    }
+
+    #[test]
+    fn listener_tuning_bounds() {
+        let mut config = ApiConfig {
+            keep_alive_secs: MAX_KEEP_ALIVE_SECS,
+            ..Default::default()
+        };
+        futures::executor::block_on(config.verify()).unwrap();
+        config.keep_alive_secs = MAX_KEEP_ALIVE_SECS + 1;
+        let error = futures::executor::block_on(config.verify()).unwrap_err();
+        assert!(error.to_string().contains("keep alive"), "{}", error);
+    }
 }
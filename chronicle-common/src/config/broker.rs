@@ -12,6 +12,11 @@ use serde_json::Value;
 use std::{
     collections::HashSet,
     net::SocketAddr,
+    ops::Range,
+    path::{
+        Path,
+        PathBuf,
+    },
 };
 use url::Url;
 
@@ -24,6 +29,9 @@ pub struct BrokerConfig {
     pub mqtt_brokers: HashMap<MqttType, HashSet<Url>>,
     /// Mqtt stream capacity
     pub mqtt_stream_capacity: usize,
+    /// The MQTT QoS level (0 or 1) of the feed subscriptions
+    #[serde(default)]
+    pub mqtt_qos: u8,
     /// API endpoints the broker will use to request missing data
     pub api_endpoints: HashSet<Url>,
     /// Retries per api endpoint.
@@ -48,6 +56,128 @@ pub struct BrokerConfig {
     pub logs_dir: Option<String>,
     /// The maximum log file size
     pub max_log_size: Option<u64>,
+    /// The feed sources to collectors channel config
+    #[serde(default)]
+    pub feed_channel: FeedChannelConfig,
+    /// The number of recently seen feed events per collector, whose duplicates are dropped (0 disables it)
+    #[serde(default = "default_feed_dedup_window")]
+    pub feed_dedup_window: usize,
+    /// The max width of a requested import range, wider ones are rejected unless forced
+    #[serde(default = "default_max_import_range")]
+    pub max_import_range: u32,
+    /// Bounds the time a milestone may take to solidify
+    #[serde(default)]
+    pub solidification_timeout: SolidificationTimeoutConfig,
+    /// The consistency level of the sync record writes
+    #[serde(default)]
+    pub sync_write_consistency: WriteConsistency,
+    /// The number of gap ranges the syncer works in parallel, each one bounded by the `parallelism`
+    #[serde(default = "default_syncer_concurrency")]
+    pub syncer_concurrency: u8,
+    /// The time in seconds a skipped (expected) milestone may stay missing before it's requested, zero disables it
+    #[serde(default = "default_expected_timeout_secs")]
+    pub expected_timeout_secs: u64,
+    /// The dead-letter log file of the feed messages which fail to deserialize, None disables it
+    #[serde(default)]
+    pub dead_letter_path: Option<String>,
+    /// The keyspaces the feed is mirrored into, next to the default (first) storage keyspace, each one must be a
+    /// configured storage keyspace
+    #[serde(default)]
+    pub mirror_keyspaces: Vec<String>,
+}
+
+fn default_max_import_range() -> u32 {
+    1_000_000
+}
+
+fn default_syncer_concurrency() -> u8 {
+    1
+}
+
+fn default_expected_timeout_secs() -> u64 {
+    30
+}
+
+fn default_feed_dedup_window() -> usize {
+    10000
+}
+
+/// The consistency level of the broker storage writes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum WriteConsistency {
+    /// A single replica acknowledges
+    One,
+    /// A quorum of the replicas in the local datacenter acknowledges
+    LocalQuorum,
+    /// A quorum of the replicas across all the datacenters acknowledges
+    Quorum,
+}
+
+impl Default for WriteConsistency {
+    fn default() -> Self {
+        WriteConsistency::One
+    }
+}
+
+/// Bounds the feed sources (mqtt) to collector channel
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub struct FeedChannelConfig {
+    /// The maximum number of pending feed events per collector
+    pub capacity: usize,
+    /// The policy to apply once the channel is full
+    pub overflow_policy: FeedOverflowPolicy,
+}
+
+impl Default for FeedChannelConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 10000,
+            overflow_policy: FeedOverflowPolicy::Block,
+        }
+    }
+}
+
+/// The policy applied by the feed sources when the collector channel is full
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum FeedOverflowPolicy {
+    /// Apply backpressure by awaiting till the collector catches up
+    Block,
+    /// Drop the feed event and increment the `dropped_feed_events` metric
+    Drop,
+}
+
+/// Bounds the time the solidifiers hold a partially solidified milestone data
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub struct SolidificationTimeoutConfig {
+    /// The time in seconds a milestone data may stay unsolidified, zero disables the timeout
+    pub timeout_secs: u64,
+    /// The policy to apply once the timeout is reached
+    pub policy: SolidificationTimeoutPolicy,
+}
+
+impl Default for SolidificationTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 0,
+            policy: SolidificationTimeoutPolicy::Drop,
+        }
+    }
+}
+
+impl SolidificationTimeoutConfig {
+    /// Get the timeout, if it's enabled
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        (self.timeout_secs > 0).then(|| std::time::Duration::from_secs(self.timeout_secs))
+    }
+}
+
+/// The policy applied by the solidifiers to a milestone data which did not solidify in time
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum SolidificationTimeoutPolicy {
+    /// Pass the partial milestone data, with its pending set, to the archiver
+    Persist,
+    /// Drop the partial milestone data
+    Drop,
 }
 
 /// Enumerated MQTT feed source type
@@ -71,6 +201,7 @@ impl Default for BrokerConfig {
             complete_gaps_interval_secs: 60 * 60,
             websocket_address: ([127, 0, 0, 1], 9000).into(),
             mqtt_stream_capacity: 10000,
+            mqtt_qos: 0,
             mqtt_brokers: hashmap! {
                 MqttType::Messages => hashset![
                     url::Url::parse("tcp://api.hornet-0.testnet.chrysalis2.com:1883").unwrap(),
@@ -89,6 +220,15 @@ impl Default for BrokerConfig {
             sync_range: Some(Default::default()),
             logs_dir: Some("chronicle/logs/".to_owned()),
             max_log_size: Some(4 * 1024 * 1024 * 1024),
+            feed_channel: Default::default(),
+            feed_dedup_window: default_feed_dedup_window(),
+            max_import_range: default_max_import_range(),
+            solidification_timeout: Default::default(),
+            sync_write_consistency: WriteConsistency::default(),
+            syncer_concurrency: default_syncer_concurrency(),
+            expected_timeout_secs: default_expected_timeout_secs(),
+            dead_letter_path: None,
+            mirror_keyspaces: Vec::new(),
         }
     }
 }
@@ -115,6 +255,16 @@ impl BrokerConfig {
         for endpoint in self.api_endpoints.iter() {
             Self::verify_endpoint(&client, endpoint).await?
         }
+        if self.mqtt_qos > 1 {
+            bail!("Error verifying mqtt qos: {}, it must be either 0 or 1", self.mqtt_qos);
+        }
+        if self.feed_channel.capacity == 0 {
+            bail!("Error verifying feed channel capacity, zero provided!");
+        }
+        if self.syncer_concurrency == 0 {
+            bail!("Error verifying syncer concurrency, zero provided!");
+        }
+        self.verify_logs_dir()?;
         let sync_range = self.sync_range.get_or_insert_with(|| SyncRange::default());
         if sync_range.from == 0 || sync_range.to == 0 {
             bail!("Error verifying sync from/to, zero provided!\nPlease provide non-zero milestone index");
@@ -123,6 +273,72 @@ impl BrokerConfig {
         }
         Ok(())
     }
+    /// Verify that the logs directory, if provided, or its closest existing ancestor, which the archiver creates it in
+    /// once the logs start, is a writable directory
+    pub fn verify_logs_dir(&self) -> anyhow::Result<()> {
+        if let Some(logs_dir) = self.logs_dir.as_ref() {
+            let logs_dir = PathBuf::from(logs_dir);
+            let existing = logs_dir
+                .ancestors()
+                .find(|path| path.exists())
+                .unwrap_or_else(|| Path::new("."));
+            let metadata = std::fs::metadata(existing)
+                .map_err(|e| anyhow!("Error verifying logs directory {}: {}", logs_dir.display(), e))?;
+            if !metadata.is_dir() {
+                bail!(
+                    "Error verifying logs directory {}, {} is not a directory",
+                    logs_dir.display(),
+                    existing.display()
+                );
+            }
+            if metadata.permissions().readonly() {
+                bail!(
+                    "Error verifying logs directory {}, {} is not writable",
+                    logs_dir.display(),
+                    existing.display()
+                );
+            }
+        }
+        Ok(())
+    }
+    /// Verify that the mirror keyspaces are distinct configured storage keyspaces, other than the default (first) one
+    pub fn verify_mirror_keyspaces(&self, keyspaces: &[KeyspaceConfig]) -> anyhow::Result<()> {
+        let mut mirrors = HashSet::new();
+        for mirror in self.mirror_keyspaces.iter() {
+            if !keyspaces.iter().any(|keyspace| &keyspace.name == mirror) {
+                bail!(
+                    "Error verifying mirror keyspace {}, it's not a configured storage keyspace",
+                    mirror
+                );
+            }
+            if keyspaces
+                .first()
+                .map(|keyspace| &keyspace.name == mirror)
+                .unwrap_or_default()
+            {
+                bail!("Error verifying mirror keyspace {}, it's the default keyspace", mirror);
+            }
+            if !mirrors.insert(mirror) {
+                bail!("Error verifying mirror keyspace {}, it's listed more than once", mirror);
+            }
+        }
+        Ok(())
+    }
+    /// Verify that the requested import range, where none imports every milestone, is within the max import range,
+    /// unless the import is forced
+    pub fn verify_import_range(&self, import_range: Option<&Range<u32>>, force: bool) -> anyhow::Result<()> {
+        let import_range = import_range.cloned().unwrap_or(0..u32::MAX);
+        let width = import_range.end.saturating_sub(import_range.start);
+        if !force && width > self.max_import_range {
+            bail!(
+                "Import range {:?} ({} milestones) exceeds the max import range of {} milestones, force the import to proceed",
+                import_range,
+                width,
+                self.max_import_range
+            );
+        }
+        Ok(())
+    }
     /// Adjust IOTA api endpoint url and ensure it's correct or return None otherwise
     pub fn adjust_api_endpoint(endpoint: Url) -> Option<Url> {
         let path = endpoint.as_str();
@@ -167,3 +383,70 @@ impl BrokerConfig {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logs_dir_verification() {
+        let logs_dir = std::env::temp_dir().join(format!("chronicle_logs_{}", rand::random::<u64>()));
+        let config: BrokerConfig = ron::from_str(
+            &ron::to_string(&BrokerConfig {
+                logs_dir: Some(logs_dir.to_string_lossy().into_owned()),
+                ..Default::default()
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        assert_ne!(config.logs_dir, BrokerConfig::default().logs_dir);
+        assert!(config.verify_logs_dir().is_ok());
+        // Verifying has no side effects, the directory is created once the logs start
+        assert!(!logs_dir.exists());
+        std::fs::create_dir_all(&logs_dir).unwrap();
+        assert!(config.verify_logs_dir().is_ok());
+        // A directory nested in a file can't be created, nor written to
+        let file = logs_dir.join("file");
+        std::fs::write(&file, []).unwrap();
+        let config = BrokerConfig {
+            logs_dir: Some(file.join("logs").to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+        assert!(config.verify_logs_dir().is_err());
+        std::fs::remove_dir_all(logs_dir).unwrap();
+    }
+
+    #[test]
+    fn import_range_guard() {
+        let config = BrokerConfig {
+            max_import_range: 100,
+            ..Default::default()
+        };
+        // no range imports every milestone
+        assert!(config.verify_import_range(None, false).is_err());
+        assert!(config.verify_import_range(None, true).is_ok());
+        assert!(config.verify_import_range(Some(&(1..101)), false).is_ok());
+        assert!(config.verify_import_range(Some(&(0..u32::MAX)), false).is_err());
+        assert!(config.verify_import_range(Some(&(0..u32::MAX)), true).is_ok());
+    }
+
+    #[test]
+    fn mirror_keyspaces_verification() {
+        let keyspace = |name: &str| KeyspaceConfig {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let keyspaces = vec![keyspace("permanode"), keyspace("mirror")];
+        let config = |mirrors: &[&str]| BrokerConfig {
+            mirror_keyspaces: mirrors.iter().map(|mirror| mirror.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(config(&[]).verify_mirror_keyspaces(&keyspaces).is_ok());
+        assert!(config(&["mirror"]).verify_mirror_keyspaces(&keyspaces).is_ok());
+        assert!(config(&["unknown"]).verify_mirror_keyspaces(&keyspaces).is_err());
+        assert!(config(&["permanode"]).verify_mirror_keyspaces(&keyspaces).is_err());
+        assert!(config(&["mirror", "mirror"])
+            .verify_mirror_keyspaces(&keyspaces)
+            .is_err());
+    }
+}
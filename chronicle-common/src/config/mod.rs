@@ -22,10 +22,12 @@ use std::{
     path::Path,
 };
 pub use storage::*;
+pub use telemetry::*;
 
 mod api;
 mod broker;
 mod storage;
+mod telemetry;
 
 /// The default config file path
 pub const CONFIG_PATH: &str = "./config.ron";
@@ -210,6 +212,9 @@ pub struct Config {
     pub api_config: ApiConfig,
     /// Broker configuration
     pub broker_config: BrokerConfig,
+    /// Telemetry (OpenTelemetry exporter) configuration
+    #[serde(default)]
+    pub telemetry_config: TelemetryConfig,
     /// Historical config file path
     pub historical_config_path: String,
 }
@@ -221,6 +226,7 @@ impl Default for Config {
             storage_config: Default::default(),
             api_config: Default::default(),
             broker_config: Default::default(),
+            telemetry_config: Default::default(),
             historical_config_path: HISTORICAL_CONFIG_PATH.to_owned(),
         }
     }
@@ -250,6 +256,9 @@ impl Config {
         self.storage_config.verify().await?;
         self.api_config.verify().await?;
         self.broker_config.verify().await?;
+        self.broker_config
+            .verify_mirror_keyspaces(&self.storage_config.keyspaces)?;
+        self.telemetry_config.verify().await?;
         Ok(self)
     }
 }
@@ -285,6 +294,9 @@ mod test {
                             replication_factor: 1,
                         },
                     },
+                    bech32_hrp: None,
+                    replication_strategy: ReplicationStrategy::NetworkTopologyStrategy,
+                    row_ttl_secs: None,
                 }],
                 listen_address: ([127, 0, 0, 1], 8080).into(),
                 thread_count: ThreadCount::CoreMultiple(1),
@@ -292,8 +304,30 @@ mod test {
                 local_datacenter: "datacenter1".to_owned(),
                 nodes: hashset![([127, 0, 0, 1], 9042).into()],
                 partition_config: PartitionConfig::default(),
+                add_nodes_retry: RetryConfig {
+                    max_attempts: 10,
+                    initial_backoff_ms: 500,
+                    max_backoff_ms: 30000,
+                },
+                create_schema: true,
+                sync_page_size: 5000,
+            },
+            api_config: ApiConfig {
+                listen_address: ([127, 0, 0, 1], 8000).into(),
+                tls: None,
+                default_page_size: 1000,
+                max_page_size: 1000,
+                max_children: 1000,
+                message_cache_capacity: 10000,
+                missing_output_ttl_ms: 1000,
+                query_timeout_ms: 10000,
+                max_in_flight_queries: 512,
+                query_queue_timeout_ms: 100,
+                bech32_hrp: "iota".to_string(),
+                read_consistency: ReadConsistency::One,
+                keep_alive_secs: 5,
+                max_response_bytes: 16777216,
             },
-            api_config: ApiConfig {},
             broker_config: BrokerConfig {
                 collector_count: 10,
                 requester_count: 10,
@@ -304,6 +338,7 @@ mod test {
                 complete_gaps_interval_secs: 3600,
                 websocket_address: ([127, 0, 0, 1], 9000).into(),
                 mqtt_stream_capacity: 10000,
+                mqtt_qos: 0,
                 mqtt_brokers: hashmap! {
                     MqttType::Messages => hashset![
                         url::Url::parse("tcp://api.hornet-0.testnet.chrysalis2.com:1883").unwrap(),
@@ -322,6 +357,26 @@ mod test {
                 sync_range: Some(SyncRange::default()),
                 logs_dir: Some("chronicle/logs/".to_owned()),
                 max_log_size: Some(4294967296),
+                feed_channel: FeedChannelConfig {
+                    capacity: 10000,
+                    overflow_policy: FeedOverflowPolicy::Block,
+                },
+                feed_dedup_window: 10000,
+                max_import_range: 1000000,
+                solidification_timeout: SolidificationTimeoutConfig {
+                    timeout_secs: 0,
+                    policy: SolidificationTimeoutPolicy::Drop,
+                },
+                sync_write_consistency: WriteConsistency::One,
+                syncer_concurrency: 1,
+                expected_timeout_secs: 30,
+                dead_letter_path: None,
+                mirror_keyspaces: Vec::new(),
+            },
+            telemetry_config: TelemetryConfig {
+                otlp_endpoint: None,
+                service_name: "chronicle".to_owned(),
+                log_format: LogFormat::Text,
             },
             historical_config_path: HISTORICAL_CONFIG_PATH.to_owned(),
         };
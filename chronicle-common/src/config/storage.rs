@@ -17,6 +17,9 @@ pub type DatacenterName = String;
 /// Type alias for scylla keysapce names
 pub type KeyspaceName = String;
 
+/// The max TTL of the Scylla writes (20 years)
+pub const MAX_ROW_TTL_SECS: u32 = 20 * 365 * 24 * 60 * 60;
+
 /// Enum specifying a thread count
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub enum ThreadCount {
@@ -32,6 +35,16 @@ impl Default for ThreadCount {
     }
 }
 
+impl ThreadCount {
+    /// Resolve the number of threads, a core multiple is relative to the available cores
+    pub fn resolve(&self) -> usize {
+        match self {
+            ThreadCount::Count(c) => *c,
+            ThreadCount::CoreMultiple(c) => num_cpus::get() * c,
+        }
+    }
+}
+
 /// Scylla storage configuration. Defines data which can be used
 /// to construct and access the scylla cluster.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -52,6 +65,54 @@ pub struct StorageConfig {
     /// The partition config
     #[serde(default)]
     pub partition_config: PartitionConfig,
+    /// The retry policy of the nodes registration on startup
+    #[serde(default)]
+    pub add_nodes_retry: RetryConfig,
+    /// Whether the keyspace and its tables are created on startup, otherwise they are only verified to exist
+    #[serde(default = "default_create_schema")]
+    pub create_schema: bool,
+    /// The number of sync rows read per page of the sync table select
+    #[serde(default = "default_sync_page_size")]
+    pub sync_page_size: u32,
+}
+
+fn default_create_schema() -> bool {
+    true
+}
+
+fn default_sync_page_size() -> u32 {
+    5000
+}
+
+/// Bounded exponential backoff retry policy
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct RetryConfig {
+    /// The maximum number of attempts before giving up
+    pub max_attempts: u32,
+    /// The backoff after the first failed attempt, which doubles after each failed attempt
+    pub initial_backoff_ms: u64,
+    /// The upper bound of the backoff
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 10,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30000,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Get the backoff after the given (1-based) failed attempt
+    pub fn backoff(&self, attempt: u32) -> std::time::Duration {
+        let backoff = self
+            .initial_backoff_ms
+            .saturating_mul(1u64.checked_shl(attempt.saturating_sub(1)).unwrap_or(u64::MAX));
+        std::time::Duration::from_millis(backoff.min(self.max_backoff_ms))
+    }
 }
 
 impl Default for StorageConfig {
@@ -64,6 +125,9 @@ impl Default for StorageConfig {
             local_datacenter: "datacenter1".to_string(),
             nodes: hashset![([127, 0, 0, 1], 9042).into()],
             partition_config: Default::default(),
+            add_nodes_retry: Default::default(),
+            create_schema: default_create_schema(),
+            sync_page_size: default_sync_page_size(),
         }
     }
 }
@@ -71,11 +135,11 @@ impl Default for StorageConfig {
 impl StorageConfig {
     /// Verify that the storage config is valid
     pub async fn verify(&mut self) -> anyhow::Result<()> {
-        if self
-            .keyspaces
-            .iter()
-            .any(|k| k.data_centers.iter().any(|dc| dc.1.replication_factor == 0))
-        {
+        if self.keyspaces.iter().any(|k| {
+            k.replication_factors()
+                .iter()
+                .any(|replication_factor| *replication_factor == 0)
+        }) {
             bail!("replication_factor must be greater than zero, ensure your config is correct");
         }
         if self.reporter_count.eq(&0) {
@@ -84,8 +148,46 @@ impl StorageConfig {
         if self.local_datacenter.eq(&"") {
             bail!("local_datacenter must be non-empty string, ensure your config is correct");
         }
+        if self.add_nodes_retry.max_attempts == 0 {
+            bail!("add_nodes_retry max_attempts must be greater than zero, ensure your config is correct");
+        }
+        if self.sync_page_size == 0 || self.sync_page_size > i32::MAX as u32 {
+            bail!(
+                "sync_page_size must be within 1..={}, ensure your config is correct",
+                i32::MAX
+            );
+        }
+        for keyspace in self.keyspaces.iter() {
+            if let Some(row_ttl_secs) = keyspace.row_ttl_secs {
+                if row_ttl_secs == 0 || row_ttl_secs > MAX_ROW_TTL_SECS {
+                    bail!(
+                        "row_ttl_secs of keyspace {} must be within 1..={}, ensure your config is correct",
+                        keyspace.name,
+                        MAX_ROW_TTL_SECS
+                    );
+                }
+            }
+        }
+        self.verify_thread_count()?;
         Ok(())
     }
+    /// Get the row TTL of the configured keyspace, None if it keeps its rows forever or it's not configured
+    pub fn row_ttl_secs(&self, keyspace: &str) -> Option<u32> {
+        self.keyspaces
+            .iter()
+            .find(|keyspace_config| keyspace_config.name == keyspace)
+            .and_then(|keyspace_config| keyspace_config.row_ttl_secs)
+    }
+    /// Verify that the thread count resolves to at least one thread, returns the resolved thread count
+    pub fn verify_thread_count(&self) -> anyhow::Result<usize> {
+        match self.thread_count.resolve() {
+            0 => bail!(
+                "thread_count {:?} resolves to zero threads, ensure your config is correct",
+                self.thread_count
+            ),
+            thread_count => Ok(thread_count),
+        }
+    }
 }
 
 /// Configuration for a scylla keyspace
@@ -95,6 +197,30 @@ pub struct KeyspaceConfig {
     pub name: KeyspaceName,
     /// Datacenters configured for this keyspace, keyed by name
     pub data_centers: HashMap<DatacenterName, DatacenterConfig>,
+    /// The bech32 human readable part of the network addresses stored in this keyspace, the api one if absent
+    #[serde(default)]
+    pub bech32_hrp: Option<String>,
+    /// The replication strategy used to create this keyspace
+    #[serde(default)]
+    pub replication_strategy: ReplicationStrategy,
+    /// The TTL in seconds of the rows written to this keyspace, after which they expire, None keeps them forever
+    #[serde(default)]
+    pub row_ttl_secs: Option<u32>,
+}
+
+/// The replication strategy of a scylla keyspace
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum ReplicationStrategy {
+    /// Replicate the keyspace with the provided replication factor, regardless of the datacenters
+    SimpleStrategy(u8),
+    /// Replicate the keyspace with the replication factor of each configured datacenter
+    NetworkTopologyStrategy,
+}
+
+impl Default for ReplicationStrategy {
+    fn default() -> Self {
+        ReplicationStrategy::NetworkTopologyStrategy
+    }
 }
 
 impl Default for KeyspaceConfig {
@@ -109,19 +235,69 @@ impl Default for KeyspaceConfig {
                     replication_factor: 1,
                 },
             },
+            bech32_hrp: None,
+            replication_strategy: Default::default(),
+            row_ttl_secs: None,
         }
     }
 }
+
+impl KeyspaceConfig {
+    /// Get the replication map of this keyspace, ie `{'class': 'SimpleStrategy', 'replication_factor': 2}`
+    pub fn replication(&self) -> String {
+        match self.replication_strategy {
+            ReplicationStrategy::SimpleStrategy(replication_factor) => format!(
+                "{{'class': 'SimpleStrategy', 'replication_factor': {}}}",
+                replication_factor
+            ),
+            ReplicationStrategy::NetworkTopologyStrategy => {
+                let mut data_centers = self.data_centers.iter().collect::<Vec<_>>();
+                data_centers.sort_unstable_by_key(|(datacenter_name, _)| *datacenter_name);
+                let data_centers = data_centers
+                    .iter()
+                    .map(|(datacenter_name, datacenter_config)| {
+                        format!("'{}': {}", datacenter_name, datacenter_config.replication_factor)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{{'class': 'NetworkTopologyStrategy', {}}}", data_centers)
+            }
+        }
+    }
+
+    /// Get the statement which creates this keyspace with its replication strategy
+    pub fn create_keyspace_statement(&self) -> String {
+        format!(
+            "CREATE KEYSPACE IF NOT EXISTS {0}
+                WITH replication = {1}
+                AND durable_writes = true;",
+            self.name,
+            self.replication()
+        )
+    }
+
+    /// Get the replication factors of this keyspace
+    fn replication_factors(&self) -> Vec<u8> {
+        match self.replication_strategy {
+            ReplicationStrategy::SimpleStrategy(replication_factor) => vec![replication_factor],
+            ReplicationStrategy::NetworkTopologyStrategy => self
+                .data_centers
+                .values()
+                .map(|data_center_config| data_center_config.replication_factor)
+                .collect(),
+        }
+    }
+}
+
 impl StorageConfig {
-    /// Try to get the uniform replication factor, which is the lowest rf in all keyspace across all dc
+    /// Try to get the uniform replication factor, which is the lowest rf in all keyspace across all dc (or the simple
+    /// strategy rf)
     pub fn try_get_uniform_rf(&self) -> Option<u8> {
         // collect all data_centers from all keyspaces
         let mut replication_factors = Vec::new();
-        self.keyspaces.iter().for_each(|k| {
-            for (_, data_center_config) in k.data_centers.iter() {
-                replication_factors.push(data_center_config.replication_factor)
-            }
-        });
+        self.keyspaces
+            .iter()
+            .for_each(|k| replication_factors.extend(k.replication_factors()));
         replication_factors.iter().min().and_then(|e| Some(*e))
     }
 }
@@ -163,3 +339,42 @@ impl PartitionConfig {
         ((milestone_index / self.milestone_chunk_size) % (self.partition_count as u32)) as u16
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_keyspace_statement() {
+        let mut keyspace_config = KeyspaceConfig::default();
+        assert_eq!(
+            keyspace_config.replication(),
+            "{'class': 'NetworkTopologyStrategy', 'Canada': 1, 'USA': 2}"
+        );
+        keyspace_config.replication_strategy = ReplicationStrategy::SimpleStrategy(3);
+        let statement = keyspace_config.create_keyspace_statement();
+        assert!(statement.starts_with("CREATE KEYSPACE IF NOT EXISTS permanode"));
+        assert!(statement.contains("WITH replication = {'class': 'SimpleStrategy', 'replication_factor': 3}"));
+        let storage_config = StorageConfig {
+            keyspaces: vec![keyspace_config],
+            ..Default::default()
+        };
+        assert_eq!(storage_config.try_get_uniform_rf(), Some(3));
+    }
+
+    #[test]
+    fn thread_count_verification() {
+        let config = |thread_count| StorageConfig {
+            thread_count,
+            ..Default::default()
+        };
+        assert!(config(ThreadCount::Count(0)).verify_thread_count().is_err());
+        assert!(config(ThreadCount::CoreMultiple(0)).verify_thread_count().is_err());
+        assert_eq!(config(ThreadCount::Count(4)).verify_thread_count().unwrap(), 4);
+        assert_eq!(
+            config(ThreadCount::CoreMultiple(2)).verify_thread_count().unwrap(),
+            2 * num_cpus::get()
+        );
+        assert!(config(ThreadCount::default()).verify_thread_count().unwrap() >= 1);
+    }
+}
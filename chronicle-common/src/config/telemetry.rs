@@ -0,0 +1,60 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use url::Url;
+
+/// Configuration for the OpenTelemetry (OTLP) exporter, used only when built with the `otel` feature
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    /// The OTLP collector endpoint which receives the spans, the exporter is disabled if not provided
+    pub otlp_endpoint: Option<Url>,
+    /// The service name attached to the exported spans
+    pub service_name: String,
+    /// The format of the log lines
+    pub log_format: LogFormat,
+}
+
+/// The format of the log lines
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable lines
+    Text,
+    /// A JSON object per line
+    Json,
+}
+
+impl Default for LogFormat {
+    fn default() -> Self {
+        LogFormat::Text
+    }
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: "chronicle".to_owned(),
+            log_format: LogFormat::default(),
+        }
+    }
+}
+
+impl TelemetryConfig {
+    /// Verify that the telemetry config is valid
+    pub async fn verify(&mut self) -> anyhow::Result<()> {
+        if self.service_name.is_empty() {
+            bail!("Error verifying telemetry service name, empty name provided!");
+        }
+        if let Some(endpoint) = self.otlp_endpoint.as_ref() {
+            if endpoint.scheme() != "http" && endpoint.scheme() != "https" {
+                bail!(
+                    "Error verifying otlp endpoint {}, expected an http(s) endpoint!",
+                    endpoint
+                );
+            }
+        }
+        Ok(())
+    }
+}
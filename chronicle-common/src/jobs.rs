@@ -0,0 +1,190 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use std::sync::{
+    Arc,
+    PoisonError,
+    RwLock as SyncRwLock,
+};
+
+lazy_static! {
+    /// The active long running jobs of the broker
+    pub static ref JOBS: ActiveJobs = ActiveJobs::default();
+}
+
+/// The shared registry of the active jobs, which is updated by the broker and read by the api
+#[derive(Debug, Clone, Default)]
+pub struct ActiveJobs(Arc<SyncRwLock<Jobs>>);
+
+impl ActiveJobs {
+    /// Get a snapshot of the active jobs
+    pub fn snapshot(&self) -> Jobs {
+        self.0.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+    /// Update the active jobs
+    pub fn update<F: FnOnce(&mut Jobs)>(&self, f: F) {
+        f(&mut *self.0.write().unwrap_or_else(PoisonError::into_inner))
+    }
+    /// Register the import of the (from_ms, to_ms) LogFile, which stays active as long as the returned guard lives
+    pub fn track_import(&self, from_ms: u32, to_ms: u32, log_file_size: u64) -> ImportJobGuard {
+        self.update(|jobs| jobs.import_started(from_ms, to_ms, log_file_size));
+        ImportJobGuard {
+            jobs: self.clone(),
+            from_ms,
+            to_ms,
+        }
+    }
+}
+
+/// Finishes the tracked import once dropped, even if the importer never reports its end
+#[derive(Debug)]
+pub struct ImportJobGuard {
+    jobs: ActiveJobs,
+    from_ms: u32,
+    to_ms: u32,
+}
+
+impl Drop for ImportJobGuard {
+    fn drop(&mut self) {
+        let (from_ms, to_ms) = (self.from_ms, self.to_ms);
+        self.jobs.update(|jobs| jobs.import_finished(from_ms, to_ms));
+    }
+}
+
+/// The progress snapshot of an active import, which imports a single LogFile
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportJob {
+    /// LogFile start range
+    pub from_ms: u32,
+    /// LogFile end range
+    pub to_ms: u32,
+    /// Total size of the LogFile
+    pub log_file_size: u64,
+    /// The size of the processed milestones data
+    pub processed_bytes: u64,
+    /// The number of imported milestones
+    pub imported: u32,
+    /// The number of skipped (already synced) milestones
+    pub skipped: u32,
+    /// The most recently processed milestone index
+    pub milestone_index: Option<u32>,
+}
+
+/// The active jobs, which pair the broker session events with a pull-based status view
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Jobs {
+    /// The active imports, in ascending LogFile range order
+    pub imports: Vec<ImportJob>,
+}
+
+impl Jobs {
+    /// Start tracking the import of the (from_ms, to_ms) LogFile
+    pub fn import_started(&mut self, from_ms: u32, to_ms: u32, log_file_size: u64) {
+        if let Err(i) = self
+            .imports
+            .binary_search_by_key(&(from_ms, to_ms), |job| (job.from_ms, job.to_ms))
+        {
+            let job = ImportJob {
+                from_ms,
+                to_ms,
+                log_file_size,
+                ..Default::default()
+            };
+            self.imports.insert(i, job);
+        }
+    }
+
+    /// Record the progress of the import of the (from_ms, to_ms) LogFile, unless it's already finished
+    pub fn import_progress(
+        &mut self,
+        from_ms: u32,
+        to_ms: u32,
+        log_file_size: u64,
+        ms_bytes_size: usize,
+        milestone_index: u32,
+        skipped: bool,
+    ) {
+        let job = match self
+            .imports
+            .binary_search_by_key(&(from_ms, to_ms), |job| (job.from_ms, job.to_ms))
+        {
+            Ok(i) => &mut self.imports[i],
+            Err(_) => return,
+        };
+        job.log_file_size = log_file_size;
+        // the initial progress is sent before any milestone is processed
+        if ms_bytes_size > 0 {
+            job.processed_bytes += ms_bytes_size as u64;
+            if skipped {
+                job.skipped += 1;
+            } else {
+                job.imported += 1;
+            }
+            job.milestone_index.replace(milestone_index);
+        }
+    }
+
+    /// Drop the finished import of the (from_ms, to_ms) LogFile
+    pub fn import_finished(&mut self, from_ms: u32, to_ms: u32) {
+        self.imports.retain(|job| (job.from_ms, job.to_ms) != (from_ms, to_ms));
+    }
+
+    /// Drop all the imports, once the import session is closed
+    pub fn close_imports(&mut self) {
+        self.imports.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_progress_snapshot() {
+        let mut jobs = Jobs::default();
+        // the progress of an untracked import is ignored
+        jobs.import_progress(1, 100, 5000, 40, 1, true);
+        assert!(jobs.imports.is_empty());
+        jobs.import_started(100, 200, 8000);
+        jobs.import_started(1, 100, 5000);
+        jobs.import_progress(1, 100, 5000, 0, 0, true);
+        assert_eq!(jobs.imports.len(), 2);
+        assert_eq!(jobs.imports[0].milestone_index, None);
+        jobs.import_progress(1, 100, 5000, 40, 1, true);
+        jobs.import_progress(1, 100, 5000, 60, 2, false);
+        jobs.import_progress(100, 200, 8000, 10, 100, false);
+        assert_eq!(
+            jobs.imports[0],
+            ImportJob {
+                from_ms: 1,
+                to_ms: 100,
+                log_file_size: 5000,
+                processed_bytes: 100,
+                imported: 1,
+                skipped: 1,
+                milestone_index: Some(2),
+            }
+        );
+        assert_eq!(jobs.imports[1].from_ms, 100);
+        jobs.import_finished(1, 100);
+        assert_eq!(jobs.imports.len(), 1);
+        assert_eq!(jobs.imports[0].to_ms, 200);
+        jobs.close_imports();
+        assert!(jobs.imports.is_empty());
+    }
+
+    #[test]
+    fn import_finished_on_guard_drop() {
+        let active = ActiveJobs::default();
+        let guard = active.track_import(1, 100, 5000);
+        active.update(|jobs| jobs.import_progress(1, 100, 5000, 1200, 1, false));
+        assert_eq!(active.snapshot().imports[0].processed_bytes, 1200);
+        // the importer went away without reporting its end
+        drop(guard);
+        assert!(active.snapshot().imports.is_empty());
+        // late progress events don't resurrect the finished import
+        active.update(|jobs| jobs.import_progress(1, 100, 5000, 100, 2, false));
+        assert!(active.snapshot().imports.is_empty());
+    }
+}
@@ -28,8 +28,13 @@ use tokio::sync::RwLock;
 
 /// Configuration for the Chronicle application
 pub mod config;
+/// The active long running jobs of the broker
+pub mod jobs;
 /// Metrics for prometheus integration
 pub mod metrics;
+/// OpenTelemetry (OTLP) spans exporter
+#[cfg(feature = "otel")]
+pub mod telemetry;
 use lazy_static::lazy_static;
 
 /// Defines a range of milestone indexes to be synced
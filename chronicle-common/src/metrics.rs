@@ -6,26 +6,50 @@ pub use prometheus;
 use prometheus::{
     HistogramOpts,
     HistogramVec,
-    IntCounter,
     IntCounterVec,
     Opts,
     Registry,
 };
 
 lazy_static! {
-    /// Metrics registry
+    /// Metrics registry, every metric carries a `keyspace` label
     pub static ref REGISTRY: Registry = Registry::new();
     /// Incoming request counter
-    pub static ref INCOMING_REQUESTS: IntCounter =
-        IntCounter::new("incoming_requests", "Incoming Requests").expect("failed to create metric");
+    pub static ref INCOMING_REQUESTS: IntCounterVec =
+        IntCounterVec::new(Opts::new("incoming_requests", "Incoming Requests"), &["keyspace"]).expect("failed to create metric");
     /// Response code collector
     pub static ref RESPONSE_CODE_COLLECTOR: IntCounterVec = IntCounterVec::new(
         Opts::new("response_code", "Response Codes"),
-        &["statuscode", "type"]
+        &["keyspace", "statuscode", "type"]
     )
     .expect("failed to create metric");
+    /// Dropped feed events counter, used by the `Drop` feed overflow policy
+    pub static ref DROPPED_FEED_EVENTS: IntCounterVec =
+        IntCounterVec::new(Opts::new("dropped_feed_events", "Dropped Feed Events"), &["keyspace"]).expect("failed to create metric");
+    /// Duplicate feed events counter, the feed events dropped by the collector dedup window
+    pub static ref DUPLICATE_FEED_EVENTS: IntCounterVec =
+        IntCounterVec::new(Opts::new("duplicate_feed_events", "Duplicate Feed Events"), &["keyspace", "topic"]).expect("failed to create metric");
+    /// Feed messages which failed to deserialize, labelled by topic only as the feed sources are keyspace agnostic
+    pub static ref DEAD_LETTER_FEED_MESSAGES: IntCounterVec =
+        IntCounterVec::new(Opts::new("dead_letter_feed_messages", "Dead Letter Feed Messages"), &["topic"]).expect("failed to create metric");
+    /// Milestone data which did not solidify in time
+    pub static ref SOLIDIFICATION_TIMEOUTS: IntCounterVec =
+        IntCounterVec::new(Opts::new("solidification_timeouts", "Solidification Timeouts"), &["keyspace"]).expect("failed to create metric");
+    /// The time milestone data take to solidify (in seconds), from their creation to their last pending message
+    pub static ref SOLIDIFICATION_LATENCY: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("solidification_latency", "Milestone Solidification Latency")
+            .buckets(vec![0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0, 300.0, 600.0]),
+        &["keyspace", "created_by"]
+    )
+    .expect("failed to create metric");
+    /// Message cache hit counter
+    pub static ref MESSAGE_CACHE_HITS: IntCounterVec =
+        IntCounterVec::new(Opts::new("message_cache_hits", "Message Cache Hits"), &["keyspace"]).expect("failed to create metric");
+    /// Message cache miss counter
+    pub static ref MESSAGE_CACHE_MISSES: IntCounterVec =
+        IntCounterVec::new(Opts::new("message_cache_misses", "Message Cache Misses"), &["keyspace"]).expect("failed to create metric");
     /// Response time collector
     pub static ref RESPONSE_TIME_COLLECTOR: HistogramVec =
-        HistogramVec::new(HistogramOpts::new("response_time", "Response Times"), &["endpoint"])
+        HistogramVec::new(HistogramOpts::new("response_time", "Response Times"), &["keyspace", "endpoint"])
             .expect("failed to create metric");
 }
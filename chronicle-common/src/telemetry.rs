@@ -0,0 +1,129 @@
+// Copyright 2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use config::TelemetryConfig;
+pub use opentelemetry;
+use opentelemetry::{
+    global::{
+        self,
+        BoxedSpan,
+        BoxedTracer,
+    },
+    sdk::{
+        trace::{
+            self,
+            TracerProvider,
+        },
+        Resource,
+    },
+    trace::{
+        Span,
+        Tracer,
+    },
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+
+/// The name of the chronicle tracer
+const TRACER_NAME: &str = "chronicle";
+
+/// Install the OTLP span exporter as the global tracer provider, if an endpoint is configured.
+/// Note: this must be invoked within the tokio runtime, as the spans are exported in batches by a tokio task
+pub fn init(config: &TelemetryConfig) -> anyhow::Result<()> {
+    if let Some(endpoint) = config.otlp_endpoint.as_ref() {
+        debug!("Installing the OTLP span exporter with endpoint: {}", endpoint);
+        opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(endpoint.as_str()),
+            )
+            .with_trace_config(trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                config.service_name.clone(),
+            )])))
+            .install_batch(opentelemetry::runtime::Tokio)
+            .map_err(|e| anyhow!("Unable to install the OTLP span exporter: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Install the given tracer provider as the global one
+pub fn install(provider: TracerProvider) {
+    global::set_tracer_provider(provider);
+}
+
+/// Flush the pending spans and shutdown the global tracer provider
+pub fn shutdown() {
+    global::shutdown_tracer_provider();
+}
+
+/// Get the chronicle tracer from the global tracer provider
+pub fn tracer() -> BoxedTracer {
+    global::tracer(TRACER_NAME)
+}
+
+/// Start a new span with the given attributes, the span ends once it's dropped
+pub fn start_span(name: &'static str, attributes: Vec<KeyValue>) -> BoxedSpan {
+    let mut span = tracer().start(name);
+    for attribute in attributes {
+        span.set_attribute(attribute);
+    }
+    span
+}
+
+/// Run the given closure within a new span
+pub fn in_span<T, F: FnOnce() -> T>(name: &'static str, f: F) -> T {
+    tracer().in_span(name, |_| f())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::sdk::export::trace::{
+        ExportResult,
+        SpanData,
+        SpanExporter,
+    };
+    use std::sync::{
+        Arc,
+        Mutex,
+    };
+
+    #[derive(Clone, Debug, Default)]
+    struct InMemoryExporter(Arc<Mutex<Vec<SpanData>>>);
+
+    #[async_trait::async_trait]
+    impl SpanExporter for InMemoryExporter {
+        async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
+            self.0.lock().unwrap().extend(batch);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn records_spans() {
+        let exporter = InMemoryExporter::default();
+        install(TracerProvider::builder().with_simple_exporter(exporter.clone()).build());
+        let res = in_span("sample_operation", || 1 + 1);
+        assert_eq!(res, 2);
+        drop(start_span(
+            "sample_ingestion",
+            vec![KeyValue::new("milestone_index", 1i64)],
+        ));
+        shutdown();
+        let spans = exporter.0.lock().unwrap();
+        let names = spans.iter().map(|span| span.name.as_ref()).collect::<Vec<_>>();
+        assert!(names.contains(&"sample_operation"));
+        let ingestion = spans
+            .iter()
+            .find(|span| span.name == "sample_ingestion")
+            .expect("Expected the ingestion span");
+        assert!(ingestion
+            .attributes
+            .iter()
+            .any(|(key, _)| key.as_str() == "milestone_index"));
+    }
+}
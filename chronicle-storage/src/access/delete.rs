@@ -168,3 +168,56 @@ impl Delete<ParentPK, ParentRecord> for ChronicleKeyspace {
             .value(&message_id.to_string())
     }
 }
+
+/// Delete Message record from messages table
+impl Delete<MessageId, Message> for ChronicleKeyspace {
+    type QueryOrPrepared = PreparedStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!("DELETE FROM {}.messages WHERE message_id = ?", self.name()).into()
+    }
+    fn bind_values<T: Values>(builder: T, message_id: &MessageId) -> T::Return {
+        builder.value(&message_id.to_string())
+    }
+}
+
+/// Delete the Milestone partition from milestones table
+impl Delete<MilestoneIndex, Milestone> for ChronicleKeyspace {
+    type QueryOrPrepared = PreparedStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!("DELETE FROM {}.milestones WHERE milestone_index = ?", self.name()).into()
+    }
+    fn bind_values<T: Values>(builder: T, milestone_index: &MilestoneIndex) -> T::Return {
+        builder.value(&milestone_index.0)
+    }
+}
+
+/// Delete the Transaction partition from transactions table, which holds its inputs, outputs and unlocks rows
+impl Delete<TransactionId, TransactionRecord> for ChronicleKeyspace {
+    type QueryOrPrepared = PreparedStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!("DELETE FROM {}.transactions WHERE transaction_id = ?", self.name()).into()
+    }
+    fn bind_values<T: Values>(builder: T, transaction_id: &TransactionId) -> T::Return {
+        builder.value(&transaction_id.to_string())
+    }
+}
+
+/// Delete Tag record from Tags table, unless the tag is written by a later milestone of the partition, as the tag
+/// rows are written with the milestone index as timestamp
+impl Delete<Partitioned<Tag>, ()> for ChronicleKeyspace {
+    type QueryOrPrepared = PreparedStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!(
+            "DELETE FROM {}.tags USING TIMESTAMP ? WHERE bucket = ? AND partition_id = ? AND tag = ?",
+            self.name()
+        )
+        .into()
+    }
+    fn bind_values<T: Values>(builder: T, tag: &Partitioned<Tag>) -> T::Return {
+        builder
+            .value(&(tag.milestone_index() as i64))
+            .value(&tag.bucket())
+            .value(&tag.partition_id())
+            .value(&tag.0)
+    }
+}
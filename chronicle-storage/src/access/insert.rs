@@ -7,8 +7,9 @@ impl Insert<MessageId, Message> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "INSERT INTO {}.messages (message_id, message) VALUES (?, ?)",
-            self.name()
+            "INSERT INTO {}.messages (message_id, message) VALUES (?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -25,8 +26,9 @@ impl Insert<MessageId, MessageMetadata> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "INSERT INTO {}.messages (message_id, metadata) VALUES (?, ?)",
-            self.name()
+            "INSERT INTO {}.messages (message_id, metadata) VALUES (?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -40,8 +42,9 @@ impl Insert<MessageId, (Message, MessageMetadata)> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "INSERT INTO {}.messages (message_id, message, metadata) VALUES (?, ?, ?)",
-            self.name()
+            "INSERT INTO {}.messages (message_id, message, metadata) VALUES (?, ?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -61,14 +64,34 @@ impl Insert<MessageId, (Message, MessageMetadata)> for ChronicleKeyspace {
             .value(meta)
     }
 }
+/// Insert the message and its metadata, unless the message is already stored
+impl Insert<IfAbsent<MessageId>, (Message, MessageMetadata)> for ChronicleKeyspace {
+    type QueryOrPrepared = PreparedStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!(
+            "INSERT INTO {}.messages (message_id, message, metadata) VALUES (?, ?, ?) IF NOT EXISTS{}",
+            self.name(),
+            self.using_ttl()
+        )
+        .into()
+    }
+    fn bind_values<T: Values>(
+        builder: T,
+        IfAbsent(message_id): &IfAbsent<MessageId>,
+        message_tuple: &(Message, MessageMetadata),
+    ) -> T::Return {
+        <Self as Insert<MessageId, (Message, MessageMetadata)>>::bind_values(builder, message_id, message_tuple)
+    }
+}
 /// Insert Address into addresses table
 impl Insert<Partitioned<Ed25519Address>, AddressRecord> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
             "INSERT INTO {}.addresses (address, partition_id, milestone_index, output_type, transaction_id, idx, amount, address_type, inclusion_state)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            self.name()
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -102,8 +125,9 @@ impl Insert<Partitioned<Indexation>, IndexationRecord> for ChronicleKeyspace {
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
             "INSERT INTO {}.indexes (indexation, partition_id, milestone_index, message_id, inclusion_state)
-            VALUES (?, ?, ?, ?, ?)",
-            self.name()
+            VALUES (?, ?, ?, ?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -130,8 +154,9 @@ impl Insert<Partitioned<MessageId>, ParentRecord> for ChronicleKeyspace {
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
             "INSERT INTO {}.parents (parent_id, partition_id, milestone_index, message_id, inclusion_state)
-            VALUES (?, ?, ?, ?, ?)",
-            self.name()
+            VALUES (?, ?, ?, ?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -161,8 +186,9 @@ impl Insert<(TransactionId, Index), TransactionRecord> for ChronicleKeyspace {
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
             "INSERT INTO {}.transactions (transaction_id, idx, variant, message_id, data, inclusion_state, milestone_index)
-            VALUES (?, ?, ?, ?, ?, ?, ?)",
-            self.name()
+            VALUES (?, ?, ?, ?, ?, ?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -193,8 +219,9 @@ impl Insert<OutputId, TransactionRecord> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "INSERT INTO {}.transactions (transaction_id, idx, variant, message_id, data, inclusion_state, milestone_index) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-            self.name()
+            "INSERT INTO {}.transactions (transaction_id, idx, variant, message_id, data, inclusion_state, milestone_index) VALUES (?, ?, ?, ?, ?, ?, ?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -225,8 +252,9 @@ impl Insert<Hint, Partition> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "INSERT INTO {}.hints (hint, variant, partition_id, milestone_index) VALUES (?, ?, ?, ?)",
-            self.name()
+            "INSERT INTO {}.hints (hint, variant, partition_id, milestone_index) VALUES (?, ?, ?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -239,12 +267,37 @@ impl Insert<Hint, Partition> for ChronicleKeyspace {
     }
 }
 
+/// Insert Tag into Tags table. The write timestamp is the milestone index, so the row keeps the latest milestone of
+/// the tag within its partition whatever the write order (ie gaps filled by the syncer), and the pruner deletes it
+/// only if no later milestone of the partition holds the tag
+impl Insert<Partitioned<Tag>, ()> for ChronicleKeyspace {
+    type QueryOrPrepared = PreparedStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!(
+            "INSERT INTO {}.tags (bucket, partition_id, tag) VALUES (?, ?, ?) USING TIMESTAMP ?{}",
+            self.name(),
+            self.row_ttl_secs()
+                .map(|row_ttl_secs| format!(" AND TTL {}", row_ttl_secs))
+                .unwrap_or_default()
+        )
+        .into()
+    }
+    fn bind_values<T: Values>(builder: T, tag: &Partitioned<Tag>, _: &()) -> T::Return {
+        builder
+            .value(&tag.bucket())
+            .value(&tag.partition_id())
+            .value(&tag.0)
+            .value(&(tag.milestone_index() as i64))
+    }
+}
+
 impl Insert<MilestoneIndex, (MessageId, Box<MilestonePayload>)> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "INSERT INTO {}.milestones (milestone_index, message_id, timestamp, payload) VALUES (?, ?, ?, ?)",
-            self.name()
+            "INSERT INTO {}.milestones (milestone_index, message_id, timestamp, payload) VALUES (?, ?, ?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -269,8 +322,9 @@ impl Insert<Synckey, SyncRecord> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "INSERT INTO {}.sync (key, milestone_index, synced_by, logged_by) VALUES (?, ?, ?, ?)",
-            self.name()
+            "INSERT INTO {}.sync (key, milestone_index, synced_by, logged_by, created_by) VALUES (?, ?, ?, ?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -281,6 +335,7 @@ impl Insert<Synckey, SyncRecord> for ChronicleKeyspace {
             milestone_index,
             synced_by,
             logged_by,
+            created_by,
         }: &SyncRecord,
     ) -> T::Return {
         builder
@@ -288,6 +343,7 @@ impl Insert<Synckey, SyncRecord> for ChronicleKeyspace {
             .value(&milestone_index.0)
             .value(synced_by)
             .value(logged_by)
+            .value(created_by)
     }
 }
 
@@ -295,8 +351,9 @@ impl Insert<Synckey, AnalyticRecord> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "INSERT INTO {}.analytics (key, milestone_index, message_count, transaction_count, transferred_tokens) VALUES (?, ?, ?, ?, ?)",
-            self.name()
+            "INSERT INTO {}.analytics (key, milestone_index, message_count, transaction_count, transferred_tokens, reattachment_count) VALUES (?, ?, ?, ?, ?, ?){}",
+            self.name(),
+            self.using_ttl()
         )
         .into()
     }
@@ -308,6 +365,7 @@ impl Insert<Synckey, AnalyticRecord> for ChronicleKeyspace {
             message_count,
             transaction_count,
             transferred_tokens,
+            reattachment_count,
         }: &AnalyticRecord,
     ) -> T::Return {
         builder
@@ -316,5 +374,86 @@ impl Insert<Synckey, AnalyticRecord> for ChronicleKeyspace {
             .value(&message_count.0)
             .value(&transaction_count.0)
             .value(&transferred_tokens.0)
+            .value(&reattachment_count.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chronicle_common::config::{
+        KeyspaceConfig,
+        StorageConfig,
+    };
+
+    #[test]
+    fn row_ttl_insert_statement() {
+        let keyspace = ChronicleKeyspace::new("permanode".to_string());
+        let statement = Insert::<MessageId, Message>::statement(&keyspace);
+        assert_eq!(
+            statement,
+            "INSERT INTO permanode.messages (message_id, message) VALUES (?, ?)"
+        );
+        let keyspace = keyspace.with_row_ttl(Some(3600));
+        let statement = Insert::<MessageId, Message>::statement(&keyspace);
+        assert_eq!(
+            statement,
+            "INSERT INTO permanode.messages (message_id, message) VALUES (?, ?) USING TTL 3600"
+        );
+        assert!(
+            Insert::<Partitioned<Ed25519Address>, AddressRecord>::statement(&keyspace).ends_with(" USING TTL 3600")
+        );
+        assert_eq!(
+            Insert::<Partitioned<Tag>, ()>::statement(&keyspace),
+            "INSERT INTO permanode.tags (bucket, partition_id, tag) VALUES (?, ?, ?) USING TIMESTAMP ? AND TTL 3600"
+        );
+        // the sync and analytics records expire along with the milestone data
+        assert!(Insert::<Synckey, SyncRecord>::statement(&keyspace).ends_with(" USING TTL 3600"));
+        assert!(Insert::<Synckey, AnalyticRecord>::statement(&keyspace).ends_with(" USING TTL 3600"));
+    }
+
+    #[test]
+    fn tag_statements() {
+        let keyspace = ChronicleKeyspace::new("permanode".to_string());
+        // the tag rows are written and deleted with the milestone index as timestamp
+        assert_eq!(
+            Insert::<Partitioned<Tag>, ()>::statement(&keyspace),
+            "INSERT INTO permanode.tags (bucket, partition_id, tag) VALUES (?, ?, ?) USING TIMESTAMP ?"
+        );
+        assert_eq!(
+            Delete::<Partitioned<Tag>, ()>::statement(&keyspace),
+            "DELETE FROM permanode.tags USING TIMESTAMP ? WHERE bucket = ? AND partition_id = ? AND tag = ?"
+        );
+        let prefix = Partitioned::new(TagPrefix::new("6368".to_string()).unwrap(), 3, 25);
+        assert!(Select::<Partitioned<TagPrefix>, Vec<String>>::statement(&keyspace)
+            .contains("WHERE bucket = ? AND partition_id = ? AND tag >= ? AND tag < ?"));
+        assert_eq!(prefix.bucket(), "63");
+        assert_eq!(prefix.range(), ("6368".to_string(), "6368g".to_string()));
+    }
+
+    #[test]
+    fn configured_row_ttl() {
+        let storage_config = StorageConfig {
+            keyspaces: vec![
+                KeyspaceConfig {
+                    name: "permanode".to_string(),
+                    row_ttl_secs: Some(86400),
+                    ..Default::default()
+                },
+                KeyspaceConfig {
+                    name: "archive".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let keyspace = ChronicleKeyspace::configured("permanode".to_string(), &storage_config);
+        assert_eq!(keyspace.row_ttl_secs(), Some(86400));
+        assert!(Insert::<Hint, Partition>::statement(&keyspace).ends_with(" USING TTL 86400"));
+        for name in vec!["archive", "unknown"] {
+            let keyspace = ChronicleKeyspace::configured(name.to_string(), &storage_config);
+            assert_eq!(keyspace.row_ttl_secs(), None);
+            assert!(!Insert::<Hint, Partition>::statement(&keyspace).contains("TTL"));
+        }
     }
 }
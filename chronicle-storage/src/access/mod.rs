@@ -216,6 +216,7 @@ pub struct SyncRecord {
     pub milestone_index: MilestoneIndex,
     pub synced_by: Option<SyncedBy>,
     pub logged_by: Option<LoggedBy>,
+    pub created_by: Option<CreatedByKind>,
 }
 
 impl SyncRecord {
@@ -225,8 +226,14 @@ impl SyncRecord {
             milestone_index,
             synced_by,
             logged_by,
+            created_by: None,
         }
     }
+    /// Set the source which created the milestone data, None leaves the stored one as is
+    pub fn with_created_by(mut self, created_by: Option<CreatedByKind>) -> Self {
+        self.created_by = created_by;
+        self
+    }
 }
 /// An `addresses` table row
 #[allow(missing_docs)]
@@ -15,7 +15,12 @@ use std::{
 impl Select<MessageId, Message> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
-        format!("SELECT message FROM {}.messages WHERE message_id = ?", self.name()).into()
+        format!(
+            "SELECT message FROM {}.messages WHERE message_id = ?{}",
+            self.name(),
+            self.using_timeout()
+        )
+        .into()
     }
     fn bind_values<T: Values>(builder: T, message_id: &MessageId) -> T::Return {
         builder.value(&message_id.to_string())
@@ -36,7 +41,12 @@ impl RowsDecoder<MessageId, Message> for ChronicleKeyspace {
 impl Select<MessageId, MessageMetadata> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
-        format!("SELECT metadata FROM {}.messages WHERE message_id = ?", self.name()).into()
+        format!(
+            "SELECT metadata FROM {}.messages WHERE message_id = ?{}",
+            self.name(),
+            self.using_timeout()
+        )
+        .into()
     }
     fn bind_values<T: Values>(builder: T, message_id: &MessageId) -> T::Return {
         builder.value(&message_id.to_string())
@@ -59,8 +69,9 @@ impl Select<MessageId, (Option<Message>, Option<MessageMetadata>)> for Chronicle
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "SELECT message, metadata FROM {}.messages WHERE message_id = ?",
-            self.name()
+            "SELECT message, metadata FROM {}.messages WHERE message_id = ?{}",
+            self.name(),
+            self.using_timeout()
         )
         .into()
     }
@@ -88,8 +99,9 @@ impl Select<Partitioned<MessageId>, Paged<VecDeque<Partitioned<ParentRecord>>>>
         format!(
             "SELECT partition_id, milestone_index, message_id, inclusion_state
             FROM {}.parents
-            WHERE parent_id = ? AND partition_id = ? AND milestone_index <= ?",
-            self.name()
+            WHERE parent_id = ? AND partition_id = ? AND milestone_index <= ?{}",
+            self.name(),
+            self.using_timeout()
         )
         .into()
     }
@@ -127,8 +139,9 @@ impl Select<Partitioned<Indexation>, Paged<VecDeque<Partitioned<IndexationRecord
         format!(
             "SELECT partition_id, milestone_index, message_id, inclusion_state
             FROM {}.indexes
-            WHERE indexation = ? AND partition_id = ? AND milestone_index <= ?",
-            self.name()
+            WHERE indexation = ? AND partition_id = ? AND milestone_index <= ?{}",
+            self.name(),
+            self.using_timeout()
         )
         .into()
     }
@@ -166,8 +179,9 @@ impl Select<Partitioned<Ed25519Address>, Paged<VecDeque<Partitioned<AddressRecor
         format!(
             "SELECT partition_id, milestone_index, output_type, transaction_id, idx, amount, inclusion_state
             FROM {}.addresses
-            WHERE address = ? AND partition_id = ? AND milestone_index <= ?",
-            self.name()
+            WHERE address = ? AND partition_id = ? AND milestone_index <= ?{}",
+            self.name(),
+            self.using_timeout()
         )
         .into()
     }
@@ -236,8 +250,9 @@ impl Select<OutputId, OutputRes> for ChronicleKeyspace {
             FROM {}.transactions
             WHERE transaction_id = ?
             AND idx = ?
-            AND variant IN ('output', 'unlock')",
-            self.name()
+            AND variant IN ('output', 'unlock'){}",
+            self.name(),
+            self.using_timeout()
         )
         .into()
     }
@@ -281,8 +296,9 @@ impl Select<TransactionId, MessageId> for ChronicleKeyspace {
         format!(
             "SELECT message_id FROM {}.transactions
             WHERE transaction_id = ? and inclusion_state = ? and variant = 'input'
-            LIMIT 1 ALLOW FILTERING",
-            self.name()
+            LIMIT 1 ALLOW FILTERING{}",
+            self.name(),
+            self.using_timeout()
         )
         .into()
     }
@@ -309,8 +325,9 @@ impl Select<MilestoneIndex, Milestone> for ChronicleKeyspace {
     type QueryOrPrepared = PreparedStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "SELECT message_id, timestamp FROM {}.milestones WHERE milestone_index = ?",
-            self.name()
+            "SELECT message_id, timestamp FROM {}.milestones WHERE milestone_index = ?{}",
+            self.name(),
+            self.using_timeout()
         )
         .into()
     }
@@ -336,8 +353,9 @@ impl Select<Hint, Vec<(MilestoneIndex, PartitionId)>> for ChronicleKeyspace {
         format!(
             "SELECT milestone_index, partition_id
             FROM {}.hints
-            WHERE hint = ? AND variant = ?",
-            self.name()
+            WHERE hint = ? AND variant = ?{}",
+            self.name(),
+            self.using_timeout()
         )
         .into()
     }
@@ -363,12 +381,47 @@ impl<K> RowsDecoder<K, Vec<(MilestoneIndex, PartitionId)>> for ChronicleKeyspace
     }
 }
 
+impl Select<Partitioned<TagPrefix>, Vec<String>> for ChronicleKeyspace {
+    type QueryOrPrepared = PreparedStatement;
+
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!(
+            "SELECT tag
+            FROM {}.tags
+            WHERE bucket = ? AND partition_id = ? AND tag >= ? AND tag < ?{}",
+            self.name(),
+            self.using_timeout()
+        )
+        .into()
+    }
+
+    fn bind_values<T: Values>(builder: T, prefix: &Partitioned<TagPrefix>) -> T::Return {
+        let (from, to) = prefix.range();
+        builder
+            .value(&prefix.bucket())
+            .value(&prefix.partition_id())
+            .value(&from)
+            .value(&to)
+    }
+}
+
+impl RowsDecoder<Partitioned<TagPrefix>, Vec<String>> for ChronicleKeyspace {
+    type Row = Record<String>;
+
+    fn try_decode(decoder: Decoder) -> anyhow::Result<Option<Vec<String>>> {
+        ensure!(decoder.is_rows()?, "Decoded response is not rows!");
+        Ok(Some(
+            Self::Row::rows_iter(decoder)?.map(|row| row.into_inner()).collect(),
+        ))
+    }
+}
+
 impl Select<SyncRange, Iter<SyncRecord>> for ChronicleKeyspace {
     type QueryOrPrepared = QueryStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "SELECT milestone_index, synced_by, logged_by FROM {}.sync WHERE key = ? AND milestone_index >= ? AND milestone_index < ?",
-            self.name()
+            "SELECT milestone_index, synced_by, logged_by FROM {}.sync WHERE key = ? AND milestone_index >= ? AND milestone_index < ?{}",
+            self.name(), self.using_timeout()
         )
         .into()
     }
@@ -393,12 +446,35 @@ impl RowsDecoder<SyncRange, Iter<SyncRecord>> for ChronicleKeyspace {
     }
 }
 
+impl Select<(Synckey, MilestoneIndex), SyncRecord> for ChronicleKeyspace {
+    type QueryOrPrepared = PreparedStatement;
+    fn statement(&self) -> std::borrow::Cow<'static, str> {
+        format!(
+            "SELECT milestone_index, synced_by, logged_by, created_by FROM {}.sync WHERE key = ? AND milestone_index = ?{}",
+            self.name(),
+            self.using_timeout()
+        )
+        .into()
+    }
+    fn bind_values<T: Values>(builder: T, (_, milestone_index): &(Synckey, MilestoneIndex)) -> T::Return {
+        builder.value(&"permanode").value(&milestone_index.0)
+    }
+}
+
+impl RowsDecoder<(Synckey, MilestoneIndex), SyncRecord> for ChronicleKeyspace {
+    type Row = Record<SyncRecord>;
+    fn try_decode(decoder: Decoder) -> anyhow::Result<Option<SyncRecord>> {
+        ensure!(decoder.is_rows()?, "Decoded response is not rows!");
+        Ok(Self::Row::rows_iter(decoder)?.next().map(|row| row.into_inner()))
+    }
+}
+
 impl Select<SyncRange, Iter<AnalyticRecord>> for ChronicleKeyspace {
     type QueryOrPrepared = QueryStatement;
     fn statement(&self) -> std::borrow::Cow<'static, str> {
         format!(
-            "SELECT milestone_index, message_count, transaction_count, transferred_tokens FROM {}.analytics WHERE key = ? AND milestone_index >= ? AND milestone_index < ?",
-            self.name()
+            "SELECT milestone_index, message_count, transaction_count, transferred_tokens, reattachment_count FROM {}.analytics WHERE key = ? AND milestone_index >= ? AND milestone_index < ?{}",
+            self.name(), self.using_timeout()
         )
         .into()
     }
@@ -491,6 +567,21 @@ impl Row for Record<(MessageId, u64)> {
     }
 }
 
+impl Row for Record<String> {
+    fn try_decode_row<R: Rows + ColumnValue>(rows: &mut R) -> anyhow::Result<Self> {
+        Ok(Record::new(rows.column_value::<String>()?))
+    }
+}
+
+impl Row for Record<(String, String)> {
+    fn try_decode_row<R: Rows + ColumnValue>(rows: &mut R) -> anyhow::Result<Self> {
+        Ok(Record::new((
+            rows.column_value::<String>()?,
+            rows.column_value::<String>()?,
+        )))
+    }
+}
+
 impl Row for Record<(u32, u16)> {
     fn try_decode_row<R: Rows + ColumnValue>(rows: &mut R) -> anyhow::Result<Self> {
         Ok(Record::new((rows.column_value::<u32>()?, rows.column_value::<u16>()?)))
@@ -552,17 +643,28 @@ impl Row for SyncRecord {
     }
 }
 
+impl Row for Record<SyncRecord> {
+    fn try_decode_row<T: ColumnValue>(rows: &mut T) -> anyhow::Result<Self> {
+        let sync_record = SyncRecord::try_decode_row(rows)?;
+        let created_by = rows.column_value::<Option<CreatedByKind>>()?;
+        Ok(Record::new(sync_record.with_created_by(created_by)))
+    }
+}
+
 impl Row for AnalyticRecord {
     fn try_decode_row<T: ColumnValue>(rows: &mut T) -> anyhow::Result<Self> {
         let milestone_index = MilestoneIndex(rows.column_value::<u32>()?);
         let message_count = MessageCount(rows.column_value::<u32>()?);
         let transaction_count = TransactionCount(rows.column_value::<u32>()?);
         let transferred_tokens = TransferredTokens(rows.column_value::<u64>()?);
+        // The analytics written before the reattachment count was introduced have none
+        let reattachment_count = ReattachmentCount(rows.column_value::<Option<u32>>()?.unwrap_or_default());
         Ok(AnalyticRecord::new(
             milestone_index,
             message_count,
             transaction_count,
             transferred_tokens,
+            reattachment_count,
         ))
     }
 }
@@ -51,6 +51,18 @@ impl ComputeToken<Hint> for ChronicleKeyspace {
     }
 }
 
+impl ComputeToken<Partitioned<Tag>> for ChronicleKeyspace {
+    fn token(key: &Partitioned<Tag>) -> i64 {
+        key.bucket().chain_token(&key.partition_id()).finish()
+    }
+}
+
+impl ComputeToken<Partitioned<TagPrefix>> for ChronicleKeyspace {
+    fn token(key: &Partitioned<TagPrefix>) -> i64 {
+        key.bucket().chain_token(&key.partition_id()).finish()
+    }
+}
+
 impl ComputeToken<SyncRange> for ChronicleKeyspace {
     fn token(_: &SyncRange) -> i64 {
         "permanode".get_token()
@@ -63,6 +75,12 @@ impl ComputeToken<Synckey> for ChronicleKeyspace {
     }
 }
 
+impl ComputeToken<(Synckey, MilestoneIndex)> for ChronicleKeyspace {
+    fn token(_: &(Synckey, MilestoneIndex)) -> i64 {
+        "permanode".get_token()
+    }
+}
+
 impl ComputeToken<(TransactionId, Index)> for ChronicleKeyspace {
     fn token(key: &(TransactionId, Index)) -> i64 {
         key.0.to_string().chain_token(&key.1).finish()
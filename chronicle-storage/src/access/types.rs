@@ -34,6 +34,8 @@ pub type SyncedBy = u8;
 /// Identify theoretical nodeid which updated/set the logged_by column in sync table.
 /// This enables the admin to locate the generated logs across cluster of chronicles
 pub type LoggedBy = u8;
+/// Identify the source which created the milestone data of the synced milestone (ie the broker `CreatedBy`)
+pub type CreatedByKind = u8;
 
 /// A `bee` type wrapper which is used to apply the `ColumnEncoder`
 /// functionality over predefined types which are `Packable`.
@@ -259,6 +261,36 @@ pub struct MessageMetadata {
     pub should_promote: Option<bool>,
     #[serde(rename = "shouldReattach")]
     pub should_reattach: Option<bool>,
+    /// The node's conflict reason code of a conflicting message
+    #[serde(rename = "conflictReason", default)]
+    pub conflict_reason: Option<u8>,
+}
+
+/// The MessageMetadata layout stored before the conflict reason was added
+#[derive(Deserialize)]
+struct LegacyMessageMetadata {
+    message_id: MessageId,
+    parent_message_ids: Vec<MessageId>,
+    is_solid: bool,
+    referenced_by_milestone_index: Option<u32>,
+    ledger_inclusion_state: Option<LedgerInclusionState>,
+    should_promote: Option<bool>,
+    should_reattach: Option<bool>,
+}
+
+impl From<LegacyMessageMetadata> for MessageMetadata {
+    fn from(legacy: LegacyMessageMetadata) -> Self {
+        Self {
+            message_id: legacy.message_id,
+            parent_message_ids: legacy.parent_message_ids,
+            is_solid: legacy.is_solid,
+            referenced_by_milestone_index: legacy.referenced_by_milestone_index,
+            ledger_inclusion_state: legacy.ledger_inclusion_state,
+            should_promote: legacy.should_promote,
+            should_reattach: legacy.should_reattach,
+            conflict_reason: None,
+        }
+    }
 }
 
 /// A message's ledger inclusion state
@@ -299,7 +331,13 @@ impl ColumnEncoder for MessageMetadata {
 
 impl ColumnDecoder for MessageMetadata {
     fn try_decode(slice: &[u8]) -> anyhow::Result<Self> {
-        bincode_config().deserialize(slice).map_err(Into::into)
+        // Rows written before the conflict reason was added lack its trailing bytes
+        bincode_config().deserialize(slice).or_else(|_| {
+            bincode_config()
+                .deserialize::<LegacyMessageMetadata>(slice)
+                .map(Into::into)
+                .map_err(Into::into)
+        })
     }
 }
 impl ColumnEncoder for TransactionData {
@@ -337,6 +375,11 @@ pub struct UnlockRes {
 /// A type alias for partition ids
 pub type PartitionId = u16;
 
+/// A key whose row is only inserted if it's absent (ie `IF NOT EXISTS`), so reinserting it never overwrites the
+/// stored row
+#[derive(Clone, Copy, Debug)]
+pub struct IfAbsent<K>(pub K);
+
 /// An index in plain-text, unhashed
 #[derive(Clone)]
 pub struct Indexation(pub String);
@@ -346,7 +389,7 @@ pub struct Indexation(pub String);
 pub struct Hint {
     /// The hint string
     pub hint: String,
-    /// The hint variant. Can be 'parent', 'address', or 'index'.
+    /// The hint variant. Can be 'parent', 'address', 'index' or 'tag'.
     pub variant: HintVariant,
 }
 
@@ -374,6 +417,14 @@ impl Hint {
             variant: HintVariant::Parent,
         }
     }
+
+    /// Creates a new tag bucket hint
+    pub fn tag(bucket: String) -> Self {
+        Self {
+            hint: bucket,
+            variant: HintVariant::Tag,
+        }
+    }
 }
 
 /// Hint variants
@@ -385,6 +436,8 @@ pub enum HintVariant {
     Index,
     /// A parent message id
     Parent,
+    /// A tag bucket
+    Tag,
 }
 
 impl std::fmt::Display for HintVariant {
@@ -396,11 +449,67 @@ impl std::fmt::Display for HintVariant {
                 HintVariant::Address => "address",
                 HintVariant::Index => "index",
                 HintVariant::Parent => "parent",
+                HintVariant::Tag => "tag",
             }
         )
     }
 }
 
+/// The length of the tag bucket, which is the hex encoded first byte of the tag
+pub const TAG_BUCKET_LEN: usize = 2;
+
+/// A hex encoded indexation tag, stored in the `tags` table under its bucket and partition so the tags can be range
+/// scanned by prefix within each partition
+#[derive(Clone)]
+pub struct Tag(pub String);
+
+impl Tag {
+    /// Get the bucket (hex encoded first byte) of the tag
+    pub fn bucket(&self) -> &str {
+        &self.0[..self.0.len().min(TAG_BUCKET_LEN)]
+    }
+}
+
+/// A lowercase hex prefix, used to range scan the `tags` table.
+/// The prefix must span at least the tag bucket, as only the tags within a single bucket can be scanned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TagPrefix(String);
+
+impl TagPrefix {
+    /// Create a tag prefix, returning None if the prefix is not lowercase hex or is shorter than the tag bucket
+    pub fn new(prefix: String) -> Option<Self> {
+        if prefix.len() >= TAG_BUCKET_LEN && prefix.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')) {
+            Some(Self(prefix))
+        } else {
+            None
+        }
+    }
+
+    /// Get the bucket of the prefixed tags
+    pub fn bucket(&self) -> &str {
+        &self.0[..TAG_BUCKET_LEN]
+    }
+
+    /// Get the (inclusive, exclusive) range of the prefixed tags. Any lowercase hex tag which starts with the prefix
+    /// sorts before the prefix followed by 'g'.
+    pub fn range(&self) -> (String, String) {
+        (self.0.clone(), format!("{}g", self.0))
+    }
+
+    /// Check whether the tag starts with the prefix
+    pub fn matches(&self, tag: &str) -> bool {
+        tag.starts_with(&self.0)
+    }
+}
+
+impl Deref for TagPrefix {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 /// A marker for a paged result
 #[derive(Clone, Debug)]
 pub struct Paged<T> {
@@ -480,6 +589,16 @@ impl Deref for TransferredTokens {
     }
 }
 #[derive(Clone, Debug)]
+/// Wrapper around ReattachmentCount u32
+pub struct ReattachmentCount(pub u32);
+impl Deref for ReattachmentCount {
+    type Target = u32;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+#[derive(Clone, Debug)]
 /// MilestoneData analytics information.
 pub struct AnalyticRecord {
     /// Duh it's the milestone index
@@ -490,6 +609,8 @@ pub struct AnalyticRecord {
     pub transaction_count: TransactionCount,
     /// Transferred IOTA tokens volume within a milestone cone
     pub transferred_tokens: TransferredTokens,
+    /// The number of messages within a milestone cone which reattach an already attached transaction
+    pub reattachment_count: ReattachmentCount,
 }
 
 impl AnalyticRecord {
@@ -499,12 +620,14 @@ impl AnalyticRecord {
         message_count: MessageCount,
         transaction_count: TransactionCount,
         transferred_tokens: TransferredTokens,
+        reattachment_count: ReattachmentCount,
     ) -> Self {
         Self {
             milestone_index,
             message_count,
             transaction_count,
             transferred_tokens,
+            reattachment_count,
         }
     }
     /// Gets the milestone index
@@ -523,4 +646,49 @@ impl AnalyticRecord {
     pub fn transferred_tokens(&self) -> &TransferredTokens {
         &self.transferred_tokens
     }
+    /// Gets the reattachment count
+    pub fn reattachment_count(&self) -> &ReattachmentCount {
+        &self.reattachment_count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_legacy_message_metadata() {
+        let message_id = MessageId::new([1; 32]);
+        let parent_message_ids = vec![MessageId::new([2; 32]), MessageId::new([3; 32])];
+        // The fields of the MessageMetadata stored before the conflict reason was added
+        let legacy = (
+            message_id,
+            parent_message_ids.clone(),
+            true,
+            Some(42u32),
+            Some(LedgerInclusionState::Conflicting),
+            Some(false),
+            None::<bool>,
+        );
+        let blob = bincode_config().serialize(&legacy).unwrap();
+        let metadata = MessageMetadata::try_decode(&blob).expect("Unable to decode the legacy MessageMetadata");
+        assert_eq!(metadata.message_id, message_id);
+        assert_eq!(metadata.parent_message_ids, parent_message_ids);
+        assert!(metadata.is_solid);
+        assert_eq!(metadata.referenced_by_milestone_index, Some(42));
+        assert_eq!(metadata.ledger_inclusion_state, Some(LedgerInclusionState::Conflicting));
+        assert_eq!(metadata.should_promote, Some(false));
+        assert_eq!(metadata.should_reattach, None);
+        assert_eq!(metadata.conflict_reason, None);
+
+        // The current layout keeps the conflict reason
+        let metadata = MessageMetadata {
+            conflict_reason: Some(2),
+            ..metadata
+        };
+        let blob = bincode_config().serialize(&metadata).unwrap();
+        let decoded = MessageMetadata::try_decode(&blob).expect("Unable to decode the MessageMetadata");
+        assert_eq!(decoded.conflict_reason, Some(2));
+        assert_eq!(decoded.referenced_by_milestone_index, Some(42));
+    }
 }
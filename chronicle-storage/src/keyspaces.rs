@@ -1,6 +1,7 @@
 // Copyright 2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use chronicle_common::config::StorageConfig;
 pub use scylla_rs::prelude::Keyspace;
 use std::borrow::Cow;
 
@@ -8,12 +9,52 @@ use std::borrow::Cow;
 #[derive(Default, Clone, Debug)]
 pub struct ChronicleKeyspace {
     name: Cow<'static, str>,
+    /// The TTL in seconds of the inserted rows
+    row_ttl_secs: Option<u32>,
+    /// The timeout in milliseconds after which the queried node aborts the select requests
+    query_timeout_ms: Option<u64>,
 }
 
 impl ChronicleKeyspace {
     /// Create a new instance of the keyspace
     pub fn new(name: String) -> Self {
-        Self { name: name.into() }
+        Self {
+            name: name.into(),
+            row_ttl_secs: None,
+            query_timeout_ms: None,
+        }
+    }
+    /// Create a new instance of the keyspace, with the row TTL it's configured with
+    pub fn configured(name: String, storage_config: &StorageConfig) -> Self {
+        let row_ttl_secs = storage_config.row_ttl_secs(&name);
+        Self::new(name).with_row_ttl(row_ttl_secs)
+    }
+    /// Set the TTL in seconds of the inserted rows, None keeps them forever
+    pub fn with_row_ttl(mut self, row_ttl_secs: Option<u32>) -> Self {
+        self.row_ttl_secs = row_ttl_secs;
+        self
+    }
+    /// Get the TTL in seconds of the inserted rows
+    pub fn row_ttl_secs(&self) -> Option<u32> {
+        self.row_ttl_secs
+    }
+    /// The `USING TTL` clause of the insert statements, empty if the rows are kept forever
+    pub(crate) fn using_ttl(&self) -> String {
+        self.row_ttl_secs
+            .map(|row_ttl_secs| format!(" USING TTL {}", row_ttl_secs))
+            .unwrap_or_default()
+    }
+    /// Set the timeout in milliseconds of the select requests, None (or zero) waits for the node's own timeout
+    pub fn with_query_timeout(mut self, query_timeout_ms: Option<u64>) -> Self {
+        self.query_timeout_ms = query_timeout_ms.filter(|timeout_ms| *timeout_ms > 0);
+        self
+    }
+    /// The `USING TIMEOUT` clause of the select statements, so the node aborts the query past the timeout (rather
+    /// than the query being abandoned). Empty if the timeout is unset
+    pub(crate) fn using_timeout(&self) -> String {
+        self.query_timeout_ms
+            .map(|query_timeout_ms| format!(" USING TIMEOUT {}ms", query_timeout_ms))
+            .unwrap_or_default()
     }
 }
 
@@ -52,10 +52,7 @@ impl Builder for AppsBuilder {
         let storage_config = config.storage_config;
         let scylla_builder = ScyllaBuilder::new()
             .listen_address(storage_config.listen_address.to_string())
-            .thread_count(match storage_config.thread_count {
-                ThreadCount::Count(c) => c,
-                ThreadCount::CoreMultiple(c) => num_cpus::get() * c,
-            })
+            .thread_count(storage_config.thread_count.resolve())
             .reporter_count(storage_config.reporter_count)
             .local_dc(storage_config.local_datacenter.clone());
 
@@ -92,25 +89,11 @@ async fn init_database() {
     for keyspace_config in storage_config.keyspaces.first().iter() {
         let keyspace = ChronicleKeyspace::new(keyspace_config.name.clone());
         assert_eq!(keyspace.name(), "chronicle_test");
-        let datacenters = keyspace_config
-            .data_centers
-            .iter()
-            .map(|(datacenter_name, datacenter_config)| {
-                format!("'{}': {}", datacenter_name, datacenter_config.replication_factor)
-            })
-            .collect::<Vec<_>>()
-            .join(", ");
         let (sender, mut inbox) = unbounded_channel::<Result<(), WorkerError>>();
         let worker = BatchWorker::boxed(sender.clone());
         let token = 1;
         let keyspace_statement = Query::new()
-            .statement(&format!(
-                "CREATE KEYSPACE IF NOT EXISTS {0}
-                WITH replication = {{'class': 'NetworkTopologyStrategy', {1}}}
-                AND durable_writes = true;",
-                keyspace.name(),
-                datacenters
-            ))
+            .statement(&keyspace_config.create_keyspace_statement())
             .consistency(Consistency::One)
             .build()
             .unwrap();
@@ -188,6 +171,13 @@ async fn init_database() {
                 PRIMARY KEY (hint, variant, partition_id)
             ) WITH CLUSTERING ORDER BY (variant DESC, partition_id DESC);
 
+            CREATE TABLE IF NOT EXISTS {0}.tags  (
+                bucket text,
+                partition_id smallint,
+                tag text,
+                PRIMARY KEY ((bucket, partition_id), tag)
+            ) WITH CLUSTERING ORDER BY (tag ASC);
+
             CREATE TABLE IF NOT EXISTS {0}.sync  (
                 key text,
                 milestone_index int,
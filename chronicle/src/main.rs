@@ -3,18 +3,35 @@
 
 #![warn(missing_docs)]
 //! # Chronicle
-use anyhow::bail;
+use anyhow::{
+    anyhow,
+    bail,
+};
 use chronicle_api::application::*;
 use chronicle_broker::application::*;
 use chronicle_common::{
     config::*,
     get_config,
     get_config_async,
-    get_history_mut,
+    get_history_mut_async,
     metrics::*,
 };
-use chronicle_storage::access::ChronicleKeyspace;
-use scylla_rs::prelude::*;
+use chronicle_storage::access::{
+    ChronicleKeyspace,
+    Record,
+};
+use futures::Future;
+use scylla_rs::{
+    cql::{
+        Decoder,
+        Rows,
+    },
+    prelude::*,
+};
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+};
 use tokio::sync::mpsc::{
     unbounded_channel,
     UnboundedSender,
@@ -49,10 +66,7 @@ impl Builder for AppsBuilder {
             .complete_gaps_interval_secs(broker_config.complete_gaps_interval_secs);
         let scylla_builder = ScyllaBuilder::new()
             .listen_address(storage_config.listen_address.to_string())
-            .thread_count(match storage_config.thread_count {
-                ThreadCount::Count(c) => c,
-                ThreadCount::CoreMultiple(c) => num_cpus::get() * c,
-            })
+            .thread_count(storage_config.thread_count.resolve())
             .reporter_count(storage_config.reporter_count)
             .local_dc(storage_config.local_datacenter.clone());
         let websocket_builder = WebsocketBuilder::new();
@@ -67,18 +81,17 @@ impl Builder for AppsBuilder {
 
 fn main() {
     dotenv::dotenv().ok();
-    env_logger::init();
-    register_metrics();
     let config = get_config();
-    let thread_count;
-    match config.storage_config.thread_count {
-        ThreadCount::Count(c) => {
-            thread_count = c;
-        }
-        ThreadCount::CoreMultiple(c) => {
-            thread_count = num_cpus::get() * c;
-        }
-    }
+    init_logger(config.telemetry_config.log_format);
+    register_metrics();
+    let thread_count = config
+        .storage_config
+        .verify_thread_count()
+        .expect("Expected a valid thread count");
+    info!(
+        "Resolved the thread count {:?} to {} threads",
+        config.storage_config.thread_count, thread_count
+    );
     let apps = AppsBuilder::new().build();
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
@@ -87,25 +100,79 @@ fn main() {
         .thread_stack_size(apps.app_count * 4 * 1024 * 1024)
         .build()
         .expect("Expected to build tokio runtime");
-    let new_config = runtime.block_on(config.clone().verify()).unwrap();
-    if new_config != config {
-        get_history_mut().update(new_config.into());
+    runtime.block_on(startup(config, apps));
+    #[cfg(feature = "otel")]
+    chronicle_common::telemetry::shutdown();
+}
+
+/// Install the logger of the configured format, which is filtered by `RUST_LOG` either way
+fn init_logger(log_format: LogFormat) {
+    let mut builder = env_logger::Builder::from_default_env();
+    if let LogFormat::Json = log_format {
+        builder.format(|buf, record| {
+            use std::io::Write;
+            writeln!(buf, "{}", json_log_line(record))
+        });
+    }
+    builder.init();
+}
+
+/// Format the log record as a JSON line of its fields
+fn json_log_line(record: &log::Record) -> String {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    serde_json::json!({
+        "timestamp": timestamp,
+        "level": record.level().to_string(),
+        "target": record.target(),
+        "module_path": record.module_path(),
+        "file": record.file(),
+        "line": record.line(),
+        "message": record.args().to_string(),
+    })
+    .to_string()
+}
+
+/// Verify the config and start chronicle, everything is awaited within the runtime
+async fn startup(config: Config, apps: Apps) {
+    if let Some(new_config) = verify_config(&config).await.unwrap() {
+        get_history_mut_async().await.update(new_config.into());
     }
-    runtime.block_on(chronicle(apps));
+    #[cfg(feature = "otel")]
+    chronicle_common::telemetry::init(&get_config_async().await.telemetry_config)
+        .expect("Expected to install the OTLP span exporter");
+    chronicle(apps).await;
+}
+
+/// Verify the config, returns the verified config only if the verification adjusted it
+async fn verify_config(config: &Config) -> anyhow::Result<Option<Config>> {
+    let new_config = config.clone().verify().await?;
+    Ok((&new_config != config).then(|| new_config))
 }
 
 async fn chronicle(apps: Apps) {
     apps.Scylla()
         .await
         .future(|apps| async {
-            let storage_config = get_config_async().await.storage_config;
+            let config = get_config_async().await;
+            let storage_config = config.storage_config;
             let uniform_rf = storage_config.try_get_uniform_rf().expect("Expected Unifrom RF");
             debug!("Adding nodes: {:?}", storage_config.nodes);
             let ws = format!("ws://{}/", storage_config.listen_address);
-            add_nodes(&ws, storage_config.nodes.iter().cloned().collect(), uniform_rf)
-                .await
-                .ok();
-            init_database().await.ok();
+            retry_with_backoff("Adding nodes", &storage_config.add_nodes_retry, || {
+                add_nodes(&ws, storage_config.nodes.iter().cloned().collect(), uniform_rf)
+            })
+            .await
+            .map_err(|e| error!("Unable to add nodes: {}", e))
+            .ok();
+            let schema = init_database(&storage_config, &config.broker_config.mirror_keyspaces).await;
+            if storage_config.create_schema {
+                schema.map_err(|e| error!("Unable to create the schema: {}", e)).ok();
+            } else {
+                schema.expect("Expected the schema to exist, as its creation is disabled");
+            }
             apps
         })
         .await
@@ -119,6 +186,75 @@ async fn chronicle(apps: Apps) {
         .await;
 }
 
+/// Retry the given operation with a bounded exponential backoff till it succeeds or the attempts are exhausted
+async fn retry_with_backoff<T, E, F, Fut>(operation: &str, retry: &RetryConfig, mut f: F) -> Result<T, E>
+where
+    E: std::fmt::Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+    loop {
+        info!("{}, attempt: {}/{}", operation, attempt, retry.max_attempts);
+        match f().await {
+            Ok(res) => return Ok(res),
+            Err(e) if attempt < retry.max_attempts => {
+                let backoff = retry.backoff(attempt);
+                warn!("{} failed: {}, retrying in {} ms", operation, e, backoff.as_millis());
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Await on the termination signals, which are SIGTERM/SIGINT on unix and ctrl-c otherwise
+async fn shutdown_signal() -> anyhow::Result<&'static str> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{
+            signal,
+            SignalKind,
+        };
+        let mut sigterm = signal(SignalKind::terminate())?;
+        let mut sigint = signal(SignalKind::interrupt())?;
+        tokio::select! {
+            _ = sigterm.recv() => Ok("SIGTERM"),
+            _ = sigint.recv() => Ok("SIGINT"),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await?;
+        Ok("ctrl-c")
+    }
+}
+
+/// Invoke the exit once the signal is received, the exit is not invoked if listening to the signal failed
+async fn exit_on_signal<S, E>(signal: S, exit: E)
+where
+    S: Future<Output = anyhow::Result<&'static str>>,
+    E: FnOnce(),
+{
+    match signal.await {
+        Ok(signal) => {
+            info!("Received {}, gracefully shutting down chronicle", signal);
+            exit();
+        }
+        Err(e) => error!("Unable to listen to the shutdown signals: {}", e),
+    }
+}
+
+/// Spawn the (only once) signal handler which asks the launcher to exit the program, the launcher will then
+/// shutdown the apps and await their acknowledgement before breaking, which unblocks the main thread
+pub(crate) fn spawn_signal_handler<H: LauncherSender<WebsocketBuilder<H>>>(mut launcher: H) {
+    static SPAWNED: std::sync::Once = std::sync::Once::new();
+    SPAWNED.call_once(|| {
+        tokio::spawn(exit_on_signal(shutdown_signal(), move || launcher.exit_program(true)));
+    });
+}
+
 fn register_metrics() {
     REGISTRY
         .register(Box::new(INCOMING_REQUESTS.clone()))
@@ -131,156 +267,336 @@ fn register_metrics() {
     REGISTRY
         .register(Box::new(RESPONSE_TIME_COLLECTOR.clone()))
         .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(DROPPED_FEED_EVENTS.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(DUPLICATE_FEED_EVENTS.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(DEAD_LETTER_FEED_MESSAGES.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(SOLIDIFICATION_TIMEOUTS.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(SOLIDIFICATION_LATENCY.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(MESSAGE_CACHE_HITS.clone()))
+        .expect("Could not register collector");
+
+    REGISTRY
+        .register(Box::new(MESSAGE_CACHE_MISSES.clone()))
+        .expect("Could not register collector");
 }
 
-async fn init_database() -> anyhow::Result<()> {
-    let storage_config = get_config_async().await.storage_config;
+/// Create the schema of the default (first) keyspace and the mirror keyspaces, or only verify it exists if the schema
+/// creation is disabled
+async fn init_database(storage_config: &StorageConfig, mirror_keyspaces: &[String]) -> anyhow::Result<()> {
+    for keyspace_config in storage_config
+        .keyspaces
+        .iter()
+        .enumerate()
+        .filter(|(i, keyspace_config)| *i == 0 || mirror_keyspaces.contains(&keyspace_config.name))
+        .map(|(_, keyspace_config)| keyspace_config)
+    {
+        let keyspace = &ChronicleKeyspace::new(keyspace_config.name.clone());
+        init_schema(
+            keyspace_config,
+            storage_config.create_schema,
+            |statement| async move { execute(keyspace, &statement).await.map(|_| ()) },
+            |keyspace_name| async move {
+                let statement = format!(
+                    "SELECT table_name, column_name FROM system_schema.columns WHERE keyspace_name = '{}'",
+                    keyspace_name
+                );
+                let decoder = Decoder::try_from(execute(keyspace, &statement).await?)?;
+                Ok(Record::<(String, String)>::rows_iter(decoder)?
+                    .map(|row| row.into_inner())
+                    .collect())
+            },
+        )
+        .await?;
+    }
+    Ok(())
+}
 
-    for keyspace_config in storage_config.keyspaces.first().iter() {
-        let keyspace = ChronicleKeyspace::new(keyspace_config.name.clone());
-        let datacenters = keyspace_config
-            .data_centers
+/// Create the keyspace and its tables with the executed DDL statements and add the columns of the migrations missing
+/// from the fetched (table, column) pairs, or, if the schema creation is disabled, verify that the fetched columns of
+/// the keyspace include all the columns of the created tables and of the migrations
+async fn init_schema<E, EFut, C, CFut>(
+    keyspace_config: &KeyspaceConfig,
+    create_schema: bool,
+    mut execute: E,
+    fetch_columns: C,
+) -> anyhow::Result<()>
+where
+    E: FnMut(String) -> EFut,
+    EFut: Future<Output = anyhow::Result<()>>,
+    C: FnOnce(String) -> CFut,
+    CFut: Future<Output = anyhow::Result<HashSet<(String, String)>>>,
+{
+    let statements = table_statements(&keyspace_config.name);
+    if create_schema {
+        execute(keyspace_config.create_keyspace_statement())
+            .await
+            .map_err(|e| anyhow!("Unable to create the keyspace {}: {}", keyspace_config.name, e))?;
+        for statement in statements {
+            execute(statement)
+                .await
+                .map_err(|e| anyhow!("Unable to create the table: {}", e))?;
+        }
+        let columns = fetch_columns(keyspace_config.name.clone()).await?;
+        for (table, column, statement) in schema_migrations(&keyspace_config.name) {
+            if !columns.contains(&(table.to_owned(), column.to_owned())) {
+                info!("Adding the column {}.{}.{}", keyspace_config.name, table, column);
+                execute(statement)
+                    .await
+                    .map_err(|e| anyhow!("Unable to add the column {}.{}: {}", table, column, e))?;
+            }
+        }
+    } else {
+        let expected = statements
             .iter()
-            .map(|(datacenter_name, datacenter_config)| {
-                format!("'{}': {}", datacenter_name, datacenter_config.replication_factor)
-            })
-            .collect::<Vec<_>>()
-            .join(", ");
-        let (sender, mut inbox) = unbounded_channel::<Result<(), WorkerError>>();
-        let worker = BatchWorker::boxed(sender.clone());
-        let token = 1;
-        let keyspace_statement = Query::new()
-            .statement(&format!(
-                "CREATE KEYSPACE IF NOT EXISTS {0}
-                WITH replication = {{'class': 'NetworkTopologyStrategy', {1}}}
-                AND durable_writes = true;",
-                keyspace.name(),
-                datacenters
-            ))
-            .consistency(Consistency::One)
-            .build()?;
-        send_local(token, keyspace_statement.0, worker, keyspace.name().to_string());
-        if let Some(msg) = inbox.recv().await {
-            match msg {
-                Ok(_) => (),
-                Err(e) => bail!(e),
+            .map(|statement| table_columns(&keyspace_config.name, statement))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let columns = fetch_columns(keyspace_config.name.clone()).await?;
+        let tables = columns.iter().map(|(table, _)| table.as_str()).collect::<HashSet<_>>();
+        let missing = expected
+            .iter()
+            .map(|(table, _)| table.as_str())
+            .filter(|table| !tables.contains(table))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            bail!(
+                "The keyspace {} is missing the tables: {}, either create them or enable the storage create_schema",
+                keyspace_config.name,
+                missing.join(", ")
+            );
+        }
+        let mut missing = Vec::new();
+        let expected_columns = expected
+            .iter()
+            .flat_map(|(table, columns)| columns.iter().map(move |column| (table.clone(), column.clone())))
+            .chain(
+                schema_migrations(&keyspace_config.name)
+                    .into_iter()
+                    .map(|(table, column, _)| (table.to_owned(), column.to_owned())),
+            );
+        for (table, column) in expected_columns {
+            let name = format!("{}.{}", table, column);
+            if !columns.contains(&(table, column)) && !missing.contains(&name) {
+                missing.push(name);
             }
-        } else {
-            bail!("Could not verify if keyspace was created!")
         }
-        let table_queries = format!(
-            "CREATE TABLE IF NOT EXISTS {0}.messages (
-                message_id text PRIMARY KEY,
-                message blob,
-                metadata blob,
+        if !missing.is_empty() {
+            bail!(
+                "The keyspace {} is missing the columns: {}, either add them or enable the storage create_schema",
+                keyspace_config.name,
+                missing.join(", ")
             );
+        }
+        info!("Verified the schema of the keyspace {}", keyspace_config.name);
+    }
+    Ok(())
+}
 
-            CREATE TABLE IF NOT EXISTS {0}.addresses  (
-                address text,
-                partition_id smallint,
-                milestone_index int,
-                output_type tinyint,
-                transaction_id text,
-                idx smallint,
-                amount bigint,
-                address_type tinyint,
-                inclusion_state blob,
-                PRIMARY KEY ((address, partition_id), milestone_index, output_type, transaction_id, idx)
-            ) WITH CLUSTERING ORDER BY (milestone_index DESC, output_type DESC, transaction_id DESC, idx DESC);
-
-            CREATE TABLE IF NOT EXISTS {0}.indexes  (
-                indexation text,
-                partition_id smallint,
-                milestone_index int,
-                message_id text,
-                inclusion_state blob,
-                PRIMARY KEY ((indexation, partition_id), milestone_index, message_id)
-            ) WITH CLUSTERING ORDER BY (milestone_index DESC);
-
-            CREATE TABLE IF NOT EXISTS {0}.parents  (
-                parent_id text,
-                partition_id smallint,
-                milestone_index int,
-                message_id text,
-                inclusion_state blob,
-                PRIMARY KEY ((parent_id, partition_id), milestone_index, message_id)
-            ) WITH CLUSTERING ORDER BY (milestone_index DESC);
-
-            CREATE TABLE IF NOT EXISTS {0}.transactions  (
-                transaction_id text,
-                idx smallint,
-                variant text,
-                message_id text,
-                data blob,
-                inclusion_state blob,
-                milestone_index int,
-                PRIMARY KEY (transaction_id, idx, variant, message_id, data)
-            );
+/// Execute the statement on the keyspace, returns the response payload
+async fn execute(keyspace: &ChronicleKeyspace, statement: &str) -> anyhow::Result<Vec<u8>> {
+    let (sender, mut inbox) = unbounded_channel::<Result<Vec<u8>, WorkerError>>();
+    let worker = BatchWorker::boxed(sender);
+    let query = Query::new()
+        .statement(statement)
+        .consistency(Consistency::One)
+        .build()?;
+    send_local(1, query.0, worker, keyspace.name().to_string());
+    match inbox.recv().await {
+        Some(Ok(giveload)) => Ok(giveload),
+        Some(Err(e)) => bail!(e),
+        None => bail!("Could not verify if the statement was executed!"),
+    }
+}
 
-            CREATE TABLE IF NOT EXISTS {0}.milestones  (
-                milestone_index int,
-                message_id text,
-                timestamp bigint,
-                payload blob,
-                PRIMARY KEY (milestone_index, message_id)
-            );
+/// The statements which create the tables of the keyspace
+fn table_statements(keyspace_name: &str) -> Vec<String> {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {0}.messages (
+            message_id text PRIMARY KEY,
+            message blob,
+            metadata blob,
+        );
+
+        CREATE TABLE IF NOT EXISTS {0}.addresses  (
+            address text,
+            partition_id smallint,
+            milestone_index int,
+            output_type tinyint,
+            transaction_id text,
+            idx smallint,
+            amount bigint,
+            address_type tinyint,
+            inclusion_state blob,
+            PRIMARY KEY ((address, partition_id), milestone_index, output_type, transaction_id, idx)
+        ) WITH CLUSTERING ORDER BY (milestone_index DESC, output_type DESC, transaction_id DESC, idx DESC);
 
-            CREATE TABLE IF NOT EXISTS {0}.hints  (
-                hint text,
-                variant text,
-                partition_id smallint,
-                milestone_index int,
-                PRIMARY KEY (hint, variant, partition_id)
-            ) WITH CLUSTERING ORDER BY (variant DESC, partition_id DESC);
-
-            CREATE TABLE IF NOT EXISTS {0}.sync  (
-                key text,
-                milestone_index int,
-                synced_by tinyint,
-                logged_by tinyint,
-                PRIMARY KEY (key, milestone_index)
-            ) WITH CLUSTERING ORDER BY (milestone_index DESC);
-            
-            CREATE TABLE IF NOT EXISTS {0}.analytics (
-                key text,
-                milestone_index int,
-                message_count int,
-                transaction_count int,
-                transferred_tokens bigint,
-                PRIMARY KEY (key, milestone_index)
-            ) WITH CLUSTERING ORDER BY (milestone_index DESC);",
-            keyspace.name()
+        CREATE TABLE IF NOT EXISTS {0}.indexes  (
+            indexation text,
+            partition_id smallint,
+            milestone_index int,
+            message_id text,
+            inclusion_state blob,
+            PRIMARY KEY ((indexation, partition_id), milestone_index, message_id)
+        ) WITH CLUSTERING ORDER BY (milestone_index DESC);
+
+        CREATE TABLE IF NOT EXISTS {0}.parents  (
+            parent_id text,
+            partition_id smallint,
+            milestone_index int,
+            message_id text,
+            inclusion_state blob,
+            PRIMARY KEY ((parent_id, partition_id), milestone_index, message_id)
+        ) WITH CLUSTERING ORDER BY (milestone_index DESC);
+
+        CREATE TABLE IF NOT EXISTS {0}.transactions  (
+            transaction_id text,
+            idx smallint,
+            variant text,
+            message_id text,
+            data blob,
+            inclusion_state blob,
+            milestone_index int,
+            PRIMARY KEY (transaction_id, idx, variant, message_id, data)
         );
-        for query in table_queries.split(";").map(str::trim).filter(|s| !s.is_empty()) {
-            let worker = BatchWorker::boxed(sender.clone());
-            let statement = Query::new().statement(query).consistency(Consistency::One).build()?;
-            send_local(token, statement.0, worker, keyspace.name().to_string());
-            if let Some(msg) = inbox.recv().await {
-                match msg {
-                    Ok(_) => (),
-                    Err(e) => bail!(e),
-                }
-            } else {
-                bail!("Could not verify if table was created!")
+
+        CREATE TABLE IF NOT EXISTS {0}.milestones  (
+            milestone_index int,
+            message_id text,
+            timestamp bigint,
+            payload blob,
+            PRIMARY KEY (milestone_index, message_id)
+        );
+
+        CREATE TABLE IF NOT EXISTS {0}.hints  (
+            hint text,
+            variant text,
+            partition_id smallint,
+            milestone_index int,
+            PRIMARY KEY (hint, variant, partition_id)
+        ) WITH CLUSTERING ORDER BY (variant DESC, partition_id DESC);
+
+        CREATE TABLE IF NOT EXISTS {0}.tags  (
+            bucket text,
+            partition_id smallint,
+            tag text,
+            PRIMARY KEY ((bucket, partition_id), tag)
+        ) WITH CLUSTERING ORDER BY (tag ASC);
+
+        CREATE TABLE IF NOT EXISTS {0}.sync  (
+            key text,
+            milestone_index int,
+            synced_by tinyint,
+            logged_by tinyint,
+            created_by tinyint,
+            PRIMARY KEY (key, milestone_index)
+        ) WITH CLUSTERING ORDER BY (milestone_index DESC);
+        
+        CREATE TABLE IF NOT EXISTS {0}.analytics (
+            key text,
+            milestone_index int,
+            message_count int,
+            transaction_count int,
+            transferred_tokens bigint,
+            reattachment_count int,
+            PRIMARY KEY (key, milestone_index)
+        ) WITH CLUSTERING ORDER BY (milestone_index DESC);",
+        keyspace_name
+    )
+    .split(";")
+    .map(str::trim)
+    .filter(|s| !s.is_empty())
+    .map(str::to_owned)
+    .collect()
+}
+
+/// The columns added to the tables after their creation, as (table, column, statement), which are added to the
+/// existing tables missing them
+fn schema_migrations(keyspace_name: &str) -> Vec<(&'static str, &'static str, String)> {
+    vec![
+        (
+            "analytics",
+            "reattachment_count",
+            format!("ALTER TABLE {}.analytics ADD reattachment_count int", keyspace_name),
+        ),
+        (
+            "sync",
+            "created_by",
+            format!("ALTER TABLE {}.sync ADD created_by tinyint", keyspace_name),
+        ),
+    ]
+}
+
+/// Parse the table and the column names defined by a `CREATE TABLE IF NOT EXISTS <keyspace>.<table> (..)` statement
+fn table_columns(keyspace_name: &str, statement: &str) -> anyhow::Result<(String, Vec<String>)> {
+    let prefix = format!("CREATE TABLE IF NOT EXISTS {}.", keyspace_name);
+    let definition = statement
+        .strip_prefix(prefix.as_str())
+        .ok_or_else(|| anyhow!("Unexpected table statement: {}", statement))?;
+    let open = definition
+        .find('(')
+        .ok_or_else(|| anyhow!("Missing the columns of the table statement: {}", statement))?;
+    let table = definition[..open].trim();
+    // split the column definitions on the commas outside of the nested (primary key) parentheses
+    let (mut depth, mut start, mut close) = (0usize, open + 1, None);
+    let mut definitions = Vec::new();
+    for (i, c) in definition.char_indices().filter(|(i, _)| *i > open) {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                definitions.push(&definition[start..i]);
+                close = Some(i);
+                break;
+            }
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                definitions.push(&definition[start..i]);
+                start = i + 1;
             }
+            _ => (),
         }
     }
-    Ok(())
+    if table.is_empty() || close.is_none() {
+        bail!("Malformed table statement: {}", statement);
+    }
+    let columns = definitions
+        .into_iter()
+        .map(str::trim)
+        .filter(|column| !column.is_empty() && !column.starts_with("PRIMARY KEY"))
+        .filter_map(|column| column.split_whitespace().next())
+        .map(str::to_owned)
+        .collect();
+    Ok((table.to_owned(), columns))
 }
 
 struct BatchWorker {
-    sender: UnboundedSender<Result<(), WorkerError>>,
+    sender: UnboundedSender<Result<Vec<u8>, WorkerError>>,
 }
 
 impl BatchWorker {
-    pub fn boxed(sender: UnboundedSender<Result<(), WorkerError>>) -> Box<Self> {
+    pub fn boxed(sender: UnboundedSender<Result<Vec<u8>, WorkerError>>) -> Box<Self> {
         Box::new(Self { sender: sender.into() })
     }
 }
 
 impl Worker for BatchWorker {
-    fn handle_response(self: Box<Self>, _giveload: Vec<u8>) -> anyhow::Result<()> {
-        self.sender.send(Ok(()))?;
+    fn handle_response(self: Box<Self>, giveload: Vec<u8>) -> anyhow::Result<()> {
+        self.sender.send(Ok(giveload))?;
         Ok(())
     }
 
@@ -289,3 +605,259 @@ impl Worker for BatchWorker {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn signal_triggers_exit() {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let (signal_tx, signal_rx) = tokio::sync::oneshot::channel::<&'static str>();
+        let handler = tokio::spawn(exit_on_signal(async move { Ok(signal_rx.await?) }, move || {
+            tx.send(()).ok();
+        }));
+        // simulate SIGTERM
+        signal_tx.send("SIGTERM").unwrap();
+        handler.await.unwrap();
+        assert!(rx.await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn verify_config_within_runtime() {
+        use tokio::io::{
+            AsyncReadExt,
+            AsyncWriteExt,
+        };
+        // a local IOTA api endpoint which is requested by the broker config verification
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = url::Url::parse(&format!("http://{}/api/v1/", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                stream.read(&mut buf).await.ok();
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n{}")
+                    .await
+                    .ok();
+            }
+        });
+        let mut config = Config::default();
+        config.broker_config.mqtt_brokers.clear();
+        config.broker_config.api_endpoints = std::iter::once(endpoint).collect();
+        // the verification awaits on the endpoint request within the (current thread) runtime
+        assert_eq!(verify_config(&config).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn retry_add_nodes() {
+        let retry = RetryConfig {
+            max_attempts: 5,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 4,
+        };
+        // fails a couple times then succeeds
+        let mut attempts = 0;
+        let res = retry_with_backoff("Stub", &retry, || {
+            attempts += 1;
+            let res = if attempts <= 2 { Err("not ready") } else { Ok(attempts) };
+            async move { res }
+        })
+        .await;
+        assert_eq!(res, Ok(3));
+        // gives up once the attempts are exhausted
+        let mut attempts = 0;
+        let res: Result<(), _> = retry_with_backoff("Stub", &retry, || {
+            attempts += 1;
+            async { Err("not ready") }
+        })
+        .await;
+        assert_eq!(res, Err("not ready"));
+        assert_eq!(attempts, 5);
+        assert_eq!(retry.backoff(1).as_millis(), 1);
+        assert_eq!(retry.backoff(3).as_millis(), 4);
+        assert_eq!(retry.backoff(10).as_millis(), 4);
+    }
+
+    #[tokio::test]
+    async fn schema_creation() {
+        let keyspace_config = KeyspaceConfig::default();
+        let created_columns = || {
+            let mut columns = HashSet::new();
+            columns.insert(("analytics".to_owned(), "reattachment_count".to_owned()));
+            columns.insert(("sync".to_owned(), "created_by".to_owned()));
+            futures::future::ready(Ok(columns))
+        };
+        let mut executed = Vec::new();
+        init_schema(
+            &keyspace_config,
+            true,
+            |statement| {
+                executed.push(statement);
+                futures::future::ready(Ok(()))
+            },
+            |_| created_columns(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(executed[0], keyspace_config.create_keyspace_statement());
+        assert_eq!(&executed[1..], table_statements(&keyspace_config.name).as_slice());
+        // the analytics and sync tables created before the reattachment count and the provenance are migrated
+        let mut migrated = Vec::new();
+        init_schema(
+            &keyspace_config,
+            true,
+            |statement| {
+                migrated.push(statement);
+                futures::future::ready(Ok(()))
+            },
+            |_| futures::future::ready(Ok(HashSet::new())),
+        )
+        .await
+        .unwrap();
+        assert_eq!(&migrated[..executed.len()], executed.as_slice());
+        assert_eq!(
+            &migrated[executed.len()..],
+            &[
+                "ALTER TABLE permanode.analytics ADD reattachment_count int".to_owned(),
+                "ALTER TABLE permanode.sync ADD created_by tinyint".to_owned()
+            ]
+        );
+        let tables = executed[1..]
+            .iter()
+            .map(|statement| table_columns(&keyspace_config.name, statement).unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tables,
+            vec![
+                "messages",
+                "addresses",
+                "indexes",
+                "parents",
+                "transactions",
+                "milestones",
+                "hints",
+                "tags",
+                "sync",
+                "analytics"
+            ]
+        );
+        // a failed statement fails the creation
+        let res = init_schema(
+            &keyspace_config,
+            true,
+            |_| futures::future::ready(Err(anyhow!("Unavailable"))),
+            |_| futures::future::ready(Ok(HashSet::new())),
+        )
+        .await;
+        assert!(res.unwrap_err().to_string().contains("Unavailable"));
+    }
+
+    #[tokio::test]
+    async fn schema_verification() {
+        let keyspace_config = KeyspaceConfig::default();
+        let all_columns = table_statements(&keyspace_config.name)
+            .iter()
+            .flat_map(|statement| {
+                let (table, columns) = table_columns(&keyspace_config.name, statement).unwrap();
+                columns.into_iter().map(move |column| (table.clone(), column))
+            })
+            .collect::<HashSet<_>>();
+        let verify = |columns: HashSet<(String, String)>| {
+            let keyspace_config = keyspace_config.clone();
+            async move {
+                init_schema(
+                    &keyspace_config,
+                    false,
+                    |_| futures::future::ready(Err(anyhow!("Unexpected statement"))),
+                    |keyspace_name| {
+                        assert_eq!(keyspace_name, "permanode");
+                        futures::future::ready(Ok(columns))
+                    },
+                )
+                .await
+            }
+        };
+        verify(all_columns.clone()).await.unwrap();
+        let mut columns = all_columns.clone();
+        columns.retain(|(table, _)| table != "tags");
+        let err = verify(columns).await.unwrap_err().to_string();
+        assert!(err.contains("missing the tables: tags"), "{}", err);
+        // a table created by an older version without one of its columns fails loudly
+        let mut columns = all_columns.clone();
+        columns.remove(&("tags".to_owned(), "partition_id".to_owned()));
+        let err = verify(columns).await.unwrap_err().to_string();
+        assert!(err.contains("missing the columns: tags.partition_id"), "{}", err);
+        // an analytics table without the migrated column fails loudly
+        let mut columns = all_columns.clone();
+        columns.remove(&("analytics".to_owned(), "reattachment_count".to_owned()));
+        let err = verify(columns).await.unwrap_err().to_string();
+        assert!(
+            err.contains("missing the columns: analytics.reattachment_count"),
+            "{}",
+            err
+        );
+        // the column names are parsed from the statements, skipping the primary key definition
+        let (table, columns) =
+            table_columns(&keyspace_config.name, &table_statements(&keyspace_config.name)[1]).unwrap();
+        assert_eq!(table, "addresses");
+        assert_eq!(
+            columns,
+            vec![
+                "address",
+                "partition_id",
+                "milestone_index",
+                "output_type",
+                "transaction_id",
+                "idx",
+                "amount",
+                "address_type",
+                "inclusion_state"
+            ]
+        );
+        assert!(table_columns("other", &table_statements(&keyspace_config.name)[0]).is_err());
+        // a missing keyspace has no tables
+        let err = verify(HashSet::new()).await.unwrap_err().to_string();
+        assert!(err.contains("messages, addresses"), "{}", err);
+    }
+
+    #[test]
+    fn json_log_lines() {
+        let line = json_log_line(
+            &log::Record::builder()
+                .args(format_args!("[{}] Querying \"{}\"", "5f2a", "Message"))
+                .level(log::Level::Debug)
+                .target("chronicle_api")
+                .module_path(Some("chronicle_api::listener"))
+                .file(Some("chronicle-api/src/listener/rocket_event_loop.rs"))
+                .line(Some(42))
+                .build(),
+        );
+        let line: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(line["level"], "DEBUG");
+        assert_eq!(line["target"], "chronicle_api");
+        assert_eq!(line["module_path"], "chronicle_api::listener");
+        assert_eq!(line["file"], "chronicle-api/src/listener/rocket_event_loop.rs");
+        assert_eq!(line["line"], 42);
+        assert_eq!(line["message"], "[5f2a] Querying \"Message\"");
+        assert!(line["timestamp"].is_u64());
+
+        let line = json_log_line(
+            &log::Record::builder()
+                .args(format_args!("Unable to update sync table for milestone index: {}", 42))
+                .level(log::Level::Error)
+                .build(),
+        );
+        let line: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(line["level"], "ERROR");
+        assert_eq!(line["message"], "Unable to update sync table for milestone index: 42");
+        assert!(line["line"].is_null());
+    }
+
+    #[tokio::test]
+    async fn signal_error_does_not_exit() {
+        let mut exited = false;
+        exit_on_signal(async { Err(anyhow::anyhow!("no signals")) }, || exited = true).await;
+        assert!(!exited);
+    }
+}
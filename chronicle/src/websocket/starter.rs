@@ -19,6 +19,8 @@ impl<H: WebsocketScope> Starter<H> for WebsocketBuilder<H> {
             .clone()
             .ok_or_else(|| anyhow!("No supervisor for websocket!"))?;
 
+        crate::spawn_signal_handler(handle.clone());
+
         tokio::spawn(websocket.start(Some(handle)));
 
         Ok(supervisor)